@@ -0,0 +1,88 @@
+//! Enemy turn timings with 200 enemies closing in on the player across an
+//! open level scattered with pillars.
+//!
+//! Run with `cargo bench --bench enemy_turns --features bench`. Each iteration puts the
+//! enemies back where they started with no energy banked, then runs the
+//! timeline for one turn, as `end_turn` does, so that each of them acts
+//! once. `shared_paths` chases the player along the per-turn flow fields;
+//! `a_star` turns them off so that every enemy searches its own path.
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use saltglass_steppe::game::systems::{System, TimelineSystem};
+use saltglass_steppe::{Enemy, GameState, Tile};
+
+const ENEMIES: usize = 200;
+
+/// Open floor with a pillar every few tiles and `ENEMIES` void stalkers
+/// within their sight range of the player
+fn crowded_state() -> GameState {
+    let mut state = GameState::new(42);
+    state.enemies.clear();
+    state.npcs.clear();
+    state.items.clear();
+    let (width, height) = (state.map.width as i32, state.map.height as i32);
+    for y in 1..height - 1 {
+        for x in 1..width - 1 {
+            let idx = state.map.idx(x, y);
+            state.map.tiles[idx] = if x % 6 == 0 && y % 4 == 0 {
+                Tile::Wall {
+                    id: "sandstone".into(),
+                    hp: 10,
+                }
+            } else {
+                Tile::default_floor()
+            };
+        }
+    }
+    state.player_x = width / 2 + 1;
+    state.player_y = height / 2 + 1;
+
+    let (px, py) = (state.player_x, state.player_y);
+    'rings: for dist in 2..12i32 {
+        for dy in -dist..=dist {
+            for dx in [dist - dy.abs(), dy.abs() - dist] {
+                let (x, y) = (px + dx, py + dy);
+                if state.enemies.len() == ENEMIES {
+                    break 'rings;
+                }
+                if state.map.get(x, y).is_some_and(|t| t.walkable())
+                    && state.enemy_at(x, y).is_none()
+                {
+                    state.spawn_enemy(Enemy::new(x, y, "void_stalker"));
+                }
+            }
+        }
+    }
+    assert_eq!(state.enemies.len(), ENEMIES);
+    state.update_fov();
+    state.update_lighting();
+    state
+}
+
+fn bench_enemy_turns(c: &mut Criterion) {
+    let mut group = c.benchmark_group("enemy_turns");
+    group.sample_size(10);
+    for (name, shared_paths) in [("shared_paths", true), ("a_star", false)] {
+        let mut state = crowded_state();
+        state.flow_fields.disabled = !shared_paths;
+        let start: Vec<(i32, i32)> = state.enemies.iter().map(|e| (e.x, e.y)).collect();
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                for (enemy, &(x, y)) in state.enemies.iter_mut().zip(&start) {
+                    enemy.x = x;
+                    enemy.y = y;
+                    enemy.energy = 0;
+                }
+                state.rebuild_spatial_index();
+                state.player_hp = 10_000;
+                state.messages.clear();
+                state.turn += 1;
+                TimelineSystem.update(&mut state);
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_enemy_turns);
+criterion_main!(benches);
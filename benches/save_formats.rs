@@ -0,0 +1,57 @@
+//! Save/load timings for RON and compressed binary saves of a late-game state.
+//!
+//! Run with `cargo bench --bench save_formats`. File sizes are printed once
+//! before the timings.
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use saltglass_steppe::game::save::{decode_bytes, encode_as};
+use saltglass_steppe::{GameState, ItemInstance, SaveFormat, Tile};
+use std::hint::black_box;
+
+/// A state that has explored a stretch of the world and gone underground,
+/// so the tile store holds several full maps
+fn late_game_state() -> GameState {
+    let mut state = GameState::new(42);
+    let (wx, wy) = (state.world_x, state.world_y);
+    for step in 1..=6 {
+        state.travel_to_tile(wx + step, wy);
+    }
+    for _ in 0..2 {
+        let stairs = state.map.idx(state.player_x, state.player_y);
+        state.map.tiles[stairs] = Tile::StairsDown;
+        state.enter_subterranean();
+    }
+    state.revealed.extend(0..state.map.tiles.len());
+    for i in 0..40 {
+        state.give_item(ItemInstance::new(if i % 2 == 0 {
+            "brine_vial"
+        } else {
+            "storm_glass"
+        }));
+    }
+    state.turn = 5000;
+    state.player_level = 8;
+    state
+}
+
+fn bench_save_formats(c: &mut Criterion) {
+    let state = late_game_state();
+
+    for format in SaveFormat::ALL {
+        let bytes = encode_as(&state, format).unwrap();
+        println!("{:?} save: {} KiB", format, bytes.len() / 1024);
+
+        let mut group = c.benchmark_group(format!("{:?}", format).to_lowercase());
+        group.sample_size(10);
+        group.bench_function("save", |b| {
+            b.iter(|| encode_as(black_box(&state), format).unwrap())
+        });
+        group.bench_function("load", |b| {
+            b.iter(|| decode_bytes(black_box(&bytes)).unwrap())
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_save_formats);
+criterion_main!(benches);
@@ -0,0 +1,72 @@
+//! Validate the content files: JSON syntax, duplicate ids and references
+//! between items, enemies, NPCs, quests, dialogues, recipes, loot tables,
+//! spawn tables, traders, chests, classes and adaptations. Errors are
+//! printed with the file and JSON path they were found at.
+
+use saltglass_steppe::content::{Content, ContentConfig};
+use saltglass_steppe::content_check::check;
+use std::env;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let mut config = ContentConfig::default();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--data-dir" | "--mod" => {
+                let Some(dir) = args.next().map(PathBuf::from) else {
+                    eprintln!("{} expects a directory", arg);
+                    print_usage();
+                    return ExitCode::FAILURE;
+                };
+                if arg == "--mod" {
+                    config.mods.push(dir);
+                } else {
+                    config.data_dir = Some(dir);
+                }
+            }
+            "-h" | "--help" => {
+                print_usage();
+                return ExitCode::SUCCESS;
+            }
+            _ => {
+                eprintln!("Unknown argument: {}", arg);
+                print_usage();
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let content = Content::build(&config);
+    let report = content.report();
+    for conflict in &report.conflicts {
+        println!("warning: conflict: {}", conflict);
+    }
+    for error in &report.errors {
+        println!("error: {}", error);
+    }
+
+    let errors = check(&content);
+    for error in &errors {
+        println!("error: {}", error);
+    }
+
+    let total = report.errors.len() + errors.len();
+    println!("{} errors, {} mod conflicts", total, report.conflicts.len());
+    if total == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn print_usage() {
+    println!("Saltglass Steppe - Content Check");
+    println!();
+    println!("USAGE:");
+    println!("  cargo run --bin content-check [--data-dir DIR] [--mod DIR]...");
+    println!();
+    println!("Checks the embedded data files, or DIR and mods layered over them,");
+    println!("for invalid JSON, duplicate ids and references to undefined ids.");
+}
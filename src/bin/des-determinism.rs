@@ -0,0 +1,125 @@
+//! Check that DES scenarios reproduce exactly: each scenario is run twice in
+//! one thread and then through `run_parallel` on thread pools of different
+//! sizes. The first turn and the state fields where runs diverge are printed.
+
+use saltglass_steppe::des::Scenario;
+use saltglass_steppe::des::determinism::{check_repeat, check_thread_counts};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let mut thread_counts = vec![1, 4];
+    let mut paths = Vec::new();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--threads" => {
+                let parsed = args.next().map(|list| {
+                    list.split(',')
+                        .map(|n| n.trim().parse::<usize>())
+                        .collect::<Result<Vec<_>, _>>()
+                });
+                match parsed {
+                    Some(Ok(counts)) if !counts.is_empty() => thread_counts = counts,
+                    _ => {
+                        eprintln!("--threads expects a comma-separated list of counts");
+                        print_usage();
+                        return ExitCode::FAILURE;
+                    }
+                }
+            }
+            "-h" | "--help" => {
+                print_usage();
+                return ExitCode::SUCCESS;
+            }
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    if paths.is_empty() {
+        print_usage();
+        return ExitCode::FAILURE;
+    }
+
+    let scenarios = match load_scenarios(&paths) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut desyncs = 0;
+    for scenario in &scenarios {
+        match check_repeat(scenario, 2) {
+            Some(desync) => {
+                println!("DESYNC {}", desync);
+                desyncs += 1;
+            }
+            None => println!("ok     {}", scenario.name),
+        }
+    }
+
+    match check_thread_counts(&scenarios, &thread_counts) {
+        Ok(found) => {
+            for desync in &found {
+                println!("DESYNC {}", desync);
+            }
+            desyncs += found.len();
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    println!(
+        "{} scenarios, threads {:?}: {} divergences",
+        scenarios.len(),
+        thread_counts,
+        desyncs
+    );
+    if desyncs == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Load scenario files. Directories are expanded to the `.json` files they
+/// hold; files in them that fail to parse are skipped with a warning.
+fn load_scenarios(paths: &[PathBuf]) -> Result<Vec<Scenario>, String> {
+    let mut scenarios = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            let mut entries: Vec<PathBuf> = fs::read_dir(path)
+                .map_err(|e| format!("{}: {}", path.display(), e))?
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+                .collect();
+            entries.sort();
+            for file in entries {
+                match Scenario::from_file(&file) {
+                    Ok(scenario) => scenarios.push(scenario),
+                    Err(e) => eprintln!("skip   {}: {}", file.display(), e),
+                }
+            }
+        } else {
+            let scenario =
+                Scenario::from_file(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            scenarios.push(scenario);
+        }
+    }
+    Ok(scenarios)
+}
+
+fn print_usage() {
+    println!("Saltglass Steppe - DES Determinism Check");
+    println!();
+    println!("USAGE:");
+    println!("  cargo run --bin des-determinism [--threads 1,4] <scenario.json|dir>...");
+    println!();
+    println!("Runs each scenario twice, then all of them through run_parallel on");
+    println!("thread pools of each listed size, comparing per-turn state hashes.");
+}
@@ -0,0 +1,79 @@
+//! Convert save files between the RON and compressed binary encodings.
+//! Older saves are migrated to the current format version on the way through.
+
+use saltglass_steppe::game::save::{decode_bytes, encode_as};
+use saltglass_steppe::{SAVE_FORMAT_VERSION, SaveFormat};
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 3 {
+        print_usage();
+        return ExitCode::FAILURE;
+    }
+
+    let input = &args[1];
+    let output = &args[2];
+    let target = match args.get(3) {
+        Some(name) => match SaveFormat::from_name(name) {
+            Some(f) => f,
+            None => {
+                eprintln!("Unknown format '{}'", name);
+                print_usage();
+                return ExitCode::FAILURE;
+            }
+        },
+        None => Path::new(output)
+            .extension()
+            .and_then(|ext| SaveFormat::from_name(&ext.to_string_lossy()))
+            .unwrap_or_default(),
+    };
+
+    match convert(input, output, target) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Conversion failed: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn convert(input: &str, output: &str, target: SaveFormat) -> Result<(), String> {
+    let data = fs::read(input).map_err(|e| format!("{}: {}", input, e))?;
+    let source = SaveFormat::detect(&data);
+    let (header, state) = decode_bytes(&data)?;
+    let converted = encode_as(&state, target)?;
+    fs::write(output, &converted).map_err(|e| format!("{}: {}", output, e))?;
+
+    println!(
+        "{} ({:?}, v{}, game {}, seed {}) -> {} ({:?}, v{})",
+        input,
+        source,
+        header.format_version,
+        header.game_version,
+        header.seed,
+        output,
+        target,
+        SAVE_FORMAT_VERSION
+    );
+    println!(
+        "{} bytes -> {} bytes ({:.1}%)",
+        data.len(),
+        converted.len(),
+        converted.len() as f64 * 100.0 / data.len().max(1) as f64
+    );
+    Ok(())
+}
+
+fn print_usage() {
+    println!("Saltglass Steppe - Save Converter");
+    println!();
+    println!("USAGE:");
+    println!("  cargo run --bin save-convert <input> <output> [ron|binary]");
+    println!();
+    println!("The input encoding is detected. The output encoding defaults to the");
+    println!("output file extension (.ron or .sgsb), falling back to RON.");
+}
@@ -1,8 +1,21 @@
 use clap::{Arg, Command};
+use std::path::PathBuf;
+
+use crate::game::SaveFormat;
+use crate::game::content::ContentConfig;
 
 #[derive(Debug, Clone)]
 pub enum LaunchMode {
-    MainGame,
+    MainGame {
+        save_format: SaveFormat,
+        record: Option<PathBuf>,
+        content: ContentConfig,
+    },
+    Replay {
+        path: PathBuf,
+        headless: bool,
+        content: ContentConfig,
+    },
     LogUi,
     GameLogUi,
     StatusUi,
@@ -44,8 +57,62 @@ pub fn parse_args() -> LaunchMode {
                 .help("Launch as debug console terminal")
                 .action(clap::ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("save-format")
+                .long("save-format")
+                .value_name("FORMAT")
+                .value_parser(["ron", "binary"])
+                .default_value("ron")
+                .help("Encoding for new saves: ron (text) or binary (compressed)"),
+        )
+        .arg(
+            Arg::new("record")
+                .long("record")
+                .value_name("FILE")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("Record every action of the session to a replay file"),
+        )
+        .arg(
+            Arg::new("replay")
+                .long("replay")
+                .value_name("FILE")
+                .value_parser(clap::value_parser!(PathBuf))
+                .conflicts_with("record")
+                .help("Replay a recorded session and check it for divergence"),
+        )
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .requires("replay")
+                .help("Replay without drawing, printing the result")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("data-dir")
+                .long("data-dir")
+                .value_name("DIR")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("Read content files from DIR instead of the built-in data"),
+        )
+        .arg(
+            Arg::new("mod")
+                .long("mod")
+                .value_name("DIR")
+                .value_parser(clap::value_parser!(PathBuf))
+                .action(clap::ArgAction::Append)
+                .help("Layer a mod folder over the content; repeat to stack mods in order"),
+        )
         .get_matches();
 
+    // Flags extend or override SALTGLASS_DATA_DIR / SALTGLASS_MODS
+    let mut content = ContentConfig::from_env();
+    if let Some(dir) = matches.get_one::<PathBuf>("data-dir") {
+        content.data_dir = Some(dir.clone());
+    }
+    if let Some(mods) = matches.get_many::<PathBuf>("mod") {
+        content.mods.extend(mods.cloned());
+    }
+
     if matches.get_flag("log-ui") {
         LaunchMode::LogUi
     } else if matches.get_flag("game-log-ui") {
@@ -56,7 +123,21 @@ pub fn parse_args() -> LaunchMode {
         LaunchMode::InventoryUi
     } else if matches.get_flag("debug-ui") {
         LaunchMode::DebugUi
+    } else if let Some(path) = matches.get_one::<PathBuf>("replay") {
+        LaunchMode::Replay {
+            path: path.clone(),
+            headless: matches.get_flag("headless"),
+            content,
+        }
     } else {
-        LaunchMode::MainGame
+        let save_format = matches
+            .get_one::<String>("save-format")
+            .and_then(|name| SaveFormat::from_name(name))
+            .unwrap_or_default();
+        LaunchMode::MainGame {
+            save_format,
+            record: matches.get_one::<PathBuf>("record").cloned(),
+            content,
+        }
     }
 }
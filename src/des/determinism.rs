@@ -0,0 +1,179 @@
+//! Determinism checks for DES scenarios
+//!
+//! A scenario is run more than once with per-turn state hashing, and the hash
+//! logs are compared turn by turn. The first turn whose hashes differ is
+//! reported together with the `GameState` fields that differ on it.
+
+use std::fmt;
+
+use super::{DesExecutor, ExecutionResult, Scenario, TurnHash, run_parallel_hashed};
+
+/// First point where two hash logs disagree
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    /// Scenario turn
+    pub turn: u32,
+    /// `GameState::turn` at that point, taken from the first run if both
+    /// reached it
+    pub game_turn: u32,
+    /// Fields whose hashes differ; empty if one run simply ended sooner
+    pub fields: Vec<&'static str>,
+}
+
+/// A scenario that did not reproduce
+#[derive(Debug, Clone, PartialEq)]
+pub struct Desync {
+    pub scenario: String,
+    /// Which runs were compared, e.g. "run 1 vs run 2"
+    pub comparison: String,
+    pub divergence: Divergence,
+}
+
+impl fmt::Display for Desync {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let d = &self.divergence;
+        write!(
+            f,
+            "{} ({}): diverged at scenario turn {} (game turn {}): ",
+            self.scenario, self.comparison, d.turn, d.game_turn
+        )?;
+        if d.fields.is_empty() {
+            write!(f, "one run ended early")
+        } else {
+            write!(f, "{}", d.fields.join(", "))
+        }
+    }
+}
+
+/// Run a scenario with per-turn state hashes recorded
+pub fn run_hashed(scenario: &Scenario) -> ExecutionResult {
+    DesExecutor::new(scenario).with_hash_log().run(scenario)
+}
+
+/// Compare two hash logs turn by turn
+pub fn first_divergence(a: &[TurnHash], b: &[TurnHash]) -> Option<Divergence> {
+    for (ta, tb) in a.iter().zip(b) {
+        if ta.hash != tb.hash || ta.turn != tb.turn {
+            let fields = ta
+                .fields
+                .iter()
+                .zip(&tb.fields)
+                .filter(|(fa, fb)| fa != fb)
+                .map(|(fa, _)| fa.0)
+                .collect();
+            return Some(Divergence {
+                turn: ta.turn,
+                game_turn: ta.game_turn,
+                fields,
+            });
+        }
+    }
+    if a.len() != b.len() {
+        let last = a.len().min(b.len());
+        let longer = if a.len() > b.len() { a } else { b };
+        return Some(Divergence {
+            turn: longer[last].turn,
+            game_turn: longer[last].game_turn,
+            fields: Vec::new(),
+        });
+    }
+    None
+}
+
+/// Run a scenario `runs` times in this thread and compare every run to the first
+pub fn check_repeat(scenario: &Scenario, runs: usize) -> Option<Desync> {
+    let baseline = run_hashed(scenario);
+    (2..=runs).find_map(|run| {
+        let result = run_hashed(scenario);
+        first_divergence(&baseline.turn_hashes, &result.turn_hashes).map(|divergence| Desync {
+            scenario: scenario.name.clone(),
+            comparison: format!("run 1 vs run {}", run),
+            divergence,
+        })
+    })
+}
+
+/// Run all scenarios through `run_parallel` on thread pools of each size and
+/// compare every pool's results to the first one's
+pub fn check_thread_counts(
+    scenarios: &[Scenario],
+    thread_counts: &[usize],
+) -> Result<Vec<Desync>, String> {
+    let mut runs = Vec::new();
+    for &threads in thread_counts {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .map_err(|e| format!("Thread pool error: {}", e))?;
+        runs.push((threads, pool.install(|| run_parallel_hashed(scenarios))));
+    }
+
+    let mut desyncs = Vec::new();
+    let Some(((base_threads, baseline), rest)) = runs.split_first() else {
+        return Ok(desyncs);
+    };
+    for (threads, results) in rest {
+        for ((scenario, base), result) in scenarios.iter().zip(baseline).zip(results) {
+            if let Some(divergence) = first_divergence(&base.turn_hashes, &result.turn_hashes) {
+                desyncs.push(Desync {
+                    scenario: scenario.name.clone(),
+                    comparison: format!("{} vs {} threads", base_threads, threads),
+                    divergence,
+                });
+            }
+        }
+    }
+    Ok(desyncs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::des::Action;
+
+    fn scenario() -> Scenario {
+        Scenario::from_json(
+            r#"{
+                "name": "determinism",
+                "seed": 7,
+                "entities": [{"entity_type": "enemy", "id": "mirage_hound", "x": 14, "y": 10}],
+                "player": {"x": 10, "y": 10},
+                "actions": [
+                    {"turn": 0, "action": {"type": "move", "dx": 1, "dy": 0}},
+                    {"turn": 1, "action": {"type": "wait", "turns": 2}},
+                    {"turn": 2, "action": {"type": "move", "dx": 0, "dy": 1}},
+                    {"turn": 3, "action": {"type": "auto_explore"}}
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn repeated_runs_match() {
+        let scenario = scenario();
+        let result = run_hashed(&scenario);
+        assert_eq!(result.turn_hashes.len(), 5);
+        assert_eq!(check_repeat(&scenario, 3), None);
+    }
+
+    #[test]
+    fn thread_counts_match() {
+        let scenarios = vec![scenario(), scenario()];
+        assert_eq!(check_thread_counts(&scenarios, &[1, 3]), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn reports_first_divergent_turn_and_field() {
+        let scenario = scenario();
+        let baseline = run_hashed(&scenario);
+
+        let mut changed = scenario.clone();
+        changed.actions[2].action = Action::SetSaltScrip { amount: 99 };
+        let result = run_hashed(&changed);
+
+        let divergence = first_divergence(&baseline.turn_hashes, &result.turn_hashes).unwrap();
+        assert_eq!(divergence.turn, 2);
+        assert!(divergence.fields.contains(&"salt_scrip"));
+    }
+}
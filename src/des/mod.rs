@@ -3,8 +3,13 @@
 //! Runs game scenarios without rendering for automated testing and validation.
 
 use crate::game::{
-    Enemy, GameState, Interactable, Item, Npc, adaptation::Adaptation, chest::Chest,
+    Enemy, EntityId, GameState, Interactable, Item, ItemInstance, Npc,
+    adaptation::Adaptation,
+    chest::Chest,
     inspect::inspect_item,
+    state_hash::{combine_field_hashes, state_field_hashes},
+    status::get_status_def,
+    systems::TURN_SCHEDULE,
 };
 use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
@@ -14,25 +19,20 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
-fn parse_adaptation(id: &str) -> Option<Adaptation> {
-    match id.to_lowercase().as_str() {
-        "prismhide" => Some(Adaptation::Prismhide),
-        "sunveins" => Some(Adaptation::Sunveins),
-        "mirage_step" | "miragestep" => Some(Adaptation::MirageStep),
-        "saltblood" => Some(Adaptation::Saltblood),
-        _ => None,
-    }
+pub mod determinism;
+
+/// Adaptation by data id in any case, also accepting `miragestep`
+fn parse_adaptation(id: &str) -> Result<Adaptation, String> {
+    let lower = id.to_lowercase();
+    let data_id = match lower.as_str() {
+        "miragestep" => "mirage_step",
+        other => other,
+    };
+    Adaptation::from_id(data_id).ok_or_else(|| format!("Unknown adaptation '{}'", id))
 }
 
 fn parse_status_type(id: &str) -> Option<String> {
-    match id.to_lowercase().as_str() {
-        "poison" => Some("poison".to_string()),
-        "burn" => Some("burn".to_string()),
-        "stun" => Some("stun".to_string()),
-        "bleed" => Some("bleed".to_string()),
-        "slow" => Some("slow".to_string()),
-        _ => None,
-    }
+    get_status_def(&id.to_lowercase()).map(|d| d.id.clone())
 }
 
 // ============================================================================
@@ -70,6 +70,9 @@ pub struct Scenario {
     pub variables: HashMap<String, serde_json::Value>,
     #[serde(default)]
     pub map_setup: MapSetup,
+    /// Turn systems to leave out, by their name in `TURN_SCHEDULE`
+    #[serde(default)]
+    pub disabled_systems: Vec<String>,
 }
 
 /// Map setup options for test scenarios
@@ -146,6 +149,14 @@ pub enum AssertionCheck {
         op: CmpOp,
         value: u32,
     },
+    /// The actors named in `order` took their actions in that order, counting
+    /// only actions in `turn` when it is given. Actors are `player` or the
+    /// definition id of an enemy or NPC.
+    ActionOrder {
+        order: Vec<String>,
+        #[serde(default)]
+        turn: Option<u32>,
+    },
     // New assertions
     EnemyHp {
         id: String,
@@ -201,6 +212,17 @@ pub enum AssertionCheck {
         op: CmpOp,
         value: i32,
     },
+    /// Durability left on the first carried or equipped copy of `item`
+    ItemDurability {
+        item: String,
+        op: CmpOp,
+        value: u32,
+    },
+    /// Some carried or equipped copy of `item` has the affix
+    ItemHasAffix {
+        item: String,
+        affix: String,
+    },
     EnemyProvoked {
         id: String,
         provoked: bool,
@@ -492,6 +514,14 @@ impl CmpOp {
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PlayerSetup {
+    /// World map tile to start on; the game's starting tile if unset
+    #[serde(default)]
+    pub world_x: Option<usize>,
+    #[serde(default)]
+    pub world_y: Option<usize>,
+    /// Depth to start at: 0 is the surface, negative is underground
+    #[serde(default)]
+    pub layer: Option<i32>,
     #[serde(default)]
     pub x: Option<i32>,
     #[serde(default)]
@@ -506,8 +536,9 @@ pub struct PlayerSetup {
     pub max_ap: Option<i32>,
     #[serde(default)]
     pub xp: Option<u32>,
+    /// Item ids, or instances with durability, affixes and so on
     #[serde(default)]
-    pub inventory: Vec<String>,
+    pub inventory: Vec<ItemInstance>,
     #[serde(default)]
     pub adaptations: Vec<String>,
     #[serde(default)]
@@ -518,6 +549,9 @@ pub struct PlayerSetup {
 pub struct EntitySpawn {
     pub entity_type: EntityType,
     pub id: String,
+    /// Name for `Actor::Entity`; enemies can also be addressed by `id`
+    #[serde(default)]
+    pub name: Option<String>,
     pub x: i32,
     pub y: i32,
     #[serde(default)]
@@ -553,9 +587,8 @@ pub struct ScheduledAction {
 pub enum Actor {
     #[default]
     Player,
-    Entity {
-        id: String,
-    },
+    /// An enemy, by its spawn `name` or definition id
+    Entity { id: String },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -590,6 +623,11 @@ pub enum Action {
         x: i32,
         y: i32,
     },
+    ThrowItem {
+        item_index: usize,
+        x: i32,
+        y: i32,
+    },
     Equip {
         item_index: usize,
         slot: String,
@@ -598,6 +636,7 @@ pub enum Action {
         slot: String,
     },
     AutoExplore,
+    ToggleSneak,
     Wait {
         turns: u32,
     },
@@ -787,9 +826,23 @@ pub struct ExecutionResult {
     pub logs: Vec<ExecutionLog>,
     pub assertion_results: Vec<AssertionResult>,
     pub snapshots: Vec<StateSnapshot>,
+    /// Filled when the executor was built `with_hash_log`
+    pub turn_hashes: Vec<TurnHash>,
     pub final_state: Option<GameState>,
 }
 
+/// State hash taken after a scenario turn's actions have run
+#[derive(Debug, Clone, PartialEq)]
+pub struct TurnHash {
+    /// Scenario turn
+    pub turn: u32,
+    /// `GameState::turn` at the time
+    pub game_turn: u32,
+    pub hash: u64,
+    /// Per-field hashes, for locating what diverged
+    pub fields: Vec<(&'static str, u64)>,
+}
+
 #[derive(Debug, Clone)]
 pub struct ExecutionLog {
     pub turn: u32,
@@ -813,6 +866,14 @@ impl Scenario {
         serde_json::from_str(json).map_err(|e| format!("Parse error: {}", e))
     }
 
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("Serialize error: {}", e))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        fs::write(path, self.to_json()?).map_err(|e| format!("Write error: {}", e))
+    }
+
     pub fn from_file(path: impl AsRef<Path>) -> Result<Self, String> {
         let content = fs::read_to_string(&path).map_err(|e| format!("Read error: {}", e))?;
         let mut scenario: Self =
@@ -836,6 +897,15 @@ impl Scenario {
         }
 
         // Merge player setup (child overrides base)
+        if self.player.world_x.is_none() {
+            self.player.world_x = base.player.world_x;
+        }
+        if self.player.world_y.is_none() {
+            self.player.world_y = base.player.world_y;
+        }
+        if self.player.layer.is_none() {
+            self.player.layer = base.player.layer;
+        }
         if self.player.x.is_none() {
             self.player.x = base.player.x;
         }
@@ -873,6 +943,12 @@ impl Scenario {
             self.variables.entry(k).or_insert(v);
         }
 
+        for name in base.disabled_systems {
+            if !self.disabled_systems.contains(&name) {
+                self.disabled_systems.push(name);
+            }
+        }
+
         Ok(())
     }
 
@@ -917,7 +993,11 @@ pub struct DesExecutor {
     action_index: usize,
     snapshots: Vec<StateSnapshot>,
     capture_snapshots: bool,
+    turn_hashes: Vec<TurnHash>,
+    log_hashes: bool,
     current_location_type: Option<String>,
+    /// Handles of spawned enemies by spawn name and definition id
+    entity_handles: HashMap<String, EntityId>,
     // Trading and dialogue state
     current_trade_interface: Option<crate::game::trading::TradeInterface>,
     current_dialogue: Option<crate::game::dialogue::DialogueState>,
@@ -929,6 +1009,16 @@ impl DesExecutor {
         let seed = scenario.seed.unwrap_or(42);
         let mut state = GameState::new(seed);
 
+        // Travel to the starting tile and depth before placing the player
+        let setup = &scenario.player;
+        if setup.world_x.is_some() || setup.world_y.is_some() || setup.layer.is_some() {
+            state.go_to_location(
+                setup.world_x.unwrap_or(state.world_x),
+                setup.world_y.unwrap_or(state.world_y),
+                setup.layer.unwrap_or(0),
+            );
+        }
+
         // Apply player setup
         if let Some(x) = scenario.player.x {
             state.player_x = x;
@@ -966,6 +1056,9 @@ impl DesExecutor {
                 state.debug_disable_glare = true;
             }
         }
+        state
+            .disabled_systems
+            .extend(scenario.disabled_systems.iter().cloned());
 
         if let Some(hp) = scenario.player.hp {
             state.player_hp = hp;
@@ -982,19 +1075,20 @@ impl DesExecutor {
         if let Some(xp) = scenario.player.xp {
             state.player_xp = xp;
         }
-        for item_id in &scenario.player.inventory {
-            state.inventory.push(item_id.clone());
+        for item in &scenario.player.inventory {
+            state.give_item(item.clone());
         }
         // Wire adaptations
         for adaptation_id in &scenario.player.adaptations {
-            if let Some(a) = parse_adaptation(adaptation_id) {
+            // Unknown ids fail the scenario when it runs
+            if let Ok(a) = parse_adaptation(adaptation_id) {
                 state.adaptations.push(a);
             }
         }
         // Wire equipped weapon (both legacy and new equipment system)
         if let Some(weapon_id) = &scenario.player.equipped_weapon {
             state.equipped_weapon = Some(weapon_id.clone());
-            state.equipment.weapon = Some(weapon_id.clone());
+            state.equipment.weapon = Some(ItemInstance::new(weapon_id));
         }
 
         // Clear generated enemies if scenario specifies its own entities
@@ -1004,6 +1098,7 @@ impl DesExecutor {
         }
 
         // Spawn entities
+        let mut entity_handles = HashMap::new();
         for spawn in &scenario.entities {
             match spawn.entity_type {
                 EntityType::Enemy => {
@@ -1013,7 +1108,11 @@ impl DesExecutor {
                     }
                     enemy.ai_disabled = spawn.ai_disabled;
                     enemy.inventory = spawn.inventory.clone();
-                    state.enemies.push(enemy);
+                    let handle = state.spawn_enemy(enemy);
+                    if let Some(name) = &spawn.name {
+                        entity_handles.insert(name.clone(), handle);
+                    }
+                    entity_handles.entry(spawn.id.clone()).or_insert(handle);
                 }
                 EntityType::Npc => {
                     state.npcs.push(Npc::new(spawn.x, spawn.y, &spawn.id));
@@ -1023,11 +1122,10 @@ impl DesExecutor {
                 }
                 EntityType::Chest => {
                     let mut chest = Chest::new(spawn.x, spawn.y, &spawn.id);
-                    // Convert item IDs to Item objects
                     chest.inventory = spawn
                         .inventory
                         .iter()
-                        .map(|id| Item::new(spawn.x, spawn.y, id))
+                        .map(|id| ItemInstance::new(id))
                         .collect();
                     state.chests.push(chest);
                 }
@@ -1052,7 +1150,10 @@ impl DesExecutor {
             action_index: 0,
             snapshots: Vec::new(),
             capture_snapshots: false,
+            turn_hashes: Vec::new(),
+            log_hashes: false,
             current_location_type: None,
+            entity_handles,
             current_trade_interface: None,
             current_dialogue: None,
             last_trade_result: None,
@@ -1179,6 +1280,12 @@ impl DesExecutor {
         self
     }
 
+    /// Record a state hash after every scenario turn
+    pub fn with_hash_log(mut self) -> Self {
+        self.log_hashes = true;
+        self
+    }
+
     /// Inject a specific RNG seed for deterministic testing
     pub fn with_rng_seed(mut self, seed: u64) -> Self {
         self.state.rng = ChaCha8Rng::seed_from_u64(seed);
@@ -1205,10 +1312,42 @@ impl DesExecutor {
         }
     }
 
+    fn log_turn_hash(&mut self, turn: u32) {
+        if self.log_hashes {
+            let fields = state_field_hashes(&self.state);
+            self.turn_hashes.push(TurnHash {
+                turn,
+                game_turn: self.state.turn,
+                hash: combine_field_hashes(&fields),
+                fields,
+            });
+        }
+    }
+
     pub fn run(mut self, scenario: &Scenario) -> ExecutionResult {
         let mut current_turn = 0;
         let max_turns = scenario.actions.iter().map(|a| a.turn).max().unwrap_or(0) + 1;
 
+        // A misspelled system name would silently leave the system running
+        for name in &scenario.disabled_systems {
+            if !TURN_SCHEDULE.contains(name) {
+                self.assertion_results.push(AssertionResult {
+                    passed: false,
+                    check: "disabled_systems".to_string(),
+                    message: Some(format!("Unknown system '{}'", name)),
+                });
+            }
+        }
+        for id in &scenario.player.adaptations {
+            if let Err(message) = parse_adaptation(id) {
+                self.assertion_results.push(AssertionResult {
+                    passed: false,
+                    check: "adaptations".to_string(),
+                    message: Some(message),
+                });
+            }
+        }
+
         // Initial snapshot
         self.capture_snapshot();
 
@@ -1221,6 +1360,7 @@ impl DesExecutor {
                     self.action_index += 1;
                 }
             }
+            self.log_turn_hash(current_turn);
             // Check assertions for this turn
             for assertion in &scenario.assertions {
                 if assertion.after_turn == Some(current_turn) {
@@ -1244,6 +1384,7 @@ impl DesExecutor {
             logs: self.logs,
             assertion_results: self.assertion_results,
             snapshots: self.snapshots,
+            turn_hashes: self.turn_hashes,
             final_state: Some(self.state),
         }
     }
@@ -1257,6 +1398,15 @@ impl DesExecutor {
         });
     }
 
+    /// Copies of `item` in the player's inventory, then in their equipment
+    fn carried_copies<'a>(&'a self, item: &'a str) -> impl Iterator<Item = &'a ItemInstance> {
+        self.state
+            .inventory
+            .iter()
+            .chain(self.state.equipment.iter().filter_map(|(_, i)| i))
+            .filter(move |i| i.id == item)
+    }
+
     fn evaluate_check(&mut self, check: &AssertionCheck) -> bool {
         match check {
             AssertionCheck::PlayerHp { op, value } => op.compare(self.state.player_hp, *value),
@@ -1265,23 +1415,28 @@ impl DesExecutor {
             }
             AssertionCheck::PlayerAlive => self.state.player_hp > 0,
             AssertionCheck::PlayerDead => self.state.player_hp <= 0,
-            AssertionCheck::InventoryContains { item } => self.state.inventory.contains(item),
+            AssertionCheck::InventoryContains { item } => self.state.has_item(item),
             AssertionCheck::InventorySize { op, value } => {
                 op.compare(self.state.inventory.len() as i32, *value as i32)
             }
             AssertionCheck::EnemyAt { x, y, alive } => self
                 .state
                 .enemy_at(*x, *y)
-                .map(|i| {
-                    if *alive {
-                        self.state.enemies[i].hp > 0
-                    } else {
-                        self.state.enemies[i].hp <= 0
-                    }
-                })
+                .and_then(|id| self.state.enemy(id))
+                .map(|e| if *alive { e.hp > 0 } else { e.hp <= 0 })
                 .unwrap_or(false),
             AssertionCheck::NoEnemyAt { x, y } => self.state.enemy_at(*x, *y).is_none(),
             AssertionCheck::Turn { op, value } => op.compare(self.state.turn as i32, *value as i32),
+            AssertionCheck::ActionOrder { order, turn } => {
+                let taken: Vec<&String> = self
+                    .state
+                    .action_log
+                    .iter()
+                    .filter(|a| turn.is_none_or(|t| a.turn == t) && order.contains(&a.actor))
+                    .map(|a| &a.actor)
+                    .collect();
+                taken.into_iter().eq(order)
+            }
             // New assertions
             AssertionCheck::EnemyHp { id, op, value } => self
                 .state
@@ -1296,9 +1451,9 @@ impl DesExecutor {
             AssertionCheck::EnemyDead { id } => {
                 self.state.enemies.iter().any(|e| e.id() == id && e.hp <= 0)
             }
-            AssertionCheck::PlayerHasAdaptation { adaptation } => parse_adaptation(adaptation)
-                .map(|a| self.state.adaptations.contains(&a))
-                .unwrap_or(false),
+            AssertionCheck::PlayerHasAdaptation { adaptation } => {
+                parse_adaptation(adaptation).is_ok_and(|a| self.state.adaptations.contains(&a))
+            }
             AssertionCheck::AdaptationCount { op, value } => {
                 op.compare(self.state.adaptations.len() as i32, *value as i32)
             }
@@ -1335,11 +1490,19 @@ impl DesExecutor {
                 .parse::<crate::game::equipment::EquipSlot>()
                 .ok()
                 .and_then(|s| self.state.equipment.get(s))
-                .map(|e| Some(e) == item.as_ref())
+                .map(|e| Some(&e.id) == item.as_ref())
                 .unwrap_or(item.is_none()),
             AssertionCheck::PlayerArmor { op, value } => {
                 op.compare(self.state.player_armor, *value)
             }
+            AssertionCheck::ItemDurability { item, op, value } => self
+                .carried_copies(item)
+                .next()
+                .and_then(|i| i.durability)
+                .is_some_and(|d| op.compare(d, *value)),
+            AssertionCheck::ItemHasAffix { item, affix } => {
+                self.carried_copies(item).any(|i| i.affixes.contains(affix))
+            }
             AssertionCheck::EnemyProvoked { id, provoked } => self
                 .state
                 .enemies
@@ -1458,7 +1621,7 @@ impl DesExecutor {
                     .state
                     .items
                     .iter()
-                    .filter(|item| item.id == *item_id)
+                    .filter(|item| item.id() == item_id)
                     .count();
                 if let Some(min) = min_count {
                     count >= *min
@@ -1492,16 +1655,13 @@ impl DesExecutor {
                 // Simplified check - would need proper ritual definition loading
                 match ritual_id.as_str() {
                     "storm_walk" => {
-                        self.state.inventory.contains(&"storm_glass".to_string())
+                        self.state.has_item("storm_glass")
                             && self.state.adaptations.len() >= 1
                             && self.current_location_type.as_deref() == Some("shrine")
                     }
                     "crucible_transformation" => {
-                        self.state.inventory.contains(&"saint_key".to_string())
-                            && self
-                                .state
-                                .inventory
-                                .contains(&"scripture_shard".to_string())
+                        self.state.has_item("saint_key")
+                            && self.state.has_item("scripture_shard")
                             && self.state.get_reputation("monks") >= 25
                             && self.state.adaptations.len() >= 3
                             && self.current_location_type.as_deref() == Some("archive")
@@ -1561,10 +1721,9 @@ impl DesExecutor {
                 op.compare(count, *value)
             }
             // Crafting assertions
-            AssertionCheck::HasItem { item } => self.state.inventory.contains(item),
+            AssertionCheck::HasItem { item } => self.state.has_item(item),
             AssertionCheck::ItemCount { item, op, value } => {
-                let count = self.state.inventory.iter().filter(|id| *id == item).count() as u32;
-                op.compare(count as i32, *value as i32)
+                op.compare(self.state.item_count(item) as i32, *value as i32)
             }
             AssertionCheck::CraftingSuccess { recipe, op, value } => {
                 if let Some(recipe_def) = crate::game::get_recipe(recipe) {
@@ -1675,12 +1834,60 @@ impl DesExecutor {
     fn execute_action(&mut self, action: &Action, actor: &Actor) {
         match actor {
             Actor::Player => self.execute_player_action(action),
-            Actor::Entity { id } => {
-                self.log(format!("Entity action for '{}' not yet implemented", id));
+            Actor::Entity { id } => self.execute_entity_action(id, action),
+        }
+    }
+
+    /// Act as a spawned enemy. Its handle is looked up for each action, so an
+    /// enemy that has since been removed is reported rather than replaced.
+    fn execute_entity_action(&mut self, name: &str, action: &Action) {
+        let Some(&handle) = self.entity_handles.get(name) else {
+            self.log(format!("Unknown entity '{}'", name));
+            return;
+        };
+        let Some(i) = self
+            .state
+            .enemy_index(handle)
+            .filter(|&i| self.state.enemies[i].hp > 0)
+        else {
+            self.log(format!("Entity '{}' ({}) is gone", name, handle));
+            return;
+        };
+
+        match action {
+            Action::Move { dx, dy } => {
+                let (x, y) = (self.state.enemies[i].x + dx, self.state.enemies[i].y + dy);
+                let blocked = !self.state.map.get(x, y).is_some_and(|t| t.walkable())
+                    || self.state.enemy_at(x, y).is_some()
+                    || (x, y) == (self.state.player_x, self.state.player_y);
+                if blocked {
+                    self.log(format!("Entity '{}' is blocked at ({}, {})", name, x, y));
+                } else {
+                    self.place_enemy(i, x, y);
+                    self.log(format!("Entity '{}' moved ({}, {})", name, dx, dy));
+                }
+            }
+            Action::Teleport { x, y } => {
+                self.place_enemy(i, *x, *y);
+                self.log(format!("Entity '{}' teleported to ({}, {})", name, x, y));
+            }
+            Action::Wait { turns } => {
+                self.log(format!("Entity '{}' waited {} turns", name, turns));
+            }
+            _ => {
+                self.log(format!("Action {:?} is not supported for entities", action));
             }
         }
     }
 
+    fn place_enemy(&mut self, index: usize, x: i32, y: i32) {
+        let enemy = &mut self.state.enemies[index];
+        self.state.enemy_positions.remove(&(enemy.x, enemy.y));
+        enemy.x = x;
+        enemy.y = y;
+        self.state.enemy_positions.insert((x, y), enemy.entity_id);
+    }
+
     fn execute_player_action(&mut self, action: &Action) {
         match action {
             Action::Move { dx, dy } => {
@@ -1745,6 +1952,13 @@ impl DesExecutor {
                 self.state.use_item_on_tile(*item_index, *x, *y);
                 self.log(format!("Player used item {} on ({}, {})", item_index, x, y));
             }
+            Action::ThrowItem { item_index, x, y } => {
+                self.state.throw_item(*item_index, *x, *y);
+                self.log(format!(
+                    "Player threw item {} at ({}, {})",
+                    item_index, x, y
+                ));
+            }
             Action::Equip { item_index, slot } => {
                 if let Ok(equip_slot) = slot.parse::<crate::game::equipment::EquipSlot>() {
                     self.state.equip_item(*item_index, equip_slot);
@@ -1768,6 +1982,10 @@ impl DesExecutor {
                     if moved { "moved" } else { "no path" }
                 ));
             }
+            Action::ToggleSneak => {
+                self.state.toggle_sneak();
+                self.log(format!("Sneaking: {}", self.state.sneaking));
+            }
             Action::Wait { turns } => {
                 for _ in 0..*turns {
                     self.state.wait_turn();
@@ -2077,17 +2295,28 @@ impl DesExecutor {
                     self.log(format!("Chose dialogue option {}", option_index));
                 }
             }
-            Action::GiveAdaptation { adaptation_id } => {
-                if let Some(adaptation) = crate::game::Adaptation::from_id(adaptation_id) {
+            Action::GiveAdaptation { adaptation_id } => match parse_adaptation(adaptation_id) {
+                Ok(adaptation) => {
                     self.state.adaptations.push(adaptation);
                     self.log(format!("Gave adaptation: {}", adaptation_id));
                 }
-            }
+                Err(message) => self.assertion_results.push(AssertionResult {
+                    passed: false,
+                    check: "give_adaptation".to_string(),
+                    message: Some(message),
+                }),
+            },
             Action::ExecuteSell { .. } => {
                 self.log("ExecuteSell action not implemented in DES".to_string());
             }
-            Action::SpawnEnemy { .. } => {
-                self.log("SpawnEnemy action not implemented in DES".to_string());
+            Action::SpawnEnemy { enemy_id, x, y, hp } => {
+                let mut enemy = Enemy::new(*x, *y, enemy_id);
+                enemy.hp = *hp;
+                let handle = self.state.spawn_enemy(enemy);
+                self.entity_handles
+                    .entry(enemy_id.clone())
+                    .or_insert(handle);
+                self.log(format!("Spawned {} at ({}, {})", enemy_id, x, y));
             }
             Action::DialogueAction { .. } => {
                 self.log("DialogueAction not implemented in DES".to_string());
@@ -2272,6 +2501,7 @@ where
         logs: executor.logs,
         assertion_results: executor.assertion_results,
         snapshots: executor.snapshots,
+        turn_hashes: executor.turn_hashes,
         final_state: Some(executor.state),
     }
 }
@@ -2287,6 +2517,14 @@ pub fn run_parallel(scenarios: &[Scenario]) -> Vec<ExecutionResult> {
         .collect()
 }
 
+/// Run multiple scenarios in parallel, recording per-turn state hashes
+pub fn run_parallel_hashed(scenarios: &[Scenario]) -> Vec<ExecutionResult> {
+    scenarios
+        .par_iter()
+        .map(|scenario| DesExecutor::new(scenario).with_hash_log().run(scenario))
+        .collect()
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
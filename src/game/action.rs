@@ -1,3 +1,4 @@
+use crate::game::content;
 use once_cell::sync::Lazy;
 use serde::Deserialize;
 use std::collections::HashMap;
@@ -5,12 +6,15 @@ use std::collections::HashMap;
 #[derive(Deserialize)]
 struct ActionsFile {
     actions: HashMap<String, i32>,
+    /// How many steps away each noisy action can be heard
+    #[serde(default)]
+    noise: HashMap<String, i32>,
     default_player_ap: i32,
     default_enemy_ap: i32,
 }
 
 static ACTIONS: Lazy<ActionsFile> = Lazy::new(|| {
-    let data = include_str!("../../data/actions.json");
+    let data = &content::load("actions.json");
     serde_json::from_str(data).expect("Failed to parse actions.json")
 });
 
@@ -18,6 +22,11 @@ pub fn action_cost(action: &str) -> i32 {
     ACTIONS.actions.get(action).copied().unwrap_or(1)
 }
 
+/// Hearing range of the noise an action makes, silent if not listed
+pub fn action_noise(action: &str) -> i32 {
+    ACTIONS.noise.get(action).copied().unwrap_or(0)
+}
+
 pub fn default_player_ap() -> i32 {
     ACTIONS.default_player_ap
 }
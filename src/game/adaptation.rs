@@ -1,6 +1,10 @@
+use crate::game::content;
+use crate::game::damage::Resistances;
 use once_cell::sync::Lazy;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde::de::{self, EnumAccess, VariantAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::fmt;
 
 #[derive(Clone, Debug, Deserialize, Default)]
 pub struct StatModifiers {
@@ -34,6 +38,13 @@ pub struct AdaptationDef {
     pub stat_modifiers: StatModifiers,
     #[serde(default)]
     pub effects: Vec<AdaptationEffect>,
+    /// Percent resistance by damage type; negative for a vulnerability
+    #[serde(default)]
+    pub resistances: Resistances,
+    /// Effects drawn on the player while they have this adaptation, in the
+    /// `effects.json` syntax, e.g. `"S(@6 &Cyan &White)"`
+    #[serde(default)]
+    pub visual_effects: Vec<String>,
 }
 
 #[derive(Deserialize)]
@@ -41,8 +52,8 @@ struct AdaptationsFile {
     adaptations: Vec<AdaptationDef>,
 }
 
-static ADAPTATION_DEFS: Lazy<HashMap<String, AdaptationDef>> = Lazy::new(|| {
-    let data = include_str!("../../data/adaptations.json");
+static ADAPTATION_DEFS: Lazy<BTreeMap<String, AdaptationDef>> = Lazy::new(|| {
+    let data = &content::load("adaptations.json");
     let file: AdaptationsFile =
         serde_json::from_str(data).expect("Failed to parse adaptations.json");
     file.adaptations
@@ -59,60 +70,30 @@ pub fn all_adaptation_ids() -> Vec<&'static str> {
     ADAPTATION_DEFS.keys().map(|s| s.as_str()).collect()
 }
 
-/// Legacy enum for backward compatibility with save files
-#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
-pub enum Adaptation {
-    Prismhide,
-    Sunveins,
-    MirageStep,
-    Saltblood,
-    QuantumEntanglement,
-    PhaseWalking,
-    StormAffinity,
-    CollectiveInterface,
-    ArchiveResonance,
-    CrystallineConsciousness,
-}
+/// An adaptation the player has, identified by its id in `adaptations.json`
+///
+/// Saves store the id the way the fixed enum this replaced stored its
+/// variants, so saves written before adaptations were data-driven still load.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Adaptation(&'static str);
 
 impl Adaptation {
+    /// The adaptation with this id, if `adaptations.json` defines one
     pub fn from_id(id: &str) -> Option<Self> {
-        match id {
-            "prismhide" => Some(Self::Prismhide),
-            "sunveins" => Some(Self::Sunveins),
-            "mirage_step" => Some(Self::MirageStep),
-            "saltblood" => Some(Self::Saltblood),
-            "quantum_entanglement" => Some(Self::QuantumEntanglement),
-            "phase_walking" => Some(Self::PhaseWalking),
-            "storm_affinity" => Some(Self::StormAffinity),
-            "collective_interface" => Some(Self::CollectiveInterface),
-            "archive_resonance" => Some(Self::ArchiveResonance),
-            "crystalline_consciousness" => Some(Self::CrystallineConsciousness),
-            _ => None,
-        }
+        get_adaptation_def(id).map(|d| Self(d.id.as_str()))
     }
 
-    pub fn id(&self) -> &str {
-        match self {
-            Self::Prismhide => "prismhide",
-            Self::Sunveins => "sunveins",
-            Self::MirageStep => "mirage_step",
-            Self::Saltblood => "saltblood",
-            Self::QuantumEntanglement => "quantum_entanglement",
-            Self::PhaseWalking => "phase_walking",
-            Self::StormAffinity => "storm_affinity",
-            Self::CollectiveInterface => "collective_interface",
-            Self::ArchiveResonance => "archive_resonance",
-            Self::CrystallineConsciousness => "crystalline_consciousness",
-        }
+    pub fn id(&self) -> &'static str {
+        self.0
     }
 
-    pub fn name(&self) -> &str {
+    pub fn name(&self) -> &'static str {
         get_adaptation_def(self.id())
             .map(|d| d.name.as_str())
             .unwrap_or("Unknown")
     }
 
-    pub fn description(&self) -> &str {
+    pub fn description(&self) -> &'static str {
         get_adaptation_def(self.id())
             .map(|d| d.description.as_str())
             .unwrap_or("")
@@ -168,6 +149,54 @@ impl Adaptation {
     }
 }
 
+impl Serialize for Adaptation {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_unit_variant("Adaptation", 0, self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Adaptation {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_enum("Adaptation", &[], AdaptationVisitor)
+    }
+}
+
+struct AdaptationVisitor;
+
+impl<'de> Visitor<'de> for AdaptationVisitor {
+    type Value = Adaptation;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an adaptation id")
+    }
+
+    fn visit_str<E: de::Error>(self, name: &str) -> Result<Adaptation, E> {
+        // Older saves name the enum variant, e.g. `MirageStep` for `mirage_step`
+        let mut id = String::with_capacity(name.len() + 2);
+        for (i, c) in name.chars().enumerate() {
+            if c.is_ascii_uppercase() && i > 0 {
+                id.push('_');
+            }
+            id.push(c.to_ascii_lowercase());
+        }
+        Adaptation::from_id(&id).ok_or_else(|| E::custom(format!("unknown adaptation '{}'", name)))
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Adaptation, A::Error> {
+        let (adaptation, variant) = data.variant_seed(self)?;
+        variant.unit_variant()?;
+        Ok(adaptation)
+    }
+}
+
+impl<'de> de::DeserializeSeed<'de> for AdaptationVisitor {
+    type Value = Adaptation;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Adaptation, D::Error> {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
 /// Calculate total stat modifiers from a list of adaptations
 pub fn total_stat_modifiers(adaptations: &[Adaptation]) -> StatModifiers {
     let mut total = StatModifiers::default();
@@ -179,3 +208,33 @@ pub fn total_stat_modifiers(adaptations: &[Adaptation]) -> StatModifiers {
     }
     total
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saves_name_adaptations_by_id() {
+        let ids = vec![
+            Adaptation::from_id("mirage_step").unwrap(),
+            Adaptation::from_id("storm_affinity").unwrap(),
+        ];
+        let ron = ron::to_string(&ids).unwrap();
+        assert_eq!(ron, "[mirage_step,storm_affinity]");
+        assert_eq!(ron::from_str::<Vec<Adaptation>>(&ron).unwrap(), ids);
+
+        let packed = rmp_serde::to_vec_named(&ids).unwrap();
+        assert_eq!(
+            rmp_serde::from_slice::<Vec<Adaptation>>(&packed).unwrap(),
+            ids
+        );
+    }
+
+    #[test]
+    fn legacy_enum_variants_still_load() {
+        let loaded: Vec<Adaptation> = ron::from_str("[Prismhide,MirageStep]").unwrap();
+        assert_eq!(loaded[0].id(), "prismhide");
+        assert_eq!(loaded[1].id(), "mirage_step");
+        assert!(ron::from_str::<Vec<Adaptation>>("[Glassbones]").is_err());
+    }
+}
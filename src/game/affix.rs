@@ -0,0 +1,159 @@
+//! Item affixes
+//!
+//! Affixes from affixes.json are rolled onto equipment found in loot. A
+//! prefix goes before the item's name ("Keen Salt Knife") and a suffix after
+//! it ("Salt Knife of the Dunes"); an item has at most one of each. Better
+//! loot has better odds of an affix and can roll affixes with a higher
+//! `min_tier`.
+
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+use serde::Deserialize;
+
+use super::content::{Changes, Registry};
+use super::damage::Resistances;
+use super::item::ItemDef;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AffixKind {
+    Prefix,
+    Suffix,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct AffixDef {
+    pub id: String,
+    /// Word added to the item's name, e.g. "Keen" or "of the Dunes"
+    pub name: String,
+    pub kind: AffixKind,
+    /// `equip_slot` values it can roll on; any equipment when empty
+    #[serde(default)]
+    pub slots: Vec<String>,
+    /// Lowest loot tier it rolls at
+    #[serde(default = "default_min_tier")]
+    pub min_tier: u32,
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+    /// Added to melee damage while equipped
+    #[serde(default)]
+    pub damage: i32,
+    /// Added to armor while equipped
+    #[serde(default)]
+    pub armor: i32,
+    #[serde(default)]
+    pub resistances: Resistances,
+    /// Added to the item's maximum durability
+    #[serde(default)]
+    pub durability: i32,
+    /// Percent added to the item's trade value
+    #[serde(default)]
+    pub value: i32,
+}
+
+fn default_min_tier() -> u32 {
+    1
+}
+fn default_weight() -> u32 {
+    1
+}
+
+impl AffixDef {
+    fn fits(&self, def: &ItemDef, tier: u32) -> bool {
+        self.min_tier <= tier
+            && def
+                .equip_slot
+                .as_ref()
+                .is_some_and(|slot| self.slots.is_empty() || self.slots.iter().any(|s| s == slot))
+    }
+}
+
+#[derive(Deserialize)]
+struct AffixesFile {
+    affixes: Vec<AffixDef>,
+}
+
+static AFFIX_DEFS: Registry<Vec<AffixDef>> = Registry::new("affixes.json", parse_affixes);
+
+fn parse_affixes(data: &str) -> Result<Vec<AffixDef>, String> {
+    let file: AffixesFile = serde_json::from_str(data).map_err(|e| e.to_string())?;
+    Ok(file.affixes)
+}
+
+pub fn get_affix_def(id: &str) -> Option<&'static AffixDef> {
+    AFFIX_DEFS.get().iter().find(|a| a.id == id)
+}
+
+/// Replace the affix definitions with those parsed from `data`
+pub fn reload_affix_defs(data: &str) -> Result<Changes, String> {
+    AFFIX_DEFS.replace(data)
+}
+
+/// Percent chance of each of a prefix and a suffix at a loot tier
+pub fn affix_chance(tier: u32) -> u32 {
+    (tier * 10).min(50)
+}
+
+/// Roll affix ids for a copy of `def` found in loot of `tier`. Items that
+/// cannot be equipped never get affixes and draw nothing from `rng`.
+pub fn roll_affixes(def: &ItemDef, tier: u32, rng: &mut ChaCha8Rng) -> Vec<String> {
+    if def.equip_slot.is_none() {
+        return Vec::new();
+    }
+    let mut affixes = Vec::new();
+    for kind in [AffixKind::Prefix, AffixKind::Suffix] {
+        if rng.gen_range(0..100) >= affix_chance(tier) {
+            continue;
+        }
+        let options: Vec<&AffixDef> = AFFIX_DEFS
+            .get()
+            .iter()
+            .filter(|a| a.kind == kind && a.fits(def, tier))
+            .collect();
+        let total: u32 = options.iter().map(|a| a.weight).sum();
+        if total == 0 {
+            continue;
+        }
+        let mut roll = rng.gen_range(0..total);
+        for affix in options {
+            if roll < affix.weight {
+                affixes.push(affix.id.clone());
+                break;
+            }
+            roll -= affix.weight;
+        }
+    }
+    affixes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::item::get_item_def;
+    use rand::SeedableRng;
+
+    #[test]
+    fn affixes_only_roll_on_equipment_that_fits() {
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        let vial = get_item_def("brine_vial").unwrap();
+        assert!((0..50).all(|_| roll_affixes(vial, 5, &mut rng).is_empty()));
+
+        let knife = get_item_def("salt_knife").unwrap();
+        let mut seen = 0;
+        for _ in 0..200 {
+            let affixes = roll_affixes(knife, 5, &mut rng);
+            let defs: Vec<_> = affixes
+                .iter()
+                .map(|id| get_affix_def(id).unwrap())
+                .collect();
+            assert!(defs.len() <= 2);
+            assert!(defs.iter().all(|a| a.fits(knife, 5)));
+            if defs.len() == 2 {
+                assert_eq!(defs[0].kind, AffixKind::Prefix);
+                assert_eq!(defs[1].kind, AffixKind::Suffix);
+            }
+            seen += defs.len();
+        }
+        assert!(seen > 0);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::game::content;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 
@@ -26,7 +27,7 @@ struct AutoExploreConfigFile {
 }
 
 static AUTO_EXPLORE_CONFIG: Lazy<AutoExploreConfig> = Lazy::new(|| {
-    let data = include_str!("../../data/auto_explore_config.json");
+    let data = &content::load("auto_explore_config.json");
     let file: AutoExploreConfigFile =
         serde_json::from_str(data).expect("Failed to parse auto_explore_config.json");
     file.auto_explore
@@ -0,0 +1,302 @@
+//! Behavior trees for enemy AI
+//!
+//! enemies.json defines named trees under `behavior_trees`. An enemy runs the
+//! tree named by its `behavior_id` (`standard_melee` when unset), or its own
+//! inline `ai` tree, which can include named trees with `{"type": "tree"}`.
+//! References are resolved when the file is parsed, so every `EnemyDef` holds
+//! a complete tree and unknown or cyclic references are parse errors.
+//!
+//! Each node either succeeds or fails. Composites combine children, conditions
+//! test the world without changing it, and actions fail when the enemy cannot
+//! perform them (an attack out of reach, a blocked step), letting a selector
+//! fall through to its next choice. `systems::ai::run_tree` runs a tree.
+
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+use crate::game::damage::DamageType;
+
+/// Tree run by enemies without a `behavior_id` or `ai`
+pub const DEFAULT_TREE: &str = "standard_melee";
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BehaviorNode {
+    // Composites
+    /// Run children in order until one succeeds
+    Selector {
+        children: Vec<BehaviorNode>,
+    },
+    /// Run children in order until one fails
+    Sequence {
+        children: Vec<BehaviorNode>,
+    },
+    /// Try options from highest to lowest score until one succeeds
+    Utility {
+        options: Vec<UtilityOption>,
+    },
+    /// Succeed when the child fails and fail when it succeeds
+    Invert {
+        child: Box<BehaviorNode>,
+    },
+    /// Run the child, then fail so that a selector carries on
+    Passthrough {
+        child: Box<BehaviorNode>,
+    },
+    /// The tree of that name from `behavior_trees`
+    Tree {
+        id: String,
+    },
+
+    // Conditions
+    /// Own HP below this percentage of max HP
+    HpBelow {
+        percent: i32,
+    },
+    /// Manhattan distance to the target within the given bounds
+    Distance {
+        #[serde(default)]
+        min: Option<i32>,
+        #[serde(default)]
+        max: Option<i32>,
+    },
+    /// Target closer than the enemy's sight range; the player must also
+    /// have been noticed
+    InSight,
+    /// Target within the enemy's attack range
+    InAttackRange,
+    /// The enemy stands in the player's field of view
+    LineOfSight,
+    /// The enemy has cover against the target and a line of fire to it
+    InCover,
+    /// Another living enemy within `range`, optionally only injured ones
+    AllyNearby {
+        range: i32,
+        #[serde(default)]
+        injured: bool,
+    },
+    /// Demeanor and provocation make the enemy hostile
+    Hostile,
+    /// Demeanor says to run, e.g. a defensive enemy at low HP
+    ShouldFlee,
+    /// The target is the player rather than a decoy
+    TargetIsPlayer,
+    Chance {
+        percent: u32,
+    },
+    PlayerAdaptations {
+        min: usize,
+    },
+    PlayerHasItem {
+        item: String,
+    },
+
+    // Actions
+    /// Do nothing this turn
+    Idle,
+    /// Target a decoy within sight (50% chance each) instead of the player
+    PickTarget,
+    /// Step toward the target, along the shared map to the player or an A*
+    /// path to other targets while the player can see the enemy, unless
+    /// `pathfind` is false
+    Approach {
+        #[serde(default = "default_true")]
+        pathfind: bool,
+    },
+    /// Step down the shared flee map away from the player, or directly away
+    /// from other targets and when cornered
+    Flee,
+    /// Step toward the last noise heard; fails once there or if the way is
+    /// blocked, and forgets the noise
+    Investigate,
+    /// Step toward the nearest tile within `range` that is `in_cover` and
+    /// within attack range of the target
+    SeekCover {
+        range: i32,
+    },
+    /// Attack an adjacent target
+    MeleeAttack,
+    /// Fire at the player; needs `ranged_attack` in the enemy definition and
+    /// a line of fire
+    RangedAttack,
+    /// Hit the player with a beam, light damage unless `damage_type` says
+    /// otherwise
+    Laser {
+        damage: i32,
+        #[serde(default = "default_laser_damage")]
+        damage_type: DamageType,
+    },
+    /// Damage the player and die
+    Explode {
+        damage: i32,
+        #[serde(default)]
+        radius: i32,
+        #[serde(default)]
+        damage_type: DamageType,
+    },
+    /// Jump to a random open tile within `range`
+    Teleport {
+        range: i32,
+    },
+    /// Heal the most injured ally within `range`
+    HealAlly {
+        range: i32,
+        amount: i32,
+    },
+    /// Afflict the player with a status effect
+    Cast {
+        status: String,
+        duration: i32,
+        #[serde(default)]
+        message: Option<String>,
+    },
+    /// Spawn one of `ids`, or of the definition's `spawn_types`, when the
+    /// spawner is ready
+    Summon {
+        #[serde(default)]
+        ids: Vec<String>,
+    },
+    /// Start charging the definition's area attack at the player
+    ChargeAoe,
+    /// Warn while an area attack charges
+    AoeWarning,
+    /// Count down a charging area attack and unleash it when ready
+    ReleaseAoe,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_laser_damage() -> DamageType {
+    DamageType::Light
+}
+
+/// A choice in a `utility` node, scored as `weight` times its `factor`
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct UtilityOption {
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+    #[serde(default)]
+    pub factor: Option<UtilityFactor>,
+    pub node: BehaviorNode,
+}
+
+fn default_weight() -> f32 {
+    1.0
+}
+
+/// A measure of the situation between 0 and 1
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UtilityFactor {
+    /// Share of own HP lost
+    MissingHp,
+    /// Distance to the target relative to sight range
+    Distance,
+    /// One minus `distance`
+    Closeness,
+    /// Injured allies in sight, saturating at three
+    InjuredAllies,
+}
+
+impl BehaviorNode {
+    /// Replace `tree` references with the trees they name
+    pub fn resolve(&self, trees: &BTreeMap<String, BehaviorNode>) -> Result<Self, String> {
+        self.resolve_in(trees, &mut Vec::new())
+    }
+
+    fn resolve_in<'a>(
+        &self,
+        trees: &'a BTreeMap<String, BehaviorNode>,
+        stack: &mut Vec<&'a str>,
+    ) -> Result<Self, String> {
+        let all = |children: &[BehaviorNode], stack: &mut Vec<&'a str>| {
+            children
+                .iter()
+                .map(|c| c.resolve_in(trees, stack))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(match self {
+            BehaviorNode::Tree { id } => {
+                let (name, tree) = trees
+                    .get_key_value(id)
+                    .ok_or_else(|| format!("unknown behavior tree '{}'", id))?;
+                if stack.contains(&name.as_str()) {
+                    return Err(format!(
+                        "behavior tree '{}' includes itself via {}",
+                        id,
+                        stack.join(" -> ")
+                    ));
+                }
+                stack.push(name);
+                let resolved = tree.resolve_in(trees, stack)?;
+                stack.pop();
+                resolved
+            }
+            BehaviorNode::Selector { children } => BehaviorNode::Selector {
+                children: all(children, stack)?,
+            },
+            BehaviorNode::Sequence { children } => BehaviorNode::Sequence {
+                children: all(children, stack)?,
+            },
+            BehaviorNode::Utility { options } => BehaviorNode::Utility {
+                options: options
+                    .iter()
+                    .map(|o| {
+                        Ok(UtilityOption {
+                            node: o.node.resolve_in(trees, stack)?,
+                            ..o.clone()
+                        })
+                    })
+                    .collect::<Result<_, String>>()?,
+            },
+            BehaviorNode::Invert { child } => BehaviorNode::Invert {
+                child: Box::new(child.resolve_in(trees, stack)?),
+            },
+            BehaviorNode::Passthrough { child } => BehaviorNode::Passthrough {
+                child: Box::new(child.resolve_in(trees, stack)?),
+            },
+            leaf => leaf.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trees(json: &str) -> BTreeMap<String, BehaviorNode> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn tree_references_are_inlined() {
+        let trees = trees(
+            r#"{"chase": {"type": "approach"},
+                "brute": {"type": "selector", "children": [
+                    {"type": "sequence", "children": [{"type": "distance", "max": 1}, {"type": "melee_attack"}]},
+                    {"type": "tree", "id": "chase"}]}}"#,
+        );
+        let resolved = BehaviorNode::Tree { id: "brute".into() }
+            .resolve(&trees)
+            .unwrap();
+        let BehaviorNode::Selector { children } = resolved else {
+            panic!("expected a selector");
+        };
+        assert_eq!(children[1], BehaviorNode::Approach { pathfind: true });
+    }
+
+    #[test]
+    fn unknown_and_cyclic_references_are_rejected() {
+        let trees = trees(
+            r#"{"a": {"type": "invert", "child": {"type": "tree", "id": "b"}},
+                "b": {"type": "selector", "children": [{"type": "tree", "id": "a"}]},
+                "c": {"type": "tree", "id": "missing"}}"#,
+        );
+        let cycle = BehaviorNode::Tree { id: "a".into() }.resolve(&trees);
+        assert!(matches!(cycle, Err(e) if e.contains("includes itself")));
+        let missing = BehaviorNode::Tree { id: "c".into() }.resolve(&trees);
+        assert_eq!(missing, Err("unknown behavior tree 'missing'".to_string()));
+    }
+}
@@ -1,8 +1,10 @@
+use crate::game::content;
+use crate::game::entity::EntityId;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
-use super::item::Item;
+use super::item::ItemInstance;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChestDef {
@@ -22,13 +24,16 @@ pub struct Chest {
     pub x: i32,
     pub y: i32,
     pub id: String,
-    pub inventory: Vec<Item>,
+    pub inventory: Vec<ItemInstance>,
     pub opened: bool,
     pub locked: bool,
+    /// Handle given by `GameState` when the entity is added
+    #[serde(default)]
+    pub entity_id: EntityId,
 }
 
-static CHEST_DEFS: Lazy<HashMap<String, ChestDef>> = Lazy::new(|| {
-    let data = include_str!("../../data/chests.json");
+static CHEST_DEFS: Lazy<BTreeMap<String, ChestDef>> = Lazy::new(|| {
+    let data = &content::load("chests.json");
     let defs: Vec<ChestDef> = serde_json::from_str(data).expect("Failed to parse chests.json");
     defs.into_iter().map(|def| (def.id.clone(), def)).collect()
 });
@@ -51,10 +56,11 @@ impl Chest {
             inventory: Vec::new(),
             opened: false,
             locked: def.locked,
+            entity_id: EntityId::NONE,
         }
     }
 
-    pub fn new_with_loot(x: i32, y: i32, id: &str, loot: Vec<Item>) -> Self {
+    pub fn new_with_loot(x: i32, y: i32, id: &str, loot: Vec<ItemInstance>) -> Self {
         let mut chest = Self::new(x, y, id);
         chest.inventory = loot;
         chest
@@ -72,7 +78,7 @@ impl Chest {
         }
     }
 
-    pub fn add_item(&mut self, item: Item) -> bool {
+    pub fn add_item(&mut self, item: ItemInstance) -> bool {
         if self.can_add_item() {
             self.inventory.push(item);
             true
@@ -81,7 +87,7 @@ impl Chest {
         }
     }
 
-    pub fn remove_item(&mut self, index: usize) -> Option<Item> {
+    pub fn remove_item(&mut self, index: usize) -> Option<ItemInstance> {
         if index < self.inventory.len() {
             Some(self.inventory.remove(index))
         } else {
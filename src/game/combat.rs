@@ -1,3 +1,4 @@
+use crate::game::{content, damage::DamageType};
 use once_cell::sync::Lazy;
 use rand::Rng;
 use serde::Deserialize;
@@ -18,6 +19,8 @@ pub struct WeaponDef {
     pub ammo_type: Option<String>,
     #[serde(default)]
     pub description: String,
+    #[serde(default)]
+    pub damage_type: DamageType,
 }
 
 fn default_ap_cost() -> i32 {
@@ -30,7 +33,7 @@ struct WeaponsFile {
 }
 
 static WEAPON_DEFS: Lazy<HashMap<String, WeaponDef>> = Lazy::new(|| {
-    let data = include_str!("../../data/weapons.json");
+    let data = &content::load("weapons.json");
     let file: WeaponsFile = serde_json::from_str(data).expect("Failed to parse weapons.json");
     file.weapons
         .into_iter()
@@ -65,7 +68,8 @@ pub fn calc_damage(base_damage: i32, armor: i32) -> i32 {
     (base_damage - armor).max(1)
 }
 
-/// Roll attack with weapon against target
+/// Roll attack with weapon against target. Armor only reduces physical
+/// damage; resistances are applied when the damage is dealt
 pub fn roll_attack<R: Rng>(
     rng: &mut R,
     weapon: &WeaponDef,
@@ -88,7 +92,10 @@ pub fn roll_attack<R: Rng>(
     let crit = roll <= 5;
     let base_damage = rng.gen_range(weapon.damage_min..=weapon.damage_max);
     let damage = if crit { base_damage * 2 } else { base_damage };
-    let final_damage = calc_damage(damage, target_armor);
+    let final_damage = match weapon.damage_type {
+        DamageType::Physical => calc_damage(damage, target_armor),
+        _ => damage.max(1),
+    };
 
     CombatResult {
         hit: true,
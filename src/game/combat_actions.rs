@@ -1,8 +1,11 @@
 //! Combat action methods for GameState
 
 use super::{
-    action::action_cost, adaptation::total_stat_modifiers, combat::CombatResult,
-    item::get_item_def, map::Tile, state::GameState, systems::ai::AiSystem,
+    action::{action_cost, action_noise},
+    adaptation::total_stat_modifiers,
+    combat::CombatResult,
+    map::Tile,
+    state::GameState,
     systems::combat::CombatSystem,
 };
 
@@ -22,7 +25,7 @@ impl GameState {
         let has_pick = self
             .inventory
             .iter()
-            .any(|id| get_item_def(id).map(|d| d.breaks_walls).unwrap_or(false));
+            .any(|item| item.def().is_some_and(|d| d.breaks_walls()));
         if !has_pick {
             self.log("You need a tool to break walls.");
             return false;
@@ -53,6 +56,7 @@ impl GameState {
                 };
                 self.log(format!("Cracks spread through the wall. (HP: {})", new_hp));
             }
+            self.make_noise(x, y, action_noise("break_wall"));
             self.check_auto_end_turn();
             return true;
         }
@@ -83,13 +87,9 @@ impl GameState {
         self.player_armor + adapt_mods.armor
     }
 
-    /// Get effective player reflex (base + adaptations)
+    /// Get effective player reflex (base + adaptations - encumbrance)
     pub fn effective_reflex(&self) -> i32 {
         let adapt_mods = total_stat_modifiers(&self.adaptations);
-        self.player_reflex + adapt_mods.reflex
-    }
-
-    pub fn update_enemies(&mut self) {
-        AiSystem::update_enemies(self);
+        self.player_reflex + adapt_mods.reflex - self.encumbrance().reflex_penalty()
     }
 }
@@ -1,3 +1,7 @@
 pub const MAP_WIDTH: usize = 250;
 pub const MAP_HEIGHT: usize = 110;
 pub const FOV_RANGE: i32 = 32;
+/// Deepest subterranean layer; its map has no stairs down
+pub const MAX_DEPTH: i32 = 5;
+/// Turns a rest lasts unless an enemy shows up
+pub const REST_TURNS: i32 = 10;
@@ -0,0 +1,912 @@
+//! Content loading from embedded data, an external data directory and mods
+//!
+//! Every data file ships embedded in the binary. The game can be pointed at an
+//! external data directory, whose files replace the embedded ones, and at mod
+//! folders, which are layered on top in the order given. Registries read their
+//! file through `load`, so all of them see the same merged content.
+//!
+//! A mod file is merged over the content below it by these rules:
+//! - Objects merge key by key, recursively.
+//! - Arrays whose entries are all objects with a string `id` merge by id. An
+//!   entry with a known id is merged into the existing one, an entry with a
+//!   new id is appended, and `{"id": "...", "$remove": true}` deletes an entry.
+//! - Any other value, including other arrays, is replaced.
+//!
+//! When two mods set the same value the later one wins and the overlap is
+//! reported as a conflict. Files that fail to parse are reported and skipped,
+//! falling back to the layer below.
+//!
+//! Tables that can change while the game runs are held in a `Registry`, which
+//! `rebuild` and `Registry::replace` refresh from disk.
+
+use once_cell::sync::OnceCell;
+use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{PoisonError, RwLock};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Key on mod entries that deletes the entry with the same id
+pub const REMOVE_KEY: &str = "$remove";
+
+/// Every data file registries load, with its embedded contents
+pub const CONTENT_FILES: &[(&str, &str)] = &[
+    ("abilities.json", include_str!("../../data/abilities.json")),
+    ("actions.json", include_str!("../../data/actions.json")),
+    (
+        "adaptations.json",
+        include_str!("../../data/adaptations.json"),
+    ),
+    ("affixes.json", include_str!("../../data/affixes.json")),
+    (
+        "auto_explore_config.json",
+        include_str!("../../data/auto_explore_config.json"),
+    ),
+    (
+        "biome_profiles.json",
+        include_str!("../../data/biome_profiles.json"),
+    ),
+    (
+        "biome_spawn_tables.json",
+        include_str!("../../data/biome_spawn_tables.json"),
+    ),
+    ("chests.json", include_str!("../../data/chests.json")),
+    ("classes.json", include_str!("../../data/classes.json")),
+    (
+        "constraint_rules.json",
+        include_str!("../../data/constraint_rules.json"),
+    ),
+    ("dialogues.json", include_str!("../../data/dialogues.json")),
+    (
+        "dynamic_events.json",
+        include_str!("../../data/dynamic_events.json"),
+    ),
+    ("effects.json", include_str!("../../data/effects.json")),
+    ("enemies.json", include_str!("../../data/enemies.json")),
+    ("factions.json", include_str!("../../data/factions.json")),
+    ("floors.json", include_str!("../../data/floors.json")),
+    (
+        "interactables.json",
+        include_str!("../../data/interactables.json"),
+    ),
+    ("items.json", include_str!("../../data/items.json")),
+    ("lights.json", include_str!("../../data/lights.json")),
+    (
+        "loot_tables.json",
+        include_str!("../../data/loot_tables.json"),
+    ),
+    (
+        "main_questline.json",
+        include_str!("../../data/main_questline.json"),
+    ),
+    (
+        "map_features.json",
+        include_str!("../../data/map_features.json"),
+    ),
+    (
+        "microstructures.json",
+        include_str!("../../data/microstructures.json"),
+    ),
+    (
+        "narrative_integration.json",
+        include_str!("../../data/narrative_integration.json"),
+    ),
+    ("npcs.json", include_str!("../../data/npcs.json")),
+    (
+        "progression.json",
+        include_str!("../../data/progression.json"),
+    ),
+    (
+        "psychic_abilities.json",
+        include_str!("../../data/psychic_abilities.json"),
+    ),
+    ("quests.json", include_str!("../../data/quests.json")),
+    ("recipes.json", include_str!("../../data/recipes.json")),
+    ("skills.json", include_str!("../../data/skills.json")),
+    (
+        "status_effects.json",
+        include_str!("../../data/status_effects.json"),
+    ),
+    (
+        "storm_config.json",
+        include_str!("../../data/storm_config.json"),
+    ),
+    (
+        "structure_templates.json",
+        include_str!("../../data/structure_templates.json"),
+    ),
+    (
+        "terrain_config.json",
+        include_str!("../../data/terrain_config.json"),
+    ),
+    ("traders.json", include_str!("../../data/traders.json")),
+    ("tutorial.json", include_str!("../../data/tutorial.json")),
+    ("walls.json", include_str!("../../data/walls.json")),
+    ("weapons.json", include_str!("../../data/weapons.json")),
+];
+
+static CONTENT: OnceCell<Content> = OnceCell::new();
+/// Settings `CONTENT` was built with, kept for reloads
+static CONFIG: OnceCell<ContentConfig> = OnceCell::new();
+/// Watcher started by `watch`, polled by `watched_files_changed`
+static WATCHER: RwLock<Option<Watcher>> = RwLock::new(None);
+
+/// Where content is read from besides the embedded files
+#[derive(Clone, Debug, Default)]
+pub struct ContentConfig {
+    /// Directory whose files replace the embedded ones
+    pub data_dir: Option<PathBuf>,
+    /// Mod folders, applied in order
+    pub mods: Vec<PathBuf>,
+}
+
+impl ContentConfig {
+    /// Read `SALTGLASS_DATA_DIR` and `SALTGLASS_MODS` (a path list, separated
+    /// like `PATH`)
+    pub fn from_env() -> Self {
+        Self {
+            data_dir: std::env::var_os("SALTGLASS_DATA_DIR").map(PathBuf::from),
+            mods: std::env::var_os("SALTGLASS_MODS")
+                .map(|paths| std::env::split_paths(&paths).collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Where a file's base content came from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    Embedded,
+    DataDir(PathBuf),
+}
+
+/// A file that differs from its embedded version
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileReport {
+    pub file: &'static str,
+    pub base: Source,
+    /// Mods that changed the file, in the order applied
+    pub mods: Vec<String>,
+}
+
+/// Two mods setting the same value
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conflict {
+    pub file: &'static str,
+    /// JSON path of the value, e.g. `$.items[id=brine_vial].value`
+    pub path: String,
+    pub earlier: String,
+    /// The mod whose value is used
+    pub later: String,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}: set by '{}' and '{}'; using '{}'",
+            self.file, self.path, self.earlier, self.later, self.later
+        )
+    }
+}
+
+/// What `configure` loaded
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ContentReport {
+    pub files: Vec<FileReport>,
+    pub conflicts: Vec<Conflict>,
+    pub errors: Vec<String>,
+}
+
+impl ContentReport {
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty() && self.errors.is_empty()
+    }
+}
+
+impl fmt::Display for ContentReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for file in &self.files {
+            let base = match &file.base {
+                Source::Embedded => "embedded".to_string(),
+                Source::DataDir(path) => path.display().to_string(),
+            };
+            write!(f, "{} from {}", file.file, base)?;
+            if !file.mods.is_empty() {
+                write!(f, " + {}", file.mods.join(" + "))?;
+            }
+            writeln!(f)?;
+        }
+        for conflict in &self.conflicts {
+            writeln!(f, "conflict: {}", conflict)?;
+        }
+        for error in &self.errors {
+            writeln!(f, "error: {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+/// Merged content for every data file
+#[derive(Debug, Default)]
+pub struct Content {
+    texts: HashMap<&'static str, String>,
+    report: ContentReport,
+}
+
+impl Content {
+    /// Load the data directory and mods described by `config`
+    pub fn build(config: &ContentConfig) -> Self {
+        let mut content = Content::default();
+        let mods: Vec<(String, &Path)> = config
+            .mods
+            .iter()
+            .map(|dir| (mod_name(dir), dir.as_path()))
+            .collect();
+        for (_, dir) in &mods {
+            content.check_mod_files(dir);
+        }
+
+        for &(file, embedded) in CONTENT_FILES {
+            let (base_text, base) = content.read_base(config.data_dir.as_deref(), file, embedded);
+            let mut merged: Option<Value> = None;
+            let mut applied = Vec::new();
+            let mut touched = Vec::new();
+
+            for (name, dir) in &mods {
+                let path = dir.join(file);
+                if !path.is_file() {
+                    continue;
+                }
+                let overlay = match read_json(&path) {
+                    Ok(v) => v,
+                    Err(e) => {
+                        content.report.errors.push(e);
+                        continue;
+                    }
+                };
+                let value = match &mut merged {
+                    Some(v) => v,
+                    None => match serde_json::from_str(&base_text) {
+                        Ok(v) => merged.insert(v),
+                        Err(e) => {
+                            content.report.errors.push(format!("{}: {}", file, e));
+                            break;
+                        }
+                    },
+                };
+                let mut merge = Merge {
+                    file,
+                    mod_name: name,
+                    touched: &mut touched,
+                    report: &mut content.report,
+                };
+                merge.merge(value, overlay, "$");
+                applied.push(name.clone());
+            }
+
+            let text = match merged {
+                Some(value) => Some(value.to_string()),
+                None if base != Source::Embedded => Some(base_text.into_owned()),
+                None => None,
+            };
+            if let Some(text) = text {
+                content.texts.insert(file, text);
+                content.report.files.push(FileReport {
+                    file,
+                    base,
+                    mods: applied,
+                });
+            }
+        }
+        content
+    }
+
+    /// Contents of a data file after the data directory and mods are applied
+    pub fn text(&self, file: &str) -> Cow<'static, str> {
+        match self.texts.get(file) {
+            Some(text) => Cow::Owned(text.clone()),
+            None => Cow::Borrowed(
+                embedded(file)
+                    .unwrap_or_else(|| panic!("{} is not listed in content::CONTENT_FILES", file)),
+            ),
+        }
+    }
+
+    pub fn report(&self) -> &ContentReport {
+        &self.report
+    }
+
+    /// The data directory's copy of `file` if it exists and parses, else the
+    /// embedded one
+    fn read_base(
+        &mut self,
+        data_dir: Option<&Path>,
+        file: &'static str,
+        embedded: &'static str,
+    ) -> (Cow<'static, str>, Source) {
+        let Some(path) = data_dir.map(|dir| dir.join(file)).filter(|p| p.is_file()) else {
+            return (Cow::Borrowed(embedded), Source::Embedded);
+        };
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) => {
+                self.report
+                    .errors
+                    .push(format!("{}: {}; using embedded copy", path.display(), e));
+                return (Cow::Borrowed(embedded), Source::Embedded);
+            }
+        };
+        if let Err(e) = serde_json::from_str::<Value>(&text) {
+            self.report
+                .errors
+                .push(format!("{}: {}; using embedded copy", path.display(), e));
+            return (Cow::Borrowed(embedded), Source::Embedded);
+        }
+        (Cow::Owned(text), Source::DataDir(path))
+    }
+
+    /// Report files in a mod folder that no registry reads
+    fn check_mod_files(&mut self, dir: &Path) {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                self.report.errors.push(format!("{}: {}", dir.display(), e));
+                return;
+            }
+        };
+        let mut unknown: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter(|path| {
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                embedded(&name).is_none()
+            })
+            .map(|path| format!("{}: not a content file", path.display()))
+            .collect();
+        unknown.sort();
+        self.report.errors.extend(unknown);
+    }
+}
+
+/// Set where content is loaded from. Must run before any registry is first
+/// used; otherwise the content has already been loaded with the settings
+/// from the environment and an error is returned.
+pub fn configure(config: &ContentConfig) -> Result<&'static ContentReport, String> {
+    if CONFIG.set(config.clone()).is_err() {
+        return Err("Content was already loaded before it was configured".to_string());
+    }
+    Ok(CONTENT.get_or_init(|| Content::build(config)).report())
+}
+
+fn config() -> &'static ContentConfig {
+    CONFIG.get_or_init(ContentConfig::from_env)
+}
+
+/// Contents of a data file, such as `"items.json"`
+pub fn load(file: &str) -> Cow<'static, str> {
+    CONTENT.get_or_init(|| Content::build(config())).text(file)
+}
+
+/// What was loaded from outside the binary
+pub fn report() -> &'static ContentReport {
+    CONTENT.get_or_init(|| Content::build(config())).report()
+}
+
+/// Where reloads read from: the configured data directory and mods. Without
+/// a configured data directory, `data/` in the working directory is used if
+/// it exists, so edits in a source checkout are picked up.
+pub fn reload_config() -> ContentConfig {
+    let mut config = config().clone();
+    if config.data_dir.is_none() && Path::new("data").is_dir() {
+        config.data_dir = Some(PathBuf::from("data"));
+    }
+    config
+}
+
+/// Read all content again from disk, for reloading registries
+pub fn rebuild() -> Content {
+    Content::build(&reload_config())
+}
+
+/// The embedded copy of a data file
+pub fn embedded(file: &str) -> Option<&'static str> {
+    CONTENT_FILES
+        .iter()
+        .find(|(name, _)| *name == file)
+        .map(|(_, text)| *text)
+}
+
+fn mod_name(dir: &Path) -> String {
+    dir.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| dir.display().to_string())
+}
+
+fn read_json(path: &Path) -> Result<Value, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    serde_json::from_str(&text).map_err(|e| format!("{}: {}; skipped", path.display(), e))
+}
+
+fn entry_id(value: &Value) -> Option<&str> {
+    value.get("id").and_then(Value::as_str)
+}
+
+fn is_id_array(entries: &[Value]) -> bool {
+    !entries.is_empty() && entries.iter().all(|e| entry_id(e).is_some())
+}
+
+/// A table parsed from one content file that can be replaced while the game
+/// runs. Replaced tables are leaked so that references handed out earlier
+/// stay valid; reloads are a development tool, so the leak is bounded by how
+/// often someone edits a file.
+pub struct Registry<T: 'static> {
+    file: &'static str,
+    parse: fn(&str) -> Result<T, String>,
+    current: OnceCell<RwLock<Loaded<T>>>,
+}
+
+struct Loaded<T: 'static> {
+    table: &'static T,
+    /// Text the table was parsed from, to report what a reload changed
+    text: String,
+}
+
+impl<T> Registry<T> {
+    pub const fn new(file: &'static str, parse: fn(&str) -> Result<T, String>) -> Self {
+        Self {
+            file,
+            parse,
+            current: OnceCell::new(),
+        }
+    }
+
+    /// The current table, parsed from `load(file)` on first use
+    pub fn get(&self) -> &'static T {
+        self.loaded()
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .table
+    }
+
+    /// Parse `text` and swap it in, reporting which definitions changed.
+    /// The current table is kept if `text` does not parse.
+    pub fn replace(&self, text: &str) -> Result<Changes, String> {
+        let table = (self.parse)(text).map_err(|e| format!("{}: {}", self.file, e))?;
+        let mut loaded = self
+            .loaded()
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        let changes = Changes::between(self.file, &loaded.text, text);
+        *loaded = Loaded {
+            table: Box::leak(Box::new(table)),
+            text: text.to_string(),
+        };
+        Ok(changes)
+    }
+
+    fn loaded(&self) -> &RwLock<Loaded<T>> {
+        self.current.get_or_init(|| {
+            let text = load(self.file).into_owned();
+            let table = (self.parse)(&text)
+                .unwrap_or_else(|e| panic!("Failed to parse {}: {}", self.file, e));
+            RwLock::new(Loaded {
+                table: Box::leak(Box::new(table)),
+                text,
+            })
+        })
+    }
+}
+
+/// Definitions a reload added, removed or changed in one file, by id
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Changes {
+    pub file: &'static str,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+}
+
+impl Changes {
+    /// Compare the definitions in two versions of a file
+    pub fn between(file: &'static str, old: &str, new: &str) -> Self {
+        let old: Value = serde_json::from_str(old).unwrap_or(Value::Null);
+        let new: Value = serde_json::from_str(new).unwrap_or(Value::Null);
+        let (old, new) = (definitions(&old), definitions(&new));
+        let mut changes = Changes {
+            file,
+            ..Default::default()
+        };
+        for (id, def) in &new {
+            match old.get(id) {
+                None => changes.added.push(id.to_string()),
+                Some(previous) if previous != def => changes.changed.push(id.to_string()),
+                Some(_) => {}
+            }
+        }
+        changes.removed = old
+            .keys()
+            .filter(|id| !new.contains_key(*id))
+            .map(|id| id.to_string())
+            .collect();
+        changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl fmt::Display for Changes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.file)?;
+        if self.is_empty() {
+            return write!(f, " unchanged");
+        }
+        let mut first = true;
+        for (label, ids) in [
+            ("changed", &self.changed),
+            ("added", &self.added),
+            ("removed", &self.removed),
+        ] {
+            if ids.is_empty() {
+                continue;
+            }
+            write!(
+                f,
+                "{} {} {} ({})",
+                if first { "" } else { ";" },
+                ids.len(),
+                label,
+                ids.join(", ")
+            )?;
+            first = false;
+        }
+        Ok(())
+    }
+}
+
+/// Entries of a file's definition array by id: the root array, or the first
+/// id array in the root object
+fn definitions(value: &Value) -> BTreeMap<&str, &Value> {
+    let entries = match value {
+        Value::Array(entries) => Some(entries),
+        Value::Object(map) => map.values().find_map(|v| match v {
+            Value::Array(entries) if is_id_array(entries) => Some(entries),
+            _ => None,
+        }),
+        _ => None,
+    };
+    entries
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry_id(entry).map(|id| (id, entry)))
+        .collect()
+}
+
+/// How often the watcher looks at file modification times
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Polls the modification times of content files in the reload data
+/// directory and mod folders
+struct Watcher {
+    files: Vec<PathBuf>,
+    modified: Vec<Option<SystemTime>>,
+    last_poll: Instant,
+}
+
+impl Watcher {
+    fn new(files: Vec<PathBuf>) -> Self {
+        let modified = files.iter().map(|path| modified(path)).collect();
+        Self {
+            files,
+            modified,
+            last_poll: Instant::now(),
+        }
+    }
+
+    fn poll(&mut self) -> bool {
+        if self.last_poll.elapsed() < WATCH_INTERVAL {
+            return false;
+        }
+        self.last_poll = Instant::now();
+        let mut changed = false;
+        for (path, seen) in self.files.iter().zip(self.modified.iter_mut()) {
+            let current = modified(path);
+            if current != *seen {
+                *seen = current;
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Start or stop watching `files` in the reload data directory and mods.
+/// Returns the directories watched, which is empty if there is nothing on
+/// disk to watch.
+pub fn watch(files: &[&str], enabled: bool) -> Vec<PathBuf> {
+    let mut watcher = WATCHER.write().unwrap_or_else(PoisonError::into_inner);
+    *watcher = None;
+    if !enabled {
+        return Vec::new();
+    }
+    let config = reload_config();
+    let dirs: Vec<PathBuf> = config.data_dir.into_iter().chain(config.mods).collect();
+    if !dirs.is_empty() {
+        let paths = dirs
+            .iter()
+            .flat_map(|dir| files.iter().map(move |file| dir.join(file)))
+            .collect();
+        *watcher = Some(Watcher::new(paths));
+    }
+    dirs
+}
+
+pub fn is_watching() -> bool {
+    WATCHER
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .is_some()
+}
+
+/// Whether a watched file changed since the last call. Checks at most once
+/// a second, so it can be called every frame.
+pub fn watched_files_changed() -> bool {
+    WATCHER
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_mut()
+        .is_some_and(Watcher::poll)
+}
+
+/// One mod file being merged into a file's content
+struct Merge<'a> {
+    file: &'static str,
+    mod_name: &'a str,
+    /// Paths set by earlier mods on this file, and which mod set them
+    touched: &'a mut Vec<(String, String)>,
+    report: &'a mut ContentReport,
+}
+
+impl Merge<'_> {
+    fn merge(&mut self, base: &mut Value, overlay: Value, path: &str) {
+        match (base, overlay) {
+            (Value::Object(base), Value::Object(overlay)) => {
+                for (key, value) in overlay {
+                    let child = format!("{}.{}", path, key);
+                    match base.get_mut(&key) {
+                        Some(existing) => self.merge(existing, value, &child),
+                        None => {
+                            self.touch(&child);
+                            base.insert(key, value);
+                        }
+                    }
+                }
+            }
+            (Value::Array(base), Value::Array(overlay))
+                if is_id_array(&overlay) && (base.is_empty() || is_id_array(base)) =>
+            {
+                for mut entry in overlay {
+                    let id = entry_id(&entry).unwrap_or_default().to_string();
+                    let child = format!("{}[id={}]", path, id);
+                    let remove = entry
+                        .as_object_mut()
+                        .and_then(|e| e.remove(REMOVE_KEY))
+                        .is_some_and(|v| v == Value::Bool(true));
+                    let existing = base.iter().position(|e| entry_id(e) == Some(&id));
+                    match (existing, remove) {
+                        (Some(index), true) => {
+                            self.touch(&child);
+                            base.remove(index);
+                        }
+                        (Some(index), false) => self.merge(&mut base[index], entry, &child),
+                        (None, true) => self.report.errors.push(format!(
+                            "{} {} ({}): no entry to remove",
+                            self.file, child, self.mod_name
+                        )),
+                        (None, false) => {
+                            self.touch(&child);
+                            base.push(entry);
+                        }
+                    }
+                }
+            }
+            // Restating a value (such as an entry's id) changes nothing
+            (base, overlay) if *base == overlay => {}
+            (base, overlay) => {
+                self.touch(path);
+                *base = overlay;
+            }
+        }
+    }
+
+    /// Note that this mod set `path`, reporting overlap with earlier mods
+    fn touch(&mut self, path: &str) {
+        let overlaps = |a: &str, b: &str| {
+            a == b
+                || a.strip_prefix(b)
+                    .is_some_and(|rest| rest.starts_with('.') || rest.starts_with('['))
+        };
+        for (earlier_path, earlier) in self.touched.iter() {
+            if earlier != self.mod_name
+                && (overlaps(path, earlier_path) || overlaps(earlier_path, path))
+            {
+                self.report.conflicts.push(Conflict {
+                    file: self.file,
+                    path: path.to_string(),
+                    earlier: earlier.clone(),
+                    later: self.mod_name.to_string(),
+                });
+                break;
+            }
+        }
+        self.touched
+            .push((path.to_string(), self.mod_name.to_string()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::item::ItemDef;
+
+    fn write(dir: &Path, file: &str, json: &str) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join(file), json).unwrap();
+    }
+
+    fn items(content: &Content) -> Vec<ItemDef> {
+        #[derive(serde::Deserialize)]
+        struct ItemsFile {
+            items: Vec<ItemDef>,
+        }
+        let file: ItemsFile = serde_json::from_str(&content.text("items.json")).unwrap();
+        file.items
+    }
+
+    #[test]
+    fn embedded_files_are_used_without_config() {
+        let content = Content::build(&ContentConfig::default());
+        assert_eq!(content.text("items.json"), embedded("items.json").unwrap());
+        assert_eq!(content.report(), &ContentReport::default());
+    }
+
+    #[test]
+    fn data_dir_replaces_embedded_files_and_falls_back_on_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "walls.json", r#"{"walls": []}"#);
+        write(dir.path(), "floors.json", "{ not json");
+        let content = Content::build(&ContentConfig {
+            data_dir: Some(dir.path().to_path_buf()),
+            mods: Vec::new(),
+        });
+
+        assert_eq!(content.text("walls.json"), r#"{"walls": []}"#);
+        assert_eq!(
+            content.text("floors.json"),
+            embedded("floors.json").unwrap()
+        );
+        assert_eq!(content.report().files.len(), 1);
+        assert_eq!(content.report().errors.len(), 1);
+    }
+
+    #[test]
+    fn mods_override_add_and_remove_entries_by_id() {
+        let base = items(&Content::build(&ContentConfig::default()));
+        let first = &base[0];
+        let second = &base[1];
+
+        let dir = tempfile::tempdir().unwrap();
+        let overlay = format!(
+            r#"{{"items": [
+                {{"id": "{}", "value": 12345}},
+                {{"id": "{}", "$remove": true}},
+                {{"id": "modded_relic", "name": "Modded Relic", "glyph": "*", "description": "From a mod"}}
+            ]}}"#,
+            first.id, second.id
+        );
+        write(&dir.path().join("relics"), "items.json", &overlay);
+        let content = Content::build(&ContentConfig {
+            data_dir: None,
+            mods: vec![dir.path().join("relics")],
+        });
+        assert!(content.report().is_clean(), "{}", content.report());
+
+        let merged = items(&content);
+        let overridden = merged.iter().find(|d| d.id == first.id).unwrap();
+        assert_eq!(overridden.value, 12345);
+        assert_eq!(overridden.name, first.name, "unset fields are kept");
+        assert!(merged.iter().all(|d| d.id != second.id));
+        assert_eq!(merged.last().unwrap().id, "modded_relic");
+        assert_eq!(merged.len(), base.len());
+        assert_eq!(content.report().files[0].mods, vec!["relics".to_string()]);
+    }
+
+    #[test]
+    fn later_mods_win_and_overlaps_are_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a");
+        let b = dir.path().join("b");
+        write(&a, "progression.json", r#"{"level_thresholds": [0, 10]}"#);
+        write(
+            &b,
+            "progression.json",
+            r#"{"level_thresholds": [0, 20], "stat_points_per_level": 4}"#,
+        );
+        write(&b, "mystery.json", "{}");
+        let content = Content::build(&ContentConfig {
+            data_dir: None,
+            mods: vec![a, b],
+        });
+
+        let merged: Value = serde_json::from_str(&content.text("progression.json")).unwrap();
+        assert_eq!(merged["level_thresholds"], serde_json::json!([0, 20]));
+        assert_eq!(merged["stat_points_per_level"], 4);
+        assert_eq!(merged["stat_growth"]["max_hp"], 5);
+        let report = content.report();
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].path, "$.level_thresholds");
+        assert_eq!(report.conflicts[0].later, "b");
+        assert!(report.errors[0].contains("mystery.json"));
+    }
+
+    fn parse_ids(data: &str) -> Result<Vec<String>, String> {
+        let value: Value = serde_json::from_str(data).map_err(|e| e.to_string())?;
+        Ok(definitions(&value)
+            .keys()
+            .map(|id| id.to_string())
+            .collect())
+    }
+
+    #[test]
+    fn registry_swaps_tables_and_reports_changes() {
+        static STATUSES: Registry<Vec<String>> = Registry::new("status_effects.json", parse_ids);
+        let old = STATUSES.get();
+        assert!(old.iter().any(|id| id == "poison"));
+
+        let mut value: Value = serde_json::from_str(&load("status_effects.json")).unwrap();
+        let effects = value["status_effects"].as_array_mut().unwrap();
+        effects.retain(|e| e["id"] != "burn");
+        let poison = effects.iter_mut().find(|e| e["id"] == "poison").unwrap();
+        poison["tick_damage"] = serde_json::json!(99);
+        effects.push(serde_json::json!({"id": "salt_rot", "name": "Salt Rot", "description": ""}));
+
+        let changes = STATUSES.replace(&value.to_string()).unwrap();
+        assert_eq!(changes.changed, vec!["poison"]);
+        assert_eq!(changes.added, vec!["salt_rot"]);
+        assert_eq!(changes.removed, vec!["burn"]);
+        assert_eq!(
+            changes.to_string(),
+            "status_effects.json: 1 changed (poison); 1 added (salt_rot); 1 removed (burn)"
+        );
+        assert!(STATUSES.get().iter().any(|id| id == "salt_rot"));
+        // Tables handed out before the swap stay readable
+        assert!(old.iter().any(|id| id == "burn"));
+
+        assert!(STATUSES.replace("{ not json").is_err());
+        assert!(STATUSES.get().iter().any(|id| id == "salt_rot"));
+    }
+
+    #[test]
+    fn watcher_notices_modified_files() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "items.json", r#"{"items": []}"#);
+        let mut watcher = Watcher::new(vec![dir.path().join("items.json")]);
+        watcher.modified = vec![None];
+        watcher.last_poll -= WATCH_INTERVAL;
+        assert!(watcher.poll());
+        // Polls inside the interval do not touch the disk
+        assert!(!watcher.poll());
+        watcher.last_poll -= WATCH_INTERVAL;
+        assert!(!watcher.poll());
+    }
+}
@@ -0,0 +1,937 @@
+//! Cross-reference validation for the content files
+//!
+//! Registries look ids up lazily, so a loot table naming a missing item or a
+//! dialogue option leading to an unknown node only shows up in play. `check`
+//! walks the merged JSON of every content file and reports each id reference
+//! that does not resolve, with the file and JSON path it was found at.
+
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+use super::content::{CONTENT_FILES, Content};
+
+/// A problem found in a content file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContentError {
+    pub file: &'static str,
+    /// JSON path of the offending value, e.g. `$.quests[id=pest_control].reward.items[0]`
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ContentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}: {}", self.file, self.path, self.message)
+    }
+}
+
+/// Kinds of definitions that content refers to by id
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Kind {
+    Item,
+    Enemy,
+    Npc,
+    Quest,
+    LootTable,
+    Trader,
+    Adaptation,
+    Faction,
+    Status,
+}
+
+impl Kind {
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Item => "item",
+            Kind::Enemy => "enemy",
+            Kind::Npc => "NPC",
+            Kind::Quest => "quest",
+            Kind::LootTable => "loot table",
+            Kind::Trader => "trader",
+            Kind::Adaptation => "adaptation",
+            Kind::Faction => "faction",
+            Kind::Status => "status effect",
+        }
+    }
+}
+
+/// Check every content file for syntax errors, duplicate ids and references
+/// to ids that are not defined
+pub fn check(content: &Content) -> Vec<ContentError> {
+    let mut checker = Checker::default();
+    for &(file, _) in CONTENT_FILES {
+        match serde_json::from_str(&content.text(file)) {
+            Ok(value) => {
+                checker.files.insert(file, value);
+            }
+            Err(e) => checker.error(file, "$", format!("invalid JSON: {}", e)),
+        }
+    }
+
+    checker.collect_ids();
+    checker.check_items();
+    checker.check_enemies();
+    checker.check_factions();
+    checker.check_npcs();
+    checker.check_quests();
+    checker.check_dialogues();
+    checker.check_recipes();
+    checker.check_loot_tables();
+    checker.check_spawn_tables();
+    checker.check_traders();
+    checker.check_chests();
+    checker.check_classes();
+    checker.errors
+}
+
+#[derive(Default)]
+struct Checker {
+    files: HashMap<&'static str, Value>,
+    ids: HashMap<Kind, BTreeSet<String>>,
+    errors: Vec<ContentError>,
+}
+
+/// Array elements with their paths; elements with a string `id` are addressed
+/// by it, the rest by index
+fn elements<'a>(value: Option<&'a Value>, path: &str) -> Vec<(String, &'a Value)> {
+    let Some(array) = value.and_then(Value::as_array) else {
+        return Vec::new();
+    };
+    array
+        .iter()
+        .enumerate()
+        .map(|(index, element)| {
+            let path = match element.get("id").and_then(Value::as_str) {
+                Some(id) => format!("{}[id={}]", path, id),
+                None => format!("{}[{}]", path, index),
+            };
+            (path, element)
+        })
+        .collect()
+}
+
+impl Checker {
+    fn error(&mut self, file: &'static str, path: &str, message: String) {
+        self.errors.push(ContentError {
+            file,
+            path: path.to_string(),
+            message,
+        });
+    }
+
+    fn file(&self, file: &str) -> Option<&Value> {
+        self.files.get(file)
+    }
+
+    /// Record the ids of a file's definitions, reporting entries without an
+    /// id and ids defined twice
+    fn collect(&mut self, kind: Kind, file: &'static str, key: Option<&str>, id_key: &str) {
+        let Some(root) = self.files.get(file) else {
+            return;
+        };
+        let (array, base) = match key {
+            Some(key) => (root.get(key), format!("$.{}", key)),
+            None => (Some(root), "$".to_string()),
+        };
+        let Some(array) = array.and_then(Value::as_array) else {
+            self.error(file, &base, "expected an array of definitions".to_string());
+            return;
+        };
+
+        let mut found = Vec::new();
+        let mut problems = Vec::new();
+        for (index, entry) in array.iter().enumerate() {
+            match entry.get(id_key).and_then(Value::as_str) {
+                Some(id) => found.push((index, id.to_string())),
+                None => problems.push((
+                    format!("{}[{}]", base, index),
+                    format!("missing string `{}`", id_key),
+                )),
+            }
+        }
+
+        let ids = self.ids.entry(kind).or_default();
+        for (index, id) in found {
+            if !ids.insert(id.clone()) {
+                problems.push((
+                    format!("{}[{}]", base, index),
+                    format!("duplicate {} id '{}'", kind.name(), id),
+                ));
+            }
+        }
+        for (path, message) in problems {
+            self.error(file, &path, message);
+        }
+    }
+
+    fn collect_ids(&mut self) {
+        self.collect(Kind::Item, "items.json", Some("items"), "id");
+        self.collect(Kind::Enemy, "enemies.json", Some("enemies"), "id");
+        self.collect(Kind::Npc, "npcs.json", Some("npcs"), "id");
+        self.collect(Kind::Quest, "quests.json", Some("quests"), "id");
+        self.collect(
+            Kind::Quest,
+            "main_questline.json",
+            Some("main_questline"),
+            "id",
+        );
+        self.collect(Kind::LootTable, "loot_tables.json", None, "id");
+        self.collect(Kind::Trader, "traders.json", Some("traders"), "trader_id");
+        self.collect(
+            Kind::Adaptation,
+            "adaptations.json",
+            Some("adaptations"),
+            "id",
+        );
+        self.collect(Kind::Faction, "factions.json", None, "id");
+        self.collect(
+            Kind::Status,
+            "status_effects.json",
+            Some("status_effects"),
+            "id",
+        );
+        // Factions also answer to their aliases
+        let aliases: Vec<String> = self
+            .entries("factions.json", None)
+            .iter()
+            .filter_map(|(_, faction)| faction.get("aliases")?.as_array().cloned())
+            .flatten()
+            .filter_map(|alias| alias.as_str().map(str::to_string))
+            .collect();
+        self.ids.entry(Kind::Faction).or_default().extend(aliases);
+    }
+
+    /// Check that `value`, if present, names a defined id of `kind`
+    fn reference(&mut self, kind: Kind, file: &'static str, path: &str, value: Option<&Value>) {
+        let Some(value) = value.filter(|v| !v.is_null()) else {
+            return;
+        };
+        match value.as_str() {
+            Some(id) if self.ids.get(&kind).is_some_and(|ids| ids.contains(id)) => {}
+            Some(id) => self.error(file, path, format!("unknown {} '{}'", kind.name(), id)),
+            None => self.error(file, path, format!("expected a {} id", kind.name())),
+        }
+    }
+
+    /// Check each entry of an array of ids
+    fn references(&mut self, kind: Kind, file: &'static str, path: &str, value: Option<&Value>) {
+        let entries = value.and_then(Value::as_array).cloned().unwrap_or_default();
+        for (index, entry) in entries.iter().enumerate() {
+            self.reference(kind, file, &format!("{}[{}]", path, index), Some(entry));
+        }
+    }
+
+    /// Entries of a top-level array as owned values, so they can be walked
+    /// while errors are recorded
+    fn entries(&self, file: &str, key: Option<&str>) -> Vec<(String, Value)> {
+        let root = self.file(file);
+        let (array, base) = match key {
+            Some(key) => (root.and_then(|r| r.get(key)), format!("$.{}", key)),
+            None => (root, "$".to_string()),
+        };
+        elements(array, &base)
+            .into_iter()
+            .map(|(path, value)| (path, value.clone()))
+            .collect()
+    }
+
+    /// Items and enemies that item use effects spawn
+    fn check_items(&mut self) {
+        const FILE: &str = "items.json";
+        for (path, item) in self.entries(FILE, Some("items")) {
+            let use_effects = elements(item.get("use_effects"), &format!("{}.use_effects", path));
+            let throw_effects = elements(
+                item.get("throw").and_then(|t| t.get("effects")),
+                &format!("{}.throw.effects", path),
+            );
+            for (effect_path, effect) in use_effects.into_iter().chain(throw_effects) {
+                if effect.get("type").and_then(Value::as_str) != Some("spawn_entity") {
+                    continue;
+                }
+                let kind = match effect.get("entity").and_then(Value::as_str) {
+                    Some("enemy") => Kind::Enemy,
+                    _ => Kind::Item,
+                };
+                self.reference(kind, FILE, &format!("{}.id", effect_path), effect.get("id"));
+            }
+        }
+    }
+
+    /// Enemy references in loot and spawns, and item, status and enemy ids in
+    /// the named behavior trees and each enemy's inline `ai` tree
+    fn check_enemies(&mut self) {
+        const FILE: &str = "enemies.json";
+        let trees: Vec<(String, Value)> = self
+            .file(FILE)
+            .and_then(|root| root.get("behavior_trees"))
+            .and_then(Value::as_object)
+            .map(|trees| {
+                trees
+                    .iter()
+                    .map(|(name, tree)| (format!("$.behavior_trees.{}", name), tree.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        for (path, tree) in trees {
+            self.check_behavior_node(&path, &tree);
+        }
+        for (path, enemy) in self.entries(FILE, Some("enemies")) {
+            for (drop_path, drop) in
+                elements(enemy.get("loot_table"), &format!("{}.loot_table", path))
+            {
+                self.reference(
+                    Kind::Item,
+                    FILE,
+                    &format!("{}.item", drop_path),
+                    drop.get("item"),
+                );
+            }
+            self.references(
+                Kind::Enemy,
+                FILE,
+                &format!("{}.spawn_types", path),
+                enemy.get("spawn_types"),
+            );
+            self.reference(
+                Kind::Faction,
+                FILE,
+                &format!("{}.faction", path),
+                enemy.get("faction"),
+            );
+            if let Some(ai) = enemy.get("ai") {
+                self.check_behavior_node(&format!("{}.ai", path), ai);
+            }
+        }
+    }
+
+    fn check_behavior_node(&mut self, path: &str, node: &Value) {
+        const FILE: &str = "enemies.json";
+        match node.get("type").and_then(Value::as_str) {
+            Some("player_has_item") => self.reference(
+                Kind::Item,
+                FILE,
+                &format!("{}.item", path),
+                node.get("item"),
+            ),
+            Some("cast") => self.reference(
+                Kind::Status,
+                FILE,
+                &format!("{}.status", path),
+                node.get("status"),
+            ),
+            Some("summon") => {
+                self.references(Kind::Enemy, FILE, &format!("{}.ids", path), node.get("ids"))
+            }
+            _ => {}
+        }
+        for (child_path, child) in elements(node.get("children"), &format!("{}.children", path)) {
+            self.check_behavior_node(&child_path, child);
+        }
+        for (option_path, option) in elements(node.get("options"), &format!("{}.options", path)) {
+            if let Some(child) = option.get("node") {
+                self.check_behavior_node(&format!("{}.node", option_path), child);
+            }
+        }
+        if let Some(child) = node.get("child") {
+            self.check_behavior_node(&format!("{}.child", path), child);
+        }
+    }
+
+    /// Factions named in relations
+    fn check_factions(&mut self) {
+        const FILE: &str = "factions.json";
+        for (path, faction) in self.entries(FILE, None) {
+            let others: Vec<String> = faction
+                .get("relations")
+                .and_then(Value::as_object)
+                .map(|relations| relations.keys().cloned().collect())
+                .unwrap_or_default();
+            for other in others {
+                self.reference(
+                    Kind::Faction,
+                    FILE,
+                    &format!("{}.relations.{}", path, other),
+                    Some(&Value::String(other.clone())),
+                );
+            }
+        }
+    }
+
+    /// NPC dialogue and action conditions, and the items actions give or take
+    fn check_npcs(&mut self) {
+        const FILE: &str = "npcs.json";
+        for (path, npc) in self.entries(FILE, Some("npcs")) {
+            for (entry_path, entry) in elements(npc.get("dialogue"), &format!("{}.dialogue", path))
+            {
+                self.check_npc_conditions(&entry_path, entry);
+            }
+            for (action_path, action) in elements(npc.get("actions"), &format!("{}.actions", path))
+            {
+                self.check_npc_conditions(&action_path, action);
+                for key in ["gives_item", "consumes"] {
+                    self.reference(
+                        Kind::Item,
+                        FILE,
+                        &format!("{}.effect.{}", action_path, key),
+                        action.get("effect").and_then(|e| e.get(key)),
+                    );
+                }
+            }
+            self.references(
+                Kind::Item,
+                FILE,
+                &format!("{}.shop_inventory", path),
+                npc.get("shop_inventory"),
+            );
+        }
+    }
+
+    fn check_npc_conditions(&mut self, path: &str, entry: &Value) {
+        for (condition_path, condition) in
+            elements(entry.get("conditions"), &format!("{}.conditions", path))
+        {
+            self.reference(
+                Kind::Item,
+                "npcs.json",
+                &format!("{}.has_item", condition_path),
+                condition.get("has_item"),
+            );
+            self.reference(
+                Kind::Adaptation,
+                "npcs.json",
+                &format!("{}.has_adaptation", condition_path),
+                condition.get("has_adaptation"),
+            );
+        }
+    }
+
+    fn check_quests(&mut self) {
+        for (file, key) in [
+            ("quests.json", "quests"),
+            ("main_questline.json", "main_questline"),
+        ] {
+            for (path, quest) in self.entries(file, Some(key)) {
+                self.check_quest(file, &path, &quest);
+            }
+        }
+
+        const FILE: &str = "main_questline.json";
+        for (path, injection) in self.entries(FILE, Some("quest_content_injections")) {
+            self.reference(
+                Kind::Quest,
+                FILE,
+                &format!("{}.quest_id", path),
+                injection.get("quest_id"),
+            );
+            for (npc_path, npc) in elements(injection.get("npcs"), &format!("{}.npcs", path)) {
+                self.reference(
+                    Kind::Npc,
+                    FILE,
+                    &format!("{}.npc_id", npc_path),
+                    npc.get("npc_id"),
+                );
+            }
+            for (item_path, item) in elements(injection.get("items"), &format!("{}.items", path)) {
+                self.reference(
+                    Kind::Item,
+                    FILE,
+                    &format!("{}.item_id", item_path),
+                    item.get("item_id"),
+                );
+            }
+        }
+    }
+
+    fn check_quest(&mut self, file: &'static str, path: &str, quest: &Value) {
+        for (objective_path, objective) in
+            elements(quest.get("objectives"), &format!("{}.objectives", path))
+        {
+            let (kind, key) = match objective.get("type").and_then(Value::as_str) {
+                Some("kill") => (Kind::Enemy, "enemy_id"),
+                Some("collect") => (Kind::Item, "item_id"),
+                Some("talk_to") => (Kind::Npc, "npc_id"),
+                Some("interface_with_aria") => (Kind::Item, "item_required"),
+                _ => continue,
+            };
+            self.reference(
+                kind,
+                file,
+                &format!("{}.{}", objective_path, key),
+                objective.get(key),
+            );
+        }
+
+        let reward = quest.get("reward");
+        self.references(
+            Kind::Item,
+            file,
+            &format!("{}.reward.items", path),
+            reward.and_then(|r| r.get("items")),
+        );
+        self.references(
+            Kind::Quest,
+            file,
+            &format!("{}.reward.unlocks_quests", path),
+            reward.and_then(|r| r.get("unlocks_quests")),
+        );
+
+        let criteria = quest.get("criteria");
+        self.references(
+            Kind::Quest,
+            file,
+            &format!("{}.criteria.requires_quests_completed", path),
+            criteria.and_then(|c| c.get("requires_quests_completed")),
+        );
+        self.references(
+            Kind::Item,
+            file,
+            &format!("{}.criteria.required_items", path),
+            criteria.and_then(|c| c.get("required_items")),
+        );
+        for key in ["required_adaptations", "forbidden_adaptations"] {
+            self.references(
+                Kind::Adaptation,
+                file,
+                &format!("{}.criteria.{}", path, key),
+                criteria.and_then(|c| c.get(key)),
+            );
+        }
+    }
+
+    /// Dialogue trees: their NPC, node links, conditions and actions
+    fn check_dialogues(&mut self) {
+        const FILE: &str = "dialogues.json";
+        for (index, (_, tree)) in self
+            .entries(FILE, Some("dialogues"))
+            .into_iter()
+            .enumerate()
+        {
+            let path = match tree.get("npc_id").and_then(Value::as_str) {
+                Some(npc_id) => format!("$.dialogues[npc_id={}]", npc_id),
+                None => format!("$.dialogues[{}]", index),
+            };
+            self.reference(
+                Kind::Npc,
+                FILE,
+                &format!("{}.npc_id", path),
+                tree.get("npc_id"),
+            );
+
+            let nodes = elements(tree.get("nodes"), &format!("{}.nodes", path));
+            let mut node_ids = BTreeSet::new();
+            for (node_path, node) in &nodes {
+                if let Some(id) = node.get("id").and_then(Value::as_str)
+                    && !node_ids.insert(id)
+                {
+                    self.error(FILE, node_path, format!("duplicate node id '{}'", id));
+                }
+            }
+            let node_reference =
+                |checker: &mut Self, path: String, value: Option<&Value>| match value
+                    .and_then(Value::as_str)
+                {
+                    Some(id) if !node_ids.contains(id) => {
+                        checker.error(FILE, &path, format!("unknown dialogue node '{}'", id))
+                    }
+                    _ => {}
+                };
+            node_reference(self, format!("{}.root_node", path), tree.get("root_node"));
+
+            for (node_path, node) in &nodes {
+                self.check_dialogue_condition(
+                    &format!("{}.condition", node_path),
+                    node.get("condition"),
+                );
+                for (option_path, option) in
+                    elements(node.get("options"), &format!("{}.options", node_path))
+                {
+                    node_reference(
+                        self,
+                        format!("{}.leads_to", option_path),
+                        option.get("leads_to"),
+                    );
+                    self.check_dialogue_condition(
+                        &format!("{}.condition", option_path),
+                        option.get("condition"),
+                    );
+                    if let Some(action) = option.get("action") {
+                        self.check_dialogue_action(&format!("{}.action", option_path), action);
+                    }
+                }
+            }
+        }
+    }
+
+    fn check_dialogue_condition(&mut self, path: &str, condition: Option<&Value>) {
+        let Some(condition) = condition else {
+            return;
+        };
+        self.reference(
+            Kind::Item,
+            "dialogues.json",
+            &format!("{}.has_item", path),
+            condition.get("has_item"),
+        );
+        self.reference(
+            Kind::Quest,
+            "dialogues.json",
+            &format!("{}.completed_quest", path),
+            condition.get("completed_quest"),
+        );
+        self.reference(
+            Kind::Adaptation,
+            "dialogues.json",
+            &format!("{}.has_adaptation", path),
+            condition.get("has_adaptation"),
+        );
+    }
+
+    fn check_dialogue_action(&mut self, path: &str, action: &Value) {
+        let (kind, key) = match action.get("type").and_then(Value::as_str) {
+            Some("trade") => (Kind::Trader, "trader_id"),
+            Some("give_item" | "take_item") => (Kind::Item, "item_id"),
+            Some("quest") => (Kind::Quest, "quest_id"),
+            _ => return,
+        };
+        self.reference(
+            kind,
+            "dialogues.json",
+            &format!("{}.parameters.{}", path, key),
+            action.get("parameters").and_then(|p| p.get(key)),
+        );
+    }
+
+    fn check_recipes(&mut self) {
+        const FILE: &str = "recipes.json";
+        let mut recipe_ids = BTreeSet::new();
+        for (path, recipe) in self.entries(FILE, Some("recipes")) {
+            if let Some(id) = recipe.get("id").and_then(Value::as_str)
+                && !recipe_ids.insert(id.to_string())
+            {
+                self.error(FILE, &path, format!("duplicate recipe id '{}'", id));
+            }
+            let materials = recipe.get("materials").and_then(Value::as_object);
+            for item in materials.into_iter().flat_map(|m| m.keys()) {
+                self.reference(
+                    Kind::Item,
+                    FILE,
+                    &format!("{}.materials.{}", path, item),
+                    Some(&Value::String(item.clone())),
+                );
+            }
+            self.reference(
+                Kind::Item,
+                FILE,
+                &format!("{}.output", path),
+                recipe.get("output"),
+            );
+        }
+    }
+
+    fn check_loot_tables(&mut self) {
+        const FILE: &str = "loot_tables.json";
+        for (path, table) in self.entries(FILE, None) {
+            for (entry_path, entry) in elements(table.get("entries"), &format!("{}.entries", path))
+            {
+                self.reference(
+                    Kind::Item,
+                    FILE,
+                    &format!("{}.item_id", entry_path),
+                    entry.get("item_id"),
+                );
+            }
+        }
+    }
+
+    /// Biome spawn tables: items, enemies and NPCs by id for each biome
+    fn check_spawn_tables(&mut self) {
+        const FILE: &str = "biome_spawn_tables.json";
+        let biomes = self
+            .file(FILE)
+            .and_then(Value::as_object)
+            .cloned()
+            .unwrap_or_default();
+        for (biome, table) in &biomes {
+            for (key, kind) in [
+                ("items", Kind::Item),
+                ("enemies", Kind::Enemy),
+                ("npcs", Kind::Npc),
+            ] {
+                let path = format!("$.{}.{}", biome, key);
+                for (entry_path, entry) in elements(table.get(key), &path) {
+                    self.reference(kind, FILE, &format!("{}.id", entry_path), entry.get("id"));
+                }
+            }
+        }
+    }
+
+    fn check_traders(&mut self) {
+        const FILE: &str = "traders.json";
+        for (index, (_, trader)) in self.entries(FILE, Some("traders")).into_iter().enumerate() {
+            let path = match trader.get("trader_id").and_then(Value::as_str) {
+                Some(id) => format!("$.traders[trader_id={}]", id),
+                None => format!("$.traders[{}]", index),
+            };
+            for (item_path, item) in elements(trader.get("items"), &format!("{}.items", path)) {
+                self.reference(
+                    Kind::Item,
+                    FILE,
+                    &format!("{}.item_id", item_path),
+                    item.get("item_id"),
+                );
+            }
+            let modifiers = trader
+                .get("reputation_modifiers")
+                .and_then(Value::as_object);
+            for (threshold, modifier) in modifiers.into_iter().flatten() {
+                self.references(
+                    Kind::Item,
+                    FILE,
+                    &format!(
+                        "{}.reputation_modifiers.{}.exclusive_items",
+                        path, threshold
+                    ),
+                    modifier.get("exclusive_items"),
+                );
+            }
+        }
+    }
+
+    fn check_chests(&mut self) {
+        const FILE: &str = "chests.json";
+        for (path, chest) in self.entries(FILE, None) {
+            self.reference(
+                Kind::LootTable,
+                FILE,
+                &format!("{}.loot_table", path),
+                chest.get("loot_table"),
+            );
+            self.reference(
+                Kind::Item,
+                FILE,
+                &format!("{}.key_required", path),
+                chest.get("key_required"),
+            );
+        }
+    }
+
+    /// Starting items and adaptations of the character classes
+    fn check_classes(&mut self) {
+        const FILE: &str = "classes.json";
+        for (path, class) in self.entries(FILE, Some("classes")) {
+            self.references(
+                Kind::Item,
+                FILE,
+                &format!("{}.starting_items", path),
+                class.get("starting_items"),
+            );
+            self.references(
+                Kind::Adaptation,
+                FILE,
+                &format!("{}.starting_adaptations", path),
+                class.get("starting_adaptations"),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::content::ContentConfig;
+    use std::fs;
+    use std::path::Path;
+
+    fn write(dir: &Path, file: &str, json: &str) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join(file), json).unwrap();
+    }
+
+    fn find<'a>(errors: &'a [ContentError], file: &str, path: &str) -> Option<&'a ContentError> {
+        errors.iter().find(|e| e.file == file && e.path == path)
+    }
+
+    #[test]
+    fn embedded_content_is_well_formed() {
+        let errors = check(&Content::build(&ContentConfig::default()));
+        let malformed: Vec<_> = errors
+            .iter()
+            .filter(|e| !e.message.starts_with("unknown") && !e.message.starts_with("duplicate"))
+            .collect();
+        assert!(malformed.is_empty(), "{:?}", malformed);
+        assert!(errors.iter().all(|e| !e.message.contains("'brine_vial'")));
+    }
+
+    #[test]
+    fn reports_broken_references_with_file_and_path() {
+        let data = tempfile::tempdir().unwrap();
+        write(
+            data.path(),
+            "loot_tables.json",
+            r#"[{"id": "cache", "name": "Cache", "description": "", "min_items": 1, "max_items": 1,
+                "entries": [
+                    {"item_id": "brine_vial", "weight": 1, "min_count": 1, "max_count": 1, "chance": 1.0},
+                    {"item_id": "no_such_item", "weight": 1, "min_count": 1, "max_count": 1, "chance": 1.0}
+                ]}]"#,
+        );
+        write(
+            data.path(),
+            "recipes.json",
+            r#"{"recipes": [{"id": "vial", "name": "Vial", "description": "",
+                "materials": {"brine_vial": 1}, "output": "missing_output",
+                "station_required": null, "faction_required": null}]}"#,
+        );
+        write(
+            data.path(),
+            "dialogues.json",
+            r#"{"dialogues": [{"npc_id": "merchant_test", "name": "Test", "faction": "none",
+                "root_node": "start", "nodes": [{"id": "start", "speaker": "", "text": "",
+                "options": [{"text": "a", "leads_to": "nowhere"}, {"text": "b", "leads_to": "start"}]}]}]}"#,
+        );
+        let mods = tempfile::tempdir().unwrap();
+        let quest_mod = mods.path().join("quest_mod");
+        write(
+            &quest_mod,
+            "items.json",
+            r#"{"items": [{"id": "lure", "name": "Lure", "glyph": "*", "description": "",
+                "use_effects": [{"type": "spawn_entity", "entity": "enemy", "id": "no_such_enemy"}]}]}"#,
+        );
+        write(
+            &quest_mod,
+            "quests.json",
+            r#"{"quests": [{"id": "haunting", "name": "Haunting", "description": "",
+                "objectives": [{"id": "banish", "description": "", "type": "kill", "enemy_id": "ghost", "count": 1}]}]}"#,
+        );
+
+        let content = Content::build(&ContentConfig {
+            data_dir: Some(data.path().to_path_buf()),
+            mods: vec![quest_mod],
+        });
+        let errors = check(&content);
+
+        let missing = find(
+            &errors,
+            "loot_tables.json",
+            "$[id=cache].entries[1].item_id",
+        )
+        .unwrap();
+        assert_eq!(missing.message, "unknown item 'no_such_item'");
+        assert!(
+            find(
+                &errors,
+                "loot_tables.json",
+                "$[id=cache].entries[0].item_id"
+            )
+            .is_none()
+        );
+        assert!(find(&errors, "recipes.json", "$.recipes[id=vial].output").is_some());
+        assert!(
+            find(
+                &errors,
+                "recipes.json",
+                "$.recipes[id=vial].materials.brine_vial"
+            )
+            .is_none()
+        );
+        let node = find(
+            &errors,
+            "dialogues.json",
+            "$.dialogues[npc_id=merchant_test].nodes[id=start].options[0].leads_to",
+        )
+        .unwrap();
+        assert_eq!(node.message, "unknown dialogue node 'nowhere'");
+        let spawn = find(
+            &errors,
+            "items.json",
+            "$.items[id=lure].use_effects[id=no_such_enemy].id",
+        )
+        .unwrap();
+        assert_eq!(spawn.message, "unknown enemy 'no_such_enemy'");
+        assert!(
+            find(
+                &errors,
+                "quests.json",
+                "$.quests[id=haunting].objectives[id=banish].enemy_id"
+            )
+            .is_some()
+        );
+    }
+
+    #[test]
+    fn reports_unknown_ids_in_behavior_trees() {
+        let data = tempfile::tempdir().unwrap();
+        write(
+            data.path(),
+            "enemies.json",
+            r#"{"behavior_trees": {"hex": {"type": "selector", "children": [
+                    {"type": "invert", "child": {"type": "player_has_item", "item": "no_such_item"}},
+                    {"type": "cast", "status": "no_such_status", "duration": 3}
+                ]}},
+                "enemies": [{"id": "caller", "ai": {"type": "utility", "options": [
+                    {"node": {"type": "summon", "ids": ["caller", "no_such_enemy"]}}
+                ]}}]}"#,
+        );
+        let errors = check(&Content::build(&ContentConfig {
+            data_dir: Some(data.path().to_path_buf()),
+            mods: Vec::new(),
+        }));
+
+        let item = find(
+            &errors,
+            "enemies.json",
+            "$.behavior_trees.hex.children[0].child.item",
+        )
+        .unwrap();
+        assert_eq!(item.message, "unknown item 'no_such_item'");
+        let status = find(
+            &errors,
+            "enemies.json",
+            "$.behavior_trees.hex.children[1].status",
+        )
+        .unwrap();
+        assert_eq!(status.message, "unknown status effect 'no_such_status'");
+        let summon = find(
+            &errors,
+            "enemies.json",
+            "$.enemies[id=caller].ai.options[0].node.ids[1]",
+        )
+        .unwrap();
+        assert_eq!(summon.message, "unknown enemy 'no_such_enemy'");
+        assert!(
+            find(
+                &errors,
+                "enemies.json",
+                "$.enemies[id=caller].ai.options[0].node.ids[0]"
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn reports_missing_and_duplicate_ids() {
+        let data = tempfile::tempdir().unwrap();
+        write(
+            data.path(),
+            "enemies.json",
+            r#"{"enemies": [{"id": "husk"}, {"name": "no id"}, {"id": "husk"}]}"#,
+        );
+        let errors = check(&Content::build(&ContentConfig {
+            data_dir: Some(data.path().to_path_buf()),
+            mods: Vec::new(),
+        }));
+
+        assert_eq!(
+            find(&errors, "enemies.json", "$.enemies[1]")
+                .unwrap()
+                .message,
+            "missing string `id`"
+        );
+        assert_eq!(
+            find(&errors, "enemies.json", "$.enemies[2]")
+                .unwrap()
+                .message,
+            "duplicate enemy id 'husk'"
+        );
+    }
+}
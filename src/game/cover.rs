@@ -0,0 +1,116 @@
+//! Cover and line of fire
+//!
+//! A target takes cover behind what stands next to it on the side facing the
+//! attacker: walls give full cover, glass and some interactables half cover.
+//! Cover is lost when the attacker is adjacent. A shot needs a line of fire,
+//! a straight line free of walls and other creatures.
+
+use serde::{Deserialize, Serialize};
+
+use super::{interactable::get_interactable_def, map::Tile, state::GameState};
+
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Cover {
+    #[default]
+    None,
+    Half,
+    Full,
+}
+
+impl Cover {
+    /// Subtracted from an attacker's hit chance
+    pub fn bonus(self) -> i32 {
+        match self {
+            Cover::None => 0,
+            Cover::Half => 25,
+            Cover::Full => 50,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Cover::None => "no cover",
+            Cover::Half => "half cover",
+            Cover::Full => "full cover",
+        }
+    }
+
+    /// Cover given by a tile
+    pub fn of_tile(tile: &Tile) -> Self {
+        match tile {
+            Tile::Wall { .. } => Cover::Full,
+            Tile::Glass => Cover::Half,
+            _ => Cover::None,
+        }
+    }
+}
+
+/// Steps from `to` toward `from` along which cover protects `to`
+fn facing(from: (i32, i32), to: (i32, i32)) -> impl Iterator<Item = (i32, i32)> {
+    let (dx, dy) = (from.0 - to.0, from.1 - to.1);
+    let x = (dx != 0 && 2 * dx.abs() >= dy.abs()).then_some((dx.signum(), 0));
+    let y = (dy != 0 && 2 * dy.abs() >= dx.abs()).then_some((0, dy.signum()));
+    x.into_iter().chain(y)
+}
+
+impl GameState {
+    /// Cover the tile `to` has against attacks from `from`
+    pub fn cover_between(&self, from: (i32, i32), to: (i32, i32)) -> Cover {
+        if (from.0 - to.0).abs() <= 1 && (from.1 - to.1).abs() <= 1 {
+            return Cover::None;
+        }
+        facing(from, to)
+            .map(|(sx, sy)| self.cover_at(to.0 + sx, to.1 + sy))
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Cover the tile at `x`, `y` gives to whoever stands next to it
+    pub fn cover_at(&self, x: i32, y: i32) -> Cover {
+        let tile = self.map.get(x, y).map(Cover::of_tile).unwrap_or_default();
+        let interactable = self
+            .interactable_positions
+            .get(&(x, y))
+            .and_then(|&i| self.interactables.get(i))
+            .and_then(|i| get_interactable_def(&i.id))
+            .map(|d| d.cover)
+            .unwrap_or_default();
+        tile.max(interactable)
+    }
+
+    /// Whether a shot from `from` can reach `to` without passing through a
+    /// wall or another creature
+    pub fn has_line_of_fire(&self, from: (i32, i32), to: (i32, i32)) -> bool {
+        let path = super::state::line_path(from, to);
+        path.iter()
+            .skip(1)
+            .take(path.len().saturating_sub(2))
+            .all(|&(x, y)| {
+                self.map.get(x, y).is_some_and(|t| t.transparent())
+                    && self.enemy_at(x, y).is_none()
+                    && self.npc_at(x, y).is_none()
+                    && (x, y) != (self.player_x, self.player_y)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cover_faces_the_attacker() {
+        // Straight on, only the tile in between counts
+        assert_eq!(facing((5, 0), (0, 0)).collect::<Vec<_>>(), [(1, 0)]);
+        // From a diagonal, both sides toward the attacker count
+        assert_eq!(
+            facing((4, -3), (0, 0)).collect::<Vec<_>>(),
+            [(1, 0), (0, -1)]
+        );
+        // A shallow angle keeps to the main axis
+        assert_eq!(facing((-6, 1), (0, 0)).collect::<Vec<_>>(), [(-1, 0)]);
+    }
+}
@@ -1,8 +1,11 @@
 //! Data-driven crafting system
 
+use crate::game::content;
+use crate::game::inventory::count_items;
+use crate::game::item::ItemInstance;
 use once_cell::sync::Lazy;
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// A crafting recipe
 #[derive(Debug, Clone, Deserialize)]
@@ -35,8 +38,8 @@ struct RecipesFile {
     recipes: Vec<Recipe>,
 }
 
-static RECIPES: Lazy<HashMap<String, Recipe>> = Lazy::new(|| {
-    let data = include_str!("../../data/recipes.json");
+static RECIPES: Lazy<BTreeMap<String, Recipe>> = Lazy::new(|| {
+    let data = &content::load("recipes.json");
     let file: RecipesFile = serde_json::from_str(data).expect("Failed to parse recipes.json");
     file.recipes
         .into_iter()
@@ -53,9 +56,9 @@ pub fn all_recipe_ids() -> Vec<&'static str> {
 }
 
 /// Check if player has materials for a recipe
-pub fn can_craft(recipe: &Recipe, inventory: &[String]) -> bool {
+pub fn can_craft(recipe: &Recipe, inventory: &[ItemInstance]) -> bool {
     for (item_id, &required) in &recipe.materials {
-        let count = inventory.iter().filter(|id| *id == item_id).count() as u32;
+        let count = count_items(inventory, item_id);
         if count < required {
             return false;
         }
@@ -66,7 +69,7 @@ pub fn can_craft(recipe: &Recipe, inventory: &[String]) -> bool {
 /// Check if player can craft recipe (materials + skill + station + faction)
 pub fn can_craft_advanced(
     recipe: &Recipe,
-    inventory: &[String],
+    inventory: &[ItemInstance],
     player_level: u32,
     available_stations: &[String],
     faction_reputation: &HashMap<String, i32>,
@@ -100,7 +103,7 @@ pub fn can_craft_advanced(
 
 /// Get all recipes player can currently craft
 pub fn available_recipes(
-    inventory: &[String],
+    inventory: &[ItemInstance],
     player_level: u32,
     available_stations: &[String],
     faction_reputation: &HashMap<String, i32>,
@@ -0,0 +1,192 @@
+//! Damage types and resistances
+//!
+//! Every hit carries a `DamageType`. Armor only stops physical damage; each
+//! type is then scaled by the target's resistance to it, a percentage where
+//! 25 takes a quarter off, 100 or more makes the target immune and negative
+//! values are vulnerabilities (-50 takes half again as much). Resistances come
+//! from enemy definitions, and for the player from equipped items and
+//! adaptations.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::{
+    state::{GameState, MsgType},
+    systems::combat::CombatSystem,
+};
+
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum DamageType {
+    #[default]
+    Physical,
+    Light,
+    Heat,
+    Void,
+    Psychic,
+    Poison,
+}
+
+impl DamageType {
+    pub const ALL: [DamageType; 6] = [
+        DamageType::Physical,
+        DamageType::Light,
+        DamageType::Heat,
+        DamageType::Void,
+        DamageType::Psychic,
+        DamageType::Poison,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DamageType::Physical => "physical",
+            DamageType::Light => "light",
+            DamageType::Heat => "heat",
+            DamageType::Void => "void",
+            DamageType::Psychic => "psychic",
+            DamageType::Poison => "poison",
+        }
+    }
+}
+
+/// Percent resistance by damage type; negative for a vulnerability
+pub type Resistances = BTreeMap<DamageType, i32>;
+
+/// `damage` after a resistance of `percent`
+pub fn resist(damage: i32, percent: i32) -> i32 {
+    if damage <= 0 {
+        return damage;
+    }
+    (damage * (100 - percent.min(100)) / 100).max(0)
+}
+
+/// Sum of several sets of resistances
+pub fn combine<'a>(sets: impl IntoIterator<Item = &'a Resistances>) -> Resistances {
+    let mut total = Resistances::new();
+    for set in sets {
+        for (&kind, &percent) in set {
+            *total.entry(kind).or_insert(0) += percent;
+        }
+    }
+    total.retain(|_, percent| *percent != 0);
+    total
+}
+
+/// Resistances as `heat +25%, light -50%`
+pub fn describe(resistances: &Resistances) -> String {
+    resistances
+        .iter()
+        .map(|(kind, percent)| format!("{} {:+}%", kind.name(), percent))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl GameState {
+    /// Resistances from equipped items and adaptations
+    pub fn player_resistances(&self) -> Resistances {
+        let items: Vec<Resistances> = self
+            .equipment
+            .iter()
+            .filter_map(|(_, item)| item.map(|item| item.resistances()))
+            .collect();
+        let adaptations = self
+            .adaptations
+            .iter()
+            .filter_map(|a| a.def())
+            .map(|d| &d.resistances);
+        combine(items.iter().chain(adaptations))
+    }
+
+    pub fn player_resistance(&self, kind: DamageType) -> i32 {
+        self.player_resistances().get(&kind).copied().unwrap_or(0)
+    }
+
+    /// Take `amount` of `kind` damage, less the player's resistance, off the
+    /// player's HP and return what was taken
+    pub fn damage_player(&mut self, amount: i32, kind: DamageType) -> i32 {
+        let dmg = resist(amount, self.player_resistance(kind));
+        self.player_hp -= dmg;
+        dmg
+    }
+
+    /// Take `amount` of `kind` damage, less the enemy's resistance, off the
+    /// HP of the enemy at index `i` and return what was taken
+    pub fn damage_enemy(&mut self, i: usize, amount: i32, kind: DamageType) -> i32 {
+        let enemy = &mut self.enemies[i];
+        let dmg = resist(amount, enemy.resistance(kind));
+        enemy.hp -= dmg;
+        dmg
+    }
+
+    /// Damage from light beams and void distortions on the tile at `x`, `y`
+    fn energy_hazards_at(&self, x: i32, y: i32) -> [(DamageType, i32); 2] {
+        let light = self.light_system.calculate_light_damage(x, y, &self.map);
+        let void = self.void_system.calculate_void_damage(x, y);
+        [
+            (DamageType::Light, light as i32),
+            (DamageType::Void, void as i32),
+        ]
+    }
+
+    /// Light beams and void distortions hurt the player and enemies standing
+    /// in them
+    pub(crate) fn apply_energy_hazards(&mut self) {
+        if self.light_system.active_beams.is_empty()
+            && self.void_system.active_distortions.is_empty()
+        {
+            return;
+        }
+
+        for (kind, amount) in self.energy_hazards_at(self.player_x, self.player_y) {
+            if amount > 0 {
+                let dmg = self.damage_player(amount, kind);
+                self.log_typed(
+                    format!("{} energy sears you for {} damage.", kind.name(), dmg),
+                    MsgType::Combat,
+                );
+            }
+        }
+
+        let mut i = 0;
+        while i < self.enemies.len() {
+            let (x, y) = (self.enemies[i].x, self.enemies[i].y);
+            if self.enemies[i].hp > 0 {
+                for (kind, amount) in self.energy_hazards_at(x, y) {
+                    if amount > 0 {
+                        self.damage_enemy(i, amount, kind);
+                    }
+                }
+                if self.enemies[i].hp <= 0 {
+                    let name = CombatSystem::process_enemy_death(self, i, x, y, None);
+                    self.log_typed(format!("The {} is torn apart!", name), MsgType::Combat);
+                }
+            }
+            i += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resistance_scales_damage() {
+        assert_eq!(resist(8, 0), 8);
+        assert_eq!(resist(8, 25), 6);
+        assert_eq!(resist(8, -50), 12);
+        assert_eq!(resist(8, 100), 0);
+        assert_eq!(resist(8, 150), 0);
+    }
+
+    #[test]
+    fn resistances_add_up_and_parse_by_name() {
+        let armor: Resistances = serde_json::from_str(r#"{"heat": 25, "void": -10}"#).unwrap();
+        let skin: Resistances = serde_json::from_str(r#"{"heat": 15, "void": 10}"#).unwrap();
+        let total = combine([&armor, &skin]);
+        assert_eq!(total, Resistances::from([(DamageType::Heat, 40)]));
+        assert_eq!(describe(&total), "heat +40%");
+    }
+}
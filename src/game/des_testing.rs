@@ -156,7 +156,7 @@ impl DesTest {
                 if action.parameters.len() >= 1 {
                     let idx: usize = action.parameters[0].parse()?;
                     if idx < state.inventory.len() {
-                        let item_id = state.inventory[idx].clone();
+                        let item_id = state.inventory[idx].id.clone();
                         state.use_item(idx);
                         Ok(format!("Used item: {}", item_id))
                     } else {
@@ -211,7 +211,7 @@ impl DesTest {
                     Err("Position expectation must be in format 'x,y'".into())
                 }
             }
-            "inventory_contains" => Ok(state.inventory.contains(&expectation.expected_value)),
+            "inventory_contains" => Ok(state.has_item(&expectation.expected_value)),
             "inventory_count" => {
                 let expected: usize = expectation.expected_value.parse()?;
                 Ok(state.inventory.len() == expected)
@@ -1,3 +1,5 @@
+use crate::game::content;
+use crate::game::item::ItemInstance;
 use once_cell::sync::Lazy;
 use serde::Deserialize;
 use std::collections::HashMap;
@@ -68,7 +70,7 @@ struct DialoguesFile {
 }
 
 static DIALOGUES: Lazy<HashMap<String, DialogueTree>> = Lazy::new(|| {
-    let data = include_str!("../../data/dialogues.json");
+    let data = &content::load("dialogues.json");
     let file: DialoguesFile = serde_json::from_str(data).expect("Failed to parse dialogues.json");
     file.dialogues
         .into_iter()
@@ -201,7 +203,7 @@ fn check_dialogue_condition(
 
     // Check item requirement
     if let Some(required_item) = &condition.has_item {
-        if !game_state.inventory.contains(required_item) {
+        if !game_state.has_item(required_item) {
             return false;
         }
     }
@@ -265,14 +267,12 @@ fn execute_dialogue_action(action: &DialogueAction, game_state: &mut crate::game
         }
         "give_item" => {
             if let Some(item_id) = action.parameters.get("item_id").and_then(|v| v.as_str()) {
-                game_state.inventory.push(item_id.to_string());
+                game_state.give_item(ItemInstance::new(item_id));
             }
         }
         "take_item" => {
             if let Some(item_id) = action.parameters.get("item_id").and_then(|v| v.as_str()) {
-                if let Some(pos) = game_state.inventory.iter().position(|id| id == item_id) {
-                    game_state.inventory.remove(pos);
-                }
+                game_state.take_items(item_id, 1);
             }
         }
         "give_currency" => {
@@ -310,7 +310,7 @@ impl crate::game::state::GameState {
 
         // Check item requirement
         if let Some(required_item) = &condition.has_item {
-            if !self.inventory.contains(required_item) {
+            if !self.has_item(required_item) {
                 return false;
             }
         }
@@ -1,3 +1,4 @@
+use crate::game::content;
 use once_cell::sync::Lazy;
 use ratatui::style::Color;
 use serde::Deserialize;
@@ -16,6 +17,34 @@ pub enum VisualEffect {
     Drift { speed: u32, color: Color },
 }
 
+impl VisualEffect {
+    /// Foreground color the effect gives the cell at `(x, y)` on `frame`, if
+    /// it colors it at all on that frame
+    pub fn color_at(&self, frame: u64, x: i32, y: i32) -> Option<Color> {
+        let (x, y) = (x as u64, y as u64);
+        let step = |speed: &u32| frame / (*speed).max(1) as u64;
+        match self {
+            VisualEffect::Blink { speed, color } => (step(speed) % 2 == 0).then_some(*color),
+            VisualEffect::Glow { color } => Some(*color),
+            VisualEffect::HitFlash { .. } => None,
+            VisualEffect::Pulse { speed, color } => (step(speed) % 4 < 2).then_some(*color),
+            VisualEffect::Wave { speed, color } => {
+                ((step(speed) + x + y) % 6 < 3).then_some(*color)
+            }
+            VisualEffect::Shimmer { speed, colors } => colors
+                .get(((step(speed) + (x ^ y)) % colors.len().max(1) as u64) as usize)
+                .copied(),
+            VisualEffect::Rainbow { speed, colors } => colors
+                .get((step(speed) % colors.len().max(1) as u64) as usize)
+                .copied(),
+            VisualEffect::Fade { speed, color } => (step(speed) % 8 < 4).then_some(*color),
+            VisualEffect::Drift { speed, color } => {
+                ((step(speed) + x * 3 + y * 7) % 10 < 3).then_some(*color)
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct EffectCondition {
     #[serde(default)]
@@ -68,7 +97,7 @@ struct EffectIndex {
 }
 
 static EFFECT_INDEX: Lazy<EffectIndex> = Lazy::new(|| {
-    let data = include_str!("../../data/effects.json");
+    let data = &content::load("effects.json");
     let file: EffectsFile = serde_json::from_str(data).expect("Failed to parse effects.json");
 
     let mut player_effects = Vec::new();
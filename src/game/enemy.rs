@@ -1,8 +1,12 @@
-use crate::game::entity::Entity;
-use crate::game::status::StatusEffect;
-use once_cell::sync::Lazy;
+use crate::game::behavior_tree::{BehaviorNode, DEFAULT_TREE};
+use crate::game::content::{Changes, Registry};
+use crate::game::damage::{DamageType, Resistances};
+use crate::game::entity::{Entity, EntityId};
+use crate::game::faction::WILD;
+use crate::game::status::{StatusEffect, speed_modifier};
+use crate::game::systems::timeline::{MIN_SPEED, NORMAL_SPEED};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -59,14 +63,26 @@ pub struct EnemyDef {
     pub damage_max: i32,
     #[serde(default = "default_sight")]
     pub sight_range: i32,
+    /// Energy gained per turn; 100 acts once a turn
+    #[serde(default = "default_speed")]
+    pub speed: i32,
     #[serde(default)]
     pub reflex: i32,
     #[serde(default)]
     pub armor: i32,
+    /// Type of the damage dealt by its attacks
+    #[serde(default)]
+    pub damage_type: DamageType,
+    /// Percent resistance by damage type; negative for a vulnerability
+    #[serde(default)]
+    pub resistances: Resistances,
     #[serde(default)]
     pub accuracy: i32,
     #[serde(default)]
     pub demeanor: AIDemeanor,
+    /// Faction from factions.json, `wild` if unset
+    #[serde(default)]
+    pub faction: Option<String>,
     #[serde(default)]
     pub description: String,
     #[serde(default)]
@@ -89,8 +105,12 @@ pub struct EnemyDef {
     pub max_spawns: u32,
     #[serde(default)]
     pub spawn_types: Vec<String>,
+    /// Named tree from `behavior_trees` to run, `standard_melee` if unset
     #[serde(default)]
     pub behavior_id: Option<String>,
+    /// Inline behavior tree; after parsing, the resolved tree the enemy runs
+    #[serde(default)]
+    pub ai: Option<BehaviorNode>,
     #[serde(default)]
     pub ranged_attack: bool,
     #[serde(default)]
@@ -111,53 +131,52 @@ fn default_sight() -> i32 {
 fn default_level() -> u32 {
     1
 }
+fn default_speed() -> i32 {
+    NORMAL_SPEED
+}
 
 #[derive(Deserialize)]
 struct EnemiesFile {
     enemies: Vec<EnemyDef>,
+    #[serde(default)]
+    behavior_trees: BTreeMap<String, BehaviorNode>,
 }
 
-static ENEMY_DEFS: Lazy<HashMap<String, EnemyDef>> = Lazy::new(|| {
-    let data = include_str!("../../data/enemies.json");
-    let file: EnemiesFile = serde_json::from_str(data).expect("Failed to parse enemies.json");
-    file.enemies
+static ENEMY_DEFS: Registry<BTreeMap<String, EnemyDef>> =
+    Registry::new("enemies.json", parse_enemies);
+
+fn parse_enemies(data: &str) -> Result<BTreeMap<String, EnemyDef>, String> {
+    let mut file: EnemiesFile = serde_json::from_str(data).map_err(|e| e.to_string())?;
+    for def in &mut file.enemies {
+        let root = def.ai.take().unwrap_or_else(|| BehaviorNode::Tree {
+            id: def
+                .behavior_id
+                .clone()
+                .unwrap_or_else(|| DEFAULT_TREE.to_string()),
+        });
+        let tree = root
+            .resolve(&file.behavior_trees)
+            .map_err(|e| format!("enemy '{}': {}", def.id, e))?;
+        def.ai = Some(tree);
+    }
+    Ok(file
+        .enemies
         .into_iter()
         .map(|d| (d.id.clone(), d))
-        .collect()
-});
+        .collect())
+}
 
 pub fn get_enemy_def(id: &str) -> Option<&'static EnemyDef> {
-    ENEMY_DEFS.get(id)
+    ENEMY_DEFS.get().get(id)
 }
 
 pub fn all_enemy_ids() -> Vec<&'static str> {
-    ENEMY_DEFS.keys().map(|s| s.as_str()).collect()
+    ENEMY_DEFS.get().keys().map(|s| s.as_str()).collect()
 }
 
-/// Context for evaluating behavior conditions
-pub struct BehaviorContext<'a> {
-    pub player_adaptations: usize,
-    pub player_items: &'a [String],
-}
-
-impl Behavior {
-    pub fn condition_met(&self, ctx: &BehaviorContext) -> bool {
-        let cond = match &self.condition {
-            Some(c) => c,
-            None => return true,
-        };
-
-        if cond.starts_with("player_adaptations >= ") {
-            if let Ok(n) = cond[22..].parse::<usize>() {
-                return ctx.player_adaptations >= n;
-            }
-        }
-        if cond.starts_with("player_has_item:") {
-            let item_id = &cond[16..];
-            return ctx.player_items.iter().any(|i| i == item_id);
-        }
-        false
-    }
+/// Replace the enemy definitions with those parsed from `data`
+pub fn reload_enemy_defs(data: &str) -> Result<Changes, String> {
+    ENEMY_DEFS.replace(data)
 }
 
 #[derive(Serialize, Deserialize)]
@@ -186,6 +205,18 @@ pub struct Enemy {
     pub swarm_leader: bool, // Is this the swarm leader
     #[serde(default)]
     pub swarm_id: Option<String>, // Swarm group identifier
+    /// Banked energy, spent on actions as it reaches `ACTION_ENERGY`
+    #[serde(default)]
+    pub energy: i32,
+    /// Has spotted the player and tracks them while they stay in sight
+    #[serde(default)]
+    pub noticed_player: bool,
+    /// Where the enemy last heard a noise it has yet to investigate
+    #[serde(default)]
+    pub heard: Option<(i32, i32)>,
+    /// Handle given by `GameState` when the entity is added
+    #[serde(default)]
+    pub entity_id: EntityId,
 }
 
 impl Enemy {
@@ -206,6 +237,10 @@ impl Enemy {
             aoe_warning_turns: 0,
             swarm_leader: false,
             swarm_id: None,
+            energy: 0,
+            noticed_player: false,
+            heard: None,
+            entity_id: EntityId::NONE,
         }
     }
 
@@ -234,11 +269,31 @@ impl Enemy {
         self.def().map(|d| d.name.as_str()).unwrap_or("Unknown")
     }
 
+    /// Speed from the definition, changed by status effects
+    pub fn speed(&self) -> i32 {
+        let base = self.def().map_or(NORMAL_SPEED, |d| d.speed);
+        (base + speed_modifier(&self.status_effects)).max(MIN_SPEED)
+    }
+
+    /// Percent resistance to a damage type from the definition
+    pub fn resistance(&self, kind: DamageType) -> i32 {
+        self.def()
+            .and_then(|d| d.resistances.get(&kind).copied())
+            .unwrap_or(0)
+    }
+
+    pub fn faction(&self) -> &'static str {
+        self.def()
+            .and_then(|d| d.faction.as_deref())
+            .unwrap_or(WILD)
+    }
+
     pub fn demeanor(&self) -> AIDemeanor {
         self.def().map(|d| d.demeanor).unwrap_or_default()
     }
 
-    /// Returns true if this enemy should act hostile toward player
+    /// Returns true if this enemy's demeanor makes it hostile toward the
+    /// player; see `GameState::is_hostile_to_player` for its faction's say
     pub fn is_hostile(&self) -> bool {
         match self.demeanor() {
             AIDemeanor::Aggressive => true,
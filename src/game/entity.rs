@@ -1,4 +1,6 @@
 use crate::game::status::StatusEffect;
+use serde::{Deserialize, Serialize};
+use std::fmt;
 
 /// Common trait for all game entities (player, enemies, NPCs)
 /// Provides a unified interface for systems that operate on any entity
@@ -53,3 +55,107 @@ pub enum EntityType {
     Enemy,
     Npc,
 }
+
+/// Handle to an enemy, NPC, item or chest that stays valid while the entity
+/// lists in `GameState` are mutated. Slots are reused with a new generation,
+/// so a handle to a removed entity never resolves to the one that replaced it.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct EntityId {
+    index: u32,
+    generation: u32,
+}
+
+impl EntityId {
+    /// Entities that have not been given a handle yet
+    pub const NONE: EntityId = EntityId {
+        index: 0,
+        generation: 0,
+    };
+
+    pub fn is_none(&self) -> bool {
+        *self == Self::NONE
+    }
+}
+
+impl fmt::Display for EntityId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}v{}", self.index, self.generation)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Slot {
+    generation: u32,
+    live: bool,
+}
+
+/// Hands out `EntityId`s, reusing the slots of removed entities
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EntityIds {
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+}
+
+impl EntityIds {
+    pub fn allocate(&mut self) -> EntityId {
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                self.slots.push(Slot::default());
+                (self.slots.len() - 1) as u32
+            }
+        };
+        let slot = &mut self.slots[index as usize];
+        slot.generation += 1;
+        slot.live = true;
+        EntityId {
+            index,
+            generation: slot.generation,
+        }
+    }
+
+    /// Free a handle's slot. Returns false if it was not live.
+    pub fn release(&mut self, id: EntityId) -> bool {
+        if !self.is_live(id) {
+            return false;
+        }
+        self.slots[id.index as usize].live = false;
+        self.free.push(id.index);
+        true
+    }
+
+    pub fn is_live(&self, id: EntityId) -> bool {
+        !id.is_none()
+            && self
+                .slots
+                .get(id.index as usize)
+                .is_some_and(|s| s.live && s.generation == id.generation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn released_slots_come_back_with_a_new_generation() {
+        let mut ids = EntityIds::default();
+        let first = ids.allocate();
+        let second = ids.allocate();
+        assert_ne!(first, second);
+        assert!(!first.is_none());
+
+        assert!(ids.release(first));
+        assert!(!ids.release(first));
+        assert!(!ids.is_live(first));
+
+        let reused = ids.allocate();
+        assert_eq!(reused.index, first.index);
+        assert_ne!(reused, first);
+        assert!(ids.is_live(reused));
+        assert!(ids.is_live(second));
+        assert!(!ids.is_live(EntityId::NONE));
+    }
+}
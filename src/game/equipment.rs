@@ -3,6 +3,8 @@
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
+use super::item::ItemInstance;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum EquipSlot {
@@ -59,6 +61,24 @@ impl EquipSlot {
         ]
     }
 
+    /// Id accepted by `from_str`
+    pub fn id(&self) -> &'static str {
+        match self {
+            EquipSlot::Weapon => "weapon",
+            EquipSlot::RangedWeapon => "ranged_weapon",
+            EquipSlot::Head => "head",
+            EquipSlot::Jacket => "jacket",
+            EquipSlot::Pants => "pants",
+            EquipSlot::Boots => "boots",
+            EquipSlot::Gloves => "gloves",
+            EquipSlot::LeftWrist => "left_wrist",
+            EquipSlot::RightWrist => "right_wrist",
+            EquipSlot::Necklace => "necklace",
+            EquipSlot::Accessory => "accessory",
+            EquipSlot::Backpack => "backpack",
+        }
+    }
+
     pub fn display_name(&self) -> &'static str {
         match self {
             EquipSlot::Weapon => "Melee Weapon",
@@ -79,22 +99,22 @@ impl EquipSlot {
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Equipment {
-    pub weapon: Option<String>,
-    pub ranged_weapon: Option<String>,
-    pub head: Option<String>,
-    pub jacket: Option<String>,
-    pub pants: Option<String>,
-    pub boots: Option<String>,
-    pub gloves: Option<String>,
-    pub left_wrist: Option<String>,
-    pub right_wrist: Option<String>,
-    pub necklace: Option<String>,
-    pub accessory: Option<String>,
-    pub backpack: Option<String>,
+    pub weapon: Option<ItemInstance>,
+    pub ranged_weapon: Option<ItemInstance>,
+    pub head: Option<ItemInstance>,
+    pub jacket: Option<ItemInstance>,
+    pub pants: Option<ItemInstance>,
+    pub boots: Option<ItemInstance>,
+    pub gloves: Option<ItemInstance>,
+    pub left_wrist: Option<ItemInstance>,
+    pub right_wrist: Option<ItemInstance>,
+    pub necklace: Option<ItemInstance>,
+    pub accessory: Option<ItemInstance>,
+    pub backpack: Option<ItemInstance>,
 }
 
 impl Equipment {
-    pub fn get(&self, slot: EquipSlot) -> Option<&String> {
+    pub fn get(&self, slot: EquipSlot) -> Option<&ItemInstance> {
         match slot {
             EquipSlot::Weapon => self.weapon.as_ref(),
             EquipSlot::RangedWeapon => self.ranged_weapon.as_ref(),
@@ -111,7 +131,24 @@ impl Equipment {
         }
     }
 
-    pub fn set(&mut self, slot: EquipSlot, item: Option<String>) -> Option<String> {
+    pub fn get_mut(&mut self, slot: EquipSlot) -> Option<&mut ItemInstance> {
+        match slot {
+            EquipSlot::Weapon => self.weapon.as_mut(),
+            EquipSlot::RangedWeapon => self.ranged_weapon.as_mut(),
+            EquipSlot::Head => self.head.as_mut(),
+            EquipSlot::Jacket => self.jacket.as_mut(),
+            EquipSlot::Pants => self.pants.as_mut(),
+            EquipSlot::Boots => self.boots.as_mut(),
+            EquipSlot::Gloves => self.gloves.as_mut(),
+            EquipSlot::LeftWrist => self.left_wrist.as_mut(),
+            EquipSlot::RightWrist => self.right_wrist.as_mut(),
+            EquipSlot::Necklace => self.necklace.as_mut(),
+            EquipSlot::Accessory => self.accessory.as_mut(),
+            EquipSlot::Backpack => self.backpack.as_mut(),
+        }
+    }
+
+    pub fn set(&mut self, slot: EquipSlot, item: Option<ItemInstance>) -> Option<ItemInstance> {
         match slot {
             EquipSlot::Weapon => std::mem::replace(&mut self.weapon, item),
             EquipSlot::RangedWeapon => std::mem::replace(&mut self.ranged_weapon, item),
@@ -128,7 +165,7 @@ impl Equipment {
         }
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = (EquipSlot, Option<&String>)> {
+    pub fn iter(&self) -> impl Iterator<Item = (EquipSlot, Option<&ItemInstance>)> {
         EquipSlot::all().iter().map(|&slot| (slot, self.get(slot)))
     }
 }
@@ -1,9 +1,12 @@
+use super::entity::EntityId;
+
 /// Game events for decoupled communication between systems
 #[derive(Debug, Clone)]
 pub enum GameEvent {
     PlayerDamaged { amount: i32, source: String },
     PlayerHealed { amount: i32 },
-    EnemyKilled { enemy_id: String, x: i32, y: i32 },
+    /// `killer` is the creature that made the kill, or `None` for the player
+    EnemyKilled { entity: EntityId, enemy_id: String, x: i32, y: i32, killer: Option<EntityId> },
     ItemPickedUp { item_id: String },
     ItemUsed { item_id: String },
     AdaptationGained { name: String },
@@ -0,0 +1,173 @@
+//! Factions and how they stand with each other and the player
+//!
+//! Every creature and NPC belongs to a faction from factions.json; creatures
+//! without one are `wild`. Content written before factions.json names some
+//! factions differently ("MirrorMonks" for `monks`), so a faction lists those
+//! names as `aliases` and reputation kept under any of them counts toward it.
+//!
+//! Two factions are friends if they are the same, otherwise they stand as
+//! either one's `relations` says, and are neutral if neither mentions the
+//! other. Creatures hunt members of hostile factions. Toward the player a
+//! faction turns hostile once their reputation falls to its `hostile`
+//! threshold and friendly once it reaches `friendly`; in between, each
+//! creature's own demeanor decides.
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use super::content::{Changes, Registry};
+use super::enemy::Enemy;
+use super::state::GameState;
+
+/// Faction of creatures that do not name one
+pub const WILD: &str = "wild";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stance {
+    Hostile,
+    #[default]
+    Neutral,
+    Friendly,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct ReputationThresholds {
+    /// Reputation at or below which the faction turns on the player
+    pub hostile: i32,
+    /// Reputation at or above which the faction sides with the player
+    pub friendly: i32,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct FactionDef {
+    pub id: String,
+    pub name: String,
+    /// Other names content uses for the faction
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Without thresholds, reputation never changes how it treats the player
+    #[serde(default)]
+    pub reputation_thresholds: Option<ReputationThresholds>,
+    /// Stance toward other factions by id; neutral when not listed
+    #[serde(default)]
+    pub relations: BTreeMap<String, Stance>,
+    #[serde(default)]
+    pub greeting_hostile: Option<String>,
+}
+
+impl FactionDef {
+    fn answers_to(&self, name: &str) -> bool {
+        self.id == name || self.aliases.iter().any(|a| a == name)
+    }
+}
+
+static FACTION_DEFS: Registry<Vec<FactionDef>> = Registry::new("factions.json", parse_factions);
+
+fn parse_factions(data: &str) -> Result<Vec<FactionDef>, String> {
+    serde_json::from_str(data).map_err(|e| e.to_string())
+}
+
+/// The faction with id or alias `name`
+pub fn get_faction_def(name: &str) -> Option<&'static FactionDef> {
+    FACTION_DEFS.get().iter().find(|f| f.answers_to(name))
+}
+
+/// Replace the faction definitions with those parsed from `data`
+pub fn reload_faction_defs(data: &str) -> Result<Changes, String> {
+    FACTION_DEFS.replace(data)
+}
+
+/// How faction `a` stands toward faction `b`
+pub fn relation(a: &str, b: &str) -> Stance {
+    let (def_a, def_b) = (get_faction_def(a), get_faction_def(b));
+    let (a, b) = (
+        def_a.map_or(a, |d| d.id.as_str()),
+        def_b.map_or(b, |d| d.id.as_str()),
+    );
+    if a == b {
+        return Stance::Friendly;
+    }
+    def_a
+        .and_then(|d| d.relations.get(b))
+        .or_else(|| def_b.and_then(|d| d.relations.get(a)))
+        .copied()
+        .unwrap_or_default()
+}
+
+impl GameState {
+    /// The player's reputation with a faction, counting every name it goes by
+    pub fn faction_reputation_with(&self, faction: &str) -> i32 {
+        match get_faction_def(faction) {
+            Some(def) => self
+                .faction_reputation
+                .iter()
+                .filter(|(name, _)| def.answers_to(name))
+                .map(|(_, rep)| rep)
+                .sum::<i32>()
+                .clamp(-100, 100),
+            None => self.get_reputation(faction),
+        }
+    }
+
+    /// How a faction stands toward the player given their reputation
+    pub fn faction_stance(&self, faction: &str) -> Stance {
+        let Some(thresholds) = get_faction_def(faction).and_then(|d| d.reputation_thresholds)
+        else {
+            return Stance::Neutral;
+        };
+        let rep = self.faction_reputation_with(faction);
+        if rep <= thresholds.hostile {
+            Stance::Hostile
+        } else if rep >= thresholds.friendly {
+            Stance::Friendly
+        } else {
+            Stance::Neutral
+        }
+    }
+
+    /// Whether `enemy` acts hostile toward the player: its faction's stance
+    /// if it has taken a side, otherwise its demeanor. Even friendly
+    /// factions fight back once provoked.
+    pub fn is_hostile_to_player(&self, enemy: &Enemy) -> bool {
+        match self.faction_stance(enemy.faction()) {
+            Stance::Hostile => true,
+            Stance::Friendly => enemy.provoked,
+            Stance::Neutral => enemy.is_hostile(),
+        }
+    }
+
+    /// How `enemy` behaves toward the player, for display: its demeanor
+    /// unless its faction has taken a side
+    pub fn demeanor_label(&self, enemy: &Enemy) -> String {
+        match self.faction_stance(enemy.faction()) {
+            Stance::Hostile => "hostile".into(),
+            Stance::Friendly if !enemy.provoked => "friendly".into(),
+            _ => format!("{:?}", enemy.demeanor()).to_lowercase(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relations_read_either_way_and_aliases_share_reputation() {
+        assert_eq!(relation("monks", "MirrorMonks"), Stance::Friendly);
+        assert_eq!(relation("wild", "engineers"), Stance::Hostile);
+        assert_eq!(relation("engineers", "wild"), Stance::Hostile);
+        assert_eq!(relation("monks", "engineers"), Stance::Neutral);
+        assert_eq!(relation("nobody", "monks"), Stance::Neutral);
+
+        let mut state = GameState::new(42);
+        state.faction_reputation.clear();
+        state.modify_reputation("monks", -30);
+        assert_eq!(state.faction_stance("monks"), Stance::Neutral);
+        state.modify_reputation("MirrorMonks", -20);
+        assert_eq!(state.faction_reputation_with("Mirror Monks"), -50);
+        assert_eq!(state.faction_stance("monks"), Stance::Hostile);
+        assert_eq!(state.faction_stance(WILD), Stance::Neutral);
+    }
+}
@@ -0,0 +1,231 @@
+//! Shared Dijkstra maps for enemies chasing or fleeing the player
+//!
+//! Instead of each enemy searching for its own path every turn, the steps
+//! from every tile to the player are worked out once per turn and shared:
+//! an enemy closes in by stepping to the neighbour with the fewest steps
+//! left. The flee map is the chase map scaled by -1.2 and relaxed again, so
+//! it leads fleeing enemies toward open ground away from the player rather
+//! than into the nearest dead end. It is built the first time an enemy
+//! flees in a turn. Like the map's pathing exits, both move in four
+//! directions over walkable tiles and ignore creatures.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+use super::map::Map;
+use super::state::GameState;
+
+/// Value of tiles cut off from the player, and of walls
+pub const UNREACHABLE: i32 = i32::MAX;
+
+/// Chase steps cost 10 on the flee map so that it can be scaled by 1.2
+const STEP: i32 = 10;
+const FLEE_SCALE: i32 = -12;
+
+const DIRECTIONS: [(i32, i32); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Field {
+    /// Toward the player
+    Chase,
+    /// Away from the player
+    Flee,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct FlowFields {
+    /// Turn and player position the maps were built for
+    built_for: Option<(u32, i32, i32)>,
+    /// Steps from each tile to the player
+    chase: Vec<i32>,
+    /// Built from `chase` when first asked for
+    flee: Option<Vec<i32>>,
+    /// Leave the maps unused, so that enemies search their own A* paths to
+    /// the player and flee in a straight line as they did before the maps
+    /// existed, for comparing the two in benches
+    #[cfg(any(test, feature = "bench"))]
+    pub disabled: bool,
+}
+
+impl FlowFields {
+    /// Whether enemies chasing or fleeing the player step along the maps
+    #[cfg(any(test, feature = "bench"))]
+    pub fn in_use(&self) -> bool {
+        !self.disabled
+    }
+
+    /// Whether enemies chasing or fleeing the player step along the maps
+    #[cfg(not(any(test, feature = "bench")))]
+    pub fn in_use(&self) -> bool {
+        true
+    }
+
+    /// Whether the maps are up to date for `turn` with the player at `player`
+    pub fn is_current(&self, turn: u32, player: (i32, i32)) -> bool {
+        self.built_for == Some((turn, player.0, player.1))
+    }
+
+    /// Rebuild the chase map for the player at `player` and drop the flee map
+    pub fn rebuild(&mut self, map: &Map, turn: u32, player: (i32, i32)) {
+        self.chase = chase_map(map, player);
+        self.flee = None;
+        self.built_for = Some((turn, player.0, player.1));
+    }
+
+    /// Neighbours of (x, y) lower than it on `field`, lowest first
+    pub fn downhill(&mut self, map: &Map, field: Field, x: i32, y: i32) -> Vec<(i32, i32)> {
+        let values = match field {
+            Field::Chase => &self.chase,
+            Field::Flee => self.flee.get_or_insert_with(|| flee_map(map, &self.chase)),
+        };
+        if values.is_empty() || map.get(x, y).is_none() {
+            return Vec::new();
+        }
+        let here = values[map.idx(x, y)];
+        let mut steps: Vec<((i32, i32), i32)> = DIRECTIONS
+            .iter()
+            .map(|(dx, dy)| (x + dx, y + dy))
+            .filter(|&(nx, ny)| map.get(nx, ny).is_some())
+            .map(|(nx, ny)| ((nx, ny), values[map.idx(nx, ny)]))
+            .filter(|&(_, value)| value < here)
+            .collect();
+        // Stable, so ties keep the order of `DIRECTIONS`
+        steps.sort_by_key(|&(_, value)| value);
+        steps.into_iter().map(|(pos, _)| pos).collect()
+    }
+}
+
+impl GameState {
+    /// Rebuild the shared maps for the player's current position
+    pub fn rebuild_flow_fields(&mut self) {
+        let player = (self.player_x, self.player_y);
+        self.flow_fields.rebuild(&self.map, self.turn, player);
+    }
+
+    /// Neighbours of (x, y) lower than it on `field`, lowest first,
+    /// rebuilding the maps if the turn or the player's position changed
+    pub fn flow_steps(&mut self, field: Field, x: i32, y: i32) -> Vec<(i32, i32)> {
+        if !self
+            .flow_fields
+            .is_current(self.turn, (self.player_x, self.player_y))
+        {
+            self.rebuild_flow_fields();
+        }
+        self.flow_fields.downhill(&self.map, field, x, y)
+    }
+}
+
+/// Breadth-first steps from every walkable tile to `target`
+fn chase_map(map: &Map, target: (i32, i32)) -> Vec<i32> {
+    let mut steps = vec![UNREACHABLE; map.tiles.len()];
+    if map.get(target.0, target.1).is_none() {
+        return steps;
+    }
+    steps[map.idx(target.0, target.1)] = 0;
+    let mut queue = VecDeque::from([target]);
+    while let Some((x, y)) = queue.pop_front() {
+        let next = steps[map.idx(x, y)] + 1;
+        for (dx, dy) in DIRECTIONS {
+            let (nx, ny) = (x + dx, y + dy);
+            if map.get(nx, ny).is_some_and(|t| t.walkable()) {
+                let idx = map.idx(nx, ny);
+                if steps[idx] == UNREACHABLE {
+                    steps[idx] = next;
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+    }
+    steps
+}
+
+/// The chase map scaled by `FLEE_SCALE` tenths and relaxed so that no tile
+/// is more than a step above its lowest neighbour
+fn flee_map(map: &Map, chase: &[i32]) -> Vec<i32> {
+    let mut values: Vec<i32> = chase
+        .iter()
+        .map(|&steps| {
+            if steps == UNREACHABLE {
+                UNREACHABLE
+            } else {
+                steps * FLEE_SCALE
+            }
+        })
+        .collect();
+    let mut heap: BinaryHeap<Reverse<(i32, usize)>> = values
+        .iter()
+        .enumerate()
+        .filter(|&(_, &value)| value != UNREACHABLE)
+        .map(|(idx, &value)| Reverse((value, idx)))
+        .collect();
+    while let Some(Reverse((value, idx))) = heap.pop() {
+        if value > values[idx] {
+            continue;
+        }
+        let (x, y) = ((idx % map.width) as i32, (idx / map.width) as i32);
+        for (dx, dy) in DIRECTIONS {
+            let (nx, ny) = (x + dx, y + dy);
+            if map.get(nx, ny).is_some_and(|t| t.walkable()) {
+                let n = map.idx(nx, ny);
+                if value + STEP < values[n] {
+                    values[n] = value + STEP;
+                    heap.push(Reverse((value + STEP, n)));
+                }
+            }
+        }
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::map::Tile;
+
+    /// A walled map with open floor inside and walls at `walls`
+    fn room(width: usize, height: usize, walls: &[(i32, i32)]) -> Map {
+        let mut map = Map::new(width, height);
+        for y in 1..height as i32 - 1 {
+            for x in 1..width as i32 - 1 {
+                if !walls.contains(&(x, y)) {
+                    let idx = map.idx(x, y);
+                    map.tiles[idx] = Tile::default_floor();
+                }
+            }
+        }
+        map
+    }
+
+    #[test]
+    fn chase_map_leads_around_walls() {
+        // The wall at x = 4 leaves a gap at the bottom
+        let map = room(9, 5, &[(4, 1), (4, 2)]);
+        let mut fields = FlowFields::default();
+        fields.rebuild(&map, 1, (2, 1));
+        assert!(fields.is_current(1, (2, 1)));
+        assert!(!fields.is_current(2, (2, 1)));
+
+        assert_eq!(fields.chase[map.idx(6, 1)], 8);
+        assert_eq!(fields.chase[map.idx(4, 1)], UNREACHABLE);
+        assert_eq!(
+            fields.downhill(&map, Field::Chase, 6, 1),
+            vec![(5, 1), (6, 2)]
+        );
+        assert_eq!(fields.downhill(&map, Field::Chase, 2, 1), vec![]);
+    }
+
+    #[test]
+    fn flee_map_leads_away_from_the_player() {
+        let map = room(12, 3, &[]);
+        let mut fields = FlowFields::default();
+        fields.rebuild(&map, 1, (4, 1));
+        assert!(fields.flee.is_none());
+        assert_eq!(fields.downhill(&map, Field::Flee, 6, 1), vec![(7, 1)]);
+        assert_eq!(fields.downhill(&map, Field::Flee, 2, 1), vec![(1, 1)]);
+
+        // Moving the player drops the flee map with the old chase map
+        fields.rebuild(&map, 1, (8, 1));
+        assert!(fields.flee.is_none());
+        assert_eq!(fields.downhill(&map, Field::Flee, 6, 1), vec![(5, 1)]);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::game::content;
 use once_cell::sync::Lazy;
 use rand::Rng;
 use rand_chacha::ChaCha8Rng;
@@ -251,7 +252,7 @@ impl BiomeSystem {
 
 // Static biome profile data
 static BIOME_PROFILES: Lazy<HashMap<Biome, BiomeProfile>> = Lazy::new(|| {
-    let data = include_str!("../../../data/biome_profiles.json");
+    let data = &content::load("biome_profiles.json");
     let file: BiomeProfilesFile =
         serde_json::from_str(data).expect("Failed to parse biome_profiles.json");
 
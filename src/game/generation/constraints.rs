@@ -1,3 +1,4 @@
+use crate::game::content;
 use once_cell::sync::Lazy;
 use rand_chacha::ChaCha8Rng;
 use serde::Deserialize;
@@ -825,7 +826,7 @@ impl ConstraintSystem {
 
 // Static constraint rules data
 static CONSTRAINT_RULES: Lazy<Vec<ConstraintRule>> = Lazy::new(|| {
-    let data = include_str!("../../../data/constraint_rules.json");
+    let data = &content::load("constraint_rules.json");
     let file: ConstraintRulesFile =
         serde_json::from_str(data).expect("Failed to parse constraint_rules.json");
     file.rules
@@ -1,8 +1,9 @@
+use crate::game::content;
 use once_cell::sync::Lazy;
 use rand::Rng;
 use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Event trigger conditions based on player/world state
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -56,8 +57,9 @@ pub struct EventContext {
 
 /// Event system for managing dynamic events
 pub struct EventSystem {
-    events: HashMap<String, DynamicEvent>,
-    chains: HashMap<String, EventChain>,
+    // Ordered so triggers are rolled in the same order every run
+    events: BTreeMap<String, DynamicEvent>,
+    chains: BTreeMap<String, EventChain>,
     #[allow(dead_code)]
     active_events: Vec<String>,
     event_cooldowns: HashMap<String, u32>,
@@ -252,14 +254,14 @@ struct EventsFile {
     chains: Vec<EventChain>,
 }
 
-static EVENTS: Lazy<HashMap<String, DynamicEvent>> = Lazy::new(|| {
-    let data = include_str!("../../../data/dynamic_events.json");
+static EVENTS: Lazy<BTreeMap<String, DynamicEvent>> = Lazy::new(|| {
+    let data = &content::load("dynamic_events.json");
     let file: EventsFile = serde_json::from_str(data).expect("Failed to parse dynamic_events.json");
     file.events.into_iter().map(|e| (e.id.clone(), e)).collect()
 });
 
-static CHAINS: Lazy<HashMap<String, EventChain>> = Lazy::new(|| {
-    let data = include_str!("../../../data/dynamic_events.json");
+static CHAINS: Lazy<BTreeMap<String, EventChain>> = Lazy::new(|| {
+    let data = &content::load("dynamic_events.json");
     let file: EventsFile = serde_json::from_str(data).expect("Failed to parse dynamic_events.json");
     file.chains
         .into_iter()
@@ -1,3 +1,4 @@
+use crate::game::content;
 use once_cell::sync::Lazy;
 use serde::Deserialize;
 use std::collections::HashMap;
@@ -16,7 +17,7 @@ struct FeatureFile {
 }
 
 static FEATURE_MAP: Lazy<HashMap<String, FeatureDef>> = Lazy::new(|| {
-    let data = include_str!("../../../data/map_features.json");
+    let data = &content::load("map_features.json");
     let file: FeatureFile =
         serde_json::from_str(data).expect("Failed to parse data/map_features.json");
     file.features
@@ -1,11 +1,11 @@
-use once_cell::sync::Lazy;
+use crate::game::content::{Changes, Registry};
 use rand::Rng;
 use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use super::{WeightedEntry, WeightedSpawn, WeightedTable, weighted_pick};
-use crate::game::item::Item;
+use crate::game::item::ItemInstance;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LootEntry {
@@ -24,23 +24,32 @@ pub struct LootTable {
     pub entries: Vec<LootEntry>,
     pub min_items: u32,
     pub max_items: u32,
+    /// Tier affixes are rolled at, unless the item's own tier is higher
+    #[serde(default)]
+    pub tier: u32,
 }
 
-static LOOT_TABLES: Lazy<HashMap<String, LootTable>> = Lazy::new(|| {
-    let data = include_str!("../../../data/loot_tables.json");
-    let tables: Vec<LootTable> =
-        serde_json::from_str(data).expect("Failed to parse loot_tables.json");
-    tables
+static LOOT_TABLES: Registry<HashMap<String, LootTable>> =
+    Registry::new("loot_tables.json", parse_loot_tables);
+
+fn parse_loot_tables(data: &str) -> Result<HashMap<String, LootTable>, String> {
+    let tables: Vec<LootTable> = serde_json::from_str(data).map_err(|e| e.to_string())?;
+    Ok(tables
         .into_iter()
         .map(|table| (table.id.clone(), table))
-        .collect()
-});
+        .collect())
+}
 
 pub fn get_loot_table(id: &str) -> Option<&'static LootTable> {
-    LOOT_TABLES.get(id)
+    LOOT_TABLES.get().get(id)
+}
+
+/// Replace the loot tables with those parsed from `data`
+pub fn reload_loot_tables(data: &str) -> Result<Changes, String> {
+    LOOT_TABLES.replace(data)
 }
 
-pub fn generate_loot(table_id: &str, x: i32, y: i32, rng: &mut ChaCha8Rng) -> Vec<Item> {
+pub fn generate_loot(table_id: &str, rng: &mut ChaCha8Rng) -> Vec<ItemInstance> {
     let table = match get_loot_table(table_id) {
         Some(t) => t,
         None => return Vec::new(),
@@ -81,7 +90,7 @@ pub fn generate_loot(table_id: &str, x: i32, y: i32, rng: &mut ChaCha8Rng) -> Ve
 
             let count = rng.gen_range(entry.min_count..=entry.max_count);
             for _ in 0..count {
-                loot.push(Item::new(x, y, item_id));
+                loot.push(ItemInstance::rolled(item_id, table.tier, rng));
             }
         }
     }
@@ -90,7 +99,7 @@ pub fn generate_loot(table_id: &str, x: i32, y: i32, rng: &mut ChaCha8Rng) -> Ve
 }
 
 /// Enhanced loot generation using WeightedTable system
-pub fn generate_loot_enhanced(table_id: &str, x: i32, y: i32, rng: &mut ChaCha8Rng) -> Vec<Item> {
+pub fn generate_loot_enhanced(table_id: &str, rng: &mut ChaCha8Rng) -> Vec<ItemInstance> {
     let table = match get_loot_table(table_id) {
         Some(t) => t,
         None => return Vec::new(),
@@ -125,7 +134,7 @@ pub fn generate_loot_enhanced(table_id: &str, x: i32, y: i32, rng: &mut ChaCha8R
         if let Some(entry) = weighted_table.select(rng) {
             let count = rng.gen_range(entry.min_count..=entry.max_count);
             for _ in 0..count {
-                loot.push(Item::new(x, y, &entry.item_id));
+                loot.push(ItemInstance::rolled(&entry.item_id, table.tier, rng));
             }
         }
     }
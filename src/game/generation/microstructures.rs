@@ -1,8 +1,9 @@
+use crate::game::content;
 use once_cell::sync::Lazy;
 use rand::Rng;
 use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use super::generate_loot;
 use crate::game::{
@@ -54,8 +55,8 @@ pub struct PlacedMicroStructure {
     pub spawned_items: Vec<usize>,
 }
 
-static MICROSTRUCTURE_DEFS: Lazy<HashMap<String, MicroStructureDef>> = Lazy::new(|| {
-    let data = include_str!("../../../data/microstructures.json");
+static MICROSTRUCTURE_DEFS: Lazy<BTreeMap<String, MicroStructureDef>> = Lazy::new(|| {
+    let data = &content::load("microstructures.json");
     let defs: Vec<MicroStructureDef> =
         serde_json::from_str(data).expect("Failed to parse microstructures.json");
     defs.into_iter().map(|def| (def.id.clone(), def)).collect()
@@ -278,7 +279,7 @@ fn spawn_structure_entities(
                 let mut chest = Chest::new(spawn_x, spawn_y, &spawn.id);
                 if let Some(def) = crate::game::chest::get_chest_def(&spawn.id) {
                     if let Some(loot_table) = &def.loot_table {
-                        let loot = generate_loot(loot_table, spawn_x, spawn_y, rng);
+                        let loot = generate_loot(loot_table, rng);
                         for item in loot {
                             chest.add_item(item);
                         }
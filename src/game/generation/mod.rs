@@ -21,6 +21,7 @@ pub mod spawn;
 pub mod feature_registry;
 pub mod feature_materializer;
 pub mod story;
+pub mod subterranean;
 pub mod structures;
 pub mod templates;
 pub mod terrain_forge_adapter;
@@ -52,6 +53,7 @@ pub use registry::*;
 pub use spatial::*;
 pub use spawn::*;
 pub use story::*;
+pub use subterranean::*;
 pub use structures::*;
 pub use templates::*;
 pub use terrain_forge_adapter::*;
@@ -1,3 +1,4 @@
+use crate::game::content;
 use once_cell::sync::Lazy;
 use rand::Rng;
 use rand_chacha::ChaCha8Rng;
@@ -386,7 +387,7 @@ struct NarrativeFile {
 }
 
 static NARRATIVE_SEEDS: Lazy<HashMap<String, NarrativeSeed>> = Lazy::new(|| {
-    let data = include_str!("../../../data/narrative_integration.json");
+    let data = &content::load("narrative_integration.json");
     let file: NarrativeFile =
         serde_json::from_str(data).expect("Failed to parse narrative_integration.json");
     file.narrative_seeds
@@ -396,7 +397,7 @@ static NARRATIVE_SEEDS: Lazy<HashMap<String, NarrativeSeed>> = Lazy::new(|| {
 });
 
 static STORY_FRAGMENTS: Lazy<HashMap<String, StoryFragment>> = Lazy::new(|| {
-    let data = include_str!("../../../data/narrative_integration.json");
+    let data = &content::load("narrative_integration.json");
     let file: NarrativeFile =
         serde_json::from_str(data).expect("Failed to parse narrative_integration.json");
     file.story_fragments
@@ -406,7 +407,7 @@ static STORY_FRAGMENTS: Lazy<HashMap<String, StoryFragment>> = Lazy::new(|| {
 });
 
 static FACTION_INFLUENCES: Lazy<HashMap<String, FactionInfluence>> = Lazy::new(|| {
-    let data = include_str!("../../../data/narrative_integration.json");
+    let data = &content::load("narrative_integration.json");
     let file: NarrativeFile =
         serde_json::from_str(data).expect("Failed to parse narrative_integration.json");
     file.faction_influences
@@ -1,4 +1,5 @@
 use super::{WeightedEntry, WeightedTable};
+use crate::game::content;
 use crate::game::world_map::Biome;
 use once_cell::sync::Lazy;
 use rand::Rng;
@@ -52,7 +53,7 @@ pub struct SpawnTables {
 }
 
 static SPAWN_TABLES: Lazy<SpawnTables> = Lazy::new(|| {
-    let data = include_str!("../../../data/biome_spawn_tables.json");
+    let data = &content::load("biome_spawn_tables.json");
     serde_json::from_str(data).expect("Failed to parse biome_spawn_tables.json")
 });
 
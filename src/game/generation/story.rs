@@ -158,7 +158,9 @@ impl StoryModel {
             "The Salt Prophet",
             "Archive Keeper Zara",
         ];
-        let factions: Vec<String> = self.faction_dynamics.keys().cloned().collect();
+        // Sorted so the same seed assigns the same factions
+        let mut factions: Vec<String> = self.faction_dynamics.keys().cloned().collect();
+        factions.sort();
 
         for (i, name) in names.iter().enumerate() {
             let faction = factions[i % factions.len()].clone();
@@ -191,7 +193,8 @@ impl StoryModel {
                 _ => EventType::Alliance,
             };
 
-            let char_keys: Vec<String> = self.characters.keys().cloned().collect();
+            let mut char_keys: Vec<String> = self.characters.keys().cloned().collect();
+            char_keys.sort();
             let num_participants = rng.gen_range(1..=3.min(char_keys.len()));
             let participants: Vec<String> = char_keys
                 .choose_multiple(rng, num_participants)
@@ -2,7 +2,7 @@ use crate::game::generation::structures::Rectangle;
 use rand::Rng;
 use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WaveFunctionCollapseParams {
@@ -10,7 +10,7 @@ pub struct WaveFunctionCollapseParams {
     pub overlap: u32,
     pub max_iterations: u32,
     pub entropy_heuristic: EntropyHeuristic,
-    pub pattern_weights: HashMap<String, f32>,
+    pub pattern_weights: BTreeMap<String, f32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,7 +22,7 @@ pub enum EntropyHeuristic {
 
 impl Default for WaveFunctionCollapseParams {
     fn default() -> Self {
-        let mut weights = HashMap::new();
+        let mut weights = BTreeMap::new();
         weights.insert("floor".to_string(), 0.6);
         weights.insert("wall".to_string(), 0.3);
         weights.insert("door".to_string(), 0.1);
@@ -0,0 +1,168 @@
+//! Population of underground layers
+//!
+//! Subterranean maps come from `Map::generate_subterranean` as bare rooms and
+//! corridors. This fills them with enemies, floor loot and chests drawn from the
+//! surface tile's spawn table, with the effective level rising with depth.
+
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+
+use super::{generate_loot, get_biome_spawn_table, weighted_pick_by_level_and_tier};
+use crate::game::{
+    chest::{Chest, get_chest_def},
+    enemy::Enemy,
+    item::Item,
+    map::{Map, Tile},
+    world_map::Biome,
+};
+
+/// Highest level used by the spawn tables
+const MAX_SPAWN_LEVEL: u32 = 10;
+
+/// Entities generated for a fresh underground layer
+#[derive(Default)]
+pub struct SubterraneanPopulation {
+    pub enemies: Vec<Enemy>,
+    pub items: Vec<Item>,
+    pub chests: Vec<Chest>,
+}
+
+/// Spawn level for a layer: each level down adds two to the surface level
+pub fn subterranean_level(surface_level: u32, layer: i32) -> u32 {
+    (surface_level.max(1) + layer.unsigned_abs() * 2).min(MAX_SPAWN_LEVEL)
+}
+
+/// Chest type found at a given depth; deeper layers hold better containers
+fn chest_for_depth(depth: u32, rng: &mut ChaCha8Rng) -> &'static str {
+    match (depth, rng.gen_range(0..3)) {
+        (1, 0) => "supply_crate",
+        (1, _) => "wooden_chest",
+        (2, 0) => "glass_cache",
+        (2, _) => "metal_strongbox",
+        (_, 0) => "glass_cache",
+        _ => "archive_container",
+    }
+}
+
+/// Populate an underground layer. `rooms` are the room centers returned by
+/// `Map::generate_subterranean`; the first room (stairs up) is kept clear of enemies.
+pub fn populate_subterranean(
+    map: &Map,
+    rooms: &[(i32, i32)],
+    biome: &Biome,
+    surface_level: u32,
+    layer: i32,
+    rng: &mut ChaCha8Rng,
+) -> SubterraneanPopulation {
+    let mut population = SubterraneanPopulation::default();
+    if rooms.len() < 2 {
+        return population;
+    }
+
+    let depth = layer.unsigned_abs();
+    let level = subterranean_level(surface_level, layer);
+    let table = get_biome_spawn_table(biome);
+    let mut occupied: Vec<(i32, i32)> = vec![rooms[0]];
+    if let Some(&last) = rooms.last() {
+        occupied.push(last);
+    }
+
+    let deeper_rooms = &rooms[1..];
+
+    // Enemies: more of them the deeper you go
+    let enemy_count = 2 + depth as usize * 2;
+    for _ in 0..enemy_count {
+        let Some(pos) = free_floor_near(map, deeper_rooms, &occupied, rng) else {
+            break;
+        };
+        if let Some(id) = weighted_pick_by_level_and_tier(&table.enemies, level, rng, false) {
+            population.enemies.push(Enemy::new(pos.0, pos.1, id));
+            occupied.push(pos);
+        }
+    }
+
+    // Floor loot, anywhere including the arrival room
+    let item_count = 1 + depth as usize;
+    for _ in 0..item_count {
+        let Some(pos) = free_floor_near(map, rooms, &occupied, rng) else {
+            break;
+        };
+        if let Some(id) = weighted_pick_by_level_and_tier(&table.items, level, rng, true) {
+            population.items.push(Item::new(pos.0, pos.1, id));
+            occupied.push(pos);
+        }
+    }
+
+    // One chest per layer, plus a second on deeper layers
+    let chest_count = if depth >= 3 { 2 } else { 1 };
+    for _ in 0..chest_count {
+        let Some(pos) = free_floor_near(map, deeper_rooms, &occupied, rng) else {
+            break;
+        };
+        let chest_id = chest_for_depth(depth, rng);
+        let loot = get_chest_def(chest_id)
+            .and_then(|def| def.loot_table.as_deref())
+            .map(|table_id| generate_loot(table_id, rng))
+            .unwrap_or_default();
+        population
+            .chests
+            .push(Chest::new_with_loot(pos.0, pos.1, chest_id, loot));
+        occupied.push(pos);
+    }
+
+    population
+}
+
+/// Pick an unoccupied floor tile within a few tiles of a random room center
+fn free_floor_near(
+    map: &Map,
+    rooms: &[(i32, i32)],
+    occupied: &[(i32, i32)],
+    rng: &mut ChaCha8Rng,
+) -> Option<(i32, i32)> {
+    for _ in 0..20 {
+        let (cx, cy) = rooms[rng.gen_range(0..rooms.len())];
+        let x = cx + rng.gen_range(-2..=2);
+        let y = cy + rng.gen_range(-2..=2);
+        let floor = match map.get(x, y) {
+            Some(Tile::StairsUp | Tile::StairsDown) | None => false,
+            Some(tile) => tile.walkable(),
+        };
+        if floor && !occupied.contains(&(x, y)) {
+            return Some((x, y));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn deeper_layers_are_harder() {
+        assert_eq!(subterranean_level(1, -1), 3);
+        assert_eq!(subterranean_level(2, -3), 8);
+        assert_eq!(subterranean_level(7, -5), MAX_SPAWN_LEVEL);
+    }
+
+    #[test]
+    fn layer_is_populated_off_the_stairs() {
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        let (map, rooms) = Map::generate_subterranean(&mut rng, -2);
+        let pop = populate_subterranean(&map, &rooms, &Biome::Saltflat, 2, -2, &mut rng);
+
+        assert!(!pop.enemies.is_empty());
+        assert!(!pop.chests.is_empty());
+        for (x, y) in pop
+            .enemies
+            .iter()
+            .map(|e| (e.x, e.y))
+            .chain(pop.chests.iter().map(|c| (c.x, c.y)))
+        {
+            assert!(map.get(x, y).unwrap().walkable());
+            assert_ne!((x, y), rooms[0]);
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::game::content;
 use once_cell::sync::Lazy;
 use rand::distributions::{Distribution, WeightedIndex};
 use rand::{Rng, SeedableRng};
@@ -52,7 +53,7 @@ fn default_variation_intensity() -> f64 {
 }
 
 static TILE_CONFIG: Lazy<TileGenConfig> = Lazy::new(|| {
-    let data = include_str!("../../../data/terrain_config.json");
+    let data = &content::load("terrain_config.json");
     serde_json::from_str(data).expect("Failed to parse terrain_config.json")
 });
 
@@ -321,13 +321,15 @@ impl WorldGenerator {
         // First, place quest-critical POIs at fixed locations
         self.place_quest_pois(&mut pois, &mut poi_positions);
 
-        for (poi_name, poi_config) in &self.config.poi_distribution {
-            let poi_type = match poi_name.as_str() {
-                "town" => POI::Town,
-                "dungeon" => POI::Dungeon,
-                "landmark" => POI::Landmark,
-                "shrine" => POI::Shrine,
-                _ => continue,
+        // Place in a fixed order; map iteration order would vary between runs
+        for (poi_name, poi_type) in [
+            ("town", POI::Town),
+            ("dungeon", POI::Dungeon),
+            ("landmark", POI::Landmark),
+            ("shrine", POI::Shrine),
+        ] {
+            let Some(poi_config) = self.config.poi_distribution.get(poi_name) else {
+                continue;
             };
 
             for _ in 0..poi_config.count {
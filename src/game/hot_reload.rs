@@ -0,0 +1,266 @@
+//! Reloading item, enemy, faction, status effect, loot and affix definitions
+//! while the game runs
+//!
+//! The definitions are read again from the data directory and mods (see
+//! `content::reload_config`) and swapped into their registries. Entities refer
+//! to definitions by id, so they pick up the new ones on their next lookup;
+//! values copied from a definition when an entity was created, such as enemy
+//! HP and status effect names, are brought in line here.
+
+use std::collections::{BTreeSet, HashMap};
+
+use super::GameState;
+use super::affix::reload_affix_defs;
+use super::content::{self, Changes};
+use super::enemy::{Enemy, get_enemy_def, reload_enemy_defs};
+use super::faction::reload_faction_defs;
+use super::generation::loot::reload_loot_tables;
+use super::item::{get_item_def, reload_item_defs};
+use super::status::{StatusEffect, get_status_def, reload_status_defs};
+
+/// Swaps in the definitions parsed from a file's text
+pub type Reload = fn(&str) -> Result<Changes, String>;
+
+/// Files that can be reloaded, with the function swapping in their definitions
+pub const RELOADABLE_FILES: &[(&str, Reload)] = &[
+    ("items.json", reload_item_defs),
+    ("enemies.json", reload_enemy_defs),
+    ("factions.json", reload_faction_defs),
+    ("status_effects.json", reload_status_defs),
+    ("loot_tables.json", reload_loot_tables),
+    ("affixes.json", reload_affix_defs),
+];
+
+/// What a reload changed
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReloadReport {
+    /// Changes per file that was swapped in
+    pub changes: Vec<Changes>,
+    /// Files that failed to load; their previous definitions are kept
+    pub errors: Vec<String>,
+    /// Live entities whose definition no longer exists, e.g. "enemy husk"
+    pub missing: Vec<String>,
+}
+
+impl GameState {
+    /// Reload definitions from disk and update live entities to match
+    pub fn reload_content(&mut self) -> ReloadReport {
+        let content = content::rebuild();
+        let mut report = ReloadReport {
+            errors: content.report().errors.clone(),
+            ..Default::default()
+        };
+        let old_max_hp: HashMap<String, i32> = self
+            .enemies
+            .iter()
+            .chain(self.tile_store.iter().flat_map(|(_, tile)| &tile.enemies))
+            .filter_map(|e| e.def().map(|d| (e.id.clone(), d.max_hp)))
+            .collect();
+
+        for &(file, reload) in RELOADABLE_FILES {
+            match reload(&content.text(file)) {
+                Ok(changes) => report.changes.push(changes),
+                Err(e) => report.errors.push(e),
+            }
+        }
+
+        self.refresh_definitions(&old_max_hp, &mut report);
+        self.log_reload(&report);
+        report
+    }
+
+    /// Bring values copied from definitions in line with the current ones
+    fn refresh_definitions(
+        &mut self,
+        old_max_hp: &HashMap<String, i32>,
+        report: &mut ReloadReport,
+    ) {
+        let mut missing = BTreeSet::new();
+
+        refresh_enemies(&mut self.enemies, old_max_hp, &mut missing);
+        for (_, tile) in self.tile_store.iter_mut() {
+            refresh_enemies(&mut tile.enemies, old_max_hp, &mut missing);
+        }
+        refresh_status_effects(&mut self.status_effects, &mut missing);
+
+        let item_ids = self
+            .items
+            .iter()
+            .map(|item| &item.instance)
+            .chain(&self.inventory)
+            .chain(self.equipment.iter().filter_map(|(_, item)| item))
+            .chain(self.chests.iter().flat_map(|c| &c.inventory))
+            .chain(self.tile_store.iter().flat_map(|(_, tile)| {
+                tile.items
+                    .iter()
+                    .map(|item| &item.instance)
+                    .chain(tile.chests.iter().flat_map(|c| &c.inventory))
+            }))
+            .map(|item| &item.id);
+        for id in item_ids {
+            if get_item_def(id).is_none() {
+                missing.insert(format!("item {}", id));
+            }
+        }
+
+        self.recalc_equipment_stats();
+        report.missing = missing.into_iter().collect();
+    }
+
+    fn log_reload(&mut self, report: &ReloadReport) {
+        let changed: Vec<&Changes> = report.changes.iter().filter(|c| !c.is_empty()).collect();
+        if changed.is_empty() && report.errors.is_empty() {
+            self.log("Content reloaded: no changes");
+        }
+        for changes in changed {
+            self.log(format!("Reloaded {}", changes));
+        }
+        for error in &report.errors {
+            self.log(format!("Reload error: {}", error));
+        }
+        if !report.missing.is_empty() {
+            self.log(format!(
+                "Live entities without a definition: {}",
+                report.missing.join(", ")
+            ));
+        }
+    }
+}
+
+/// Rescale HP to new maximums and refresh status effects, noting enemies
+/// whose definition is gone
+fn refresh_enemies(
+    enemies: &mut [Enemy],
+    old_max_hp: &HashMap<String, i32>,
+    missing: &mut BTreeSet<String>,
+) {
+    for enemy in enemies {
+        let Some(def) = get_enemy_def(&enemy.id) else {
+            missing.insert(format!("enemy {}", enemy.id));
+            continue;
+        };
+        // Keep the enemy's share of its maximum HP
+        if let Some(&old_max) = old_max_hp.get(&enemy.id)
+            && old_max != def.max_hp
+            && old_max > 0
+            && enemy.hp > 0
+        {
+            enemy.hp = (enemy.hp * def.max_hp / old_max).clamp(1, def.max_hp.max(1));
+        }
+        refresh_status_effects(&mut enemy.status_effects, missing);
+    }
+}
+
+fn refresh_status_effects(effects: &mut [StatusEffect], missing: &mut BTreeSet<String>) {
+    for effect in effects {
+        match get_status_def(&effect.id) {
+            Some(def) => {
+                effect.name = def.name.clone();
+                if def.max_stacks > 0 {
+                    effect.stacks = effect.stacks.min(def.max_stacks);
+                }
+            }
+            None => {
+                missing.insert(format!("status {}", effect.id));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::item::Item;
+    use crate::game::map::Map;
+    use crate::game::tile_store::{TileKey, TileSnapshot};
+
+    #[test]
+    fn live_entities_follow_reloaded_definitions() {
+        let mut state = GameState::new(42);
+        let mut enemy = Enemy::new(1, 1, "mirage_hound");
+        let max_hp = enemy.def().unwrap().max_hp;
+        enemy.hp = max_hp;
+        let mut poisoned = StatusEffect::new("poison", 3);
+        poisoned.name = "Old Name".to_string();
+        enemy.status_effects.push(poisoned);
+        state.enemies = vec![enemy, Enemy::new(2, 2, "retired_enemy")];
+        state.items = vec![Item::new(3, 3, "retired_item")];
+        state.status_effects = vec![StatusEffect::new("retired_status", 2)];
+        state.inventory.clear();
+        state.chests.clear();
+        state.equipment = Default::default();
+
+        // As if mirage hounds had twice the HP before the reload
+        let old_max_hp = HashMap::from([("mirage_hound".to_string(), max_hp * 2)]);
+        let mut report = ReloadReport::default();
+        state.refresh_definitions(&old_max_hp, &mut report);
+
+        let hound = &state.enemies[0];
+        assert_eq!(hound.hp, max_hp / 2);
+        assert_eq!(
+            hound.status_effects[0].name,
+            get_status_def("poison").unwrap().name
+        );
+        assert_eq!(
+            report.missing,
+            vec![
+                "enemy retired_enemy",
+                "item retired_item",
+                "status retired_status"
+            ]
+        );
+    }
+
+    #[test]
+    fn stashed_tiles_follow_reloaded_definitions() {
+        let mut state = GameState::new(42);
+        state.enemies.clear();
+        state.items.clear();
+        state.inventory.clear();
+        state.chests.clear();
+        state.status_effects.clear();
+        state.equipment = Default::default();
+        let mut hound = Enemy::new(1, 1, "mirage_hound");
+        let max_hp = hound.def().unwrap().max_hp;
+        hound.hp = max_hp;
+        state.tile_store.insert(
+            TileKey::new(state.world_x, state.world_y, -1),
+            TileSnapshot {
+                map: Map::new(0, 0),
+                enemies: vec![hound, Enemy::new(2, 2, "retired_enemy")],
+                npcs: Vec::new(),
+                items: vec![Item::new(3, 3, "retired_item")],
+                chests: Vec::new(),
+                interactables: Vec::new(),
+                microstructures: Vec::new(),
+                revealed: Default::default(),
+            },
+        );
+
+        let old_max_hp = HashMap::from([("mirage_hound".to_string(), max_hp * 2)]);
+        let mut report = ReloadReport::default();
+        state.refresh_definitions(&old_max_hp, &mut report);
+
+        let (_, tile) = state.tile_store.iter().next().unwrap();
+        assert_eq!(tile.enemies[0].hp, max_hp / 2);
+        assert_eq!(
+            report.missing,
+            vec!["enemy retired_enemy", "item retired_item"]
+        );
+    }
+
+    #[test]
+    fn reloading_unchanged_content_reports_no_changes() {
+        let mut state = GameState::new(42);
+        let report = state.reload_content();
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert_eq!(report.changes.len(), RELOADABLE_FILES.len());
+        assert!(report.changes.iter().all(Changes::is_empty));
+        assert!(
+            state
+                .messages
+                .iter()
+                .any(|m| m.text == "Content reloaded: no changes")
+        );
+    }
+}
@@ -1,7 +1,8 @@
 //! Inspection and query functions for game state
 
 use super::combat::get_weapon_def;
-use super::item::get_item_def;
+use super::cover::Cover;
+use super::item::{ItemInstance, get_item_def};
 use super::light_defs::get_light_def;
 use super::state::GameState;
 
@@ -30,6 +31,7 @@ pub fn inspect_item(id: &str) -> Option<ItemInfo> {
                 ("Accuracy".into(), format!("{}%", w.accuracy)),
                 ("Range".into(), w.range.to_string()),
                 ("AP Cost".into(), w.ap_cost.to_string()),
+                ("Damage Type".into(), w.damage_type.name().into()),
             ],
         });
     }
@@ -48,24 +50,21 @@ pub fn inspect_item(id: &str) -> Option<ItemInfo> {
 
     add_stat!("value", d.value > 0, d.value.to_string());
     add_stat!("weight", d.weight > 0, d.weight.to_string());
-    add_stat!("heal", d.heal > 0, format!("+{} HP", d.heal));
     add_stat!(
         "armor_value",
         d.armor_value > 0,
         format!("+{}", d.armor_value)
     );
-    add_stat!(
-        "reduces_refraction",
-        d.reduces_refraction > 0,
-        format!("-{}", d.reduces_refraction)
-    );
-    add_stat!("reveals_map", d.reveals_map, "Reveals map".into());
-    add_stat!(
-        "suppresses_adaptations",
-        d.suppresses_adaptations,
-        "Hides adaptations".into()
-    );
-    add_stat!("breaks_walls", d.breaks_walls, "Breaks walls".into());
+    for (kind, percent) in &d.resistances {
+        add_stat!(
+            format!("{}_resistance", kind.name()).as_str(),
+            true,
+            format!("{:+}%", percent)
+        );
+    }
+    for (key, text) in d.use_effects.iter().filter_map(|e| e.describe()) {
+        add_stat!(key.as_str(), true, text);
+    }
     add_stat!(
         "equip_slot",
         d.equip_slot.is_some(),
@@ -88,10 +87,56 @@ pub fn inspect_item(id: &str) -> Option<ItemInfo> {
     })
 }
 
+/// Get info for one copy of an item: its base stats plus its name, quantity,
+/// condition, affixes and value as it is now
+pub fn inspect_instance(item: &ItemInstance) -> Option<ItemInfo> {
+    let mut info = inspect_item(&item.id)?;
+    info.name = item.name();
+    info.stats.retain(|(key, _)| key != "value");
+    if item.quantity > 1 {
+        info.stats
+            .push(("quantity".into(), item.quantity.to_string()));
+    }
+    if let Some(durability) = item.durability {
+        info.stats.push((
+            "durability".into(),
+            format!(
+                "{}/{} ({})",
+                durability,
+                item.max_durability().unwrap_or(durability),
+                item.condition().label()
+            ),
+        ));
+    }
+    for affix in item.affix_defs() {
+        let mut bonuses = Vec::new();
+        if affix.damage != 0 {
+            bonuses.push(format!("{:+} damage", affix.damage));
+        }
+        if affix.armor != 0 {
+            bonuses.push(format!("{:+} armor", affix.armor));
+        }
+        for (kind, percent) in &affix.resistances {
+            bonuses.push(format!("{:+}% {} resistance", percent, kind.name()));
+        }
+        if affix.durability != 0 {
+            bonuses.push(format!("{:+} durability", affix.durability));
+        }
+        info.stats.push((
+            "affix".into(),
+            format!("{} ({})", affix.name, bonuses.join(", ")),
+        ));
+    }
+    if item.value() > 0 {
+        info.stats.push(("value".into(), item.value().to_string()));
+    }
+    Some(info)
+}
+
 impl GameState {
     /// Inspect an item in inventory by index
     pub fn inspect_inventory(&self, idx: usize) -> Option<ItemInfo> {
-        self.inventory.get(idx).and_then(|id| inspect_item(id))
+        self.inventory.get(idx).and_then(inspect_instance)
     }
     /// Describe what's at a given position (for look mode)
     pub fn describe_at(&self, x: i32, y: i32) -> String {
@@ -102,17 +147,26 @@ impl GameState {
         if x == self.player_x && y == self.player_y {
             return "You".into();
         }
-        if let Some(ei) = self.enemy_at(x, y) {
-            let e = &self.enemies[ei];
+        if let Some(e) = self.enemy_at(x, y).and_then(|id| self.enemy(id)) {
             let desc = e
                 .def()
                 .map(|d| d.description.as_str())
                 .unwrap_or("A creature");
-            let demeanor = format!("{:?}", e.demeanor()).to_lowercase();
-            return format!("{} (HP: {}, {}) - {}", e.name(), e.hp, demeanor, desc);
+            let demeanor = self.demeanor_label(e);
+            let cover = match self.cover_between((self.player_x, self.player_y), (x, y)) {
+                Cover::None => String::new(),
+                cover => format!(", {}", cover.label()),
+            };
+            return format!(
+                "{} (HP: {}, {}{}) - {}",
+                e.name(),
+                e.hp,
+                demeanor,
+                cover,
+                desc
+            );
         }
-        if let Some(ni) = self.npc_at(x, y) {
-            let n = &self.npcs[ni];
+        if let Some(n) = self.npc_at(x, y).and_then(|id| self.npc(id)) {
             let desc = n
                 .def()
                 .map(|d| d.description.as_str())
@@ -127,7 +181,7 @@ impl GameState {
             return info;
         }
         if let Some(item) = self.items.iter().find(|i| i.x == x && i.y == y) {
-            if let Some(def) = get_item_def(&item.id) {
+            if let Some(def) = item.def() {
                 let item_type = if def.armor_value > 0 {
                     "armor"
                 } else if def.usable {
@@ -135,9 +189,9 @@ impl GameState {
                 } else {
                     "item"
                 };
-                return format!("{} ({}) - {}", def.name, item_type, def.description);
+                return format!("{} ({}) - {}", item.name(), item_type, def.description);
             }
-            if let Some(wdef) = get_weapon_def(&item.id) {
+            if let Some(wdef) = get_weapon_def(item.id()) {
                 return format!("{} (weapon) - {}", wdef.name, wdef.description);
             }
         }
@@ -1,6 +1,8 @@
+use crate::game::content;
+use crate::game::cover::Cover;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct InteractableDef {
@@ -11,6 +13,9 @@ pub struct InteractableDef {
     pub interaction_type: String,
     pub states: Vec<String>,
     pub messages: InteractableMessages,
+    /// Cover given to whoever stands next to it
+    #[serde(default)]
+    pub cover: Cover,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -85,8 +90,8 @@ struct InteractablesFile {
     interactables: Vec<InteractableDef>,
 }
 
-static INTERACTABLES: Lazy<HashMap<String, InteractableDef>> = Lazy::new(|| {
-    let data = include_str!("../../data/interactables.json");
+static INTERACTABLES: Lazy<BTreeMap<String, InteractableDef>> = Lazy::new(|| {
+    let data = &content::load("interactables.json");
     let file: InteractablesFile =
         serde_json::from_str(data).expect("Failed to parse interactables.json");
     file.interactables
@@ -0,0 +1,264 @@
+//! The player's inventory
+//!
+//! The inventory is a list of `ItemInstance`s. Copies of a stackable item
+//! share a slot up to its `max_stack`. Quests, recipes, rituals and dialogue
+//! ask for items by id and take them one unit at a time, whichever copy that
+//! comes from.
+//!
+//! Everything carried or worn counts against a carrying capacity that grows
+//! with max HP and the pack in the backpack slot. Going over it costs AP each
+//! turn and reflex, and more so past half as much again.
+
+use super::{
+    equipment::EquipSlot,
+    item::ItemInstance,
+    state::{GameState, MsgType},
+};
+
+/// How many units of the item `id` are in `items`
+pub fn count_items(items: &[ItemInstance], id: &str) -> u32 {
+    items
+        .iter()
+        .filter(|item| item.id == id)
+        .map(|item| item.quantity)
+        .sum()
+}
+
+/// Capacity before max HP and backpack bonuses
+pub const BASE_CARRY_CAPACITY: u32 = 20;
+
+/// How weighed down the player is
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Encumbrance {
+    Unburdened,
+    /// Carrying more than their capacity
+    Burdened,
+    /// Carrying more than half as much again
+    Overloaded,
+}
+
+impl Encumbrance {
+    pub fn label(self) -> &'static str {
+        match self {
+            Encumbrance::Unburdened => "unburdened",
+            Encumbrance::Burdened => "burdened",
+            Encumbrance::Overloaded => "overloaded",
+        }
+    }
+
+    /// AP lost at the start of each turn
+    pub fn ap_penalty(self) -> i32 {
+        match self {
+            Encumbrance::Unburdened => 0,
+            Encumbrance::Burdened => 1,
+            Encumbrance::Overloaded => 2,
+        }
+    }
+
+    pub fn reflex_penalty(self) -> i32 {
+        match self {
+            Encumbrance::Unburdened => 0,
+            Encumbrance::Burdened => 2,
+            Encumbrance::Overloaded => 5,
+        }
+    }
+}
+
+/// Add `item` to `items`, topping up matching stacks before starting new ones
+pub fn add_to_stacks(items: &mut Vec<ItemInstance>, mut item: ItemInstance) {
+    let limit = item.def().map_or(1, |d| d.stack_limit());
+    for stack in items.iter_mut() {
+        if !stack.stacks_with(&item) {
+            continue;
+        }
+        let moved = limit.saturating_sub(stack.quantity).min(item.quantity);
+        stack.quantity += moved;
+        item.quantity -= moved;
+        if item.quantity == 0 {
+            return;
+        }
+    }
+    while item.quantity > limit {
+        item.quantity -= limit;
+        items.push(ItemInstance {
+            quantity: limit,
+            ..item.clone()
+        });
+    }
+    items.push(item);
+}
+
+/// Take one unit of the item at index `idx` of `items`
+pub fn take_one(items: &mut Vec<ItemInstance>, idx: usize) -> Option<ItemInstance> {
+    let item = items.get_mut(idx)?;
+    if item.quantity > 1 {
+        item.quantity -= 1;
+        return Some(ItemInstance {
+            quantity: 1,
+            ..item.clone()
+        });
+    }
+    Some(items.remove(idx))
+}
+
+/// Take `count` units of the item `id` from `items`, or nothing if there
+/// are fewer
+pub fn take_units(
+    items: &mut Vec<ItemInstance>,
+    id: &str,
+    count: u32,
+) -> Option<Vec<ItemInstance>> {
+    if count_items(items, id) < count {
+        return None;
+    }
+    let mut taken = Vec::new();
+    for _ in 0..count {
+        let idx = items.iter().position(|item| item.id == id)?;
+        taken.extend(take_one(items, idx));
+    }
+    Some(taken)
+}
+
+impl GameState {
+    pub fn has_item(&self, id: &str) -> bool {
+        self.inventory.iter().any(|item| item.id == id)
+    }
+
+    pub fn item_count(&self, id: &str) -> u32 {
+        count_items(&self.inventory, id)
+    }
+
+    /// Put an item in the inventory
+    pub fn give_item(&mut self, item: ItemInstance) {
+        add_to_stacks(&mut self.inventory, item);
+    }
+
+    /// Take one unit of the item at inventory index `idx`
+    pub fn take_item(&mut self, idx: usize) -> Option<ItemInstance> {
+        take_one(&mut self.inventory, idx)
+    }
+
+    /// Take `count` units of the item `id`, or nothing if there are fewer
+    pub fn take_items(&mut self, id: &str, count: u32) -> bool {
+        take_units(&mut self.inventory, id, count).is_some()
+    }
+
+    /// Weight of everything carried and worn
+    pub fn carried_weight(&self) -> u32 {
+        self.inventory
+            .iter()
+            .chain(self.equipment.iter().filter_map(|(_, item)| item))
+            .map(|item| item.weight())
+            .sum()
+    }
+
+    pub fn carry_capacity(&self) -> u32 {
+        let pack = self
+            .equipment
+            .get(EquipSlot::Backpack)
+            .filter(|pack| !pack.is_broken())
+            .and_then(|pack| pack.def())
+            .map_or(0, |d| d.carry_capacity);
+        BASE_CARRY_CAPACITY + self.player_max_hp.max(0) as u32 / 2 + pack
+    }
+
+    pub fn encumbrance(&self) -> Encumbrance {
+        let (weight, capacity) = (self.carried_weight(), self.carry_capacity());
+        if weight * 2 > capacity * 3 {
+            Encumbrance::Overloaded
+        } else if weight > capacity {
+            Encumbrance::Burdened
+        } else {
+            Encumbrance::Unburdened
+        }
+    }
+
+    /// Whether picking up `item` keeps the player within their capacity
+    pub fn can_carry(&self, item: &ItemInstance) -> bool {
+        self.carried_weight() + item.weight() <= self.carry_capacity()
+    }
+
+    /// Warn the player if they are more weighed down than they were
+    pub(crate) fn note_encumbrance(&mut self, before: Encumbrance) {
+        let now = self.encumbrance();
+        if now > before {
+            self.log_typed(format!("You are {}.", now.label()), MsgType::System);
+        }
+    }
+
+    /// Wear down the item in `slot` by `amount`, noting if it breaks
+    pub fn wear_equipped(&mut self, slot: EquipSlot, amount: u32) {
+        let Some(item) = self.equipment.get_mut(slot) else {
+            return;
+        };
+        if item.wear(amount) {
+            let name = item.name();
+            self.log_typed(format!("Your {} breaks!", name), MsgType::Combat);
+            self.recalc_equipment_stats();
+        }
+    }
+
+    /// Wear down every equipped piece that is giving armor
+    pub fn wear_armor(&mut self, amount: u32) {
+        for &slot in EquipSlot::all() {
+            if self
+                .equipment
+                .get(slot)
+                .is_some_and(|item| item.armor() > 0)
+            {
+                self.wear_equipped(slot, amount);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn items_stack_up_to_their_limit() {
+        let mut state = GameState::new(42);
+        state.inventory.clear();
+        // Brine vials stack to 10
+        state.give_item(ItemInstance {
+            quantity: 8,
+            ..ItemInstance::new("brine_vial")
+        });
+        state.give_item(ItemInstance {
+            quantity: 5,
+            ..ItemInstance::new("brine_vial")
+        });
+        let stacks: Vec<u32> = state.inventory.iter().map(|i| i.quantity).collect();
+        assert_eq!(stacks, [10, 3]);
+
+        // Knives do not stack, and worn vials keep apart from fresh ones
+        state.give_item(ItemInstance::new("salt_knife"));
+        state.give_item(ItemInstance::new("salt_knife"));
+        assert_eq!(state.inventory.len(), 4);
+        let mut custom = ItemInstance::new("brine_vial");
+        custom.custom_name = Some("Last Vial".into());
+        state.give_item(custom);
+        assert_eq!(state.inventory.len(), 5);
+    }
+
+    #[test]
+    fn taking_items_splits_stacks_and_spans_copies() {
+        let mut state = GameState::new(42);
+        state.inventory.clear();
+        state.give_item(ItemInstance {
+            quantity: 12,
+            ..ItemInstance::new("brine_vial")
+        });
+        assert_eq!(state.item_count("brine_vial"), 12);
+
+        let one = state.take_item(0).unwrap();
+        assert_eq!(one.quantity, 1);
+        assert_eq!(state.inventory.len(), 2);
+
+        assert!(!state.take_items("brine_vial", 12));
+        assert_eq!(state.item_count("brine_vial"), 11);
+        assert!(state.take_items("brine_vial", 11));
+        assert!(state.inventory.is_empty());
+    }
+}
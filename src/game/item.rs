@@ -1,6 +1,12 @@
-use once_cell::sync::Lazy;
+use crate::game::affix::{AffixDef, AffixKind, get_affix_def, roll_affixes};
+use crate::game::content::{Changes, Registry};
+use crate::game::damage::{Resistances, combine};
+use crate::game::entity::EntityId;
+use crate::game::item_effect::{EffectKind, ItemEffect};
+use crate::game::throwing::ThrowDef;
+use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct EntityEffect {
@@ -26,34 +32,43 @@ pub struct ItemDef {
     pub weight: u32,
     #[serde(default)]
     pub usable: bool,
+    /// What using the item does, see `item_effect`
     #[serde(default)]
-    pub heal: i32,
+    pub use_effects: Vec<ItemEffect>,
+    /// How far away `use_item_on_tile` can target
+    #[serde(default = "default_use_range")]
+    pub use_range: i32,
+    /// How the item flies and what it does when thrown, see `throwing`
     #[serde(default)]
-    pub reveals_map: bool,
-    #[serde(default)]
-    pub suppresses_adaptations: bool,
-    #[serde(default)]
-    pub breaks_walls: bool,
+    pub throw: Option<ThrowDef>,
     #[serde(default)]
     pub reveals_storm_path: bool,
     #[serde(default)]
-    pub reduces_refraction: u32,
-    #[serde(default)]
     pub grants_invisibility: bool,
     #[serde(default)]
     pub reveals_storm_timing: bool,
     #[serde(default)]
     pub reveals_locations: bool,
+    /// Whether copies share an inventory slot, up to `max_stack`
     #[serde(default)]
     pub stackable: bool,
+    #[serde(default = "default_max_stack")]
+    pub max_stack: u32,
     #[serde(default)]
     pub grows_over_time: bool,
     #[serde(default)]
-    pub book_id: Option<String>,
-    #[serde(default)]
     pub armor_value: i32,
+    /// Hits it can take or deal before breaking; 0 for items that do not wear
+    #[serde(default)]
+    pub max_durability: u32,
+    /// Percent resistance by damage type while equipped
+    #[serde(default)]
+    pub resistances: Resistances,
     #[serde(default)]
     pub equip_slot: Option<String>,
+    /// Carrying capacity it adds while worn in the backpack slot
+    #[serde(default)]
+    pub carry_capacity: u32,
     #[serde(default)]
     pub effects: Vec<EntityEffect>,
     #[serde(default)]
@@ -62,8 +77,6 @@ pub struct ItemDef {
     pub pickup: bool,
     #[serde(default)]
     pub light_source: Option<LightSource>,
-    #[serde(default)]
-    pub enables_aria_dialogue: bool,
     #[serde(default = "default_consumable")]
     pub consumable: bool,
     #[serde(default = "default_tier")]
@@ -139,21 +152,6 @@ pub struct ItemDef {
     pub monk_artifact: bool,
     #[serde(default)]
     pub identifies_items: bool,
-    // New system integration properties
-    #[serde(default)]
-    pub light_energy: u32,
-    #[serde(default)]
-    pub teaches_light_manipulation: bool,
-    #[serde(default)]
-    pub void_exposure: u32,
-    #[serde(default)]
-    pub void_energy: u32,
-    #[serde(default)]
-    pub teaches_crystal_resonance: bool,
-    #[serde(default)]
-    pub resonance_energy: u32,
-    #[serde(default)]
-    pub crystal_frequency: Option<String>,
 }
 
 fn default_pickup() -> bool {
@@ -165,44 +163,387 @@ fn default_consumable() -> bool {
 fn default_tier() -> u32 {
     1
 }
+fn default_use_range() -> i32 {
+    1
+}
+fn default_max_stack() -> u32 {
+    20
+}
+
+impl ItemDef {
+    /// Most units one inventory slot holds
+    pub fn stack_limit(&self) -> u32 {
+        if self.stackable {
+            self.max_stack.max(1)
+        } else {
+            1
+        }
+    }
+
+    /// Whether the item can damage walls, as a tool or when used on them
+    pub fn breaks_walls(&self) -> bool {
+        self.use_effects
+            .iter()
+            .any(|e| matches!(e.kind, EffectKind::BreakWall { .. }))
+    }
+}
 
 #[derive(Deserialize)]
 struct ItemsFile {
     items: Vec<ItemDef>,
 }
 
-static ITEM_DEFS: Lazy<HashMap<String, ItemDef>> = Lazy::new(|| {
-    let data = include_str!("../../data/items.json");
-    let file: ItemsFile = serde_json::from_str(data).expect("Failed to parse items.json");
-    file.items.into_iter().map(|d| (d.id.clone(), d)).collect()
-});
+static ITEM_DEFS: Registry<BTreeMap<String, ItemDef>> = Registry::new("items.json", parse_items);
+
+fn parse_items(data: &str) -> Result<BTreeMap<String, ItemDef>, String> {
+    let file: ItemsFile = serde_json::from_str(data).map_err(|e| e.to_string())?;
+    Ok(file.items.into_iter().map(|d| (d.id.clone(), d)).collect())
+}
 
 pub fn get_item_def(id: &str) -> Option<&'static ItemDef> {
-    ITEM_DEFS.get(id)
+    ITEM_DEFS.get().get(id)
 }
 
 pub fn all_item_ids() -> Vec<&'static str> {
-    ITEM_DEFS.keys().map(|s| s.as_str()).collect()
+    ITEM_DEFS.get().keys().map(|s| s.as_str()).collect()
+}
+
+/// Replace the item definitions with those parsed from `data`
+pub fn reload_item_defs(data: &str) -> Result<Changes, String> {
+    ITEM_DEFS.replace(data)
+}
+
+/// How worn an item is, from its durability
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Condition {
+    Broken,
+    Damaged,
+    Worn,
+    Pristine,
+}
+
+impl Condition {
+    pub fn label(self) -> &'static str {
+        match self {
+            Condition::Broken => "broken",
+            Condition::Damaged => "damaged",
+            Condition::Worn => "worn",
+            Condition::Pristine => "pristine",
+        }
+    }
+
+    /// Percent of its full value an item in this condition trades for
+    pub fn value_percent(self) -> u32 {
+        match self {
+            Condition::Broken => 10,
+            Condition::Damaged => 50,
+            Condition::Worn => 80,
+            Condition::Pristine => 100,
+        }
+    }
+}
+
+/// One carried or stored copy of an item. Copies of the same item can differ
+/// in wear, affixes and name.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "InstanceRepr")]
+pub struct ItemInstance {
+    pub id: String,
+    pub quantity: u32,
+    /// Durability left; `None` for items that do not wear
+    pub durability: Option<u32>,
+    /// Affix ids from affixes.json, prefix first
+    pub affixes: Vec<String>,
+    /// Name given to this copy, shown instead of the generated one
+    pub custom_name: Option<String>,
+}
+
+/// Inventories and equipment held bare item ids before they held instances,
+/// and content may still name an item by id alone
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum InstanceRepr {
+    Id(String),
+    Instance {
+        id: String,
+        #[serde(default = "default_quantity")]
+        quantity: u32,
+        #[serde(default)]
+        durability: Option<u32>,
+        #[serde(default)]
+        affixes: Vec<String>,
+        #[serde(default)]
+        custom_name: Option<String>,
+    },
+}
+
+fn default_quantity() -> u32 {
+    1
+}
+
+impl From<InstanceRepr> for ItemInstance {
+    fn from(repr: InstanceRepr) -> Self {
+        match repr {
+            InstanceRepr::Id(id) => ItemInstance::new(&id),
+            InstanceRepr::Instance {
+                id,
+                quantity,
+                durability,
+                affixes,
+                custom_name,
+            } => {
+                let mut item = ItemInstance {
+                    id,
+                    quantity,
+                    durability: None,
+                    affixes,
+                    custom_name,
+                };
+                // Copies saved before their item started to wear get full durability
+                item.durability = item.max_durability().map(|max| durability.unwrap_or(max));
+                item
+            }
+        }
+    }
+}
+
+impl ItemInstance {
+    /// A fresh copy of the item `id`
+    pub fn new(id: &str) -> Self {
+        let mut item = Self {
+            id: id.to_string(),
+            quantity: 1,
+            durability: None,
+            affixes: Vec::new(),
+            custom_name: None,
+        };
+        item.durability = item.max_durability();
+        item
+    }
+
+    /// A copy of `id` found in loot of `tier`, with random affixes
+    pub fn rolled(id: &str, tier: u32, rng: &mut ChaCha8Rng) -> Self {
+        let mut item = Self::new(id);
+        if let Some(def) = item.def() {
+            item.affixes = roll_affixes(def, tier.max(def.tier), rng);
+            item.durability = item.max_durability();
+        }
+        item
+    }
+
+    pub fn def(&self) -> Option<&'static ItemDef> {
+        get_item_def(&self.id)
+    }
+
+    pub fn affix_defs(&self) -> impl Iterator<Item = &'static AffixDef> + '_ {
+        self.affixes.iter().filter_map(|id| get_affix_def(id))
+    }
+
+    /// Name with affixes, e.g. "Keen Salt Knife of the Dunes"
+    pub fn name(&self) -> String {
+        if let Some(name) = &self.custom_name {
+            return name.clone();
+        }
+        let base = self.def().map(|d| d.name.as_str()).unwrap_or(&self.id);
+        let mut words = Vec::new();
+        words.extend(
+            self.affix_defs()
+                .filter(|a| a.kind == AffixKind::Prefix)
+                .map(|a| a.name.as_str()),
+        );
+        words.push(base);
+        words.extend(
+            self.affix_defs()
+                .filter(|a| a.kind == AffixKind::Suffix)
+                .map(|a| a.name.as_str()),
+        );
+        words.join(" ")
+    }
+
+    /// Name with quantity and wear, as listed in inventories
+    pub fn display_name(&self) -> String {
+        let mut name = self.name();
+        if self.quantity > 1 {
+            name = format!("{} x{}", name, self.quantity);
+        }
+        match self.condition() {
+            Condition::Pristine => name,
+            condition => format!("{} ({})", name, condition.label()),
+        }
+    }
+
+    /// Durability when new, counting affixes; `None` for items that do not wear
+    pub fn max_durability(&self) -> Option<u32> {
+        let base = self.def().map(|d| d.max_durability).unwrap_or(0);
+        if base == 0 {
+            return None;
+        }
+        let bonus: i32 = self.affix_defs().map(|a| a.durability).sum();
+        Some((base as i32 + bonus).max(1) as u32)
+    }
+
+    pub fn condition(&self) -> Condition {
+        let (Some(left), Some(max)) = (self.durability, self.max_durability()) else {
+            return Condition::Pristine;
+        };
+        match left * 100 / max {
+            0 => Condition::Broken,
+            1..50 => Condition::Damaged,
+            50..100 => Condition::Worn,
+            _ => Condition::Pristine,
+        }
+    }
+
+    pub fn is_broken(&self) -> bool {
+        self.condition() == Condition::Broken
+    }
+
+    /// Whether `other` can join this copy's stack: the same stackable item
+    /// with the same wear, affixes and name
+    pub fn stacks_with(&self, other: &ItemInstance) -> bool {
+        self.id == other.id
+            && self.def().is_some_and(|d| d.stack_limit() > 1)
+            && self.durability == other.durability
+            && self.affixes == other.affixes
+            && self.custom_name == other.custom_name
+    }
+
+    /// Weight of the whole stack
+    pub fn weight(&self) -> u32 {
+        self.def().map_or(0, |d| d.weight) * self.quantity
+    }
+
+    /// Take `amount` off its durability. Returns true if that broke it.
+    pub fn wear(&mut self, amount: u32) -> bool {
+        match &mut self.durability {
+            Some(left) if *left > 0 => {
+                *left = left.saturating_sub(amount);
+                *left == 0
+            }
+            _ => false,
+        }
+    }
+
+    /// Restore it to full durability
+    pub fn repair(&mut self) {
+        self.durability = self.max_durability();
+    }
+
+    /// Trade value of one, after affixes and wear
+    pub fn value(&self) -> u32 {
+        let base = self.def().map(|d| d.value).unwrap_or(0) as i32;
+        let percent: i32 = 100 + self.affix_defs().map(|a| a.value).sum::<i32>();
+        let value = (base * percent.max(0) / 100) as u32;
+        value * self.condition().value_percent() / 100
+    }
+
+    /// Armor it gives while equipped; broken items give none
+    pub fn armor(&self) -> i32 {
+        if self.is_broken() {
+            return 0;
+        }
+        let base = self.def().map(|d| d.armor_value).unwrap_or(0);
+        base + self.affix_defs().map(|a| a.armor).sum::<i32>()
+    }
+
+    /// Melee damage its affixes add while equipped
+    pub fn damage_bonus(&self) -> i32 {
+        if self.is_broken() {
+            return 0;
+        }
+        self.affix_defs().map(|a| a.damage).sum()
+    }
+
+    /// Resistances it gives while equipped; broken items give none
+    pub fn resistances(&self) -> Resistances {
+        if self.is_broken() {
+            return Resistances::new();
+        }
+        let base = self.def().map(|d| &d.resistances);
+        combine(
+            base.into_iter()
+                .chain(self.affix_defs().map(|a| &a.resistances)),
+        )
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(from = "ItemRepr")]
 pub struct Item {
     pub x: i32,
     pub y: i32,
-    pub id: String,
+    /// What lies here, kept whole so dropped items keep their wear and affixes
+    pub instance: ItemInstance,
+    /// Handle given by `GameState` when the entity is added
+    #[serde(default)]
+    pub entity_id: EntityId,
+}
+
+/// Items on the ground held a bare `id` before they held an instance
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ItemRepr {
+    Instance {
+        x: i32,
+        y: i32,
+        instance: ItemInstance,
+        #[serde(default)]
+        entity_id: EntityId,
+    },
+    Id {
+        x: i32,
+        y: i32,
+        id: String,
+        #[serde(default)]
+        entity_id: EntityId,
+    },
+}
+
+impl From<ItemRepr> for Item {
+    fn from(repr: ItemRepr) -> Self {
+        let (x, y, instance, entity_id) = match repr {
+            ItemRepr::Instance {
+                x,
+                y,
+                instance,
+                entity_id,
+            } => (x, y, instance, entity_id),
+            ItemRepr::Id {
+                x,
+                y,
+                id,
+                entity_id,
+            } => (x, y, ItemInstance::new(&id), entity_id),
+        };
+        Item {
+            entity_id,
+            ..Item::place(x, y, instance)
+        }
+    }
 }
 
 impl Item {
+    /// A fresh copy of the item `id` at `x`, `y`
     pub fn new(x: i32, y: i32, id: &str) -> Self {
+        Self::place(x, y, ItemInstance::new(id))
+    }
+
+    /// Put `instance` down at `x`, `y`
+    pub fn place(x: i32, y: i32, instance: ItemInstance) -> Self {
         Self {
             x,
             y,
-            id: id.to_string(),
+            instance,
+            entity_id: EntityId::NONE,
         }
     }
 
+    pub fn id(&self) -> &str {
+        &self.instance.id
+    }
+
     pub fn def(&self) -> Option<&'static ItemDef> {
-        get_item_def(&self.id)
+        self.instance.def()
     }
 
     pub fn glyph(&self) -> char {
@@ -211,7 +552,7 @@ impl Item {
             .unwrap_or('?')
     }
 
-    pub fn name(&self) -> &str {
-        self.def().map(|d| d.name.as_str()).unwrap_or("Unknown")
+    pub fn name(&self) -> String {
+        self.instance.name()
     }
 }
@@ -0,0 +1,731 @@
+//! Data-driven item use effects
+//!
+//! Items list what they do when used in `use_effects` in items.json. Each
+//! entry is an effect kind with a target and optional conditions, and
+//! `GameState::apply_item_effects` interprets the list for both `use_item`
+//! and `use_item_on_tile`:
+//!
+//! ```json
+//! "use_effects": [
+//!     { "type": "heal", "amount": 15 },
+//!     { "type": "modify_stat", "stat": "refraction", "amount": -10,
+//!       "message": "Your glow fades slightly. ({amount} Refraction)" },
+//!     { "type": "area_damage", "damage": 6, "target": "area", "radius": 2,
+//!       "conditions": [{ "type": "chance", "percent": 75 }] }
+//! ]
+//! ```
+
+use rand::Rng;
+use serde::Deserialize;
+
+use super::crystal_resonance::CrystalFrequency;
+use super::damage::DamageType;
+use super::enemy::Enemy;
+use super::item::{Item, ItemDef};
+use super::map::{Tile, get_floor_def};
+use super::state::{GameState, MsgType};
+use super::systems::combat::CombatSystem;
+
+/// One effect of using an item
+#[derive(Clone, Debug, Deserialize)]
+pub struct ItemEffect {
+    #[serde(flatten)]
+    pub kind: EffectKind,
+    #[serde(default)]
+    pub target: EffectTarget,
+    /// Reach of `area` targets, in tiles around the target
+    #[serde(default)]
+    pub radius: i32,
+    /// All must hold for the effect to apply
+    #[serde(default)]
+    pub conditions: Vec<UseCondition>,
+    /// Replaces the default log line; `{amount}` is the signed amount applied
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// What an effect does
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EffectKind {
+    /// Restore HP, capped at max HP
+    Heal {
+        amount: i32,
+    },
+    ApplyStatus {
+        status: String,
+        duration: i32,
+    },
+    /// Move the player to the target tile, or to a random open tile within
+    /// `range` when used on themselves
+    Teleport {
+        #[serde(default)]
+        range: i32,
+    },
+    /// Reveal tiles around the target, or the whole map without a radius
+    RevealMap {
+        #[serde(default)]
+        radius: Option<i32>,
+    },
+    SpawnEntity {
+        entity: SpawnKind,
+        id: String,
+        #[serde(default = "default_count")]
+        count: u32,
+    },
+    ModifyStat {
+        stat: Stat,
+        amount: i32,
+    },
+    /// Damage enemies on the targeted tiles
+    AreaDamage {
+        damage: i32,
+        #[serde(default)]
+        damage_type: DamageType,
+    },
+    /// Damage a wall on the target tile, turning it to floor at 0 HP
+    BreakWall {
+        damage: i32,
+    },
+    OpenBook {
+        book_id: String,
+    },
+    /// Progress quests waiting on ARIA being contacted with this item
+    InterfaceAria,
+    /// Grow a crystal of the given frequency on the target tile
+    GrowCrystal {
+        frequency: String,
+    },
+    /// Turn open ground on the targeted tiles into `glass`, `glare` or a
+    /// floor from floors.json
+    SetTerrain {
+        tile: String,
+    },
+    /// Only log `message`
+    Message,
+}
+
+fn default_count() -> u32 {
+    1
+}
+
+/// Where an effect lands
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EffectTarget {
+    /// The player using the item
+    #[default]
+    User,
+    /// The tile the item is used on; the player's own tile for `use_item`
+    Tile,
+    /// Every tile within `radius` of the target tile
+    Area,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SpawnKind {
+    Enemy,
+    Item,
+}
+
+/// Player values `modify_stat` can change
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Stat {
+    MaxHp,
+    Ap,
+    Refraction,
+    AdaptationsHiddenTurns,
+    SaltScrip,
+    LightEnergy,
+    VoidExposure,
+    VoidEnergy,
+    ResonanceEnergy,
+}
+
+impl Stat {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Stat::MaxHp => "Max HP",
+            Stat::Ap => "AP",
+            Stat::Refraction => "Refraction",
+            Stat::AdaptationsHiddenTurns => "Adaptations hidden",
+            Stat::SaltScrip => "Salt Scrip",
+            Stat::LightEnergy => "Light Energy",
+            Stat::VoidExposure => "Void Exposure",
+            Stat::VoidEnergy => "Void Energy",
+            Stat::ResonanceEnergy => "Resonance Energy",
+        }
+    }
+}
+
+/// A requirement checked just before an effect applies
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum UseCondition {
+    /// Player HP below this percentage of max HP
+    HpBelow {
+        percent: i32,
+    },
+    HasStatus {
+        status: String,
+    },
+    LacksStatus {
+        status: String,
+    },
+    MinRefraction {
+        value: u32,
+    },
+    TargetIsWall,
+    TargetHasEnemy,
+    /// Succeeds with this percent chance
+    Chance {
+        percent: u32,
+    },
+}
+
+fn add(value: u32, amount: i32) -> u32 {
+    value.saturating_add_signed(amount)
+}
+
+impl ItemEffect {
+    /// Key and text shown when inspecting the item, matched against
+    /// `hidden_properties`
+    pub fn describe(&self) -> Option<(String, String)> {
+        let text = match &self.kind {
+            EffectKind::Heal { amount } => format!("+{} HP", amount),
+            EffectKind::ApplyStatus { status, duration } => {
+                format!("{} ({} turns)", status, duration)
+            }
+            EffectKind::Teleport { .. } => "Teleports".into(),
+            EffectKind::RevealMap { radius: None } => "Reveals map".into(),
+            EffectKind::RevealMap { radius: Some(r) } => format!("Reveals radius {}", r),
+            EffectKind::SpawnEntity { id, count, .. } => format!("Summons {} x{}", id, count),
+            EffectKind::ModifyStat { stat, amount } => {
+                return Some((self.key().into(), format!("{:+} {}", amount, stat.label())));
+            }
+            EffectKind::AreaDamage {
+                damage,
+                damage_type: DamageType::Physical,
+            } => format!("{} damage", damage),
+            EffectKind::AreaDamage {
+                damage,
+                damage_type,
+            } => format!("{} {} damage", damage, damage_type.name()),
+            EffectKind::BreakWall { .. } => "Breaks walls".into(),
+            EffectKind::GrowCrystal { frequency } => format!("Grows {} crystal", frequency),
+            EffectKind::SetTerrain { tile } => format!("Leaves {}", tile.replace('_', " ")),
+            EffectKind::OpenBook { .. } | EffectKind::InterfaceAria | EffectKind::Message => {
+                return None;
+            }
+        };
+        let text = match self.target {
+            EffectTarget::Area if self.radius > 0 => format!("{} (radius {})", text, self.radius),
+            _ => text,
+        };
+        Some((self.key().into(), text))
+    }
+
+    /// Name of the effect in content, or of the stat for `modify_stat`
+    pub fn key(&self) -> &'static str {
+        match &self.kind {
+            EffectKind::Heal { .. } => "heal",
+            EffectKind::ApplyStatus { .. } => "apply_status",
+            EffectKind::Teleport { .. } => "teleport",
+            EffectKind::RevealMap { .. } => "reveal_map",
+            EffectKind::SpawnEntity { .. } => "spawn_entity",
+            EffectKind::ModifyStat { stat, .. } => match stat {
+                Stat::MaxHp => "max_hp",
+                Stat::Ap => "ap",
+                Stat::Refraction => "refraction",
+                Stat::AdaptationsHiddenTurns => "adaptations_hidden_turns",
+                Stat::SaltScrip => "salt_scrip",
+                Stat::LightEnergy => "light_energy",
+                Stat::VoidExposure => "void_exposure",
+                Stat::VoidEnergy => "void_energy",
+                Stat::ResonanceEnergy => "resonance_energy",
+            },
+            EffectKind::AreaDamage { .. } => "area_damage",
+            EffectKind::BreakWall { .. } => "break_wall",
+            EffectKind::OpenBook { .. } => "open_book",
+            EffectKind::InterfaceAria => "interface_aria",
+            EffectKind::GrowCrystal { .. } => "grow_crystal",
+            EffectKind::SetTerrain { .. } => "set_terrain",
+            EffectKind::Message => "message",
+        }
+    }
+}
+
+impl GameState {
+    /// Apply an item's use effects with `(x, y)` as the target tile.
+    /// Returns how many effects applied; effects whose conditions fail or
+    /// that find nothing to act on do not count.
+    pub fn apply_item_effects(&mut self, def: &ItemDef, x: i32, y: i32) -> usize {
+        self.apply_effects(def, &def.use_effects, x, y, None)
+    }
+
+    /// Apply `effects` of the item `def` with `(x, y)` as the target tile.
+    /// With `blast`, every effect lands on those tiles instead of the ones
+    /// its own `target` picks.
+    pub(crate) fn apply_effects(
+        &mut self,
+        def: &ItemDef,
+        effects: &[ItemEffect],
+        x: i32,
+        y: i32,
+        blast: Option<&[(i32, i32)]>,
+    ) -> usize {
+        let mut applied = 0;
+        for effect in effects {
+            if !effect
+                .conditions
+                .iter()
+                .all(|c| self.use_condition_met(c, x, y))
+            {
+                continue;
+            }
+            let tiles = match blast {
+                Some(tiles) => tiles.to_vec(),
+                None => self.effect_tiles(effect, x, y),
+            };
+            if let Some(amount) = self.apply_item_effect(def, effect, x, y, tiles) {
+                applied += 1;
+                if let Some(message) = &effect.message {
+                    let text = message.replace("{amount}", &format!("{:+}", amount));
+                    self.log_typed(text, MsgType::Status);
+                }
+            }
+        }
+        applied
+    }
+
+    fn use_condition_met(&mut self, condition: &UseCondition, x: i32, y: i32) -> bool {
+        match condition {
+            UseCondition::HpBelow { percent } => {
+                self.player_hp * 100 < self.player_max_hp * percent
+            }
+            UseCondition::HasStatus { status } => self.has_status_effect(status),
+            UseCondition::LacksStatus { status } => !self.has_status_effect(status),
+            UseCondition::MinRefraction { value } => self.refraction >= *value,
+            UseCondition::TargetIsWall => matches!(self.map.get(x, y), Some(Tile::Wall { .. })),
+            UseCondition::TargetHasEnemy => self
+                .enemy_at(x, y)
+                .and_then(|id| self.enemy(id))
+                .is_some_and(|e| e.hp > 0),
+            UseCondition::Chance { percent } => self.rng.gen_range(0..100) < *percent,
+        }
+    }
+
+    /// Tiles an effect lands on, nearest rows first
+    fn effect_tiles(&self, effect: &ItemEffect, x: i32, y: i32) -> Vec<(i32, i32)> {
+        match effect.target {
+            EffectTarget::User => vec![(self.player_x, self.player_y)],
+            EffectTarget::Tile => vec![(x, y)],
+            EffectTarget::Area => {
+                let r = effect.radius.max(0);
+                (-r..=r)
+                    .flat_map(|dy| (-r..=r).map(move |dx| (x + dx, y + dy)))
+                    .filter(|&(tx, ty)| self.map.get(tx, ty).is_some())
+                    .collect()
+            }
+        }
+    }
+
+    /// Apply one effect to `tiles`, returning the amount it applied if it
+    /// took hold
+    fn apply_item_effect(
+        &mut self,
+        def: &ItemDef,
+        effect: &ItemEffect,
+        x: i32,
+        y: i32,
+        tiles: Vec<(i32, i32)>,
+    ) -> Option<i32> {
+        let default_message = effect.message.is_none();
+        match &effect.kind {
+            EffectKind::Heal { amount } => {
+                let mut total = 0;
+                let mut hit = false;
+                for (tx, ty) in tiles {
+                    if (tx, ty) == (self.player_x, self.player_y) {
+                        let heal = (*amount).min(self.player_max_hp - self.player_hp).max(0);
+                        self.player_hp += heal;
+                        if default_message {
+                            self.log_typed(
+                                format!("You use {}. (+{} HP)", def.name, heal),
+                                MsgType::Loot,
+                            );
+                        }
+                        total += heal;
+                        hit = true;
+                    } else if let Some(i) = self.live_enemy_index(tx, ty) {
+                        let max = self.enemies[i].def().map(|d| d.max_hp).unwrap_or(i32::MAX);
+                        let heal = (*amount).min(max - self.enemies[i].hp).max(0);
+                        self.enemies[i].hp += heal;
+                        self.spawn_damage_number(tx, ty, heal, true);
+                        total += heal;
+                        hit = true;
+                    }
+                }
+                hit.then_some(total)
+            }
+            EffectKind::ApplyStatus { status, duration } => {
+                let mut hit = false;
+                for (tx, ty) in tiles {
+                    if (tx, ty) == (self.player_x, self.player_y) {
+                        self.apply_status_effect(status, *duration);
+                        hit = true;
+                    } else if let Some(i) = self.live_enemy_index(tx, ty) {
+                        self.enemies[i].apply_status(status, *duration);
+                        if default_message {
+                            let name = self.enemies[i].name().to_string();
+                            self.log_typed(
+                                format!("The {} is affected by {}.", name, status),
+                                MsgType::Combat,
+                            );
+                        }
+                        hit = true;
+                    }
+                }
+                hit.then_some(*duration)
+            }
+            EffectKind::Teleport { range } => {
+                let dest = match effect.target {
+                    EffectTarget::User => self.random_open_tile(*range),
+                    _ => Some((x, y)).filter(|&(tx, ty)| self.is_open_tile(tx, ty)),
+                }?;
+                self.player_x = dest.0;
+                self.player_y = dest.1;
+                self.update_fov();
+                if default_message {
+                    self.log_typed("Space folds around you.", MsgType::Status);
+                }
+                Some(0)
+            }
+            EffectKind::RevealMap { radius } => {
+                match radius {
+                    None => self.revealed.extend(0..self.map.tiles.len()),
+                    Some(r) => {
+                        for dy in -r..=*r {
+                            for dx in -r..=*r {
+                                if self.map.get(x + dx, y + dy).is_some() {
+                                    let idx = self.map.idx(x + dx, y + dy);
+                                    self.revealed.insert(idx);
+                                }
+                            }
+                        }
+                    }
+                }
+                if default_message {
+                    self.log_typed(
+                        format!("The {} reveals hidden paths...", def.name),
+                        MsgType::Loot,
+                    );
+                }
+                Some(0)
+            }
+            EffectKind::SpawnEntity { entity, id, count } => {
+                let mut spawned = 0;
+                for (tx, ty) in self.spawn_tiles(x, y, *entity, *count as usize) {
+                    match entity {
+                        SpawnKind::Enemy => self.spawn_enemy(Enemy::new(tx, ty, id)),
+                        SpawnKind::Item => self.spawn_item(Item::new(tx, ty, id)),
+                    };
+                    spawned += 1;
+                }
+                if spawned > 0 && default_message {
+                    let name = match entity {
+                        SpawnKind::Enemy => super::enemy::get_enemy_def(id).map(|d| d.name.clone()),
+                        SpawnKind::Item => super::item::get_item_def(id).map(|d| d.name.clone()),
+                    }
+                    .unwrap_or_else(|| id.clone());
+                    self.log_typed(format!("{} appears!", name), MsgType::Status);
+                }
+                (spawned > 0).then_some(spawned)
+            }
+            EffectKind::ModifyStat { stat, amount } => {
+                let change = self.modify_stat(*stat, *amount);
+                if default_message {
+                    self.log_typed(format!("({:+} {})", change, stat.label()), MsgType::Status);
+                }
+                Some(change)
+            }
+            EffectKind::AreaDamage {
+                damage,
+                damage_type,
+            } => {
+                let mut hit = 0;
+                let mut total = 0;
+                for (tx, ty) in tiles {
+                    if (tx, ty) == (self.player_x, self.player_y) {
+                        let damage = self.damage_player(*damage, *damage_type);
+                        self.trigger_hit_flash(tx, ty);
+                        self.spawn_damage_number(tx, ty, damage, false);
+                        self.log_typed(
+                            format!("You are caught in the blast for {} damage!", damage),
+                            MsgType::Combat,
+                        );
+                        hit += 1;
+                        total += damage;
+                        continue;
+                    }
+                    let Some(i) = self.live_enemy_index(tx, ty) else {
+                        continue;
+                    };
+                    let damage = self.damage_enemy(i, *damage, *damage_type);
+                    self.enemies[i].provoked = true;
+                    self.trigger_hit_flash(tx, ty);
+                    self.spawn_damage_number(tx, ty, damage, false);
+                    let name = self.enemies[i].name().to_string();
+                    if self.enemies[i].hp <= 0 {
+                        CombatSystem::process_enemy_death(self, i, tx, ty, None);
+                        self.log_typed(format!("The {} is destroyed!", name), MsgType::Combat);
+                    } else {
+                        self.log_typed(
+                            format!("The {} takes {} damage.", name, damage),
+                            MsgType::Combat,
+                        );
+                    }
+                    hit += 1;
+                    total += damage;
+                }
+                (hit > 0).then_some(total)
+            }
+            EffectKind::BreakWall { damage } => {
+                self.map.get(x, y)?;
+                let idx = self.map.idx(x, y);
+                let Tile::Wall { hp, .. } = &mut self.map.tiles[idx] else {
+                    return None;
+                };
+                *hp -= damage;
+                let broken = *hp <= 0;
+                self.log_typed(
+                    "You strike the wall. Cracks spread through the glass.",
+                    MsgType::Combat,
+                );
+                if broken {
+                    self.map.tiles[idx] = Tile::default_floor();
+                    self.log_typed("The wall shatters!", MsgType::Combat);
+                    self.update_lighting(); // Wall break changes lighting
+                }
+                Some(*damage)
+            }
+            EffectKind::OpenBook { book_id } => {
+                self.pending_book_open = Some(book_id.clone());
+                if default_message {
+                    self.log(format!("You read {}.", def.name));
+                }
+                Some(0)
+            }
+            EffectKind::InterfaceAria => {
+                if default_message {
+                    self.log_typed("You interface with ARIA...", MsgType::System);
+                }
+                self.quest_log.on_aria_interfaced(&def.id);
+                Some(0)
+            }
+            EffectKind::GrowCrystal { frequency } => {
+                let freq = match frequency.as_str() {
+                    "beta" => CrystalFrequency::Beta,
+                    "gamma" => CrystalFrequency::Gamma,
+                    "delta" => CrystalFrequency::Delta,
+                    "epsilon" => CrystalFrequency::Epsilon,
+                    _ => CrystalFrequency::Alpha,
+                };
+                let (cx, cy) = tiles[0];
+                self.crystal_system.add_crystal(cx, cy, freq);
+                if default_message {
+                    self.log_typed(
+                        format!("A {} crystal grows at your feet!", frequency),
+                        MsgType::Loot,
+                    );
+                }
+                Some(0)
+            }
+            EffectKind::SetTerrain { tile } => {
+                let new_tile = match tile.as_str() {
+                    "glass" => Tile::Glass,
+                    "glare" => Tile::Glare,
+                    id => {
+                        get_floor_def(id)?;
+                        Tile::floor(id)
+                    }
+                };
+                let mut changed = 0;
+                for (tx, ty) in tiles {
+                    if !matches!(
+                        self.map.get(tx, ty),
+                        Some(Tile::Floor { .. } | Tile::Glass | Tile::Glare)
+                    ) {
+                        continue;
+                    }
+                    let idx = self.map.idx(tx, ty);
+                    if self.map.tiles[idx] != new_tile {
+                        self.map.tiles[idx] = new_tile.clone();
+                        changed += 1;
+                    }
+                }
+                (changed > 0).then_some(changed)
+            }
+            EffectKind::Message => Some(0),
+        }
+    }
+
+    /// Change a player stat, returning the change actually made
+    fn modify_stat(&mut self, stat: Stat, amount: i32) -> i32 {
+        match stat {
+            Stat::MaxHp => {
+                self.player_max_hp = (self.player_max_hp + amount).max(1);
+                self.player_hp = self.player_hp.min(self.player_max_hp);
+                amount
+            }
+            Stat::Ap => {
+                self.player_ap = (self.player_ap + amount).max(0);
+                amount
+            }
+            Stat::Refraction => {
+                let old = self.refraction;
+                self.refraction = add(old, amount);
+                self.refraction as i32 - old as i32
+            }
+            Stat::AdaptationsHiddenTurns => {
+                let old = self.adaptations_hidden_turns;
+                self.adaptations_hidden_turns = add(old, amount);
+                self.adaptations_hidden_turns as i32 - old as i32
+            }
+            Stat::SaltScrip => {
+                let old = self.salt_scrip;
+                self.salt_scrip = add(old, amount);
+                self.salt_scrip as i32 - old as i32
+            }
+            Stat::LightEnergy => {
+                let old = self.light_system.light_energy;
+                self.light_system.light_energy = add(old, amount);
+                self.light_system.light_energy as i32 - old as i32
+            }
+            Stat::VoidExposure => {
+                let old = self.void_system.void_exposure;
+                if amount >= 0 {
+                    if self.void_system.add_exposure(amount as u32) {
+                        self.log_typed(
+                            format!(
+                                "Void exposure level: {:?}",
+                                self.void_system.exposure_level()
+                            ),
+                            MsgType::Status,
+                        );
+                    }
+                } else {
+                    self.void_system.void_exposure = add(old, amount);
+                }
+                self.void_system.void_exposure as i32 - old as i32
+            }
+            Stat::VoidEnergy => {
+                let old = self.void_system.void_energy;
+                if amount >= 0 {
+                    self.void_system.gain_energy(amount as u32);
+                } else {
+                    self.void_system.void_energy = add(old, amount);
+                }
+                self.void_system.void_energy as i32 - old as i32
+            }
+            Stat::ResonanceEnergy => {
+                let crystal = &mut self.crystal_system;
+                let old = crystal.resonance_energy;
+                crystal.resonance_energy = add(old, amount).min(crystal.max_resonance_energy);
+                crystal.resonance_energy as i32 - old as i32
+            }
+        }
+    }
+
+    fn live_enemy_index(&self, x: i32, y: i32) -> Option<usize> {
+        self.enemy_at(x, y)
+            .and_then(|id| self.enemy_index(id))
+            .filter(|&i| self.enemies[i].hp > 0)
+    }
+
+    /// Walkable and not occupied by the player, an enemy or an NPC
+    fn is_open_tile(&self, x: i32, y: i32) -> bool {
+        self.map.get(x, y).is_some_and(|t| t.walkable())
+            && (x, y) != (self.player_x, self.player_y)
+            && self.live_enemy_index(x, y).is_none()
+            && self.npc_at(x, y).is_none()
+    }
+
+    fn random_open_tile(&mut self, range: i32) -> Option<(i32, i32)> {
+        let (px, py) = (self.player_x, self.player_y);
+        let open: Vec<(i32, i32)> = (-range..=range)
+            .flat_map(|dy| (-range..=range).map(move |dx| (px + dx, py + dy)))
+            .filter(|&(x, y)| self.is_open_tile(x, y))
+            .collect();
+        if open.is_empty() {
+            return None;
+        }
+        Some(open[self.rng.gen_range(0..open.len())])
+    }
+
+    /// Up to `count` tiles at or next to the target for spawned entities
+    fn spawn_tiles(&self, x: i32, y: i32, kind: SpawnKind, count: usize) -> Vec<(i32, i32)> {
+        let mut around = vec![(x, y)];
+        around.extend(
+            (-1..=1)
+                .flat_map(|dy| (-1..=1).map(move |dx| (x + dx, y + dy)))
+                .filter(|&pos| pos != (x, y)),
+        );
+        around
+            .into_iter()
+            .filter(|&(tx, ty)| match kind {
+                SpawnKind::Enemy => self.is_open_tile(tx, ty),
+                SpawnKind::Item => self.map.get(tx, ty).is_some_and(|t| t.walkable()),
+            })
+            .take(count)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> ItemEffect {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn effects_parse_with_default_target() {
+        let heal = parse(r#"{"type": "heal", "amount": 5}"#);
+        assert_eq!(heal.kind, EffectKind::Heal { amount: 5 });
+        assert_eq!(heal.target, EffectTarget::User);
+        assert!(heal.conditions.is_empty());
+
+        let blast = parse(
+            r#"{"type": "area_damage", "damage": 4, "target": "area", "radius": 2,
+                "conditions": [{"type": "chance", "percent": 50}]}"#,
+        );
+        assert_eq!(
+            blast.kind,
+            EffectKind::AreaDamage {
+                damage: 4,
+                damage_type: DamageType::Physical
+            }
+        );
+        assert_eq!(blast.target, EffectTarget::Area);
+        assert_eq!(blast.conditions, vec![UseCondition::Chance { percent: 50 }]);
+        assert_eq!(
+            blast.describe(),
+            Some(("area_damage".into(), "4 damage (radius 2)".into()))
+        );
+    }
+
+    #[test]
+    fn unknown_effect_types_are_rejected() {
+        assert!(serde_json::from_str::<ItemEffect>(r#"{"type": "explode"}"#).is_err());
+        assert!(
+            serde_json::from_str::<ItemEffect>(
+                r#"{"type": "modify_stat", "stat": "charisma", "amount": 1}"#
+            )
+            .is_err()
+        );
+    }
+}
@@ -1,6 +1,7 @@
+use crate::game::content;
 use once_cell::sync::Lazy;
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct LightDef {
@@ -15,7 +16,8 @@ pub struct LightDef {
 #[derive(Debug, Clone, Deserialize)]
 pub struct SpawnRule {
     pub lights_per_room: [u32; 2],
-    pub weights: HashMap<String, u32>,
+    /// Ordered so weighted picks are stable across runs
+    pub weights: BTreeMap<String, u32>,
 }
 
 #[derive(Deserialize)]
@@ -25,7 +27,7 @@ struct LightsFile {
 }
 
 static LIGHTS_DATA: Lazy<LightsFile> = Lazy::new(|| {
-    let data = include_str!("../../data/lights.json");
+    let data = &content::load("lights.json");
     serde_json::from_str(data).expect("Failed to parse lights.json")
 });
 
@@ -1,3 +1,4 @@
+use crate::game::content;
 use bracket_algorithm_traits::prelude::{Algorithm2D, BaseMap};
 use bracket_geometry::prelude::Point;
 use bracket_pathfinding::prelude::*;
@@ -8,7 +9,7 @@ use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 use std::collections::{HashMap, HashSet};
 
-use super::constants::{FOV_RANGE, MAP_HEIGHT, MAP_WIDTH};
+use super::constants::{FOV_RANGE, MAP_HEIGHT, MAP_WIDTH, MAX_DEPTH};
 use super::generation::TerrainForgeGenerator;
 use super::light_defs::{get_spawn_rule, pick_light_type};
 use super::world_map::{Biome, POI, Terrain};
@@ -43,13 +44,13 @@ struct FloorsFile {
 }
 
 static WALL_DEFS: Lazy<HashMap<String, WallDef>> = Lazy::new(|| {
-    let data = include_str!("../../data/walls.json");
+    let data = &content::load("walls.json");
     let file: WallsFile = serde_json::from_str(data).expect("Failed to parse walls.json");
     file.walls.into_iter().map(|d| (d.id.clone(), d)).collect()
 });
 
 static FLOOR_DEFS: Lazy<HashMap<String, FloorDef>> = Lazy::new(|| {
-    let data = include_str!("../../data/floors.json");
+    let data = &content::load("floors.json");
     let file: FloorsFile = serde_json::from_str(data).expect("Failed to parse floors.json");
     file.floors.into_iter().map(|d| (d.id.clone(), d)).collect()
 });
@@ -456,7 +457,7 @@ impl Map {
         }
 
         // Place stairs down in last room (if not at max depth)
-        if layer > -3 {
+        if layer > -MAX_DEPTH {
             if let Some(&(rx, ry)) = room_centers.last() {
                 tiles[ry as usize * MAP_WIDTH + rx as usize] = Tile::StairsDown;
             }
@@ -519,6 +520,12 @@ impl Map {
         }
     }
 
+    /// Position of the first tile equal to `tile`, scanning row by row
+    pub fn find_tile(&self, tile: &Tile) -> Option<(i32, i32)> {
+        let idx = self.tiles.iter().position(|t| t == tile)?;
+        self.idx_to_pos(idx)
+    }
+
     /// Check if position is within map bounds
     pub fn is_valid_position(&self, x: i32, y: i32) -> bool {
         x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
@@ -1,5 +1,6 @@
 //! Meta progression - persistent unlocks across game runs
 
+use crate::game::content;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
@@ -31,7 +32,7 @@ struct ClassesFile {
 }
 
 static CLASSES: Lazy<Vec<ClassDef>> = Lazy::new(|| {
-    let data = include_str!("../../data/classes.json");
+    let data = &content::load("classes.json");
     let file: ClassesFile = serde_json::from_str(data).expect("Failed to parse classes.json");
     file.classes
 });
@@ -1,13 +1,19 @@
 pub mod action;
 pub mod adaptation;
+pub mod affix;
 pub mod auto_explore;
+pub mod behavior_tree;
 pub mod book;
 pub mod chest;
 pub mod combat;
 pub mod combat_actions;
 pub mod constants;
+pub mod content;
+pub mod content_check;
+pub mod cover;
 pub mod crafting;
 pub mod crystal_resonance;
+pub mod damage;
 pub mod des_testing;
 pub mod dialogue;
 pub mod effect;
@@ -15,11 +21,16 @@ pub mod enemy;
 pub mod entity;
 pub mod equipment;
 pub mod event;
+pub mod faction;
+pub mod flow_field;
 pub mod fov;
 pub mod generation;
+pub mod hot_reload;
 pub mod inspect;
 pub mod interactable;
+pub mod inventory;
 pub mod item;
+pub mod item_effect;
 pub mod light;
 pub mod light_defs;
 pub mod lighting;
@@ -33,12 +44,18 @@ pub mod qa_tools;
 pub mod quest;
 pub mod ritual;
 pub mod sanity;
+pub mod save;
+pub mod save_slots;
 pub mod skills;
 pub mod state;
+pub mod state_hash;
 pub mod status;
+pub mod stealth;
 pub mod storm;
 pub mod structure_templates;
 pub mod systems;
+pub mod throwing;
+pub mod tile_store;
 pub mod trading;
 pub mod tutorial;
 pub mod void_energy;
@@ -49,6 +66,7 @@ pub mod tests;
 
 pub use action::{action_cost, default_enemy_ap, default_player_ap};
 pub use adaptation::Adaptation;
+pub use affix::{AffixDef, AffixKind, get_affix_def};
 pub use auto_explore::{AutoExploreConfig, get_auto_explore_config};
 pub use chest::{Chest, ChestDef, get_chest_def};
 pub use combat::{
@@ -56,12 +74,15 @@ pub use combat::{
     roll_attack,
 };
 pub use constants::{FOV_RANGE, MAP_HEIGHT, MAP_WIDTH};
+pub use cover::Cover;
+pub use damage::{DamageType, Resistances};
 pub use effect::{
     EffectContext, VisualEffect, get_active_effects, get_enemy_effects, parse_effect,
 };
 pub use enemy::{Enemy, EnemyDef, all_enemy_ids, get_enemy_def};
-pub use entity::{Entity, EntityType};
+pub use entity::{Entity, EntityId, EntityIds, EntityType};
 pub use equipment::{EquipSlot, Equipment};
+pub use faction::{FactionDef, Stance, get_faction_def};
 pub use fov::FieldOfView;
 pub use generation::{
     AlgorithmContext,
@@ -124,8 +145,12 @@ pub use generation::{
     place_microstructures,
 };
 pub use interactable::{Interactable, InteractableDef, get_interactable_def};
-pub use item::{Item, ItemDef, all_item_ids, get_item_def};
-pub use status::{StatusEffect, StatusType, is_stunned, slow_penalty};
+pub use inventory::{Encumbrance, count_items};
+pub use item::{Condition, Item, ItemDef, ItemInstance, all_item_ids, get_item_def};
+pub use item_effect::{EffectKind, EffectTarget, ItemEffect, UseCondition};
+pub use status::{StatusEffect, StatusType, is_stunned, slow_penalty, speed_modifier};
+pub use stealth::Visibility;
+pub use throwing::{ThrowDef, ThrowTarget};
 
 pub use map::{Map, Tile, compute_fov};
 pub use map_features::MapFeatures;
@@ -142,11 +167,14 @@ pub use lighting::{LightMap, LightSource, compute_lighting, is_lit};
 pub use map::MapLight;
 pub use quest::{ActiveQuest, QuestLog, QuestReward, get_quest_def};
 pub use sanity::{MentalEffect, MentalEffectType, SanitySystem};
+pub use save::{SAVE_FORMAT_VERSION, SaveFormat, SaveHeader};
+pub use save_slots::{AUTOSAVE_SLOT, SaveSlots, SlotMeta};
 pub use skills::{
     SkillCategory, SkillsState, all_ability_ids, all_skill_ids, calculate_skill_cost,
     get_abilities_by_category, get_ability_def, get_skill_def, get_skills_by_category,
 };
 pub use storm::Storm;
+pub use tile_store::{TileKey, TileSnapshot, TileStore};
 pub use world_map::{Biome, POI, Terrain, WORLD_SIZE, WorldMap};
 
 pub use des_testing::{
@@ -1,14 +1,17 @@
 use super::adaptation::Adaptation;
-use super::entity::Entity;
+use super::entity::{Entity, EntityId};
+use super::item::ItemInstance;
 use super::status::StatusEffect;
+use super::systems::timeline::NORMAL_SPEED;
+use crate::game::content;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Context for evaluating dialogue conditions
 pub struct DialogueContext<'a> {
     pub adaptations: &'a [Adaptation],
-    pub inventory: &'a [String],
+    pub inventory: &'a [ItemInstance],
     pub salt_scrip: u32,
     pub faction_reputation: &'a HashMap<String, i32>,
 }
@@ -30,7 +33,7 @@ pub struct DialogueCondition {
 impl DialogueCondition {
     pub fn evaluate(&self, ctx: &DialogueContext) -> bool {
         if let Some(ref name) = self.has_adaptation {
-            if !ctx.adaptations.iter().any(|a| a.name() == name) {
+            if !ctx.adaptations.iter().any(|a| a.id() == name) {
                 return false;
             }
         }
@@ -40,7 +43,7 @@ impl DialogueCondition {
             }
         }
         if let Some(ref item_id) = self.has_item {
-            if !ctx.inventory.iter().any(|i| i == item_id) {
+            if !ctx.inventory.iter().any(|i| &i.id == item_id) {
                 return false;
             }
         }
@@ -103,6 +106,9 @@ pub struct NpcDef {
     /// Items available for purchase (item_id)
     #[serde(default)]
     pub shop_inventory: Vec<String>,
+    /// Energy gained per turn; 100 acts once a turn
+    #[serde(default = "default_speed")]
+    pub speed: i32,
 }
 
 #[derive(Deserialize)]
@@ -110,12 +116,16 @@ struct NpcsFile {
     npcs: Vec<NpcDef>,
 }
 
-static NPC_DEFS: Lazy<HashMap<String, NpcDef>> = Lazy::new(|| {
-    let data = include_str!("../../data/npcs.json");
+static NPC_DEFS: Lazy<BTreeMap<String, NpcDef>> = Lazy::new(|| {
+    let data = &content::load("npcs.json");
     let file: NpcsFile = serde_json::from_str(data).expect("Failed to parse npcs.json");
     file.npcs.into_iter().map(|d| (d.id.clone(), d)).collect()
 });
 
+fn default_speed() -> i32 {
+    NORMAL_SPEED
+}
+
 pub fn get_npc_def(id: &str) -> Option<&'static NpcDef> {
     NPC_DEFS.get(id)
 }
@@ -132,6 +142,12 @@ pub struct Npc {
     pub talked: bool,
     #[serde(default)]
     pub backstory: Option<String>,
+    /// Banked energy, spent on actions as it reaches `ACTION_ENERGY`
+    #[serde(default)]
+    pub energy: i32,
+    /// Handle given by `GameState` when the entity is added
+    #[serde(default)]
+    pub entity_id: EntityId,
 }
 
 impl Npc {
@@ -142,6 +158,8 @@ impl Npc {
             id: id.to_string(),
             talked: false,
             backstory: None,
+            energy: 0,
+            entity_id: EntityId::NONE,
         }
     }
 
@@ -149,6 +167,14 @@ impl Npc {
         get_npc_def(&self.id)
     }
 
+    pub fn speed(&self) -> i32 {
+        self.def().map_or(NORMAL_SPEED, |d| d.speed)
+    }
+
+    pub fn faction(&self) -> &'static str {
+        self.def().map_or("", |d| d.faction.as_str())
+    }
+
     pub fn glyph(&self) -> char {
         self.def()
             .map(|d| d.glyph.chars().next().unwrap_or('?'))
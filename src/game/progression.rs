@@ -1,5 +1,6 @@
 //! Data-driven character progression system
 
+use crate::game::content;
 use once_cell::sync::Lazy;
 use serde::Deserialize;
 use std::collections::HashMap;
@@ -17,7 +18,7 @@ fn default_stat_points() -> i32 {
 }
 
 static PROGRESSION: Lazy<ProgressionFile> = Lazy::new(|| {
-    let data = include_str!("../../data/progression.json");
+    let data = &content::load("progression.json");
     serde_json::from_str(data).expect("Failed to parse progression.json")
 });
 
@@ -1,8 +1,9 @@
 //! Quantum Consciousness / Psychic System
 
+use crate::game::content;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "snake_case")]
@@ -58,19 +59,16 @@ struct AbilitiesFile {
     abilities: Vec<PsychicAbilityDef>,
 }
 
-static ABILITIES: Lazy<HashMap<String, PsychicAbilityDef>> = Lazy::new(|| {
+static ABILITIES: Lazy<BTreeMap<String, PsychicAbilityDef>> = Lazy::new(|| {
     // We'll use a default empty list if file doesn't exist yet
     // But ideally we should create the file
-    match include_str!("../../data/psychic_abilities.json") {
-        data => {
-            let file: AbilitiesFile =
-                serde_json::from_str(data).expect("Failed to parse psychic_abilities.json");
-            file.abilities
-                .into_iter()
-                .map(|a| (a.id.clone(), a))
-                .collect()
-        }
-    }
+    let data = content::load("psychic_abilities.json");
+    let file: AbilitiesFile =
+        serde_json::from_str(&data).expect("Failed to parse psychic_abilities.json");
+    file.abilities
+        .into_iter()
+        .map(|a| (a.id.clone(), a))
+        .collect()
 });
 
 pub fn get_ability_def(id: &str) -> Option<&'static PsychicAbilityDef> {
@@ -16,6 +16,9 @@ pub struct IssueReport {
     pub severity: IssueSeverity,
     pub category: IssueCategory,
     pub gamestate_file: Option<String>,
+    /// DES scenario replaying the player's last actions before the report
+    #[serde(default)]
+    pub scenario_file: Option<String>,
     pub system_info: SystemInfo,
 }
 
@@ -88,6 +91,7 @@ impl GameState {
             severity,
             category,
             gamestate_file: Some(format!("{}.ron", id)),
+            scenario_file: None,
             system_info: SystemInfo {
                 os: std::env::consts::OS.to_string(),
                 version: env!("CARGO_PKG_VERSION").to_string(),
@@ -189,7 +193,8 @@ impl GameState {
             for entry in fs::read_dir("issue_reports")? {
                 let entry = entry?;
                 if let Some(name) = entry.file_name().to_str() {
-                    if name.ends_with(".json") {
+                    // Skip the DES scenarios saved alongside reports
+                    if name.ends_with(".json") && !name.ends_with(".scenario.json") {
                         reports.push(name.to_string());
                     }
                 }
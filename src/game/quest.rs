@@ -1,8 +1,9 @@
 //! Data-driven quest system with objectives and rewards
 
+use crate::game::content;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Quest objective types
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -88,7 +89,7 @@ pub struct QuestCriteria {
     /// Maximum refraction level allowed
     #[serde(default)]
     pub max_refraction: Option<u32>,
-    /// Required adaptations (adaptation names)
+    /// Required adaptations (adaptation ids)
     #[serde(default)]
     pub required_adaptations: Vec<String>,
     /// Forbidden adaptations (quest unavailable if player has these)
@@ -110,18 +111,18 @@ struct QuestsFile {
     quests: Vec<QuestDef>,
 }
 
-static QUEST_DEFS: Lazy<HashMap<String, QuestDef>> = Lazy::new(|| {
-    let mut quests = HashMap::new();
+static QUEST_DEFS: Lazy<BTreeMap<String, QuestDef>> = Lazy::new(|| {
+    let mut quests = BTreeMap::new();
 
     // Load regular quests
-    let data = include_str!("../../data/quests.json");
+    let data = &content::load("quests.json");
     let file: QuestsFile = serde_json::from_str(data).expect("Failed to parse quests.json");
     for quest in file.quests {
         quests.insert(quest.id.clone(), quest);
     }
 
     // Load main questline
-    let main_data = include_str!("../../data/main_questline.json");
+    let main_data = &content::load("main_questline.json");
     let main_file: MainQuestlineFile =
         serde_json::from_str(main_data).expect("Failed to parse main_questline.json");
     for quest in main_file.main_questline {
@@ -132,7 +133,7 @@ static QUEST_DEFS: Lazy<HashMap<String, QuestDef>> = Lazy::new(|| {
 });
 
 static QUEST_CONTENT_INJECTIONS: Lazy<Vec<QuestContentInjection>> = Lazy::new(|| {
-    let main_data = include_str!("../../data/main_questline.json");
+    let main_data = &content::load("main_questline.json");
     let main_file: MainQuestlineFile =
         serde_json::from_str(main_data).expect("Failed to parse main_questline.json");
     main_file.quest_content_injections
@@ -516,7 +517,7 @@ impl QuestLog {
         let player_adaptations: Vec<String> = game_state
             .adaptations
             .iter()
-            .map(|a| a.name().to_string())
+            .map(|a| a.id().to_string())
             .collect();
 
         for required_adaptation in &criteria.required_adaptations {
@@ -533,7 +534,7 @@ impl QuestLog {
 
         // Check required items
         for required_item in &criteria.required_items {
-            if !game_state.inventory.contains(required_item) {
+            if !game_state.has_item(required_item) {
                 return false;
             }
         }
@@ -565,7 +566,7 @@ impl QuestLog {
             "has_saint_key" => game_state
                 .inventory
                 .iter()
-                .any(|item| item.contains("saint_key")),
+                .any(|item| item.id.contains("saint_key")),
             "in_deep_archive" => game_state.layer < -2, // Deep underground
             "storm_active" => game_state.storm.turns_until > 0,
             "white_noon_discovered" => self.completed.contains(&"discover_white_noon".to_string()),
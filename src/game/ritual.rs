@@ -48,7 +48,7 @@ impl crate::game::state::GameState {
 
         // Check item requirements
         for required_item in &ritual.requirements.items {
-            if !self.inventory.contains(required_item) {
+            if !self.has_item(required_item) {
                 if let Some(def) = super::item::get_item_def(required_item) {
                     missing.push(format!("Missing: {}", def.name));
                 } else {
@@ -134,9 +134,7 @@ impl crate::game::state::GameState {
 
         // Consume required items
         for item in &ritual.requirements.items {
-            if let Some(pos) = self.inventory.iter().position(|x| x == item) {
-                self.inventory.remove(pos);
-            }
+            self.take_items(item, 1);
         }
 
         // Apply effects
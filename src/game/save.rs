@@ -0,0 +1,358 @@
+//! Versioned save files
+//!
+//! A save is a RON envelope holding a small header (format version, game
+//! version, seed) followed by the serialized `GameState`. Saves written by
+//! older builds are brought up to date by running the migration chain from
+//! their format version to `SAVE_FORMAT_VERSION`.
+//!
+//! Field additions only need `#[serde(default)]` and renames `#[serde(alias)]`;
+//! anything that changes meaning bumps `SAVE_FORMAT_VERSION` and adds a
+//! `Migration` fixing up states written by the previous version.
+//!
+//! The same envelope can also be written as a compact binary file: a magic
+//! tag, the MessagePack header, then the zlib-compressed MessagePack state.
+//! MessagePack keeps field names, so `#[serde(default)]` and the migration
+//! chain work the same for both encodings. Loading detects the encoding.
+
+use flate2::Compression;
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+use super::state::GameState;
+
+/// First bytes of a binary save
+const BINARY_MAGIC: &[u8; 4] = b"SGSB";
+
+/// On-disk encoding of a save
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SaveFormat {
+    /// Human-readable RON text
+    #[default]
+    Ron,
+    /// Compressed MessagePack
+    Binary,
+}
+
+impl SaveFormat {
+    pub const ALL: [SaveFormat; 2] = [SaveFormat::Ron, SaveFormat::Binary];
+
+    /// File extension used for saves in this format
+    pub fn extension(self) -> &'static str {
+        match self {
+            SaveFormat::Ron => "ron",
+            SaveFormat::Binary => "sgsb",
+        }
+    }
+
+    /// Parse a format name (`ron`, `binary`) or file extension
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "ron" => Some(SaveFormat::Ron),
+            "binary" | "bin" | "sgsb" => Some(SaveFormat::Binary),
+            _ => None,
+        }
+    }
+
+    /// Encoding of an existing save, from its leading bytes
+    pub fn detect(data: &[u8]) -> Self {
+        if data.starts_with(BINARY_MAGIC) {
+            SaveFormat::Binary
+        } else {
+            SaveFormat::Ron
+        }
+    }
+}
+
+/// Current on-disk format version
+pub const SAVE_FORMAT_VERSION: u32 = 3;
+
+/// Format version assigned to headerless saves from before the envelope existed
+pub const LEGACY_FORMAT_VERSION: u32 = 1;
+
+/// Metadata stored ahead of the game state
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SaveHeader {
+    pub format_version: u32,
+    /// Crate version of the build that wrote the save
+    pub game_version: String,
+    pub seed: u64,
+}
+
+impl SaveHeader {
+    pub fn current(seed: u64) -> Self {
+        Self {
+            format_version: SAVE_FORMAT_VERSION,
+            game_version: env!("CARGO_PKG_VERSION").to_string(),
+            seed,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SaveFileRef<'a> {
+    header: SaveHeader,
+    state: &'a GameState,
+}
+
+#[derive(Deserialize)]
+struct SaveFile<S> {
+    header: SaveHeader,
+    state: S,
+}
+
+/// A single step in the migration chain, upgrading a state written at `from`
+/// to `from + 1`
+pub struct Migration {
+    pub from: u32,
+    pub description: &'static str,
+    pub apply: fn(&mut GameState),
+}
+
+/// Migration chain, ordered by `from`. Each entry covers one version step.
+pub static MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: 1,
+        description: "recover seed missing from the oldest saves",
+        apply: migrate_v1_to_v2,
+    },
+    Migration {
+        from: 2,
+        description: "recount armor from every equipped item instance",
+        apply: migrate_v2_to_v3,
+    },
+];
+
+/// Headerless saves could be written underground, where levels were not yet
+/// kept in the tile store. The live layer is still valid, so only the seed
+/// (missing from the oldest saves) needs recovering.
+fn migrate_v1_to_v2(state: &mut GameState) {
+    if state.seed == 0
+        && let Some(wm) = &state.world_map
+    {
+        state.seed = wm.seed;
+    }
+}
+
+/// Inventories and equipment held bare item ids until v3; those load as fresh
+/// instances. Armor used to come from the jacket alone and is now summed over
+/// every slot, so it is worked out again.
+fn migrate_v2_to_v3(state: &mut GameState) {
+    state.recalc_equipment_stats();
+}
+
+/// Serialize a state into the current save format as RON text
+pub fn encode(state: &GameState) -> Result<String, String> {
+    let file = SaveFileRef {
+        header: SaveHeader::current(state.seed),
+        state,
+    };
+    ron::to_string(&file).map_err(|e| e.to_string())
+}
+
+/// Serialize a state into the current save format as compressed binary
+pub fn encode_binary(state: &GameState) -> Result<Vec<u8>, String> {
+    let header =
+        rmp_serde::to_vec_named(&SaveHeader::current(state.seed)).map_err(|e| e.to_string())?;
+    let mut out = Vec::with_capacity(64 * 1024);
+    out.extend_from_slice(BINARY_MAGIC);
+    out.extend_from_slice(&(header.len() as u32).to_le_bytes());
+    out.extend_from_slice(&header);
+
+    // Encode in one buffer first; feeding MessagePack's many small writes
+    // straight into the compressor is an order of magnitude slower
+    let body = rmp_serde::to_vec_named(state).map_err(|e| e.to_string())?;
+    let mut encoder = ZlibEncoder::new(out, Compression::fast());
+    encoder.write_all(&body).map_err(|e| e.to_string())?;
+    encoder.finish().map_err(|e| e.to_string())
+}
+
+/// Serialize a state in the given encoding
+pub fn encode_as(state: &GameState, format: SaveFormat) -> Result<Vec<u8>, String> {
+    match format {
+        SaveFormat::Ron => encode(state).map(String::into_bytes),
+        SaveFormat::Binary => encode_binary(state),
+    }
+}
+
+/// Split a binary save into its header and the compressed state that follows
+fn split_binary(data: &[u8]) -> Result<(SaveHeader, &[u8]), String> {
+    let rest = data
+        .strip_prefix(BINARY_MAGIC)
+        .ok_or("Not a binary save file")?;
+    if rest.len() < 4 {
+        return Err("Truncated binary save".to_string());
+    }
+    let header_len = u32::from_le_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+    let rest = &rest[4..];
+    if rest.len() < header_len {
+        return Err("Truncated binary save".to_string());
+    }
+    let header = rmp_serde::from_slice(&rest[..header_len]).map_err(|e| e.to_string())?;
+    Ok((header, &rest[header_len..]))
+}
+
+/// Read only the header of a save. Headerless legacy saves report
+/// `LEGACY_FORMAT_VERSION` and an unknown game version.
+pub fn read_header(data: &str) -> Result<SaveHeader, String> {
+    match ron::from_str::<SaveFile<IgnoredAny>>(data) {
+        Ok(file) => Ok(file.header),
+        Err(envelope_err) => {
+            // Legacy saves are a bare GameState; require a field every version had
+            #[derive(Deserialize)]
+            struct LegacySeed {
+                #[serde(rename = "player_x")]
+                _player_x: IgnoredAny,
+                #[serde(default)]
+                seed: u64,
+            }
+            let legacy: LegacySeed =
+                ron::from_str(data).map_err(|_| format!("Not a save file: {}", envelope_err))?;
+            Ok(SaveHeader {
+                format_version: LEGACY_FORMAT_VERSION,
+                game_version: "unknown".to_string(),
+                seed: legacy.seed,
+            })
+        }
+    }
+}
+
+/// Read only the header of a save in either encoding
+pub fn read_header_bytes(data: &[u8]) -> Result<SaveHeader, String> {
+    match SaveFormat::detect(data) {
+        SaveFormat::Binary => split_binary(data).map(|(header, _)| header),
+        SaveFormat::Ron => read_header(std::str::from_utf8(data).map_err(|e| e.to_string())?),
+    }
+}
+
+fn check_supported(header: &SaveHeader) -> Result<(), String> {
+    if header.format_version > SAVE_FORMAT_VERSION {
+        return Err(format!(
+            "Save format v{} was written by a newer game ({}); this build reads up to v{}",
+            header.format_version, header.game_version, SAVE_FORMAT_VERSION
+        ));
+    }
+    Ok(())
+}
+
+/// Parse a save in either encoding and migrate it to the current version.
+/// Returns the header as it was on disk.
+pub fn decode_bytes(data: &[u8]) -> Result<(SaveHeader, GameState), String> {
+    match SaveFormat::detect(data) {
+        SaveFormat::Ron => decode(std::str::from_utf8(data).map_err(|e| e.to_string())?),
+        SaveFormat::Binary => {
+            let (header, body) = split_binary(data)?;
+            check_supported(&header)?;
+            let mut raw = Vec::new();
+            ZlibDecoder::new(body)
+                .read_to_end(&mut raw)
+                .map_err(|e| e.to_string())?;
+            let mut state: GameState = rmp_serde::from_slice(&raw).map_err(|e| e.to_string())?;
+            migrate(&mut state, header.format_version)?;
+            Ok((header, state))
+        }
+    }
+}
+
+/// Parse a RON save of any supported version and migrate it to the current version.
+/// Returns the header as it was on disk.
+pub fn decode(data: &str) -> Result<(SaveHeader, GameState), String> {
+    let header = read_header(data)?;
+    check_supported(&header)?;
+
+    let mut state: GameState = if header.format_version == LEGACY_FORMAT_VERSION {
+        ron::from_str(data).map_err(|e| e.to_string())?
+    } else {
+        ron::from_str::<SaveFile<GameState>>(data)
+            .map_err(|e| e.to_string())?
+            .state
+    };
+
+    migrate(&mut state, header.format_version)?;
+    Ok((header, state))
+}
+
+/// Run every migration from `from_version` up to the current version
+pub fn migrate(state: &mut GameState, from_version: u32) -> Result<(), String> {
+    for version in from_version..SAVE_FORMAT_VERSION {
+        let step = MIGRATIONS
+            .iter()
+            .find(|m| m.from == version)
+            .ok_or_else(|| format!("No migration from save format v{}", version))?;
+        (step.apply)(state);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migration_chain_is_complete() {
+        for version in LEGACY_FORMAT_VERSION..SAVE_FORMAT_VERSION {
+            let steps = MIGRATIONS.iter().filter(|m| m.from == version).count();
+            assert_eq!(steps, 1, "expected one migration from v{}", version);
+        }
+    }
+
+    #[test]
+    fn envelope_round_trip() {
+        let state = GameState::new(42);
+        let data = encode(&state).unwrap();
+        let header = read_header(&data).unwrap();
+        assert_eq!(header, SaveHeader::current(42));
+
+        let (_, loaded) = decode(&data).unwrap();
+        assert_eq!(loaded.player_x, state.player_x);
+        assert_eq!(loaded.map.tiles, state.map.tiles);
+    }
+
+    #[test]
+    fn legacy_headerless_save_is_migrated() {
+        let state = GameState::new(42);
+        let legacy = ron::to_string(&state).unwrap();
+        let (header, loaded) = decode(&legacy).unwrap();
+        assert_eq!(header.format_version, LEGACY_FORMAT_VERSION);
+        assert_eq!(loaded.seed, 42);
+    }
+
+    #[test]
+    fn binary_round_trip_is_smaller() {
+        let mut state = GameState::new(42);
+        state.turn = 99;
+        let binary = encode_binary(&state).unwrap();
+        let text = encode(&state).unwrap();
+        assert_eq!(SaveFormat::detect(&binary), SaveFormat::Binary);
+        assert_eq!(SaveFormat::detect(text.as_bytes()), SaveFormat::Ron);
+        assert!(binary.len() * 4 < text.len());
+
+        assert_eq!(read_header_bytes(&binary).unwrap(), SaveHeader::current(42));
+        let (_, loaded) = decode_bytes(&binary).unwrap();
+        assert_eq!(loaded.turn, 99);
+        assert_eq!(loaded.map.tiles, state.map.tiles);
+        assert_eq!(loaded.revealed, state.revealed);
+        assert_eq!(loaded.rng.get_seed(), state.rng.get_seed());
+    }
+
+    #[test]
+    fn truncated_binary_is_an_error() {
+        let binary = encode_binary(&GameState::new(42)).unwrap();
+        assert!(decode_bytes(&binary[..6]).is_err());
+        assert!(decode_bytes(&binary[..binary.len() / 2]).is_err());
+    }
+
+    #[test]
+    fn newer_format_is_rejected() {
+        let state = GameState::new(42);
+        let data = encode(&state).unwrap().replacen(
+            &format!("format_version:{}", SAVE_FORMAT_VERSION),
+            "format_version:999",
+            1,
+        );
+        let err = decode(&data).err().expect("newer save should not load");
+        assert!(err.contains("newer"), "{}", err);
+    }
+}
@@ -0,0 +1,331 @@
+//! Named save slots
+//!
+//! Each slot is a save file plus a small metadata sidecar so the load menu can
+//! list slots without parsing whole game states. Slots are written in the
+//! directory's `SaveFormat` and read in whichever format is on disk. Slots
+//! live in the platform data directory (`$XDG_DATA_HOME/saltglass-steppe/saves`
+//! on Linux), which can be overridden with `SALTGLASS_SAVE_DIR`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::save::SaveFormat;
+use super::state::GameState;
+
+/// Slot written on every tile transition
+pub const AUTOSAVE_SLOT: &str = "autosave";
+
+/// Number of older autosaves kept as `autosave.1` .. `autosave.N`
+pub const AUTOSAVE_BACKUPS: usize = 3;
+
+const SAVE_DIR_ENV: &str = "SALTGLASS_SAVE_DIR";
+
+/// Summary of a save slot shown in the load menu
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SlotMeta {
+    pub name: String,
+    pub class_id: String,
+    pub level: u32,
+    pub turn: u32,
+    pub world_x: usize,
+    pub world_y: usize,
+    pub layer: i32,
+    pub saved_at: DateTime<Utc>,
+    pub playtime_secs: u64,
+}
+
+impl SlotMeta {
+    fn from_state(name: &str, state: &GameState) -> Self {
+        Self {
+            name: name.to_string(),
+            class_id: state.class_id.clone(),
+            level: state.player_level,
+            turn: state.turn,
+            world_x: state.world_x,
+            world_y: state.world_y,
+            layer: state.layer,
+            saved_at: Utc::now(),
+            playtime_secs: state.playtime_secs,
+        }
+    }
+
+    pub fn is_autosave(&self) -> bool {
+        self.name == AUTOSAVE_SLOT || self.name.starts_with("autosave.")
+    }
+
+    /// Playtime as `H:MM:SS`
+    pub fn playtime_display(&self) -> String {
+        let s = self.playtime_secs;
+        format!("{}:{:02}:{:02}", s / 3600, (s / 60) % 60, s % 60)
+    }
+}
+
+/// Directory of save slots
+pub struct SaveSlots {
+    dir: PathBuf,
+    format: SaveFormat,
+}
+
+impl SaveSlots {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            format: SaveFormat::default(),
+        }
+    }
+
+    /// Encoding used for new saves
+    pub fn with_format(mut self, format: SaveFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn format(&self) -> SaveFormat {
+        self.format
+    }
+
+    /// Slots in the platform data directory, or `SALTGLASS_SAVE_DIR` if set
+    pub fn open_default() -> Self {
+        Self::new(default_save_dir())
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path_for(&self, name: &str, format: SaveFormat) -> PathBuf {
+        self.dir.join(format!("{}.{}", name, format.extension()))
+    }
+
+    /// Existing save file for a slot, in whichever format it was written
+    fn save_path(&self, name: &str) -> Option<PathBuf> {
+        SaveFormat::ALL
+            .iter()
+            .map(|&f| self.path_for(name, f))
+            .find(|p| p.exists())
+    }
+
+    fn meta_path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.meta.ron", name))
+    }
+
+    /// Write a state to a slot, replacing whatever was there
+    pub fn save(&self, name: &str, state: &GameState) -> Result<SlotMeta, String> {
+        validate_slot_name(name)?;
+        fs::create_dir_all(&self.dir).map_err(|e| e.to_string())?;
+        let meta = SlotMeta::from_state(name, state);
+        state.save_as(self.path_for(name, self.format), self.format)?;
+        // Drop a copy in the other format so the slot has a single save file
+        for other in SaveFormat::ALL.into_iter().filter(|&f| f != self.format) {
+            fs::remove_file(self.path_for(name, other)).ok();
+        }
+        let meta_data = ron::to_string(&meta).map_err(|e| e.to_string())?;
+        fs::write(self.meta_path(name), meta_data).map_err(|e| e.to_string())?;
+        Ok(meta)
+    }
+
+    pub fn load(&self, name: &str) -> Result<GameState, String> {
+        validate_slot_name(name)?;
+        let path = self
+            .save_path(name)
+            .ok_or_else(|| format!("No save in slot '{}'", name))?;
+        GameState::load(path)
+    }
+
+    pub fn exists(&self, name: &str) -> bool {
+        self.save_path(name).is_some()
+    }
+
+    pub fn delete(&self, name: &str) -> Result<(), String> {
+        validate_slot_name(name)?;
+        let path = self
+            .save_path(name)
+            .ok_or_else(|| format!("No save in slot '{}'", name))?;
+        fs::remove_file(path).map_err(|e| e.to_string())?;
+        fs::remove_file(self.meta_path(name)).ok();
+        Ok(())
+    }
+
+    /// Metadata for every slot, most recently saved first. Slots whose sidecar
+    /// is missing or unreadable are skipped.
+    pub fn list(&self) -> Vec<SlotMeta> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut slots: Vec<SlotMeta> = entries
+            .filter_map(|e| e.ok())
+            .filter_map(|e| {
+                let file_name = e.file_name().to_string_lossy().to_string();
+                let name = file_name.strip_suffix(".meta.ron")?.to_string();
+                if !self.exists(&name) {
+                    return None;
+                }
+                let data = fs::read_to_string(e.path()).ok()?;
+                ron::from_str(&data).ok()
+            })
+            .collect();
+        slots.sort_by_key(|s| std::cmp::Reverse(s.saved_at));
+        slots
+    }
+
+    /// Save to the autosave slot, shifting previous autosaves into the backup chain
+    pub fn autosave(&self, state: &GameState) -> Result<SlotMeta, String> {
+        for i in (1..=AUTOSAVE_BACKUPS).rev() {
+            let older = if i == 1 {
+                AUTOSAVE_SLOT.to_string()
+            } else {
+                format!("{}.{}", AUTOSAVE_SLOT, i - 1)
+            };
+            let newer = format!("{}.{}", AUTOSAVE_SLOT, i);
+            if self.exists(&older) {
+                self.rename(&older, &newer)?;
+            }
+        }
+        self.save(AUTOSAVE_SLOT, state)
+    }
+
+    fn rename(&self, from: &str, to: &str) -> Result<(), String> {
+        let source = self
+            .save_path(from)
+            .ok_or_else(|| format!("No save in slot '{}'", from))?;
+        let format =
+            SaveFormat::from_name(&source.extension().unwrap_or_default().to_string_lossy())
+                .unwrap_or_default();
+        if let Some(stale) = self.save_path(to) {
+            fs::remove_file(stale).map_err(|e| e.to_string())?;
+        }
+        fs::rename(&source, self.path_for(to, format)).map_err(|e| e.to_string())?;
+        let meta_data = fs::read_to_string(self.meta_path(from)).map_err(|e| e.to_string())?;
+        let mut meta: SlotMeta = ron::from_str(&meta_data).map_err(|e| e.to_string())?;
+        meta.name = to.to_string();
+        let meta_data = ron::to_string(&meta).map_err(|e| e.to_string())?;
+        fs::write(self.meta_path(to), meta_data).map_err(|e| e.to_string())?;
+        fs::remove_file(self.meta_path(from)).map_err(|e| e.to_string())
+    }
+}
+
+fn default_save_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os(SAVE_DIR_ENV) {
+        return PathBuf::from(dir);
+    }
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("saltglass-steppe")
+        .join("saves")
+}
+
+/// Slot names become file names, so keep them to a safe character set
+pub fn validate_slot_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && !name.starts_with('.')
+        && !name.ends_with(".meta")
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ' '));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid save slot name: {:?}", name))
+    }
+}
+
+/// Default slot name for a new game
+pub fn slot_name_for(class_id: &str, seed: u64) -> String {
+    let class = if class_id.is_empty() {
+        "wanderer"
+    } else {
+        class_id
+    };
+    format!("{}-{}", class, seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slots_round_trip_with_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let slots = SaveSlots::new(dir.path());
+        let mut state = GameState::new_with_class(42, "scavenger");
+        state.turn = 17;
+        state.playtime_secs = 3725;
+
+        let meta = slots.save("first run", &state).unwrap();
+        assert_eq!(meta.turn, 17);
+        assert_eq!(meta.playtime_display(), "1:02:05");
+
+        let listed = slots.list();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].class_id, "scavenger");
+        assert_eq!(
+            (listed[0].world_x, listed[0].world_y),
+            (state.world_x, state.world_y)
+        );
+
+        let loaded = slots.load("first run").unwrap();
+        assert_eq!(loaded.turn, 17);
+        assert_eq!(loaded.playtime_secs, 3725);
+
+        slots.delete("first run").unwrap();
+        assert!(slots.list().is_empty());
+    }
+
+    #[test]
+    fn autosaves_rotate_into_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        let slots = SaveSlots::new(dir.path());
+        let mut state = GameState::new(42);
+
+        for turn in 1..=AUTOSAVE_BACKUPS as u32 + 2 {
+            state.turn = turn;
+            slots.autosave(&state).unwrap();
+        }
+
+        let listed = slots.list();
+        assert_eq!(listed.len(), AUTOSAVE_BACKUPS + 1);
+        assert!(listed.iter().all(SlotMeta::is_autosave));
+        let newest = AUTOSAVE_BACKUPS as u32 + 2;
+        assert_eq!(slots.load(AUTOSAVE_SLOT).unwrap().turn, newest);
+        assert_eq!(slots.load("autosave.1").unwrap().turn, newest - 1);
+        assert_eq!(
+            slots
+                .load(&format!("autosave.{}", AUTOSAVE_BACKUPS))
+                .unwrap()
+                .turn,
+            newest - AUTOSAVE_BACKUPS as u32
+        );
+    }
+
+    #[test]
+    fn binary_slots_replace_ron_slots() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut state = GameState::new(42);
+        SaveSlots::new(dir.path()).save("run", &state).unwrap();
+
+        let slots = SaveSlots::new(dir.path()).with_format(SaveFormat::Binary);
+        state.turn = 5;
+        slots.save("run", &state).unwrap();
+        assert!(!dir.path().join("run.ron").exists());
+        assert!(dir.path().join("run.sgsb").exists());
+        assert_eq!(slots.load("run").unwrap().turn, 5);
+
+        // Rotation keeps working across formats
+        slots.autosave(&state).unwrap();
+        SaveSlots::new(dir.path()).autosave(&state).unwrap();
+        assert!(dir.path().join("autosave.ron").exists());
+        assert!(dir.path().join("autosave.1.sgsb").exists());
+    }
+
+    #[test]
+    fn slot_names_are_sanitized() {
+        assert!(validate_slot_name("pilgrim-42").is_ok());
+        assert!(validate_slot_name("../etc/passwd").is_err());
+        assert!(validate_slot_name("").is_err());
+        assert!(validate_slot_name(".hidden").is_err());
+        assert!(validate_slot_name("clash.meta").is_err());
+    }
+}
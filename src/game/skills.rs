@@ -1,8 +1,9 @@
 //! Physical Skills and Abilities System
 
+use crate::game::content;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "snake_case")]
@@ -162,11 +163,11 @@ pub fn calculate_skill_cost(skill_id: &str, current_level: u32) -> u32 {
 }
 
 // Data loading
-static SKILLS: Lazy<HashMap<String, SkillDef>> = Lazy::new(|| {
-    let data = include_str!("../../data/skills.json");
+static SKILLS: Lazy<BTreeMap<String, SkillDef>> = Lazy::new(|| {
+    let data = &content::load("skills.json");
     match serde_json::from_str::<Vec<SkillDef>>(data) {
         Ok(skills_vec) => {
-            let mut skills_map = HashMap::new();
+            let mut skills_map = BTreeMap::new();
             for skill in skills_vec {
                 skills_map.insert(skill.id.clone(), skill);
             }
@@ -174,16 +175,16 @@ static SKILLS: Lazy<HashMap<String, SkillDef>> = Lazy::new(|| {
         }
         Err(e) => {
             eprintln!("Failed to parse skills.json: {}", e);
-            HashMap::new()
+            BTreeMap::new()
         }
     }
 });
 
-static ABILITIES: Lazy<HashMap<String, AbilityDef>> = Lazy::new(|| {
-    let data = include_str!("../../data/abilities.json");
+static ABILITIES: Lazy<BTreeMap<String, AbilityDef>> = Lazy::new(|| {
+    let data = &content::load("abilities.json");
     match serde_json::from_str::<Vec<AbilityDef>>(data) {
         Ok(abilities_vec) => {
-            let mut abilities_map = HashMap::new();
+            let mut abilities_map = BTreeMap::new();
             for ability in abilities_vec {
                 abilities_map.insert(ability.id.clone(), ability);
             }
@@ -191,7 +192,7 @@ static ABILITIES: Lazy<HashMap<String, AbilityDef>> = Lazy::new(|| {
         }
         Err(e) => {
             eprintln!("Failed to parse abilities.json: {}", e);
-            HashMap::new()
+            BTreeMap::new()
         }
     }
 });
@@ -1,7 +1,6 @@
 use bracket_pathfinding::prelude::*;
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
-use ratatui::style::Color;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::fs;
@@ -11,10 +10,12 @@ use super::{
     action::{action_cost, default_player_ap},
     adaptation::Adaptation,
     chest::Chest,
+    damage::DamageType,
     enemy::Enemy,
-    entity::Entity,
+    entity::{Entity, EntityId, EntityIds},
     equipment::{EquipSlot, Equipment},
     event::GameEvent,
+    flow_field::FlowFields,
     fov::FieldOfView,
     generation::{
         BiomeSystem, ConstraintSystem, GenerationConfig, GenerationPipeline, Grammar,
@@ -24,20 +25,24 @@ use super::{
     },
     generation::{
         EventType, NarrativeContext, NarrativeGenerator, StoryModel, TerrainForgeGenerator,
-        distribute_points_grid, generate_loot, get_biome_spawn_table,
+        distribute_points_grid, generate_loot, get_biome_spawn_table, populate_subterranean,
         weighted_pick_by_level_and_tier,
     },
     generation::{PlacedMicroStructure, place_microstructures},
     interactable::Interactable,
-    item::{Item, get_item_def},
+    item::{Item, ItemInstance, get_item_def},
     lighting::{LightMap, LightSource, compute_lighting},
     map::{Map, Tile},
     map_features::MapFeatures,
     npc::Npc,
     quest::QuestLog,
     sanity::SanitySystem,
+    save,
     storm::Storm,
+    systems::combat::CombatSystem,
     systems::movement::MovementSystem,
+    systems::timeline::ActionRecord,
+    tile_store::{TileKey, TileSnapshot, TileStore},
     tutorial::TutorialProgress,
     world_map::WorldMap,
 };
@@ -184,8 +189,10 @@ pub struct GameState {
     pub items: Vec<Item>,
     pub chests: Vec<Chest>,
     pub interactables: Vec<Interactable>,
-    pub inventory: Vec<String>,
+    pub inventory: Vec<ItemInstance>,
+    #[serde(serialize_with = "super::state_hash::sorted_set")]
     pub visible: HashSet<usize>,
+    #[serde(serialize_with = "super::state_hash::sorted_set")]
     pub revealed: HashSet<usize>,
     #[serde(skip)]
     pub player_fov: FieldOfView,
@@ -209,16 +216,28 @@ pub struct GameState {
     /// Decoys left by mirage_step adaptation
     #[serde(default)]
     pub decoys: Vec<Decoy>,
+    /// Moving slowly and quietly with a shielded light
+    #[serde(default)]
+    pub sneaking: bool,
+    /// Allocator for the handles of enemies, NPCs, items and chests
+    #[serde(default)]
+    pub entity_ids: EntityIds,
+    #[serde(skip)]
+    pub enemy_positions: HashMap<(i32, i32), EntityId>,
     #[serde(skip)]
-    pub enemy_positions: HashMap<(i32, i32), usize>,
+    pub npc_positions: HashMap<(i32, i32), EntityId>,
     #[serde(skip)]
-    pub npc_positions: HashMap<(i32, i32), usize>,
+    pub item_positions: HashMap<(i32, i32), Vec<EntityId>>,
     #[serde(skip)]
-    pub item_positions: HashMap<(i32, i32), Vec<usize>>,
+    pub chest_positions: HashMap<(i32, i32), EntityId>,
+    /// Where each handle was in its entity list at the last rebuild
     #[serde(skip)]
-    pub chest_positions: HashMap<(i32, i32), usize>,
+    entity_slots: HashMap<EntityId, usize>,
     #[serde(skip)]
     pub interactable_positions: HashMap<(i32, i32), usize>,
+    /// Dijkstra maps toward and away from the player, shared by enemies
+    #[serde(skip)]
+    pub flow_fields: FlowFields,
     #[serde(skip)]
     spatial_dirty: bool,
     #[serde(skip)]
@@ -249,6 +268,9 @@ pub struct GameState {
     /// Current layer: 0 = surface, negative = underground
     #[serde(default)]
     pub layer: i32,
+    /// Tiles the player has visited and left, restored on re-entry
+    #[serde(default)]
+    pub tile_store: TileStore,
     /// Time of day (0-23 hours)
     #[serde(default = "default_time_of_day")]
     pub time_of_day: u8,
@@ -289,9 +311,21 @@ pub struct GameState {
     pub debug_phase: bool,
     #[serde(skip)]
     pub debug_disable_glare: bool,
+    /// Names of `TURN_SCHEDULE` systems that are not run, e.g. by a DES scenario
+    #[serde(skip)]
+    pub disabled_systems: HashSet<String>,
+    /// Recent actions in the order they were taken, see `systems::timeline`
+    #[serde(skip)]
+    pub action_log: Vec<ActionRecord>,
     /// Original seed for reproducibility
     #[serde(default)]
     pub seed: u64,
+    /// Class the character was started as (empty for classless games)
+    #[serde(default)]
+    pub class_id: String,
+    /// Wall-clock seconds played, accumulated by the frontend
+    #[serde(default)]
+    pub playtime_secs: u64,
     /// Procedural narrative generator
     #[serde(skip)]
     pub narrative_generator: Option<NarrativeGenerator>,
@@ -627,7 +661,7 @@ impl GameState {
                 let mut chest = Chest::new(rx, ry, chest_id);
                 if let Some(def) = super::chest::get_chest_def(chest_id) {
                     if let Some(loot_table) = &def.loot_table {
-                        let loot = generate_loot(loot_table, rx, ry, &mut rng);
+                        let loot = generate_loot(loot_table, &mut rng);
                         for item in loot {
                             chest.add_item(item);
                         }
@@ -715,11 +749,15 @@ impl GameState {
             },
             triggered_effects: Vec::new(),
             decoys: Vec::new(),
+            sneaking: false,
+            entity_ids: EntityIds::default(),
             enemy_positions: HashMap::new(),
             npc_positions: HashMap::new(),
             item_positions: HashMap::new(),
             chest_positions: HashMap::new(),
+            entity_slots: HashMap::new(),
             interactable_positions: HashMap::new(),
+            flow_fields: FlowFields::default(),
             spatial_dirty: true,
             event_queue: Vec::new(),
             hit_flash_positions: Vec::new(),
@@ -733,6 +771,7 @@ impl GameState {
             world_x,
             world_y,
             layer: 0,
+            tile_store: TileStore::default(),
             time_of_day: 8,
             weather: Weather::Clear,
             wait_counter: 0,
@@ -747,7 +786,11 @@ impl GameState {
             debug_god_view: false,
             debug_phase: false,
             debug_disable_glare: false,
+            disabled_systems: HashSet::new(),
+            action_log: Vec::new(),
             seed,
+            class_id: String::new(),
+            playtime_secs: 0,
             narrative_generator: None,
             world_history: Vec::new(),
             story_model: None,
@@ -865,6 +908,7 @@ impl GameState {
         let mut state = Self::new(seed);
 
         if let Some(class) = super::meta::get_class(class_id) {
+            state.class_id = class_id.to_string();
             state.player_hp = class.starting_hp;
             state.player_max_hp = class.starting_hp;
             state.player_ap = class.starting_ap;
@@ -872,7 +916,7 @@ impl GameState {
 
             // Add starting items
             for item_id in &class.starting_items {
-                state.inventory.push(item_id.clone());
+                state.give_item(ItemInstance::new(item_id));
             }
 
             // Add starting adaptations
@@ -895,33 +939,43 @@ impl GameState {
 
     /// Ensure spatial index is up to date before querying
     fn ensure_spatial_index(&mut self) {
-        if self.spatial_dirty {
+        let unassigned = self.enemies.iter().any(|e| e.entity_id.is_none())
+            || self.npcs.iter().any(|n| n.entity_id.is_none())
+            || self.items.iter().any(|i| i.entity_id.is_none())
+            || self.chests.iter().any(|c| c.entity_id.is_none());
+        if self.spatial_dirty || unassigned {
             self.rebuild_spatial_index_internal();
         }
     }
 
     /// Internal rebuild that clears the dirty flag
     fn rebuild_spatial_index_internal(&mut self) {
+        self.assign_entity_ids();
+        self.entity_slots.clear();
         self.enemy_positions.clear();
         for (i, e) in self.enemies.iter().enumerate() {
+            self.entity_slots.insert(e.entity_id, i);
             if e.hp > 0 {
-                self.enemy_positions.insert((e.x, e.y), i);
+                self.enemy_positions.insert((e.x, e.y), e.entity_id);
             }
         }
         self.npc_positions.clear();
         for (i, n) in self.npcs.iter().enumerate() {
-            self.npc_positions.insert((n.x, n.y), i);
+            self.entity_slots.insert(n.entity_id, i);
+            self.npc_positions.insert((n.x, n.y), n.entity_id);
         }
         self.item_positions.clear();
         for (i, item) in self.items.iter().enumerate() {
+            self.entity_slots.insert(item.entity_id, i);
             self.item_positions
                 .entry((item.x, item.y))
                 .or_default()
-                .push(i);
+                .push(item.entity_id);
         }
         self.chest_positions.clear();
         for (i, chest) in self.chests.iter().enumerate() {
-            self.chest_positions.insert((chest.x, chest.y), i);
+            self.entity_slots.insert(chest.entity_id, i);
+            self.chest_positions.insert((chest.x, chest.y), chest.entity_id);
         }
         self.interactable_positions.clear();
         for (i, interactable) in self.interactables.iter().enumerate() {
@@ -936,6 +990,25 @@ impl GameState {
         self.rebuild_spatial_index_internal();
     }
 
+    /// Give a handle to every entity added without one, or sharing one with
+    /// an entity it was cloned from
+    fn assign_entity_ids(&mut self) {
+        let mut seen = HashSet::new();
+        let ids = self
+            .enemies
+            .iter_mut()
+            .map(|e| &mut e.entity_id)
+            .chain(self.npcs.iter_mut().map(|n| &mut n.entity_id))
+            .chain(self.items.iter_mut().map(|i| &mut i.entity_id))
+            .chain(self.chests.iter_mut().map(|c| &mut c.entity_id));
+        for id in ids {
+            if id.is_none() || !seen.insert(*id) {
+                *id = self.entity_ids.allocate();
+                seen.insert(*id);
+            }
+        }
+    }
+
     /// Get quest IDs that have objectives at the given world coordinates
     fn get_quest_ids_for_location(&self, world_x: usize, world_y: usize) -> Vec<String> {
         let mut quest_ids = Vec::new();
@@ -964,9 +1037,71 @@ impl GameState {
 
     /// Travel to a new world tile (lazy generation)
     pub fn travel_to_tile(&mut self, new_wx: usize, new_wy: usize) {
+        if self.world_map.is_none() {
+            return;
+        }
+        self.stash_current_tile();
+        if self.arrive_at_surface_tile(new_wx, new_wy) {
+            self.log("You return to a familiar area.");
+        }
+    }
+
+    /// Move the live tile (map, entities, exploration) into the tile store
+    fn stash_current_tile(&mut self) {
+        let key = TileKey::new(self.world_x, self.world_y, self.layer);
+        let snapshot = TileSnapshot {
+            map: std::mem::replace(&mut self.map, Map::new(0, 0)),
+            enemies: std::mem::take(&mut self.enemies),
+            npcs: std::mem::take(&mut self.npcs),
+            items: std::mem::take(&mut self.items),
+            chests: std::mem::take(&mut self.chests),
+            interactables: std::mem::take(&mut self.interactables),
+            microstructures: std::mem::take(&mut self.microstructures),
+            revealed: std::mem::take(&mut self.revealed),
+        };
+        self.tile_store.insert(key, snapshot);
+        self.mark_spatial_dirty();
+    }
+
+    /// Restore a previously visited tile from the tile store.
+    /// Returns false if the tile has never been left before.
+    fn restore_tile(&mut self, key: TileKey) -> bool {
+        let snapshot = match self.tile_store.take(key) {
+            Some(s) => s,
+            None => return false,
+        };
+        self.map = snapshot.map;
+        self.enemies = snapshot.enemies;
+        self.npcs = snapshot.npcs;
+        self.items = snapshot.items;
+        self.chests = snapshot.chests;
+        self.interactables = snapshot.interactables;
+        self.microstructures = snapshot.microstructures;
+        self.revealed = snapshot.revealed;
+        self.mark_spatial_dirty();
+        true
+    }
+
+    /// Load a surface tile, restoring it if visited before or generating it from its seed.
+    /// Returns true if the tile was restored.
+    fn arrive_at_surface_tile(&mut self, new_wx: usize, new_wy: usize) -> bool {
+        if self.restore_tile(TileKey::new(new_wx, new_wy, 0)) {
+            self.world_x = new_wx;
+            self.world_y = new_wy;
+            let (px, py) = self.find_safe_spawn_position_in_map(&self.map);
+            self.player_x = px;
+            self.player_y = py;
+
+            self.spawn_quest_required_npcs();
+            self.update_fov();
+            self.rebuild_spatial_index();
+            self.update_lighting();
+            return true;
+        }
+
         let world_map = match &self.world_map {
             Some(wm) => wm,
-            None => return,
+            None => return false,
         };
 
         let (biome, terrain, elevation, poi, _resources, _connected, level) =
@@ -1170,6 +1305,7 @@ impl GameState {
         self.generate_template_content("encounter", template_context);
 
         self.log(format!("You enter a new area ({:?} {:?}).", biome, terrain));
+        false
     }
 
     /// Spawn NPCs required for active quests
@@ -1329,32 +1465,33 @@ impl GameState {
             return false;
         }
 
+        self.stash_current_tile();
         self.layer -= 1;
-        let seed = self
-            .world_map
-            .as_ref()
-            .map(|wm| wm.tile_seed(self.world_x, self.world_y))
-            .unwrap_or(42)
-            .wrapping_add(self.layer.unsigned_abs() as u64 * 1000);
-        let mut rng = ChaCha8Rng::seed_from_u64(seed);
-
-        let (map, rooms) = Map::generate_subterranean(&mut rng, self.layer);
-        let (px, py) = rooms[0];
-
-        self.map = map;
-        self.enemies = Vec::new();
-        self.items = Vec::new();
-        self.npcs = Vec::new();
-        self.player_x = px;
-        self.player_y = py;
-        self.update_fov();
-        self.rebuild_spatial_index();
-        self.update_lighting();
+        self.arrive_at_underground_layer(Tile::StairsUp);
 
         self.log(format!("You descend to level {}.", -self.layer));
         true
     }
 
+    /// Move to the world tile and depth given, generating or restoring each
+    /// map on the way as travel and stairs would. The player ends up where
+    /// arriving there puts them.
+    pub fn go_to_location(&mut self, world_x: usize, world_y: usize, layer: i32) {
+        if (self.world_x, self.world_y, self.layer) == (world_x, world_y, layer)
+            || self.world_map.is_none()
+        {
+            return;
+        }
+        self.stash_current_tile();
+        self.layer = 0;
+        self.arrive_at_surface_tile(world_x, world_y);
+        while self.layer > layer {
+            self.stash_current_tile();
+            self.layer -= 1;
+            self.arrive_at_underground_layer(Tile::StairsUp);
+        }
+    }
+
     /// Exit subterranean layer (go up stairs)
     pub fn exit_subterranean(&mut self) -> bool {
         // Check if standing on stairs up
@@ -1370,64 +1507,90 @@ impl GameState {
             return false;
         } // Already on surface
 
+        self.stash_current_tile();
         self.layer += 1;
 
         if self.layer == 0 {
-            // Return to surface - regenerate surface tile
-            self.travel_to_tile(self.world_x, self.world_y);
+            // Return to surface - restore the tile we descended from
+            self.arrive_at_surface_tile(self.world_x, self.world_y);
+            if let Some((px, py)) = self.map.find_tile(&Tile::StairsDown) {
+                self.player_x = px;
+                self.player_y = py;
+                self.update_fov();
+                self.update_lighting();
+            }
             self.log("You return to the surface.");
         } else {
-            // Go up one underground level
-            let seed = self
-                .world_map
-                .as_ref()
-                .map(|wm| wm.tile_seed(self.world_x, self.world_y))
-                .unwrap_or(42)
-                .wrapping_add(self.layer.unsigned_abs() as u64 * 1000);
+            self.arrive_at_underground_layer(Tile::StairsDown);
+            self.log(format!("You ascend to level {}.", -self.layer));
+        }
+        true
+    }
+
+    /// Load the underground layer at `self.layer`, restoring it if visited before or
+    /// generating and populating it from its seed. The player is placed on `arrive_on`.
+    fn arrive_at_underground_layer(&mut self, arrive_on: Tile) {
+        let key = TileKey::new(self.world_x, self.world_y, self.layer);
+        let fallback = if self.restore_tile(key) {
+            None
+        } else {
+            let (biome, level, tile_seed) = match &self.world_map {
+                Some(wm) => {
+                    let (biome, _, _, _, _, _, level) = wm.get(self.world_x, self.world_y);
+                    (biome, level, wm.tile_seed(self.world_x, self.world_y))
+                }
+                None => (super::world_map::Biome::Saltflat, 1, 42),
+            };
+            let seed = tile_seed.wrapping_add(self.layer.unsigned_abs() as u64 * 1000);
             let mut rng = ChaCha8Rng::seed_from_u64(seed);
 
             let (map, rooms) = Map::generate_subterranean(&mut rng, self.layer);
-            let (px, py) = rooms.last().copied().unwrap_or((5, 5));
+            let population =
+                populate_subterranean(&map, &rooms, &biome, level, self.layer, &mut rng);
 
             self.map = map;
-            self.enemies = Vec::new();
-            self.items = Vec::new();
-            self.npcs = Vec::new();
-            self.player_x = px;
-            self.player_y = py;
-            self.update_fov();
-            self.rebuild_spatial_index();
-            self.update_lighting();
+            self.enemies = population.enemies;
+            self.items = population.items;
+            self.chests = population.chests;
+            rooms.first().copied()
+        };
 
-            self.log(format!("You ascend to level {}.", -self.layer));
-        }
-        true
+        let (px, py) = self
+            .map
+            .find_tile(&arrive_on)
+            .or(fallback)
+            .unwrap_or_else(|| self.find_safe_spawn_position_in_map(&self.map));
+        self.player_x = px;
+        self.player_y = py;
+        self.update_fov();
+        self.rebuild_spatial_index();
+        self.update_lighting();
     }
 
     pub fn update_lighting(&mut self) {
         let mut sources = Vec::new();
 
-        // Always add default player light
+        // Always add default player light, shielded while sneaking
+        let (radius, intensity) = self.player_light();
         sources.push(LightSource {
             x: self.player_x,
             y: self.player_y,
-            radius: 5,
-            intensity: 100,
+            radius,
+            intensity,
         });
 
         // Equipped light sources (check all slots)
         for (_, slot_item) in self.equipment.iter() {
-            if let Some(id) = slot_item {
-                if let Some(def) = get_item_def(id) {
-                    if let Some(ref ls) = def.light_source {
-                        sources.push(LightSource {
-                            x: self.player_x,
-                            y: self.player_y,
-                            radius: ls.radius,
-                            intensity: ls.intensity,
-                        });
-                    }
-                }
+            if let Some(item) = slot_item
+                && let Some(def) = item.def()
+                && let Some(ref ls) = def.light_source
+            {
+                sources.push(LightSource {
+                    x: self.player_x,
+                    y: self.player_y,
+                    radius: ls.radius,
+                    intensity: ls.intensity,
+                });
             }
         }
         // Map lights
@@ -1443,15 +1606,15 @@ impl GameState {
         }
         // Items on ground with light_source property
         for item in &self.items {
-            if let Some(def) = get_item_def(&item.id) {
-                if let Some(ref ls) = def.light_source {
-                    sources.push(LightSource {
-                        x: item.x,
-                        y: item.y,
-                        radius: ls.radius,
-                        intensity: ls.intensity,
-                    });
-                }
+            if let Some(def) = item.def()
+                && let Some(ref ls) = def.light_source
+            {
+                sources.push(LightSource {
+                    x: item.x,
+                    y: item.y,
+                    radius: ls.radius,
+                    intensity: ls.intensity,
+                });
             }
         }
         self.light_map = compute_lighting(&sources, self.effective_ambient_light());
@@ -1836,34 +1999,14 @@ impl GameState {
         true
     }
 
-    /// End turn: reset AP, tick status effects, run enemy turns, tick storm, tick time
+    /// End turn: run every enabled system in `TURN_SCHEDULE`, then queued events
     pub fn end_turn(&mut self) {
-        use super::systems::{StatusEffectSystem, StormSystem, System};
+        use super::systems::TURN_SCHEDULE;
 
         // Ensure spatial index is up to date before AI/systems run
         self.ensure_spatial_index();
 
-        self.player_ap = self.player_max_ap;
-        StatusEffectSystem.update(self);
-        self.psychic.tick();
-        self.skills.tick();
-        self.light_system.update(&mut self.rng);
-        self.void_system.update(&mut self.rng);
-        self.crystal_system.update(&mut self.rng);
-        self.tick_turn();
-        self.update_enemies();
-        if self.storm.tick() {
-            StormSystem::apply_storm(self);
-        }
-        self.tick_time();
-        self.update_lighting();
-        self.update_fov();
-
-        // Check for dynamic events
-        self.check_dynamic_events();
-
-        // Notify quests of turn passing (for wait objectives)
-        self.quest_log.on_turn_passed();
+        TURN_SCHEDULE.run(self);
 
         // Process queued events
         self.process_events();
@@ -2024,7 +2167,7 @@ impl GameState {
     }
 
     /// Check for dynamic events based on current game state
-    fn check_dynamic_events(&mut self) {
+    pub(crate) fn check_dynamic_events(&mut self) {
         if let Some(ref mut event_system) = self.event_system {
             let current_biome = if let Some(ref world_map) = self.world_map {
                 world_map
@@ -2111,13 +2254,12 @@ impl GameState {
     /// Process all queued game events
     /// This enables decoupled communication between systems
     fn process_events(&mut self) {
-        use super::systems::{LootSystem, QuestSystem, System};
+        use super::systems::TURN_SCHEDULE;
 
         let events = self.drain_events();
         for event in events {
-            // Dispatch to systems
-            LootSystem.on_event(self, &event);
-            QuestSystem.on_event(self, &event);
+            // Dispatch to every enabled system
+            TURN_SCHEDULE.dispatch(self, &event);
 
             // Internal logging/handling
             self.handle_event(&event);
@@ -2127,7 +2269,7 @@ impl GameState {
     /// Handle a single game event - internal logging and state updates
     fn handle_event(&mut self, event: &GameEvent) {
         match event {
-            GameEvent::EnemyKilled { enemy_id, x, y } => {
+            GameEvent::EnemyKilled { enemy_id, x, y, .. } => {
                 self.log_typed(
                     format!("[Event] Enemy '{}' killed at ({}, {})", enemy_id, x, y),
                     MsgType::System,
@@ -2199,41 +2341,58 @@ impl GameState {
 
     /// Rest to recover HP (50% max HP). Requires no nearby enemies and costs 10 turns.
     pub fn rest(&mut self) -> Result<(), String> {
-        // Check for nearby enemies (within FOV range)
-        for enemy in &self.enemies {
-            let dx = (enemy.x - self.player_x).abs();
-            let dy = (enemy.y - self.player_y).abs();
-            if dx <= super::constants::FOV_RANGE && dy <= super::constants::FOV_RANGE {
-                return Err("You cannot rest with enemies nearby!".to_string());
-            }
+        use super::constants::REST_TURNS;
+        use super::systems::{System, TimelineSystem};
+
+        if self.enemies_nearby() {
+            return Err("You cannot rest with enemies nearby!".to_string());
         }
 
-        // Heal 50% max HP
+        // Heal up to 50% max HP, an equal share for each turn that passes;
+        // enemies and NPCs act on each as their speed allows
         let heal_amount = (self.player_max_hp as f32 * 0.5) as i32;
         let old_hp = self.player_hp;
-        self.player_hp = (self.player_hp + heal_amount).min(self.player_max_hp);
-        let actual_heal = self.player_hp - old_hp;
+        let mut interrupted = false;
+        for turn in 1..=REST_TURNS {
+            self.tick_turn();
+            self.ensure_spatial_index();
+            TimelineSystem.update(self);
+            let share = heal_amount * turn / REST_TURNS - heal_amount * (turn - 1) / REST_TURNS;
+            self.player_hp = (self.player_hp + share).min(self.player_max_hp);
+            if self.enemies_nearby() {
+                interrupted = true;
+                break;
+            }
+        }
 
+        let actual_heal = self.player_hp - old_hp;
         if actual_heal > 0 {
             self.log_typed(
                 format!("You rest and recover {} HP.", actual_heal),
                 MsgType::Status,
             );
-        } else {
+        } else if !interrupted {
             self.log_typed("You rest but are already at full health.", MsgType::Status);
         }
-
-        // Advance 10 turns
-        for _ in 0..10 {
-            self.tick_turn();
+        if interrupted {
+            self.log_typed("Your rest is interrupted!", MsgType::Warning);
         }
 
-        // Process enemy turns (they get to act while you rest)
-        self.update_enemies();
-
         Ok(())
     }
 
+    /// Whether any living enemy hostile to the player is within FOV range
+    fn enemies_nearby(&self) -> bool {
+        self.enemies.iter().any(|enemy| {
+            if enemy.hp <= 0 || !self.is_hostile_to_player(enemy) {
+                return false;
+            }
+            let dx = (enemy.x - self.player_x).abs();
+            let dy = (enemy.y - self.player_y).abs();
+            dx <= super::constants::FOV_RANGE && dy <= super::constants::FOV_RANGE
+        })
+    }
+
     /// Auto-end turn if player has no AP left
     pub(crate) fn check_auto_end_turn(&mut self) {
         if self.player_ap <= 0 {
@@ -2241,7 +2400,7 @@ impl GameState {
         }
     }
 
-    fn tick_turn(&mut self) {
+    pub(crate) fn tick_turn(&mut self) {
         self.turn += 1;
         if self.adaptations_hidden_turns > 0 {
             self.adaptations_hidden_turns -= 1;
@@ -2301,23 +2460,23 @@ impl GameState {
         }
 
         // Check for NPCs at this position
-        if let Some(&npc_idx) = self.npc_positions.get(&(x, y)) {
-            if let Some(npc) = self.npcs.get(npc_idx) {
-                let npc_name = npc.name().to_string();
-                let npc_id = npc.id.clone();
-                self.log(&format!("You talk to {}.", npc_name));
-                self.quest_log.on_npc_talked(&npc_id);
-                return;
-            }
+        if let Some(&npc_id) = self.npc_positions.get(&(x, y))
+            && let Some(npc) = self.npc(npc_id)
+        {
+            let npc_name = npc.name().to_string();
+            let npc_id = npc.id.clone();
+            self.log(format!("You talk to {}.", npc_name));
+            self.quest_log.on_npc_talked(&npc_id);
+            return;
         }
 
         // Check for chests at this position
-        if let Some(&chest_idx) = self.chest_positions.get(&(x, y)) {
-            if let Some(chest) = self.chests.get(chest_idx) {
-                let chest_name = chest.name().to_string();
-                self.log(&format!("You open the {}.", chest_name));
-                return;
-            }
+        if let Some(&chest_id) = self.chest_positions.get(&(x, y))
+            && let Some(chest) = self.chest(chest_id)
+        {
+            let chest_name = chest.name().to_string();
+            self.log(format!("You open the {}.", chest_name));
+            return;
         }
 
         self.log("There's nothing to interact with here.");
@@ -2342,48 +2501,46 @@ impl GameState {
         }
 
         // Check for enemies at this position
-        if let Some(&enemy_idx) = self.enemy_positions.get(&(x, y)) {
-            if let Some(enemy) = self.enemies.get(enemy_idx) {
-                if enemy.hp > 0 {
-                    let max_hp = enemy.max_hp().unwrap_or(0);
-                    self.log(&format!(
-                        "You see a {}. HP: {}/{}",
-                        enemy.name(),
-                        enemy.hp,
-                        max_hp
-                    ));
-                    return;
-                }
-            }
+        if let Some(&enemy_id) = self.enemy_positions.get(&(x, y))
+            && let Some(enemy) = self.enemy(enemy_id)
+            && enemy.hp > 0
+        {
+            let max_hp = enemy.max_hp().unwrap_or(0);
+            self.log(format!(
+                "You see a {}. HP: {}/{}",
+                enemy.name(),
+                enemy.hp,
+                max_hp
+            ));
+            return;
         }
 
         // Check for NPCs at this position
-        if let Some(&npc_idx) = self.npc_positions.get(&(x, y)) {
-            if let Some(npc) = self.npcs.get(npc_idx) {
-                let npc_name = npc.name().to_string();
-                let npc_desc = npc.description().to_string();
-                self.log(&format!("You see {}. {}", npc_name, npc_desc));
-                return;
-            }
+        if let Some(&npc_id) = self.npc_positions.get(&(x, y))
+            && let Some(npc) = self.npc(npc_id)
+        {
+            let npc_name = npc.name().to_string();
+            let npc_desc = npc.description().to_string();
+            self.log(format!("You see {}. {}", npc_name, npc_desc));
+            return;
         }
 
         // Check for items at this position
-        if let Some(item_indices) = self.item_positions.get(&(x, y)) {
-            if !item_indices.is_empty() {
-                let item = &self.items[item_indices[0]];
-                self.log(&format!("You see {}.", item.name()));
-                return;
-            }
+        if let Some(item_ids) = self.item_positions.get(&(x, y))
+            && let Some(item) = item_ids.first().and_then(|&id| self.item(id))
+        {
+            self.log(format!("You see {}.", item.name()));
+            return;
         }
 
         // Check for chests at this position
-        if let Some(&chest_idx) = self.chest_positions.get(&(x, y)) {
-            if let Some(chest) = self.chests.get(chest_idx) {
-                let chest_name = chest.name().to_string();
-                let chest_desc = chest.description().to_string();
-                self.log(&format!("You see a {}. {}", chest_name, chest_desc));
-                return;
-            }
+        if let Some(&chest_id) = self.chest_positions.get(&(x, y))
+            && let Some(chest) = self.chest(chest_id)
+        {
+            let chest_name = chest.name().to_string();
+            let chest_desc = chest.description().to_string();
+            self.log(format!("You see a {}. {}", chest_name, chest_desc));
+            return;
         }
 
         // Examine the tile itself
@@ -2841,7 +2998,23 @@ impl GameState {
                 if parts.len() >= 3 {
                     if let (Ok(x), Ok(y)) = (parts[1].parse::<i32>(), parts[2].parse::<i32>()) {
                         if let Some(damage) = self.void_system.reality_rend(x, y) {
-                            self.log(format!("Reality rend deals {} void damage!", damage));
+                            let target = self
+                                .enemy_at(x, y)
+                                .and_then(|id| self.enemy_index(id))
+                                .filter(|&i| self.enemies[i].hp > 0);
+                            if let Some(i) = target {
+                                let dmg = self.damage_enemy(i, damage as i32, DamageType::Void);
+                                let name = self.enemies[i].name().to_string();
+                                self.log(format!(
+                                    "Reality rend deals {} void damage to the {}!",
+                                    dmg, name
+                                ));
+                                if self.enemies[i].hp <= 0 {
+                                    CombatSystem::process_enemy_death(self, i, x, y, None);
+                                }
+                            } else {
+                                self.log(format!("Reality rend deals {} void damage!", damage));
+                            }
                         } else {
                             self.log(
                                 "Cannot use reality rend (insufficient energy or not unlocked)",
@@ -3061,7 +3234,7 @@ impl GameState {
                     if super::item::get_item_def(id).is_some() {
                         let count = parts.get(2).and_then(|s| s.parse().ok()).unwrap_or(1);
                         for _ in 0..count {
-                            self.inventory.push(id.to_string());
+                            self.give_item(ItemInstance::new(id));
                         }
                         self.log(format!("Added {} x{} to inventory", id, count));
                     } else {
@@ -3157,6 +3330,29 @@ impl GameState {
                 self.quest_log.on_data_collected();
                 self.log("Debug: Triggered data collection");
             }
+            Some("reload_content") => {
+                self.reload_content();
+            }
+            Some("watch_content") => {
+                let enabled = match parts.get(1) {
+                    Some(&"on") => true,
+                    Some(&"off") => false,
+                    _ => !super::content::is_watching(),
+                };
+                let files: Vec<&str> = super::hot_reload::RELOADABLE_FILES
+                    .iter()
+                    .map(|(file, _)| *file)
+                    .collect();
+                let dirs = super::content::watch(&files, enabled);
+                if !enabled {
+                    self.log("Debug: Content watcher stopped");
+                } else if dirs.is_empty() {
+                    self.log("Debug: No data directory or mods to watch");
+                } else {
+                    let dirs: Vec<String> = dirs.iter().map(|d| d.display().to_string()).collect();
+                    self.log(format!("Debug: Watching content in {}", dirs.join(", ")));
+                }
+            }
             Some("help") => {
                 self.log("Debug Commands:");
                 self.log("  show tile, hide tile - Toggle god view");
@@ -3207,6 +3403,8 @@ impl GameState {
                 self.log("  interact <target> - Trigger interact objective");
                 self.log("  examine <target> - Trigger examine objective");
                 self.log("  collect_data - Trigger data collection objective");
+                self.log("  reload_content - Reload item, enemy, status and loot definitions");
+                self.log("  watch_content [on|off] - Reload content when data files change");
                 self.log("");
                 self.log("Console Controls:");
                 self.log("  ` - Toggle debug console");
@@ -3385,82 +3583,14 @@ impl GameState {
         None
     }
 
-    /// Generate visual effects based on player adaptations
+    /// Visual effects of the player's adaptations, from their definitions
     pub fn get_adaptation_visual_effects(&self) -> Vec<super::effect::VisualEffect> {
-        use super::effect::VisualEffect;
-        let mut effects = Vec::new();
-
-        for adaptation in &self.adaptations {
-            match adaptation.name() {
-                "Prismhide" => {
-                    // Crystalline shimmer effect
-                    effects.push(VisualEffect::Shimmer {
-                        speed: 6,
-                        colors: vec![Color::Cyan, Color::LightCyan, Color::White],
-                    });
-                }
-                "Sunveins" => {
-                    // Pulsing inner light
-                    effects.push(VisualEffect::Pulse {
-                        speed: 4,
-                        color: Color::Yellow,
-                    });
-                }
-                "Mirage Step" => {
-                    // Flickering/fading effect
-                    effects.push(VisualEffect::Fade {
-                        speed: 8,
-                        color: Color::LightBlue,
-                    });
-                }
-                "Saltblood" => {
-                    // Subtle white glow
-                    effects.push(VisualEffect::Glow {
-                        color: Color::White,
-                    });
-                }
-                "Quantum Entanglement" => {
-                    // Rainbow psychic aura
-                    effects.push(VisualEffect::Rainbow {
-                        speed: 5,
-                        colors: vec![Color::Magenta, Color::Cyan, Color::Yellow, Color::Green],
-                    });
-                }
-                "Phase Walking" => {
-                    // Drifting translucent effect
-                    effects.push(VisualEffect::Drift {
-                        speed: 7,
-                        color: Color::LightMagenta,
-                    });
-                }
-                "Storm Affinity" => {
-                    // Storm-like wave effect
-                    effects.push(VisualEffect::Wave {
-                        speed: 3,
-                        color: Color::LightCyan,
-                    });
-                }
-                "Crystalline Consciousness" => {
-                    // Complex multi-effect for transcendent adaptation
-                    effects.push(VisualEffect::Rainbow {
-                        speed: 2,
-                        colors: vec![
-                            Color::White,
-                            Color::LightCyan,
-                            Color::LightMagenta,
-                            Color::LightYellow,
-                        ],
-                    });
-                    effects.push(VisualEffect::Pulse {
-                        speed: 3,
-                        color: Color::White,
-                    });
-                }
-                _ => {} // No visual effect for other adaptations
-            }
-        }
-
-        effects
+        self.adaptations
+            .iter()
+            .filter_map(|a| a.def())
+            .flat_map(|def| &def.visual_effects)
+            .filter_map(|e| super::effect::parse_effect(e))
+            .collect()
     }
 
     pub fn check_adaptation_threshold(&mut self) {
@@ -3489,18 +3619,117 @@ impl GameState {
         }
     }
 
-    pub fn has_adaptation(&self, a: Adaptation) -> bool {
-        self.adaptations.contains(&a)
+    pub fn has_adaptation(&self, id: &str) -> bool {
+        self.adaptations.iter().any(|a| a.id() == id)
     }
 
-    pub fn enemy_at(&self, x: i32, y: i32) -> Option<usize> {
+    pub fn enemy_at(&self, x: i32, y: i32) -> Option<EntityId> {
         self.enemy_positions.get(&(x, y)).copied()
     }
 
-    pub fn npc_at(&self, x: i32, y: i32) -> Option<usize> {
+    pub fn npc_at(&self, x: i32, y: i32) -> Option<EntityId> {
         self.npc_positions.get(&(x, y)).copied()
     }
 
+    /// Current position of an enemy in `enemies`, if it is still there
+    pub fn enemy_index(&self, id: EntityId) -> Option<usize> {
+        self.slot_of(id, &self.enemies, |e| e.entity_id)
+    }
+
+    pub fn npc_index(&self, id: EntityId) -> Option<usize> {
+        self.slot_of(id, &self.npcs, |n| n.entity_id)
+    }
+
+    pub fn item_index(&self, id: EntityId) -> Option<usize> {
+        self.slot_of(id, &self.items, |i| i.entity_id)
+    }
+
+    pub fn chest_index(&self, id: EntityId) -> Option<usize> {
+        self.slot_of(id, &self.chests, |c| c.entity_id)
+    }
+
+    pub fn enemy(&self, id: EntityId) -> Option<&Enemy> {
+        self.enemy_index(id).map(|i| &self.enemies[i])
+    }
+
+    pub fn enemy_mut(&mut self, id: EntityId) -> Option<&mut Enemy> {
+        self.enemy_index(id).map(|i| &mut self.enemies[i])
+    }
+
+    pub fn npc(&self, id: EntityId) -> Option<&Npc> {
+        self.npc_index(id).map(|i| &self.npcs[i])
+    }
+
+    pub fn item(&self, id: EntityId) -> Option<&Item> {
+        self.item_index(id).map(|i| &self.items[i])
+    }
+
+    pub fn chest(&self, id: EntityId) -> Option<&Chest> {
+        self.chest_index(id).map(|i| &self.chests[i])
+    }
+
+    /// Find a handle in an entity list, checking the position from the last
+    /// rebuild before searching in case the list has changed since
+    fn slot_of<T>(&self, id: EntityId, list: &[T], id_of: impl Fn(&T) -> EntityId) -> Option<usize> {
+        if id.is_none() {
+            return None;
+        }
+        match self.entity_slots.get(&id) {
+            Some(&i) if list.get(i).is_some_and(|e| id_of(e) == id) => Some(i),
+            _ => list.iter().position(|e| id_of(e) == id),
+        }
+    }
+
+    /// Add an enemy, returning its handle
+    pub fn spawn_enemy(&mut self, mut enemy: Enemy) -> EntityId {
+        let id = self.entity_ids.allocate();
+        enemy.entity_id = id;
+        if enemy.hp > 0 {
+            self.enemy_positions.insert((enemy.x, enemy.y), id);
+        }
+        self.entity_slots.insert(id, self.enemies.len());
+        self.enemies.push(enemy);
+        id
+    }
+
+    /// Add an item on the ground, returning its handle
+    pub fn spawn_item(&mut self, mut item: Item) -> EntityId {
+        let id = self.entity_ids.allocate();
+        item.entity_id = id;
+        self.item_positions
+            .entry((item.x, item.y))
+            .or_default()
+            .push(id);
+        self.entity_slots.insert(id, self.items.len());
+        self.items.push(item);
+        id
+    }
+
+    /// Remove an enemy; its handle stops resolving and its slot may be reused
+    pub fn despawn_enemy(&mut self, id: EntityId) -> Option<Enemy> {
+        let enemy = self.enemies.remove(self.enemy_index(id)?);
+        if self.enemy_positions.get(&(enemy.x, enemy.y)) == Some(&id) {
+            self.enemy_positions.remove(&(enemy.x, enemy.y));
+        }
+        self.entity_ids.release(id);
+        self.mark_spatial_dirty();
+        Some(enemy)
+    }
+
+    /// Remove an item from the ground; its handle stops resolving
+    pub fn remove_item(&mut self, id: EntityId) -> Option<Item> {
+        let item = self.items.remove(self.item_index(id)?);
+        if let Some(ids) = self.item_positions.get_mut(&(item.x, item.y)) {
+            ids.retain(|&i| i != id);
+            if ids.is_empty() {
+                self.item_positions.remove(&(item.x, item.y));
+            }
+        }
+        self.entity_ids.release(id);
+        self.mark_spatial_dirty();
+        Some(item)
+    }
+
     /// Check if there's a decoy at position
     pub fn decoy_at(&self, x: i32, y: i32) -> bool {
         self.decoys.iter().any(|d| d.x == x && d.y == y)
@@ -3550,7 +3779,11 @@ impl GameState {
                 let y = (idx / self.map.width) as i32;
 
                 for item in &self.items {
-                    if item.x == x && item.y == y && config.should_pickup_item(&item.id) {
+                    if item.x == x
+                        && item.y == y
+                        && config.should_pickup_item(item.id())
+                        && self.can_carry(&item.instance)
+                    {
                         found_target = true;
                         break;
                     }
@@ -3704,22 +3937,34 @@ impl GameState {
         use crate::game::auto_explore::get_auto_explore_config;
         let config = get_auto_explore_config();
 
+        // Items added since the last rebuild need handles before removal
+        self.ensure_spatial_index();
+
         let player_idx = self.map.idx(self.player_x, self.player_y);
         let mut items_to_remove = Vec::new();
 
-        for (i, item) in self.items.iter().enumerate() {
+        for item in &self.items {
             let item_idx = self.map.idx(item.x, item.y);
-            if item_idx == player_idx && config.should_pickup_item(&item.id) {
-                items_to_remove.push(i);
+            if item_idx == player_idx && config.should_pickup_item(item.id()) {
+                items_to_remove.push(item.entity_id);
             }
         }
 
-        // Remove items in reverse order to maintain indices
-        for &i in items_to_remove.iter().rev() {
-            let item = self.items.remove(i);
-            self.inventory.push(item.id.clone());
+        // Leave behind whatever would weigh the player down
+        for &id in items_to_remove.iter().rev() {
+            if !self
+                .item(id)
+                .is_some_and(|item| self.can_carry(&item.instance))
+            {
+                continue;
+            }
+            let Some(item) = self.remove_item(id) else {
+                continue;
+            };
+            let name = item.name();
+            self.give_item(item.instance);
             self.messages.push(crate::game::GameMessage::new(
-                format!("Picked up {}", item.name()),
+                format!("Picked up {}", name),
                 crate::game::MsgType::System,
                 self.turn,
             ));
@@ -3763,7 +4008,7 @@ impl GameState {
         if is_locked {
             if let Some(def) = super::chest::get_chest_def(&chest_id) {
                 if let Some(key_id) = &def.key_required {
-                    if self.inventory.contains(key_id) {
+                    if self.has_item(key_id) {
                         self.chests[chest_index].unlock();
                         self.log(format!("Unlocked {} with {}.", def.name, key_id));
                     } else {
@@ -3792,12 +4037,9 @@ impl GameState {
             return false;
         }
 
-        let item_id = self.inventory.remove(inventory_index);
-        let item = Item::new(chest.x, chest.y, &item_id);
+        let item = self.inventory.remove(inventory_index);
+        let name = item.display_name();
         chest.add_item(item);
-
-        let item_def = super::item::get_item_def(&item_id);
-        let name = item_def.map(|d| d.name.as_str()).unwrap_or(&item_id);
         self.log(format!("Stored {} in chest.", name));
         true
     }
@@ -3807,13 +4049,13 @@ impl GameState {
             return false;
         }
 
+        let encumbrance = self.encumbrance();
         let chest = &mut self.chests[chest_index];
         if let Some(item) = chest.remove_item(chest_item_index) {
-            self.inventory.push(item.id.clone());
-
-            let item_def = super::item::get_item_def(&item.id);
-            let name = item_def.map(|d| d.name.as_str()).unwrap_or(&item.id);
+            let name = item.display_name();
+            self.give_item(item);
             self.log(format!("Took {} from chest.", name));
+            self.note_encumbrance(encumbrance);
             true
         } else {
             false
@@ -3828,8 +4070,7 @@ impl GameState {
         if self.player_ap < cost {
             return false;
         }
-        let id = &self.inventory[idx];
-        let def = match get_item_def(id) {
+        let def = match self.inventory[idx].def() {
             Some(d) => d,
             None => return false,
         };
@@ -3838,133 +4079,14 @@ impl GameState {
             return false;
         }
 
-        // Check if it's a book
-        if let Some(book_id) = &def.book_id {
-            self.pending_book_open = Some(book_id.clone());
-            self.log(format!("You read {}.", def.name));
-            return true;
+        let (x, y) = (self.player_x, self.player_y);
+        if self.apply_item_effects(def, x, y) == 0 && !def.use_effects.is_empty() {
+            self.log("Nothing happens.");
+            return false;
         }
-
         self.player_ap -= cost;
-        if def.heal > 0 {
-            let heal = def.heal.min(self.player_max_hp - self.player_hp);
-            self.player_hp += heal;
-            self.log_typed(
-                format!("You use {}. (+{} HP)", def.name, heal),
-                MsgType::Loot,
-            );
-        }
-        if def.reduces_refraction > 0 {
-            let reduce = def.reduces_refraction.min(self.refraction);
-            self.refraction -= reduce;
-            self.log_typed(
-                format!("Your glow fades slightly. (-{} Refraction)", reduce),
-                MsgType::Status,
-            );
-        }
-        if def.suppresses_adaptations {
-            self.adaptations_hidden_turns = 10;
-            self.log_typed(
-                "Your glow dims. The tincture masks your changes.",
-                MsgType::Status,
-            );
-        }
-        if def.reveals_map {
-            self.log_typed(
-                format!("The {} reveals hidden paths...", def.name),
-                MsgType::Loot,
-            );
-            for idx in 0..self.map.tiles.len() {
-                self.revealed.insert(idx);
-            }
-        }
-        if def.enables_aria_dialogue {
-            self.log_typed("You interface with ARIA...", MsgType::System);
-            self.quest_log.on_aria_interfaced(&def.id);
-            // Trigger ARIA dialogue if we have a pending dialogue system
-            // For now, we just log it.
-        }
-
-        // New system integrations
-        if def.light_energy > 0 {
-            self.light_system.light_energy += def.light_energy;
-            self.log_typed(
-                format!(
-                    "Light energy surges through you! (+{} Light Energy)",
-                    def.light_energy
-                ),
-                MsgType::Status,
-            );
-        }
-        if def.teaches_light_manipulation {
-            self.log_typed(
-                "You learn to manipulate light! Use debug commands: focus_beam, create_prism",
-                MsgType::System,
-            );
-        }
-        if def.void_exposure > 0 {
-            let level_changed = self.void_system.add_exposure(def.void_exposure);
-            self.log_typed(
-                format!(
-                    "Void corruption seeps into you! (+{} Void Exposure)",
-                    def.void_exposure
-                ),
-                MsgType::Status,
-            );
-            if level_changed {
-                self.log_typed(
-                    format!(
-                        "Void exposure level: {:?}",
-                        self.void_system.exposure_level()
-                    ),
-                    MsgType::Status,
-                );
-            }
-        }
-        if def.void_energy > 0 {
-            self.void_system.gain_energy(def.void_energy);
-            self.log_typed(
-                format!(
-                    "Void energy flows through you! (+{} Void Energy)",
-                    def.void_energy
-                ),
-                MsgType::Status,
-            );
-        }
-        if def.teaches_crystal_resonance {
-            self.log_typed("You learn crystal resonance! Use debug commands: create_crystal, resonate, harmonize", MsgType::System);
-        }
-        if def.resonance_energy > 0 {
-            self.crystal_system.resonance_energy = (self.crystal_system.resonance_energy
-                + def.resonance_energy)
-                .min(self.crystal_system.max_resonance_energy);
-            self.log_typed(
-                format!(
-                    "Crystal resonance fills you! (+{} Resonance Energy)",
-                    def.resonance_energy
-                ),
-                MsgType::Status,
-            );
-        }
-        if let Some(frequency) = &def.crystal_frequency {
-            let freq = match frequency.as_str() {
-                "alpha" => super::crystal_resonance::CrystalFrequency::Alpha,
-                "beta" => super::crystal_resonance::CrystalFrequency::Beta,
-                "gamma" => super::crystal_resonance::CrystalFrequency::Gamma,
-                "delta" => super::crystal_resonance::CrystalFrequency::Delta,
-                "epsilon" => super::crystal_resonance::CrystalFrequency::Epsilon,
-                _ => super::crystal_resonance::CrystalFrequency::Alpha,
-            };
-            self.crystal_system
-                .add_crystal(self.player_x, self.player_y, freq);
-            self.log_typed(
-                format!("A {} crystal grows at your feet!", frequency),
-                MsgType::Loot,
-            );
-        }
-
         if def.consumable {
-            self.inventory.remove(idx);
+            self.take_item(idx);
         }
         true
     }
@@ -3973,11 +4095,15 @@ impl GameState {
         if idx >= self.inventory.len() {
             return false;
         }
+        let def = match self.inventory[idx].def() {
+            Some(d) => d,
+            None => return false,
+        };
 
-        // Check range (must be adjacent)
+        // Check range (adjacent unless the item reaches further)
         let dx = (x - self.player_x).abs();
         let dy = (y - self.player_y).abs();
-        if dx > 1 || dy > 1 {
+        if dx > def.use_range || dy > def.use_range {
             self.log("That is too far away.");
             return false;
         }
@@ -3987,53 +4113,15 @@ impl GameState {
             return false;
         }
 
-        let id = &self.inventory[idx];
-        let def = match get_item_def(id) {
-            Some(d) => d,
-            None => return false,
-        };
-
-        if def.breaks_walls {
-            let tile_idx = (y * self.map.width as i32 + x) as usize;
-            if tile_idx >= self.map.tiles.len() {
-                return false;
-            }
-
-            let is_wall = matches!(self.map.tiles[tile_idx], super::map::Tile::Wall { .. });
-            if !is_wall {
-                self.log("You can only use this on walls.");
-                return false;
-            }
-
-            self.player_ap -= cost;
-            let mut broken = false;
-            if let super::map::Tile::Wall { id: _, hp } = &mut self.map.tiles[tile_idx] {
-                *hp -= 10; // Arbitrary damage for now
-                if *hp <= 0 {
-                    broken = true;
-                }
-            }
-
-            self.log_typed(
-                "You strike the wall. Cracks spread through the glass.",
-                MsgType::Combat,
-            );
-
-            if broken {
-                self.map.tiles[tile_idx] = super::map::Tile::default_floor();
-                self.log_typed("The wall shatters!", MsgType::Combat);
-                self.update_lighting(); // Wall break changes lighting
-            }
-
-            // Consume item if consumable (or maybe always for now as per discussion)
-            if def.consumable {
-                self.inventory.remove(idx);
-            }
-            return true;
+        if self.apply_item_effects(def, x, y) == 0 {
+            self.log(format!("You can't use {} on that.", def.name));
+            return false;
         }
-
-        self.log(format!("You can't use {} on that.", def.name));
-        false
+        self.player_ap -= cost;
+        if def.consumable {
+            self.take_item(idx);
+        }
+        true
     }
 
     pub fn use_psychic_ability(&mut self, ability_id: &str) {
@@ -4079,13 +4167,14 @@ impl GameState {
         if inv_idx >= self.inventory.len() {
             return false;
         }
-        let item_id = self.inventory[inv_idx].clone();
+        let Some(item) = self.take_item(inv_idx) else {
+            return false;
+        };
 
         // Unequip current item in slot (returns to inventory)
-        if let Some(old) = self.equipment.set(slot, Some(item_id)) {
-            self.inventory.push(old);
+        if let Some(old) = self.equipment.set(slot, Some(item)) {
+            self.give_item(old);
         }
-        self.inventory.remove(inv_idx);
         self.recalc_equipment_stats();
         true
     }
@@ -4093,7 +4182,7 @@ impl GameState {
     /// Unequip item from slot back to inventory
     pub fn unequip_slot(&mut self, slot: EquipSlot) -> bool {
         if let Some(item) = self.equipment.set(slot, None) {
-            self.inventory.push(item);
+            self.give_item(item);
             self.recalc_equipment_stats();
             true
         } else {
@@ -4102,18 +4191,23 @@ impl GameState {
     }
 
     /// Recalculate stats from equipment
-    fn recalc_equipment_stats(&mut self) {
-        // Sync equipped_weapon with equipment.weapon for backward compat
-        self.equipped_weapon = self.equipment.weapon.clone();
+    pub(crate) fn recalc_equipment_stats(&mut self) {
+        // Sync equipped_weapon with equipment.weapon for backward compat;
+        // a broken weapon strikes no better than bare fists
+        self.equipped_weapon = self
+            .equipment
+            .weapon
+            .as_ref()
+            .filter(|w| !w.is_broken())
+            .map(|w| w.id.clone());
 
-        // Calculate armor from equipped jacket item
+        // Armor from every equipped item that is not broken
         self.player_armor = self
             .equipment
-            .jacket
-            .as_ref()
-            .and_then(|id| get_item_def(id))
-            .map(|def| def.armor_value)
-            .unwrap_or(0);
+            .iter()
+            .filter_map(|(_, item)| item)
+            .map(|item| item.armor())
+            .sum();
     }
 
     /// Accept a quest by ID
@@ -4168,7 +4262,7 @@ impl GameState {
                 self.log(format!("Received {} salt scrip", reward.salt_scrip));
             }
             for item_id in &reward.items {
-                self.inventory.push(item_id.clone());
+                self.give_item(ItemInstance::new(item_id));
             }
             // Log unlocked quests
             if !reward.unlocks_quests.is_empty() {
@@ -4197,16 +4291,12 @@ impl GameState {
 
         // Remove materials
         for (item_id, &count) in &recipe.materials {
-            for _ in 0..count {
-                if let Some(idx) = self.inventory.iter().position(|id| id == item_id) {
-                    self.inventory.remove(idx);
-                }
-            }
+            self.take_items(item_id, count);
         }
 
         // Add output
         for _ in 0..recipe.output_count {
-            self.inventory.push(recipe.output.clone());
+            self.give_item(ItemInstance::new(&recipe.output));
         }
 
         self.log(format!("Crafted {}.", recipe.name));
@@ -4239,7 +4329,7 @@ impl GameState {
 
         // Execute transaction
         self.salt_scrip -= price;
-        self.inventory.push(item_id.to_string());
+        self.give_item(ItemInstance::new(item_id));
         self.log(format!("Bought {} for {} salt scrip", item_def.name, price));
         Ok(())
     }
@@ -4250,22 +4340,21 @@ impl GameState {
         let item_idx = self
             .inventory
             .iter()
-            .position(|id| id == item_id)
+            .position(|item| item.id == item_id)
             .ok_or_else(|| format!("You don't have that item"))?;
 
-        // Get item value
-        let item_def =
-            get_item_def(item_id).ok_or_else(|| format!("Item '{}' not found", item_id))?;
-
-        // Sell for half value
-        let sell_price = item_def.value / 2;
+        // Sell for half value, after affixes and wear
+        let item = self
+            .take_item(item_idx)
+            .ok_or_else(|| format!("Item '{}' not found", item_id))?;
+        let sell_price = item.value() / 2;
 
         // Execute transaction
-        self.inventory.remove(item_idx);
         self.salt_scrip += sell_price;
         self.log(format!(
             "Sold {} for {} salt scrip",
-            item_def.name, sell_price
+            item.name(),
+            sell_price
         ));
         Ok(())
     }
@@ -4354,76 +4443,6 @@ impl GameState {
         self.status_effects.iter().any(|e| e.id == effect_id)
     }
 
-    /// Process enemy behavior on attack
-    pub fn process_enemy_behavior(
-        &mut self,
-        enemy_index: usize,
-        behavior_type: &str,
-        params: &super::enemy::Behavior,
-    ) -> bool {
-        match behavior_type {
-            "reflect_damage" => {
-                if let Some(percent) = params.percent {
-                    let reflected = (self.last_damage_dealt * percent / 100) as i32;
-                    if reflected > 0 {
-                        self.player_hp -= reflected;
-                        self.log_typed(
-                            format!("The enemy reflects {} damage back at you!", reflected),
-                            MsgType::Combat,
-                        );
-                        return true;
-                    }
-                }
-            }
-            "poison_sting" => {
-                if let Some(duration) = params.value {
-                    self.apply_status_effect("poison", duration as i32);
-                    return true;
-                }
-            }
-            "web_trap" => {
-                if let Some(turns) = params.value {
-                    self.apply_status_effect("immobilized", turns as i32);
-                    self.log_typed("You are trapped in webbing!".to_string(), MsgType::Combat);
-                    return true;
-                }
-            }
-            "drain_sanity" => {
-                if let Some(amount) = params.value {
-                    // Placeholder for sanity system
-                    self.log_typed(
-                        format!("Your mind reels from the encounter! (-{} sanity)", amount),
-                        MsgType::Combat,
-                    );
-                    return true;
-                }
-            }
-            "teleport" => {
-                if let Some(range) = params.value {
-                    // Find valid teleport position
-                    for _ in 0..10 {
-                        let dx = self.rng.gen_range(-(range as i32)..=(range as i32));
-                        let dy = self.rng.gen_range(-(range as i32)..=(range as i32));
-                        let new_x = self.enemies[enemy_index].x + dx;
-                        let new_y = self.enemies[enemy_index].y + dy;
-
-                        if let Some(tile) = self.map.get(new_x, new_y) {
-                            if matches!(*tile, super::map::Tile::Floor { .. }) {
-                                self.enemies[enemy_index].x = new_x;
-                                self.enemies[enemy_index].y = new_y;
-                                self.log_typed("The enemy teleports!".to_string(), MsgType::Combat);
-                                self.rebuild_spatial_index();
-                                return true;
-                            }
-                        }
-                    }
-                }
-            }
-            _ => return false,
-        }
-        false
-    }
-
     /// Apply light-based effects (glare damage, visibility modifiers)
     pub fn apply_light_effects(&mut self) {
         if self.debug_disable_glare {
@@ -4442,8 +4461,8 @@ impl GameState {
         // }
 
         // Light-based item effects
-        for item_id in &self.inventory.clone() {
-            if let Some(def) = super::item::get_item_def(item_id) {
+        for item in &self.inventory.clone() {
+            if let Some(def) = item.def() {
                 if def.reveals_storm_timing && light_level > 150 {
                     // Storm Chart works better in bright light
                     if self.rng.gen_range(0..100) < 10 {
@@ -4469,13 +4488,18 @@ impl GameState {
     }
 
     pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
-        let data = ron::to_string(self).map_err(|e| e.to_string())?;
+        self.save_as(path, save::SaveFormat::Ron)
+    }
+
+    pub fn save_as(&self, path: impl AsRef<Path>, format: save::SaveFormat) -> Result<(), String> {
+        let data = save::encode_as(self, format)?;
         fs::write(path, data).map_err(|e| e.to_string())
     }
 
+    /// Load a save of any supported format version and encoding, migrating it to the current one
     pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
-        let data = fs::read_to_string(path).map_err(|e| e.to_string())?;
-        let mut state: Self = ron::from_str(&data).map_err(|e| e.to_string())?;
+        let data = fs::read(path).map_err(|e| e.to_string())?;
+        let (_, mut state) = save::decode_bytes(&data)?;
         state.rebuild_spatial_index();
         state.update_lighting(); // Recalculate lighting after loading
         Ok(state)
@@ -4483,7 +4507,7 @@ impl GameState {
 }
 
 /// Simple Bresenham line for projectile paths
-fn line_path(from: (i32, i32), to: (i32, i32)) -> Vec<(i32, i32)> {
+pub(crate) fn line_path(from: (i32, i32), to: (i32, i32)) -> Vec<(i32, i32)> {
     let mut path = Vec::new();
     let (mut x0, mut y0) = from;
     let (x1, y1) = to;
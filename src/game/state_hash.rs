@@ -0,0 +1,541 @@
+//! Stable content hashing of game state
+//!
+//! Hashes are computed by walking a value's serde serialization, so they cover
+//! exactly what a save file covers: `#[serde(skip)]` fields (FOV, spatial
+//! caches, UI effects) are left out. Map entries are combined independently of
+//! iteration order and hash sets are serialized sorted, so equal states hash
+//! equal within and across runs. FNV-1a keeps values stable across builds and
+//! platforms, which lets hashes be stored in replay files.
+
+use serde::ser::{self, Serialize, Serializer};
+use std::collections::HashSet;
+use std::fmt;
+
+use super::state::GameState;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Top-level `GameState` fields excluded from `state_hash`: they track the
+/// player's wall clock rather than anything that happens in play
+pub const UNHASHED_FIELDS: &[&str] = &["playtime_secs"];
+
+/// Serialize a hash set in sorted order so equal sets produce identical output
+pub fn sorted_set<T, S>(set: &HashSet<T>, s: S) -> Result<S::Ok, S::Error>
+where
+    T: Ord + Serialize,
+    S: Serializer,
+{
+    let mut items: Vec<&T> = set.iter().collect();
+    items.sort();
+    s.collect_seq(items)
+}
+
+/// Hash of any serializable value
+pub fn content_hash<T: Serialize + ?Sized>(value: &T) -> u64 {
+    let mut hasher = ContentHasher::new(false);
+    value
+        .serialize(&mut hasher)
+        .expect("content hashing never fails");
+    hasher.finish()
+}
+
+/// Hash of each top-level field of a serializable struct, in declaration order
+pub fn field_hashes<T: Serialize + ?Sized>(value: &T) -> Vec<(&'static str, u64)> {
+    let mut hasher = ContentHasher::new(true);
+    value
+        .serialize(&mut hasher)
+        .expect("content hashing never fails");
+    hasher.fields.unwrap_or_default()
+}
+
+/// Per-field hashes of a game state, including the RNG stream position
+/// (saves only keep the RNG seed) and excluding `UNHASHED_FIELDS`
+pub fn state_field_hashes(state: &GameState) -> Vec<(&'static str, u64)> {
+    let mut fields: Vec<_> = field_hashes(state)
+        .into_iter()
+        .filter(|(name, _)| !UNHASHED_FIELDS.contains(name))
+        .collect();
+    fields.push(("rng_position", content_hash(&state.rng.get_word_pos())));
+    fields
+}
+
+/// Combine per-field hashes from `state_field_hashes` into one state hash
+pub fn combine_field_hashes(fields: &[(&str, u64)]) -> u64 {
+    let mut fnv = Fnv::new();
+    for (name, hash) in fields {
+        fnv.write_str(name);
+        fnv.write_u64(*hash);
+    }
+    fnv.0
+}
+
+impl GameState {
+    /// Stable hash of everything that affects play. Two states with the same
+    /// hash continue identically given the same input.
+    pub fn state_hash(&self) -> u64 {
+        combine_field_hashes(&state_field_hashes(self))
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Fnv(u64);
+
+impl Fnv {
+    fn new() -> Self {
+        Fnv(FNV_OFFSET)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 ^= b as u64;
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_u64(&mut self, v: u64) {
+        self.write(&v.to_le_bytes());
+    }
+
+    fn write_str(&mut self, s: &str) {
+        self.write_u64(s.len() as u64);
+        self.write(s.as_bytes());
+    }
+}
+
+#[derive(Debug)]
+pub struct HashError(String);
+
+impl fmt::Display for HashError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for HashError {}
+
+impl ser::Error for HashError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        HashError(msg.to_string())
+    }
+}
+
+/// Serializer that feeds every value into an FNV hash. Each value is prefixed
+/// with a type tag so differently shaped data cannot collide by accident.
+struct ContentHasher {
+    fnv: Fnv,
+    /// Collects struct field hashes when set
+    fields: Option<Vec<(&'static str, u64)>>,
+}
+
+impl ContentHasher {
+    fn new(record_fields: bool) -> Self {
+        Self {
+            fnv: Fnv::new(),
+            fields: record_fields.then(Vec::new),
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.fnv.0
+    }
+
+    fn tag(&mut self, tag: u8) {
+        self.fnv.write(&[tag]);
+    }
+
+    fn field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) {
+        let hash = content_hash(value);
+        self.fnv.write_str(key);
+        self.fnv.write_u64(hash);
+        if let Some(fields) = &mut self.fields {
+            fields.push((key, hash));
+        }
+    }
+}
+
+/// Map entries are hashed one at a time and summed, so entry order is irrelevant
+struct MapHasher<'a> {
+    parent: &'a mut ContentHasher,
+    entry: Option<ContentHasher>,
+    sum: u64,
+    count: u64,
+}
+
+impl<'a> Serializer for &'a mut ContentHasher {
+    type Ok = ();
+    type Error = HashError;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = MapHasher<'a>;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(self, v: bool) -> Result<(), HashError> {
+        self.tag(b'b');
+        self.fnv.write(&[v as u8]);
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), HashError> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), HashError> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), HashError> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), HashError> {
+        self.tag(b'i');
+        self.fnv.write(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<(), HashError> {
+        self.tag(b'I');
+        self.fnv.write(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), HashError> {
+        self.serialize_u64(v as u64)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), HashError> {
+        self.serialize_u64(v as u64)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), HashError> {
+        self.serialize_u64(v as u64)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), HashError> {
+        self.tag(b'u');
+        self.fnv.write_u64(v);
+        Ok(())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<(), HashError> {
+        self.tag(b'U');
+        self.fnv.write(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), HashError> {
+        self.serialize_f64(v as f64)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), HashError> {
+        self.tag(b'f');
+        self.fnv.write_u64(v.to_bits());
+        Ok(())
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), HashError> {
+        self.tag(b'c');
+        self.fnv.write(&(v as u32).to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), HashError> {
+        self.tag(b's');
+        self.fnv.write_str(v);
+        Ok(())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), HashError> {
+        self.tag(b'y');
+        self.fnv.write_u64(v.len() as u64);
+        self.fnv.write(v);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), HashError> {
+        self.tag(b'n');
+        Ok(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), HashError> {
+        self.tag(b'o');
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), HashError> {
+        self.tag(b'0');
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), HashError> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<(), HashError> {
+        self.tag(b'v');
+        self.fnv.write_str(variant);
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), HashError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), HashError> {
+        self.tag(b'v');
+        self.fnv.write_str(variant);
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self, HashError> {
+        self.tag(b'[');
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self, HashError> {
+        self.tag(b'(');
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self, HashError> {
+        self.tag(b'(');
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, HashError> {
+        self.tag(b'v');
+        self.fnv.write_str(variant);
+        self.tag(b'(');
+        Ok(self)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<MapHasher<'a>, HashError> {
+        Ok(MapHasher {
+            parent: self,
+            entry: None,
+            sum: 0,
+            count: 0,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self, HashError> {
+        self.tag(b'{');
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, HashError> {
+        self.tag(b'v');
+        self.fnv.write_str(variant);
+        self.tag(b'{');
+        Ok(self)
+    }
+}
+
+impl ser::SerializeSeq for &mut ContentHasher {
+    type Ok = ();
+    type Error = HashError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), HashError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), HashError> {
+        self.tag(b']');
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for &mut ContentHasher {
+    type Ok = ();
+    type Error = HashError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), HashError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), HashError> {
+        self.tag(b')');
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for &mut ContentHasher {
+    type Ok = ();
+    type Error = HashError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), HashError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), HashError> {
+        self.tag(b')');
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleVariant for &mut ContentHasher {
+    type Ok = ();
+    type Error = HashError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), HashError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), HashError> {
+        self.tag(b')');
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for &mut ContentHasher {
+    type Ok = ();
+    type Error = HashError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), HashError> {
+        self.field(key, value);
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), HashError> {
+        self.tag(b'}');
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for &mut ContentHasher {
+    type Ok = ();
+    type Error = HashError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), HashError> {
+        self.field(key, value);
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), HashError> {
+        self.tag(b'}');
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for MapHasher<'_> {
+    type Ok = ();
+    type Error = HashError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), HashError> {
+        let mut entry = ContentHasher::new(false);
+        key.serialize(&mut entry)?;
+        self.entry = Some(entry);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), HashError> {
+        let mut entry = self
+            .entry
+            .take()
+            .ok_or_else(|| <HashError as ser::Error>::custom("map value without a key"))?;
+        value.serialize(&mut entry)?;
+        self.sum = self.sum.wrapping_add(entry.finish());
+        self.count += 1;
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), HashError> {
+        self.parent.tag(b'm');
+        self.parent.fnv.write_u64(self.count);
+        self.parent.fnv.write_u64(self.sum);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn map_hash_ignores_insertion_order() {
+        let forward: HashMap<i32, i32> = (0..100).map(|i| (i, i * 3)).collect();
+        let backward: HashMap<i32, i32> = (0..100).rev().map(|i| (i, i * 3)).collect();
+        assert_eq!(content_hash(&forward), content_hash(&backward));
+
+        let mut changed = forward.clone();
+        changed.insert(7, 0);
+        assert_ne!(content_hash(&forward), content_hash(&changed));
+    }
+
+    #[test]
+    fn equal_states_hash_equal() {
+        let a = GameState::new(42);
+        let b = GameState::new(42);
+        assert_eq!(a.state_hash(), b.state_hash());
+        assert_ne!(a.state_hash(), GameState::new(43).state_hash());
+    }
+
+    #[test]
+    fn hash_tracks_play_but_not_transient_fields() {
+        let mut state = GameState::new(42);
+        let before = state.state_hash();
+
+        state.playtime_secs += 60;
+        state.animation_frame += 1;
+        state.hit_flash_positions.push((1, 1, 3));
+        assert_eq!(state.state_hash(), before);
+
+        state.end_turn();
+        assert_ne!(state.state_hash(), before);
+    }
+
+    #[test]
+    fn hash_survives_save_round_trip() {
+        let state = GameState::new(42);
+        let data = super::super::save::encode_binary(&state).unwrap();
+        let (_, loaded) = super::super::save::decode_bytes(&data).unwrap();
+        // Saves keep only the RNG seed, so the stream position resets on load
+        let strip = |s: &GameState| {
+            state_field_hashes(s)
+                .into_iter()
+                .filter(|(name, _)| *name != "rng_position")
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(strip(&state), strip(&loaded));
+    }
+}
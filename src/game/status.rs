@@ -1,4 +1,5 @@
-use once_cell::sync::Lazy;
+use crate::game::content::{Changes, Registry};
+use crate::game::damage::DamageType;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -20,6 +21,8 @@ pub struct StatusEffectDef {
     #[serde(default)]
     pub tick_damage: i32,
     #[serde(default)]
+    pub damage_type: DamageType,
+    #[serde(default)]
     pub blocks_healing: bool,
     #[serde(default)]
     pub reduces_accuracy: i32,
@@ -27,6 +30,9 @@ pub struct StatusEffectDef {
     pub reduces_damage: i32,
     #[serde(default)]
     pub grants_invisibility: bool,
+    /// Added to the speed of whoever is affected, e.g. -50 for slow
+    #[serde(default)]
+    pub speed: i32,
 }
 
 #[derive(Deserialize)]
@@ -34,18 +40,25 @@ struct StatusEffectsFile {
     status_effects: Vec<StatusEffectDef>,
 }
 
-static STATUS_EFFECT_DEFS: Lazy<HashMap<String, StatusEffectDef>> = Lazy::new(|| {
-    let data = include_str!("../../data/status_effects.json");
-    let file: StatusEffectsFile =
-        serde_json::from_str(data).expect("Failed to parse status_effects.json");
-    file.status_effects
+static STATUS_EFFECT_DEFS: Registry<HashMap<String, StatusEffectDef>> =
+    Registry::new("status_effects.json", parse_status_effects);
+
+fn parse_status_effects(data: &str) -> Result<HashMap<String, StatusEffectDef>, String> {
+    let file: StatusEffectsFile = serde_json::from_str(data).map_err(|e| e.to_string())?;
+    Ok(file
+        .status_effects
         .into_iter()
         .map(|d| (d.id.clone(), d))
-        .collect()
-});
+        .collect())
+}
 
 pub fn get_status_def(id: &str) -> Option<&'static StatusEffectDef> {
-    STATUS_EFFECT_DEFS.get(id)
+    STATUS_EFFECT_DEFS.get().get(id)
+}
+
+/// Replace the status effect definitions with those parsed from `data`
+pub fn reload_status_defs(data: &str) -> Result<Changes, String> {
+    STATUS_EFFECT_DEFS.replace(data)
 }
 
 impl StatusEffect {
@@ -77,6 +90,15 @@ impl StatusEffect {
     }
 }
 
+/// Total speed change from `effects`
+pub fn speed_modifier(effects: &[StatusEffect]) -> i32 {
+    effects
+        .iter()
+        .filter_map(|e| get_status_def(&e.id))
+        .map(|d| d.speed)
+        .sum()
+}
+
 // Legacy compatibility functions
 pub fn is_stunned(_effects: &[StatusEffect]) -> bool {
     false // Placeholder
@@ -0,0 +1,215 @@
+//! Noise, perception and sneaking
+//!
+//! Noisy actions are heard by enemies up to the number of steps listed for
+//! them under `noise` in actions.json. Sound spreads over walkable tiles, so
+//! walls muffle it and a noise behind a door travels around. An enemy that
+//! hears something goes to look unless it already knows where the player is.
+//!
+//! Enemies notice the player by sight when nothing opaque stands between
+//! them, within their sight range scaled by the player's exposure: the light
+//! of the player's own lantern or of their surroundings, whichever is
+//! brighter, read through `lighting::visibility_modifier`, so darkness hides
+//! and glare dazzles. Sneaking shields the lantern and halves exposure. Once
+//! it has noticed the player, or been attacked by them, an enemy follows them
+//! anywhere it can see them in its sight range and afterwards searches where
+//! it last saw them.
+
+use std::collections::{HashSet, VecDeque};
+
+use super::action::action_noise;
+use super::lighting::visibility_modifier;
+use super::map::Tile;
+use super::state::{GameState, MsgType, line_path};
+
+/// Lantern radius and intensity while walking openly and while sneaking
+const OPEN_LIGHT: (i32, u8) = (5, 100);
+const SHIELDED_LIGHT: (i32, u8) = (2, 30);
+
+/// Light level standing on a Glare tile counts as
+const GLARE_LIGHT: u8 = 255;
+
+/// Enemies this close always notice the player, however dark it is
+const MIN_DETECTION_RANGE: i32 = 2;
+
+/// How easily enemies can spot the player, for the HUD
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Visibility {
+    /// Only enemies right next to the player would notice them
+    Hidden,
+    /// Enemies have to come closer than usual to notice the player
+    Obscured,
+    /// Enemies notice the player anywhere in their sight range
+    Exposed,
+    /// A hostile enemy has noticed the player
+    Seen,
+}
+
+impl Visibility {
+    pub fn label(self) -> &'static str {
+        match self {
+            Visibility::Hidden => "Hidden",
+            Visibility::Obscured => "Obscured",
+            Visibility::Exposed => "Exposed",
+            Visibility::Seen => "Seen",
+        }
+    }
+}
+
+impl GameState {
+    /// Radius and intensity of the light the player carries
+    pub fn player_light(&self) -> (i32, u8) {
+        if self.sneaking {
+            SHIELDED_LIGHT
+        } else {
+            OPEN_LIGHT
+        }
+    }
+
+    /// Start or stop sneaking
+    pub fn toggle_sneak(&mut self) {
+        self.sneaking = !self.sneaking;
+        if self.sneaking {
+            self.log("You shield your light and move quietly.");
+        } else {
+            self.log("You stop sneaking.");
+        }
+        self.update_lighting();
+    }
+
+    /// How far a step carries: silent when sneaking, loud when running
+    pub fn footstep_noise(&self) -> i32 {
+        if self.sneaking {
+            0
+        } else if self.has_status_effect("sprint") || self.has_status_effect("haste") {
+            action_noise("run")
+        } else {
+            action_noise("move")
+        }
+    }
+
+    /// Multiplier on enemy sight ranges for spotting the player
+    pub fn player_exposure(&self) -> f32 {
+        let (px, py) = (self.player_x, self.player_y);
+        let lantern = self.player_light().1;
+        let light = if matches!(self.map.get(px, py), Some(Tile::Glare)) {
+            GLARE_LIGHT
+        } else {
+            // The light map includes the lantern at full strength here
+            let surroundings = self.get_light_level(px, py).saturating_sub(lantern);
+            surroundings.max(lantern)
+        };
+        let exposure = visibility_modifier(light).min(1.0);
+        if self.sneaking {
+            exposure * 0.5
+        } else {
+            exposure
+        }
+    }
+
+    /// Distance under which an enemy with `sight_range` notices the player
+    pub fn detection_range(&self, sight_range: i32) -> i32 {
+        let range = (sight_range as f32 * self.player_exposure()).ceil() as i32;
+        range.max(MIN_DETECTION_RANGE)
+    }
+
+    pub fn player_visibility(&self) -> Visibility {
+        let seen = self
+            .enemies
+            .iter()
+            .any(|e| e.hp > 0 && e.noticed_player && self.is_hostile_to_player(e));
+        let exposure = self.player_exposure();
+        if seen {
+            Visibility::Seen
+        } else if exposure >= 1.0 {
+            Visibility::Exposed
+        } else if exposure >= 0.5 {
+            Visibility::Obscured
+        } else {
+            Visibility::Hidden
+        }
+    }
+
+    /// Make a noise at (x, y) that carries `volume` steps over walkable
+    /// tiles. Enemies that have not noticed the player go to investigate.
+    pub fn make_noise(&mut self, x: i32, y: i32, volume: i32) {
+        if volume <= 0 {
+            return;
+        }
+        let mut heard_at = HashSet::from([(x, y)]);
+        let mut queue = VecDeque::from([((x, y), 0)]);
+        while let Some(((cx, cy), dist)) = queue.pop_front() {
+            if dist == volume {
+                continue;
+            }
+            for (nx, ny) in [(cx + 1, cy), (cx - 1, cy), (cx, cy + 1), (cx, cy - 1)] {
+                if self.map.get(nx, ny).is_some_and(|t| t.walkable()) && heard_at.insert((nx, ny)) {
+                    queue.push_back(((nx, ny), dist + 1));
+                }
+            }
+        }
+        for enemy in &mut self.enemies {
+            if enemy.hp > 0 && !enemy.noticed_player && heard_at.contains(&(enemy.x, enemy.y)) {
+                enemy.heard = Some((x, y));
+            }
+        }
+    }
+
+    /// Whether every tile between `from` and `to` can be seen through
+    pub fn has_line_of_sight(&self, from: (i32, i32), to: (i32, i32)) -> bool {
+        let path = line_path(from, to);
+        path.iter()
+            .skip(1)
+            .take(path.len().saturating_sub(2))
+            .all(|&(x, y)| self.map.get(x, y).is_some_and(|t| t.transparent()))
+    }
+
+    /// Update whether the enemy at index `i` has noticed the player. An
+    /// enemy that loses sight of them goes to where it last saw them.
+    pub fn perceive(&mut self, i: usize) {
+        let (px, py) = (self.player_x, self.player_y);
+        let e = &self.enemies[i];
+        let sight = e.def().map(|d| d.sight_range).unwrap_or(6);
+        let dist = (px - e.x).abs() + (py - e.y).abs();
+        let noticed = if !self.has_line_of_sight((e.x, e.y), (px, py)) {
+            false
+        } else if e.noticed_player || e.provoked {
+            dist < sight
+        } else {
+            dist < self.detection_range(sight)
+        };
+        if noticed == e.noticed_player {
+            return;
+        }
+        let announce = noticed
+            && self.is_hostile_to_player(e)
+            && self.visible.contains(&self.map.idx(e.x, e.y));
+        let name = e.name().to_string();
+        let e = &mut self.enemies[i];
+        e.noticed_player = noticed;
+        e.heard = if noticed { None } else { Some((px, py)) };
+        if announce {
+            self.log_typed(format!("{} notices you!", name), MsgType::Warning);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sneaking_in_the_dark_hides_the_player() {
+        let mut state = GameState::new(42);
+        state.layer = -1;
+        state.map.lights.clear();
+        state.items.clear();
+        state.update_lighting();
+        assert_eq!(state.player_exposure(), 1.0);
+        assert_eq!(state.detection_range(6), 6);
+
+        state.toggle_sneak();
+        assert!(state.player_exposure() < 0.5);
+        assert_eq!(state.detection_range(6), MIN_DETECTION_RANGE);
+        assert!(state.footstep_noise() == 0);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::game::content;
 use once_cell::sync::Lazy;
 use rand::Rng;
 use rand_chacha::ChaCha8Rng;
@@ -16,7 +17,7 @@ struct StormConfigFile {
 }
 
 static STORM_CONFIG: Lazy<StormConfigFile> = Lazy::new(|| {
-    serde_json::from_str(include_str!("../../data/storm_config.json"))
+    serde_json::from_str(&content::load("storm_config.json"))
         .expect("Failed to parse storm_config.json")
 });
 
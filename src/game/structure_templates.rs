@@ -1,10 +1,11 @@
 //! Structure template system for placing prebuilt structures
 
+use crate::game::content;
 use once_cell::sync::Lazy;
 use rand::Rng;
 use rand_chacha::ChaCha8Rng;
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use super::map::{Map, Tile, get_floor_def, get_wall_def};
 use super::npc::Npc;
@@ -49,8 +50,8 @@ struct StructureTemplatesFile {
     structures: Vec<StructureTemplate>,
 }
 
-static STRUCTURE_TEMPLATES: Lazy<HashMap<String, StructureTemplate>> = Lazy::new(|| {
-    let data = include_str!("../../data/structure_templates.json");
+static STRUCTURE_TEMPLATES: Lazy<BTreeMap<String, StructureTemplate>> = Lazy::new(|| {
+    let data = &content::load("structure_templates.json");
     let file: StructureTemplatesFile =
         serde_json::from_str(data).expect("Failed to parse structure_templates.json");
     file.structures
@@ -1,361 +1,213 @@
-use super::System;
+use super::combat::CombatSystem;
 use crate::game::{
-    event::GameEvent,
-    state::{GameState, MsgType},
+    action::action_noise,
+    behavior_tree::{BehaviorNode, UtilityFactor},
+    cover::Cover,
+    damage::DamageType,
+    enemy::{AIDemeanor, Enemy},
+    entity::EntityId,
+    faction::{Stance, relation},
+    flow_field::Field,
+    state::{BeamType, GameState, MsgType},
 };
 use bracket_pathfinding::prelude::*;
-use once_cell::sync::Lazy;
 use rand::Rng;
-use std::collections::HashMap;
-
-/// Trait for AI behaviors
-pub trait AiBehavior: Send + Sync {
-    /// Execute behavior for a single enemy
-    /// Returns true if the enemy took an action (ending its turn)
-    fn execute(&self, entity_idx: usize, state: &mut GameState) -> bool;
-}
-
-pub struct AiSystem;
+use std::collections::{HashMap, VecDeque};
+
+/// Take one action for the enemy at index `i`, unless it is dead or its AI
+/// is disabled
+pub fn act(i: usize, state: &mut GameState) {
+    // Skip dead enemies
+    if state.enemies[i].hp <= 0 {
+        return;
+    }
 
-impl System for AiSystem {
-    fn update(&self, state: &mut GameState) {
-        // We need to iterate indices because we mutate state
-        // and we can't hold a reference to the enemy while mutating state
-        let enemy_count = state.enemies.len();
+    // Skip if AI disabled
+    if state.enemies[i].ai_disabled {
+        return;
+    }
 
-        for i in 0..enemy_count {
-            // Skip dead enemies
-            if state.enemies[i].hp <= 0 {
-                continue;
-            }
+    // Run the behavior tree from the enemy definition
+    if let Some(tree) = state.enemies[i].def().and_then(|d| d.ai.as_ref()) {
+        run_tree(i, tree, state);
+    }
+}
 
-            // Skip if AI disabled
-            if state.enemies[i].ai_disabled {
-                continue;
-            }
+// --- Behavior trees ---
 
-            // Determine behavior from enemy definition
-            let behavior_id = state.enemies[i]
-                .def()
-                .and_then(|d| d.behavior_id.as_deref())
-                .unwrap_or("standard_melee");
+/// What an enemy is after while its tree runs
+struct Blackboard {
+    enemy: usize,
+    target: (i32, i32),
+    target_is_decoy: bool,
+    /// Creature of a hostile faction being hunted instead of the player
+    foe: Option<EntityId>,
+}
 
-            if let Some(behavior) = BEHAVIOR_REGISTRY.get(behavior_id) {
-                behavior.execute(i, state);
-            } else {
-                // Fallback to standard behavior if specified behavior not found
-                StandardMeleeBehavior.execute(i, state);
-            }
-        }
+impl Blackboard {
+    fn distance(&self, state: &GameState) -> i32 {
+        let e = &state.enemies[self.enemy];
+        (self.target.0 - e.x).abs() + (self.target.1 - e.y).abs()
     }
 
-    fn on_event(&self, _state: &mut GameState, _event: &GameEvent) {
-        // Handle AI related events
+    fn targets_player(&self) -> bool {
+        !self.target_is_decoy && self.foe.is_none()
     }
 }
 
-impl AiSystem {
-    pub fn update_enemies(state: &mut GameState) {
-        let system = AiSystem;
-        system.update(state);
+/// Run a behavior tree for the enemy at index `i` after it looks for the
+/// player, targeting the nearest creature of a hostile faction in sight if it
+/// is closer than the player or the player is no enemy of it, and otherwise
+/// the player unless the tree picks a decoy. Returns whether the tree
+/// succeeded.
+pub fn run_tree(i: usize, tree: &BehaviorNode, state: &mut GameState) -> bool {
+    state.perceive(i);
+    let mut bb = Blackboard {
+        enemy: i,
+        target: (state.player_x, state.player_y),
+        target_is_decoy: false,
+        foe: None,
+    };
+    if let Some(foe) = nearest_foe(state, i) {
+        let e = &state.enemies[foe];
+        bb.target = (e.x, e.y);
+        bb.foe = Some(e.entity_id);
     }
+    tick(tree, &mut bb, state)
 }
 
-// --- Behaviors ---
-
-struct StandardMeleeBehavior;
+/// The closest living creature in sight of the enemy at index `i` whose
+/// faction is hostile to its own, if it should be fought before the player
+fn nearest_foe(state: &GameState, i: usize) -> Option<usize> {
+    let e = &state.enemies[i];
+    if e.demeanor() == AIDemeanor::Pacifist {
+        return None;
+    }
+    let faction = e.faction();
+    let reach = |x: i32, y: i32| (x - e.x).abs() + (y - e.y).abs();
+    let mut limit = sight_range(state, i);
+    if state.is_hostile_to_player(e) {
+        limit = limit.min(reach(state.player_x, state.player_y));
+    }
+    state
+        .enemies
+        .iter()
+        .enumerate()
+        .filter(|(idx, other)| *idx != i && other.hp > 0 && reach(other.x, other.y) < limit)
+        .filter(|(_, other)| relation(faction, other.faction()) == Stance::Hostile)
+        .filter(|(_, other)| state.has_line_of_sight((e.x, e.y), (other.x, other.y)))
+        .min_by_key(|(_, other)| reach(other.x, other.y))
+        .map(|(idx, _)| idx)
+}
 
-impl AiBehavior for StandardMeleeBehavior {
-    fn execute(&self, i: usize, state: &mut GameState) -> bool {
-        // Extract enemy properties, using data-driven behavior flags
-        let (ex, ey, sight, attack_range, has_ranged, laser_damage, attacks) = {
-            let e = &state.enemies[i];
-            let def = e.def();
-
-            // Check for laser_beam behavior in enemy definition
-            let laser_dmg = def
-                .as_ref()
-                .and_then(|d| {
-                    d.behaviors
-                        .iter()
-                        .find(|b| b.behavior_type == "laser_beam")
-                        .and_then(|b| b.value.or(b.damage))
+fn tick(node: &BehaviorNode, bb: &mut Blackboard, state: &mut GameState) -> bool {
+    let i = bb.enemy;
+    match node {
+        BehaviorNode::Selector { children } => children.iter().any(|c| tick(c, bb, state)),
+        BehaviorNode::Sequence { children } => children.iter().all(|c| tick(c, bb, state)),
+        BehaviorNode::Utility { options } => {
+            let mut scored: Vec<(f32, &BehaviorNode)> = options
+                .iter()
+                .map(|o| {
+                    let factor = o.factor.map_or(1.0, |f| utility_factor(f, bb, state));
+                    (o.weight * factor, &o.node)
                 })
-                .map(|v| v as i32)
-                .unwrap_or(0);
-
-            (
-                e.x,
-                e.y,
-                def.map(|d| d.sight_range).unwrap_or(6),
-                def.map(|d| d.attack_range).unwrap_or(1),
-                def.map(|d| d.ranged_attack).unwrap_or(false),
-                laser_dmg,
-                1, // Attacks count - could also be data-driven
-            )
-        };
-
-        let has_laser = laser_damage > 0;
-
-        // First pass: handle spawners and AOE warnings
-        // This logic was in the outer loop in ai.rs, but we can put it here for now
-        // or move it to a separate "PreTurnBehavior"
-
-        // Handle spawners
-        if state.enemies[i].can_spawn(state.turn) {
-            if let Some(def) = state.enemies[i].def() {
-                if !def.spawn_types.is_empty() {
-                    let spawn_type =
-                        &def.spawn_types[state.rng.gen_range(0..def.spawn_types.len())];
-
-                    // Find nearby spawn location
-                    let mut spawned = false;
-                    for dx in -2..=2 {
-                        for dy in -2..=2 {
-                            if dx == 0 && dy == 0 {
-                                continue;
-                            }
-                            let sx = ex + dx;
-                            let sy = ey + dy;
-
-                            if state.map.get(sx, sy).map(|t| t.walkable()).unwrap_or(false)
-                                && state.enemy_at(sx, sy).is_none()
-                                && !(sx == state.player_x && sy == state.player_y)
-                            {
-                                let mut new_enemy =
-                                    crate::game::enemy::Enemy::new(sx, sy, spawn_type);
-                                if def.swarm {
-                                    new_enemy.swarm_id = Some(format!("spawner_{}", i));
-                                }
-
-                                state.enemies.push(new_enemy);
-                                // Update spatial index for new enemy
-                                state
-                                    .enemy_positions
-                                    .insert((sx, sy), state.enemies.len() - 1);
-                                state.enemies[i].spawned_count += 1;
-                                state.enemies[i].last_spawn_turn = state.turn;
-
-                                state.log_typed(
-                                    format!("{} spawns a {}!", state.enemies[i].name(), spawn_type),
-                                    MsgType::Combat,
-                                );
-                                state.trigger_effect("S(@3 &LightCyan &White)", 2);
-                                spawned = true;
-                                break;
-                            }
-                        }
-                        if spawned {
-                            break;
-                        }
-                    }
-                }
-            }
+                .collect();
+            // Stable, so equal scores keep their listed order
+            scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+            scored.into_iter().any(|(_, node)| tick(node, bb, state))
         }
-
-        // Handle AOE warning countdown
-        if state.enemies[i].tick_aoe_warning() {
-            // AOE attack is ready to execute
-            if let Some((target_x, target_y)) = state.enemies[i].aoe_target {
-                if let Some(def) = state.enemies[i].def() {
-                    let radius = def.aoe_radius as i32;
-                    let damage = state.rng.gen_range(def.damage_min..=def.damage_max);
-
-                    state.log_typed(
-                        format!("{} unleashes an area attack!", state.enemies[i].name()),
-                        MsgType::Combat,
-                    );
-
-                    // Check if player is in AOE
-                    let player_dist = ((state.player_x - target_x).pow(2)
-                        + (state.player_y - target_y).pow(2))
-                        as f32;
-                    if player_dist <= (radius as f32).powi(2) {
-                        let player_armor = state.effective_armor();
-                        let final_damage = (damage - player_armor).max(1);
-                        state.player_hp -= final_damage;
-                        state.trigger_hit_flash(state.player_x, state.player_y);
-                        state.spawn_damage_number(
-                            state.player_x,
-                            state.player_y,
-                            final_damage,
-                            false,
-                        );
-                        state.log_typed(
-                            format!("You take {} damage from the area attack!", final_damage),
-                            MsgType::Combat,
-                        );
-                    }
-
-                    // Visual effect
-                    state.trigger_effect(&format!("B(@{} &LightRed)", radius), 4);
-
-                    // Reset AOE state
-                    state.enemies[i].aoe_target = None;
-                    state.enemies[i].aoe_warning_turns = 0;
-                }
-            }
+        BehaviorNode::Invert { child } => !tick(child, bb, state),
+        BehaviorNode::Passthrough { child } => {
+            tick(child, bb, state);
+            false
         }
+        // Resolved when enemies.json is parsed
+        BehaviorNode::Tree { .. } => false,
 
-        // Main AI Logic
-        let dist = (ex - state.player_x).abs() + (ey - state.player_y).abs();
-
-        // Check behaviors from def
-        if let Some(def) = state.enemies[i].def() {
-            let ctx = crate::game::enemy::BehaviorContext {
-                player_adaptations: state.adaptations.len(),
-                player_items: &state.inventory,
-            };
-
-            for behavior in &def.behaviors {
-                if !behavior.condition_met(&ctx) {
-                    continue;
-                }
-
-                match behavior.behavior_type.as_str() {
-                    "teleport" => {
-                        // Teleport away if too close
-                        if dist <= 2 {
-                            let range = behavior.value.unwrap_or(5) as i32;
-                            for _ in 0..10 {
-                                let dx = state.rng.gen_range(-range..=range);
-                                let dy = state.rng.gen_range(-range..=range);
-                                let nx = ex + dx;
-                                let ny = ey + dy;
-                                if state.map.get(nx, ny).map(|t| t.walkable()).unwrap_or(false)
-                                    && state.enemy_at(nx, ny).is_none()
-                                    && !(nx == state.player_x && ny == state.player_y)
-                                {
-                                    state.enemy_positions.remove(&(ex, ey));
-                                    state.enemies[i].x = nx;
-                                    state.enemies[i].y = ny;
-                                    state.enemy_positions.insert((nx, ny), i);
-                                    state.log_typed(
-                                        format!("{} teleports away!", state.enemies[i].name()),
-                                        MsgType::Combat,
-                                    );
-                                    return true;
-                                }
-                            }
-                        }
-                    }
-                    "suicide_bomber" => {
-                        // Explode when adjacent to player
-                        if dist == 1 {
-                            let bomb_damage = behavior.damage.unwrap_or(8) as i32;
-                            let bomb_radius = behavior.value.unwrap_or(2) as i32;
-                            let player_armor = state.effective_armor();
-
-                            let final_damage = (bomb_damage - player_armor).max(1);
-                            state.player_hp -= final_damage;
-                            state.trigger_hit_flash(state.player_x, state.player_y);
-                            state.spawn_damage_number(
-                                state.player_x,
-                                state.player_y,
-                                final_damage,
-                                false,
-                            );
-                            state.log_typed(
-                                format!(
-                                    "{} explodes for {} damage!",
-                                    state.enemies[i].name(),
-                                    final_damage
-                                ),
-                                MsgType::Combat,
-                            );
-
-                            // Visual effect
-                            state.trigger_effect(&format!("B(@{} &LightRed)", bomb_radius), 4);
-
-                            // Kill the bomber
-                            state.enemies[i].hp = 0;
-                            return true;
-                        }
-                    }
-                    _ => {}
-                }
-            }
+        BehaviorNode::HpBelow { percent } => {
+            let e = &state.enemies[i];
+            let max_hp = e.def().map(|d| d.max_hp).unwrap_or(10).max(1);
+            e.hp * 100 < max_hp * percent
         }
-
-        // Demeanor-based behavior
-        let mut is_passive = false;
-        let mut should_flee = false;
-        if !state.enemies[i].is_hostile() {
-            is_passive = true;
+        BehaviorNode::Distance { min, max } => {
+            let dist = bb.distance(state);
+            min.is_none_or(|m| dist >= m) && max.is_none_or(|m| dist <= m)
         }
-        if state.enemies[i].should_flee() {
-            should_flee = true;
+        BehaviorNode::InSight if bb.targets_player() => state.enemies[i].noticed_player,
+        BehaviorNode::InSight => bb.distance(state) < sight_range(state, i),
+        BehaviorNode::InAttackRange => bb.distance(state) <= state.enemies[i].attack_range() as i32,
+        BehaviorNode::LineOfSight => {
+            let e = &state.enemies[i];
+            state.visible.contains(&state.map.idx(e.x, e.y))
         }
-
-        if is_passive {
-            return false;
+        BehaviorNode::InCover => {
+            let e = &state.enemies[i];
+            in_cover(state, (e.x, e.y), bb.target)
         }
-
-        // Check if preparing AOE attack
-        if state.enemies[i].is_preparing_aoe() {
-            // Show warning effect
-            if let Some((_target_x, _target_y)) = state.enemies[i].aoe_target {
-                state.trigger_effect("F(@2 &Red)", 1);
-                if state.enemies[i].aoe_warning_turns == 1 {
-                    state.log_typed(
-                        format!(
-                            "{} is preparing a devastating attack!",
-                            state.enemies[i].name()
-                        ),
-                        MsgType::Warning,
-                    );
+        BehaviorNode::AllyNearby { range, injured } => ally_count(state, i, *range, *injured) > 0,
+        BehaviorNode::Hostile => bb.foe.is_some() || state.is_hostile_to_player(&state.enemies[i]),
+        BehaviorNode::ShouldFlee => state.enemies[i].should_flee(),
+        BehaviorNode::TargetIsPlayer => bb.targets_player(),
+        BehaviorNode::Chance { percent } => state.rng.gen_range(0..100) < *percent,
+        BehaviorNode::PlayerAdaptations { min } => state.adaptations.len() >= *min,
+        BehaviorNode::PlayerHasItem { item } => state.has_item(item),
+
+        BehaviorNode::Idle => true,
+        BehaviorNode::PickTarget => {
+            if bb.foe.is_some() {
+                return true;
+            }
+            let (ex, ey) = (state.enemies[i].x, state.enemies[i].y);
+            let sight = sight_range(state, i);
+            // Clone decoys to iterate because rolling needs the rng
+            let decoys = state.decoys.clone();
+            for decoy in &decoys {
+                let decoy_dist = (decoy.x - ex).abs() + (decoy.y - ey).abs();
+                if decoy_dist < sight && state.rng.gen_bool(0.5) {
+                    bb.target = (decoy.x, decoy.y);
+                    bb.target_is_decoy = true;
+                    break;
                 }
             }
-            return true; // Don't move while preparing AOE
+            true
         }
-
-        if should_flee && dist < sight {
-            // Flee away from player
-            let dx = (ex - state.player_x).signum();
-            let dy = (ey - state.player_y).signum();
-            let nx = ex + dx;
-            let ny = ey + dy;
-            if state.map.get(nx, ny).map(|t| t.walkable()).unwrap_or(false)
-                && state.enemy_at(nx, ny).is_none()
-                && !(nx == state.player_x && ny == state.player_y)
+        BehaviorNode::Approach { pathfind } => approach(bb, state, *pathfind),
+        BehaviorNode::Flee => {
+            if bb.targets_player()
+                && state.flow_fields.in_use()
+                && step_downhill(state, i, Field::Flee)
             {
-                state.enemy_positions.remove(&(ex, ey));
-                state.enemies[i].x = nx;
-                state.enemies[i].y = ny;
-                state.enemy_positions.insert((nx, ny), i);
-            }
-            return true;
-        }
-
-        // Check for nearby decoys - 50% chance to target decoy instead
-        let mut target_x = state.player_x;
-        let mut target_y = state.player_y;
-        let mut target_is_decoy = false;
-
-        // We need to clone decoys to iterate because we might mutate state
-        let decoys = state.decoys.clone();
-        for decoy in &decoys {
-            let decoy_dist = (decoy.x - ex).abs() + (decoy.y - ey).abs();
-            if decoy_dist < sight && state.rng.gen_bool(0.5) {
-                target_x = decoy.x;
-                target_y = decoy.y;
-                target_is_decoy = true;
-                break;
+                return true;
             }
+            let (ex, ey) = (state.enemies[i].x, state.enemies[i].y);
+            let dx = (ex - bb.target.0).signum();
+            let dy = (ey - bb.target.1).signum();
+            step_to(state, i, ex + dx, ey + dy)
         }
-        let target_dist = (target_x - ex).abs() + (target_y - ey).abs();
-
-        // Ranged attack check
-        if has_ranged && target_dist <= attack_range as i32 && target_dist > 1 && !target_is_decoy {
-            let Some(def) = state.enemies[i].def() else {
-                return true;
+        BehaviorNode::Investigate => investigate(state, i),
+        BehaviorNode::SeekCover { range } => match cover_spot(bb, state, *range) {
+            Some(spot) => step_toward(state, i, spot, *range),
+            None => false,
+        },
+        BehaviorNode::MeleeAttack => melee_attack(bb, state),
+        BehaviorNode::RangedAttack => {
+            let Some(def) = state.enemies[i].def().filter(|d| d.ranged_attack) else {
+                return false;
             };
+            if bb.target_is_decoy {
+                return false;
+            }
+            let from = (state.enemies[i].x, state.enemies[i].y);
+            if !state.has_line_of_fire(from, bb.target) {
+                return false;
+            }
             let base_dmg = state.rng.gen_range(def.damage_min..=def.damage_max);
-            let player_armor = state.effective_armor();
-            let dmg = (base_dmg - player_armor).max(1);
-            state.player_hp -= dmg;
-            state.trigger_hit_flash(state.player_x, state.player_y);
-            state.spawn_damage_number(state.player_x, state.player_y, dmg, false);
+            if let Some(foe) = bb.foe {
+                state.spawn_beam(from, bb.target, BeamType::Arrow, 6);
+                return strike_foe(state, i, foe, base_dmg, def.damage_type);
+            }
+            let dmg = hit_player(state, base_dmg, def.damage_type);
             state.log_typed(
                 format!(
                     "{} fires a ranged attack for {} damage!",
@@ -364,348 +216,555 @@ impl AiBehavior for StandardMeleeBehavior {
                 ),
                 MsgType::Combat,
             );
-
-            // Visual effect for ranged attack
-            state.spawn_beam(
-                (ex, ey),
-                (state.player_x, state.player_y),
-                crate::game::state::BeamType::Arrow,
-                6,
-            );
-
-            return true;
+            state.spawn_beam(from, (state.player_x, state.player_y), BeamType::Arrow, 6);
+            true
         }
-
-        // AOE attack initiation
-        if let Some(def) = state.enemies[i].def() {
-            if def.aoe_attack
-                && target_dist <= sight
-                && target_dist <= 4
-                && !target_is_decoy
-                && state.enemies[i].aoe_target.is_none()
-            {
-                state.enemies[i].start_aoe_attack(target_x, target_y);
-                state.log_typed(
-                    format!(
-                        "{} begins charging an area attack!",
-                        state.enemies[i].name()
-                    ),
-                    MsgType::Warning,
-                );
-                return true;
+        BehaviorNode::Laser {
+            damage,
+            damage_type,
+        } => {
+            if !bb.targets_player() {
+                return false;
             }
+            let dmg = state.damage_player(*damage, *damage_type);
+            state.trigger_hit_flash(state.player_x, state.player_y);
+            state.spawn_damage_number(state.player_x, state.player_y, dmg, false);
+            state.log_typed(
+                format!(
+                    "{} fires a laser beam for {} damage!",
+                    state.enemies[i].name(),
+                    dmg
+                ),
+                MsgType::Combat,
+            );
+            let from = (state.enemies[i].x, state.enemies[i].y);
+            state.spawn_beam(from, (state.player_x, state.player_y), BeamType::Laser, 8);
+            true
         }
-
-        if target_dist == 1 {
-            if target_is_decoy {
-                // Attack decoy - it dissipates
-                state
-                    .decoys
-                    .retain(|d| !(d.x == target_x && d.y == target_y));
-                let dir = state.direction_from(ex, ey);
+        BehaviorNode::Explode {
+            damage,
+            radius,
+            damage_type,
+        } => {
+            if let Some(foe) = bb.foe {
                 state.log_typed(
-                    format!("{} {} attacks your decoy!", state.enemies[i].name(), dir),
+                    format!("{} explodes!", state.enemies[i].name()),
                     MsgType::Combat,
                 );
+                strike_foe(state, i, foe, *damage, *damage_type);
             } else {
-                // Attack player (possibly multiple times)
-                let Some(def) = state.enemies[i].def() else {
-                    return true;
-                };
-                for _ in 0..attacks {
-                    let base_dmg = state.rng.gen_range(def.damage_min..=def.damage_max);
-                    let player_armor = state.effective_armor();
-                    let dmg = (base_dmg - player_armor).max(1);
-                    state.player_hp -= dmg;
-                    state.trigger_hit_flash(state.player_x, state.player_y);
-                    state.spawn_damage_number(state.player_x, state.player_y, dmg, false);
-                    let dir = state.direction_from(ex, ey);
+                let dmg = hit_player(state, *damage, *damage_type);
+                state.log_typed(
+                    format!("{} explodes for {} damage!", state.enemies[i].name(), dmg),
+                    MsgType::Combat,
+                );
+            }
+            state.trigger_effect(&format!("B(@{} &LightRed)", radius.max(&1)), 4);
+            let (ex, ey) = (state.enemies[i].x, state.enemies[i].y);
+            state.enemies[i].hp = 0;
+            state.enemy_positions.remove(&(ex, ey));
+            true
+        }
+        BehaviorNode::Teleport { range } => {
+            let (ex, ey) = (state.enemies[i].x, state.enemies[i].y);
+            for _ in 0..10 {
+                let nx = ex + state.rng.gen_range(-range..=*range);
+                let ny = ey + state.rng.gen_range(-range..=*range);
+                if step_to(state, i, nx, ny) {
                     state.log_typed(
-                        format!(
-                            "{} {} attacks you for {} damage!",
-                            state.enemies[i].name(),
-                            dir,
-                            dmg
-                        ),
+                        format!("{} teleports away!", state.enemies[i].name()),
                         MsgType::Combat,
                     );
-
-                    // Trigger on_hit effects
-                    for e in &def.effects {
-                        if e.condition == "on_hit" {
-                            state.trigger_effect(&e.effect, 2);
-                        }
-                    }
-
-                    // Check on_hit behaviors
-                    for behavior in &def.behaviors {
-                        if behavior.behavior_type == "on_hit_refraction" {
-                            if let Some(val) = behavior.value {
-                                state.refraction += val;
-                                state.log_typed(
-                                    format!("Glass shards pierce you. (+{} Refraction)", val),
-                                    MsgType::Status,
-                                );
-                                state.check_adaptation_threshold();
-                            }
-                        }
-                    }
-
-                    if state.player_hp <= 0 {
-                        return true;
-                    }
+                    return true;
                 }
             }
-        } else if target_dist < sight {
-            // Laser beam check
-            if has_laser && !target_is_decoy && state.visible.contains(&state.map.idx(ex, ey)) {
-                // Fire laser
-                state.player_hp -= laser_damage;
-                state.trigger_hit_flash(state.player_x, state.player_y);
-                state.spawn_damage_number(state.player_x, state.player_y, laser_damage, false);
+            false
+        }
+        BehaviorNode::HealAlly { range, amount } => {
+            let Some(idx) = injured_ally(state, i, *range) else {
+                return false;
+            };
+            let target_name = state.enemies[idx].name().to_string();
+            if let Some(def) = state.enemies[idx].def() {
+                state.enemies[idx].hp = (state.enemies[idx].hp + amount).min(def.max_hp);
+            }
+            state.log_typed(
+                format!(
+                    "{} heals {} for {} HP!",
+                    state.enemies[i].name(),
+                    target_name,
+                    amount
+                ),
+                MsgType::Combat,
+            );
+            true
+        }
+        BehaviorNode::Cast {
+            status,
+            duration,
+            message,
+        } => {
+            if !bb.targets_player() {
+                return false;
+            }
+            let text = message.clone().unwrap_or_else(|| {
+                format!("{} afflicts you with {}!", state.enemies[i].name(), status)
+            });
+            state.log_typed(text, MsgType::Combat);
+            state.apply_status_effect(status, *duration);
+            true
+        }
+        BehaviorNode::Summon { ids } => summon(state, i, ids),
+        BehaviorNode::ChargeAoe => {
+            let enemy = &state.enemies[i];
+            if !bb.targets_player() || !enemy.has_aoe_attack() || enemy.aoe_target.is_some() {
+                return false;
+            }
+            state.enemies[i].start_aoe_attack(bb.target.0, bb.target.1);
+            state.log_typed(
+                format!(
+                    "{} begins charging an area attack!",
+                    state.enemies[i].name()
+                ),
+                MsgType::Warning,
+            );
+            true
+        }
+        BehaviorNode::AoeWarning => {
+            if !state.enemies[i].is_preparing_aoe() {
+                return false;
+            }
+            state.trigger_effect("F(@2 &Red)", 1);
+            if state.enemies[i].aoe_warning_turns == 1 {
                 state.log_typed(
                     format!(
-                        "{} fires a laser beam for {} damage!",
-                        state.enemies[i].name(),
-                        laser_damage
+                        "{} is preparing a devastating attack!",
+                        state.enemies[i].name()
                     ),
-                    MsgType::Combat,
-                );
-
-                // Visual effect for beam
-                state.spawn_beam(
-                    (ex, ey),
-                    (state.player_x, state.player_y),
-                    crate::game::state::BeamType::Laser,
-                    8,
+                    MsgType::Warning,
                 );
-
-                return true;
             }
+            true
+        }
+        BehaviorNode::ReleaseAoe => release_aoe(state, i),
+    }
+}
 
-            // Move toward target (player or decoy)
-            let enemy_idx = state.map.idx(ex, ey);
-            let target_idx = state.map.idx(target_x, target_y);
-            let (nx, ny) = if state.visible.contains(&enemy_idx) {
-                // Visible: use A* pathfinding
-                let path = a_star_search(enemy_idx, target_idx, &state.map);
-                if path.success && path.steps.len() > 1 {
-                    let next = path.steps[1];
-                    (
-                        (next % state.map.width) as i32,
-                        (next / state.map.width) as i32,
-                    )
-                } else {
-                    return false;
-                }
-            } else {
-                // Not visible: simple directional movement toward target
-                let dx = (target_x - ex).signum();
-                let dy = (target_y - ey).signum();
-                (ex + dx, ey + dy)
-            };
+fn sight_range(state: &GameState, i: usize) -> i32 {
+    state.enemies[i].def().map(|d| d.sight_range).unwrap_or(6)
+}
 
-            if state.map.get(nx, ny).map(|t| t.walkable()).unwrap_or(false)
-                && state.enemy_at(nx, ny).is_none()
-                && !(nx == state.player_x && ny == state.player_y)
-            {
-                state.enemy_positions.remove(&(ex, ey));
-                state.enemies[i].x = nx;
-                state.enemies[i].y = ny;
-                state.enemy_positions.insert((nx, ny), i);
-            }
+fn utility_factor(factor: UtilityFactor, bb: &Blackboard, state: &GameState) -> f32 {
+    let i = bb.enemy;
+    let sight = sight_range(state, i).max(1) as f32;
+    let distance = (bb.distance(state) as f32 / sight).min(1.0);
+    match factor {
+        UtilityFactor::MissingHp => {
+            let e = &state.enemies[i];
+            let max_hp = e.def().map(|d| d.max_hp).unwrap_or(10).max(1) as f32;
+            (1.0 - e.hp as f32 / max_hp).clamp(0.0, 1.0)
+        }
+        UtilityFactor::Distance => distance,
+        UtilityFactor::Closeness => 1.0 - distance,
+        UtilityFactor::InjuredAllies => {
+            (ally_count(state, i, sight_range(state, i), true) as f32 / 3.0).min(1.0)
         }
-
-        true
     }
 }
 
-/// Ranged-only behavior: maintains distance and fires ranged attacks
-struct RangedOnlyBehavior;
+/// Living enemies other than `i` within `range` whose factions are not at
+/// odds with its own
+fn allies(state: &GameState, i: usize, range: i32) -> impl Iterator<Item = (usize, &Enemy)> {
+    let (ex, ey) = (state.enemies[i].x, state.enemies[i].y);
+    let faction = state.enemies[i].faction();
+    state.enemies.iter().enumerate().filter(move |(idx, e)| {
+        *idx != i
+            && e.hp > 0
+            && (e.x - ex).abs() + (e.y - ey).abs() <= range
+            && relation(faction, e.faction()) != Stance::Hostile
+    })
+}
 
-impl AiBehavior for RangedOnlyBehavior {
-    fn execute(&self, i: usize, state: &mut GameState) -> bool {
-        let ex = state.enemies[i].x;
-        let ey = state.enemies[i].y;
-        let px = state.player_x;
-        let py = state.player_y;
-        let dist = (ex - px).abs() + (ey - py).abs();
+fn ally_count(state: &GameState, i: usize, range: i32, injured: bool) -> usize {
+    allies(state, i, range)
+        .filter(|(_, e)| !injured || e.def().is_some_and(|d| e.hp < d.max_hp))
+        .count()
+}
 
-        let sight = state.enemies[i].def().map(|d| d.sight_range).unwrap_or(6);
-        let attack_range = state.enemies[i].def().map(|d| d.attack_range).unwrap_or(4) as i32;
-        let min_range = 3; // Try to stay at least this far away
+/// The ally within `range` with the lowest share of its HP left
+fn injured_ally(state: &GameState, i: usize, range: i32) -> Option<usize> {
+    let mut healed_idx = None;
+    let mut lowest_hp_ratio = 1.0f32;
+    for (idx, enemy) in allies(state, i, range) {
+        if let Some(def) = enemy.def() {
+            let ratio = enemy.hp as f32 / def.max_hp as f32;
+            if ratio < lowest_hp_ratio {
+                lowest_hp_ratio = ratio;
+                healed_idx = Some(idx);
+            }
+        }
+    }
+    healed_idx
+}
+
+/// Move the enemy onto a free walkable tile
+fn step_to(state: &mut GameState, i: usize, nx: i32, ny: i32) -> bool {
+    if state.map.get(nx, ny).map(|t| t.walkable()).unwrap_or(false)
+        && state.enemy_at(nx, ny).is_none()
+        && !(nx == state.player_x && ny == state.player_y)
+    {
+        let (ex, ey) = (state.enemies[i].x, state.enemies[i].y);
+        state.enemy_positions.remove(&(ex, ey));
+        state.enemies[i].x = nx;
+        state.enemies[i].y = ny;
+        state
+            .enemy_positions
+            .insert((nx, ny), state.enemies[i].entity_id);
+        return true;
+    }
+    false
+}
 
-        // Only act if player in sight
-        if dist > sight {
-            return true;
+/// Apply armor to physical `damage`, hit the player with the rest less their
+/// resistance and return what was taken
+fn hit_player(state: &mut GameState, damage: i32, kind: DamageType) -> i32 {
+    let damage = match kind {
+        DamageType::Physical => {
+            let damage = (damage - state.effective_armor()).max(1);
+            state.wear_armor(1);
+            damage
         }
+        _ => damage,
+    };
+    let dmg = state.damage_player(damage, kind);
+    state.trigger_hit_flash(state.player_x, state.player_y);
+    state.spawn_damage_number(state.player_x, state.player_y, dmg, false);
+    dmg
+}
 
-        // If too close, retreat
-        if dist < min_range {
-            let dx = (ex - px).signum();
-            let dy = (ey - py).signum();
-            let nx = ex + dx;
-            let ny = ey + dy;
+/// Hit the creature `foe` for the enemy at index `i`, killing it if its HP
+/// runs out. Fails if the foe is already gone.
+fn strike_foe(
+    state: &mut GameState,
+    i: usize,
+    foe: EntityId,
+    damage: i32,
+    kind: DamageType,
+) -> bool {
+    let Some(t) = state.enemy_index(foe).filter(|&t| state.enemies[t].hp > 0) else {
+        return false;
+    };
+    let dmg = state.damage_enemy(t, damage, kind);
+    let (x, y) = (state.enemies[t].x, state.enemies[t].y);
+    state.make_noise(x, y, action_noise("attack_melee"));
+    state.trigger_hit_flash(x, y);
+    state.spawn_damage_number(x, y, dmg, false);
+    let (name, foe_name) = (state.enemies[i].name(), state.enemies[t].name());
+    let text = if state.enemies[t].hp > 0 {
+        format!("{} attacks the {} for {} damage!", name, foe_name, dmg)
+    } else {
+        format!("{} kills the {}!", name, foe_name)
+    };
+    if state.visible.contains(&state.map.idx(x, y)) {
+        state.log_typed(text, MsgType::Combat);
+    }
+    if state.enemies[t].hp <= 0 {
+        let killer = state.enemies[i].entity_id;
+        CombatSystem::process_enemy_death(state, t, x, y, Some(killer));
+    }
+    true
+}
 
-            if state.map.get(nx, ny).map(|t| t.walkable()).unwrap_or(false)
-                && state.enemy_at(nx, ny).is_none()
-                && !(nx == px && ny == py)
+/// Whether `at` has cover against `target` and a line of fire to it
+fn in_cover(state: &GameState, at: (i32, i32), target: (i32, i32)) -> bool {
+    state.cover_between(target, at) > Cover::None && state.has_line_of_fire(at, target)
+}
+
+/// The closest free tile within `range` of the enemy that is in cover from
+/// the target, with the target within attack range
+fn cover_spot(bb: &Blackboard, state: &GameState, range: i32) -> Option<(i32, i32)> {
+    let e = &state.enemies[bb.enemy];
+    let (ex, ey) = (e.x, e.y);
+    let attack_range = e.attack_range() as i32;
+    let mut best: Option<((i32, i32), i32)> = None;
+    for y in ey - range..=ey + range {
+        for x in ex - range..=ex + range {
+            let dist = (x - ex).abs() + (y - ey).abs();
+            if dist == 0 || best.is_some_and(|(_, d)| d <= dist) {
+                continue;
+            }
+            let to_target = (bb.target.0 - x).abs() + (bb.target.1 - y).abs();
+            if state.map.get(x, y).is_some_and(|t| t.walkable())
+                && state.enemy_at(x, y).is_none()
+                && (x, y) != (state.player_x, state.player_y)
+                && to_target <= attack_range
+                && in_cover(state, (x, y), bb.target)
             {
-                state.enemy_positions.remove(&(ex, ey));
-                state.enemies[i].x = nx;
-                state.enemies[i].y = ny;
-                state.enemy_positions.insert((nx, ny), i);
-                return true;
+                best = Some(((x, y), dist));
             }
         }
+    }
+    best.map(|(spot, _)| spot)
+}
 
-        // If in range, fire
-        if dist <= attack_range {
-            if let Some(def) = state.enemies[i].def() {
-                let dmg = state.rng.gen_range(def.damage_min..=def.damage_max);
-                state.player_hp -= dmg;
-                state.log_typed(
-                    format!("{} shoots you for {} damage!", state.enemies[i].name(), dmg),
-                    MsgType::Combat,
-                );
-                state.spawn_damage_number(px, py, dmg, true);
+const NEIGHBOURS: [(i32, i32); 8] = [
+    (-1, 0),
+    (1, 0),
+    (0, -1),
+    (0, 1),
+    (-1, -1),
+    (1, -1),
+    (-1, 1),
+    (1, 1),
+];
+
+/// Take the first step of the shortest path to `to` through free tiles,
+/// searching no further than `range` from the enemy. `to` itself may be
+/// taken, such as by the player the enemy is looking for.
+fn step_toward(state: &mut GameState, i: usize, to: (i32, i32), range: i32) -> bool {
+    let start = (state.enemies[i].x, state.enemies[i].y);
+    let free = |(x, y): (i32, i32)| {
+        (x - start.0).abs() <= range
+            && (y - start.1).abs() <= range
+            && state.map.get(x, y).is_some_and(|t| t.walkable())
+            && state.enemy_at(x, y).is_none()
+            && (x, y) != (state.player_x, state.player_y)
+    };
+    // Breadth-first, remembering the first step taken to reach each tile
+    let mut first_step = HashMap::new();
+    let mut queue = VecDeque::from([start]);
+    while let Some((x, y)) = queue.pop_front() {
+        if (x, y) == to {
+            let (nx, ny) = first_step[&to];
+            return step_to(state, i, nx, ny);
+        }
+        for (dx, dy) in NEIGHBOURS {
+            let next = (x + dx, y + dy);
+            if next != start && !first_step.contains_key(&next) && (next == to || free(next)) {
+                let step = first_step.get(&(x, y)).copied().unwrap_or(next);
+                first_step.insert(next, step);
+                queue.push_back(next);
             }
         }
-        true
     }
+    false
 }
 
-/// Suicide bomber: rushes player and explodes on contact
-struct SuicideBomberBehavior;
-
-impl AiBehavior for SuicideBomberBehavior {
-    fn execute(&self, i: usize, state: &mut GameState) -> bool {
-        let ex = state.enemies[i].x;
-        let ey = state.enemies[i].y;
-        let px = state.player_x;
-        let py = state.player_y;
-        let dist = (ex - px).abs() + (ey - py).abs();
+/// Step to the lowest free neighbour below the enemy on a shared map
+fn step_downhill(state: &mut GameState, i: usize, field: Field) -> bool {
+    let (ex, ey) = (state.enemies[i].x, state.enemies[i].y);
+    state
+        .flow_steps(field, ex, ey)
+        .into_iter()
+        .any(|(x, y)| step_to(state, i, x, y))
+}
 
-        let sight = state.enemies[i].def().map(|d| d.sight_range).unwrap_or(8);
+/// How far around itself an investigating enemy looks for a path
+const SEARCH_RANGE: i32 = 8;
+
+/// Step toward the noise the enemy heard, searching for a way around
+/// obstacles once it is close
+fn investigate(state: &mut GameState, i: usize) -> bool {
+    let Some((hx, hy)) = state.enemies[i].heard else {
+        return false;
+    };
+    let (ex, ey) = (state.enemies[i].x, state.enemies[i].y);
+    let dist = (hx - ex).abs() + (hy - ey).abs();
+    let moved = if dist <= 1 {
+        false
+    } else if dist <= SEARCH_RANGE {
+        step_toward(state, i, (hx, hy), SEARCH_RANGE)
+    } else {
+        let (dx, dy) = ((hx - ex).signum(), (hy - ey).signum());
+        step_to(state, i, ex + dx, ey + dy)
+            || step_to(state, i, ex + dx, ey)
+            || step_to(state, i, ex, ey + dy)
+    };
+    if !moved {
+        state.enemies[i].heard = None;
+    }
+    moved
+}
 
-        if dist > sight {
-            return true;
+fn approach(bb: &Blackboard, state: &mut GameState, pathfind: bool) -> bool {
+    let i = bb.enemy;
+    let (ex, ey) = (state.enemies[i].x, state.enemies[i].y);
+    let (target_x, target_y) = bb.target;
+    let enemy_idx = state.map.idx(ex, ey);
+    let (nx, ny) = if pathfind && state.visible.contains(&enemy_idx) {
+        if bb.targets_player() && state.flow_fields.in_use() {
+            return step_downhill(state, i, Field::Chase);
+        }
+        // Visible: use A* pathfinding
+        let target_idx = state.map.idx(target_x, target_y);
+        let path = a_star_search(enemy_idx, target_idx, &state.map);
+        if path.success && path.steps.len() > 1 {
+            let next = path.steps[1];
+            (
+                (next % state.map.width) as i32,
+                (next / state.map.width) as i32,
+            )
+        } else {
+            return false;
         }
+    } else {
+        // Not visible: simple directional movement toward target
+        let dx = (target_x - ex).signum();
+        let dy = (target_y - ey).signum();
+        (ex + dx, ey + dy)
+    };
+    step_to(state, i, nx, ny)
+}
 
-        // Adjacent to player - explode!
-        if dist == 1 {
-            let bomb_damage = state.enemies[i]
-                .def()
-                .and_then(|d| {
-                    d.behaviors
-                        .iter()
-                        .find(|b| b.behavior_type == "suicide_bomber")
-                })
-                .and_then(|b| b.damage)
-                .unwrap_or(15) as i32;
+fn melee_attack(bb: &Blackboard, state: &mut GameState) -> bool {
+    let i = bb.enemy;
+    if bb.distance(state) != 1 {
+        return false;
+    }
+    let (ex, ey) = (state.enemies[i].x, state.enemies[i].y);
+    if bb.target_is_decoy {
+        // Attack decoy - it dissipates
+        let (target_x, target_y) = bb.target;
+        state
+            .decoys
+            .retain(|d| !(d.x == target_x && d.y == target_y));
+        let dir = state.direction_from(ex, ey);
+        state.log_typed(
+            format!("{} {} attacks your decoy!", state.enemies[i].name(), dir),
+            MsgType::Combat,
+        );
+        return true;
+    }
 
-            state.player_hp -= bomb_damage;
-            state.log_typed(
-                format!(
-                    "{} explodes for {} damage!",
-                    state.enemies[i].name(),
-                    bomb_damage
-                ),
-                MsgType::Combat,
-            );
-            state.spawn_damage_number(px, py, bomb_damage, true);
+    let Some(def) = state.enemies[i].def() else {
+        return false;
+    };
+    let base_dmg = state.rng.gen_range(def.damage_min..=def.damage_max);
+    if let Some(foe) = bb.foe {
+        return strike_foe(state, i, foe, base_dmg, def.damage_type);
+    }
+    let dmg = hit_player(state, base_dmg, def.damage_type);
+    let dir = state.direction_from(ex, ey);
+    state.log_typed(
+        format!(
+            "{} {} attacks you for {} damage!",
+            state.enemies[i].name(),
+            dir,
+            dmg
+        ),
+        MsgType::Combat,
+    );
 
-            // Kill self
-            state.enemies[i].hp = 0;
-            state.enemy_positions.remove(&(ex, ey));
-            return true;
+    // Trigger on_hit effects
+    for e in &def.effects {
+        if e.condition == "on_hit" {
+            state.trigger_effect(&e.effect, 2);
         }
+    }
 
-        // Rush toward player
-        let dx = (px - ex).signum();
-        let dy = (py - ey).signum();
-        let nx = ex + dx;
-        let ny = ey + dy;
-
-        if state.map.get(nx, ny).map(|t| t.walkable()).unwrap_or(false)
-            && state.enemy_at(nx, ny).is_none()
-            && !(nx == px && ny == py)
+    // Check on_hit behaviors
+    for behavior in &def.behaviors {
+        if behavior.behavior_type == "on_hit_refraction"
+            && let Some(val) = behavior.value
         {
-            state.enemy_positions.remove(&(ex, ey));
-            state.enemies[i].x = nx;
-            state.enemies[i].y = ny;
-            state.enemy_positions.insert((nx, ny), i);
+            state.refraction += val;
+            state.log_typed(
+                format!("Glass shards pierce you. (+{} Refraction)", val),
+                MsgType::Status,
+            );
+            state.check_adaptation_threshold();
         }
-        true
     }
+    true
 }
 
-/// Healer behavior: heals nearby allies instead of attacking
-struct HealerBehavior;
-
-impl AiBehavior for HealerBehavior {
-    fn execute(&self, i: usize, state: &mut GameState) -> bool {
-        let ex = state.enemies[i].x;
-        let ey = state.enemies[i].y;
-        let heal_range = 4;
-        let heal_amount = 5;
-
-        // Find injured nearby ally
-        let mut healed_idx = None;
-        let mut lowest_hp_ratio = 1.0f32;
-
-        for (idx, enemy) in state.enemies.iter().enumerate() {
-            if idx == i || enemy.hp <= 0 {
-                continue;
-            }
+fn summon(state: &mut GameState, i: usize, ids: &[String]) -> bool {
+    if !state.enemies[i].can_spawn(state.turn) {
+        return false;
+    }
+    let Some(def) = state.enemies[i].def() else {
+        return false;
+    };
+    let choices = if ids.is_empty() {
+        &def.spawn_types
+    } else {
+        ids
+    };
+    if choices.is_empty() {
+        return false;
+    }
+    let spawn_type = &choices[state.rng.gen_range(0..choices.len())];
 
-            let dist = (enemy.x - ex).abs() + (enemy.y - ey).abs();
-            if dist > heal_range {
+    // Find nearby spawn location
+    let (ex, ey) = (state.enemies[i].x, state.enemies[i].y);
+    for dx in -2..=2 {
+        for dy in -2..=2 {
+            if dx == 0 && dy == 0 {
                 continue;
             }
+            let sx = ex + dx;
+            let sy = ey + dy;
 
-            if let Some(def) = enemy.def() {
-                let ratio = enemy.hp as f32 / def.max_hp as f32;
-                if ratio < lowest_hp_ratio && ratio < 1.0 {
-                    lowest_hp_ratio = ratio;
-                    healed_idx = Some(idx);
+            if state.map.get(sx, sy).map(|t| t.walkable()).unwrap_or(false)
+                && state.enemy_at(sx, sy).is_none()
+                && !(sx == state.player_x && sy == state.player_y)
+            {
+                let mut new_enemy = Enemy::new(sx, sy, spawn_type);
+                if def.swarm {
+                    new_enemy.swarm_id = Some(format!("spawner_{}", state.enemies[i].entity_id));
                 }
-            }
-        }
 
-        if let Some(idx) = healed_idx {
-            let target_name = state.enemies[idx].name().to_string();
-            if let Some(def) = state.enemies[idx].def() {
-                state.enemies[idx].hp = (state.enemies[idx].hp + heal_amount).min(def.max_hp);
+                state.spawn_enemy(new_enemy);
+                state.enemies[i].spawned_count += 1;
+                state.enemies[i].last_spawn_turn = state.turn;
+
+                state.log_typed(
+                    format!("{} spawns a {}!", state.enemies[i].name(), spawn_type),
+                    MsgType::Combat,
+                );
+                state.trigger_effect("S(@3 &LightCyan &White)", 2);
+                return true;
             }
-            state.log_typed(
-                format!(
-                    "{} heals {} for {} HP!",
-                    state.enemies[i].name(),
-                    target_name,
-                    heal_amount
-                ),
-                MsgType::Combat,
-            );
-            return true;
         }
-
-        // No one to heal - fall back to standard behavior
-        StandardMeleeBehavior.execute(i, state)
     }
+    false
 }
 
-static BEHAVIOR_REGISTRY: Lazy<HashMap<String, Box<dyn AiBehavior>>> = Lazy::new(|| {
-    let mut m: HashMap<String, Box<dyn AiBehavior>> = HashMap::new();
-    m.insert(
-        "standard_melee".to_string(),
-        Box::new(StandardMeleeBehavior),
-    );
-    m.insert("ranged_only".to_string(), Box::new(RangedOnlyBehavior));
-    m.insert(
-        "suicide_bomber".to_string(),
-        Box::new(SuicideBomberBehavior),
+fn release_aoe(state: &mut GameState, i: usize) -> bool {
+    // Count down; the attack goes off when the warning runs out
+    if !state.enemies[i].tick_aoe_warning() {
+        return false;
+    }
+    let (Some((target_x, target_y)), Some(def)) =
+        (state.enemies[i].aoe_target, state.enemies[i].def())
+    else {
+        return false;
+    };
+    let radius = def.aoe_radius as i32;
+    let damage = state.rng.gen_range(def.damage_min..=def.damage_max);
+    let kind = def.damage_type;
+
+    state.log_typed(
+        format!("{} unleashes an area attack!", state.enemies[i].name()),
+        MsgType::Combat,
     );
-    m.insert("healer".to_string(), Box::new(HealerBehavior));
-    m
-});
+
+    // Check if player is in AOE
+    let player_dist =
+        ((state.player_x - target_x).pow(2) + (state.player_y - target_y).pow(2)) as f32;
+    if player_dist <= (radius as f32).powi(2) {
+        let final_damage = hit_player(state, damage, kind);
+        state.log_typed(
+            format!("You take {} damage from the area attack!", final_damage),
+            MsgType::Combat,
+        );
+    }
+
+    // Visual effect
+    state.trigger_effect(&format!("B(@{} &LightRed)", radius), 4);
+
+    // Reset AOE state
+    state.enemies[i].aoe_target = None;
+    state.enemies[i].aoe_warning_turns = 0;
+    true
+}
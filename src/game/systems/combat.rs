@@ -1,9 +1,12 @@
 use super::System;
 use crate::game::{
-    action::action_cost,
+    action::{action_cost, action_noise},
     adaptation::total_stat_modifiers,
     combat::{CombatResult, default_weapon, get_weapon_def, roll_attack},
+    cover::Cover,
     enemy::Enemy,
+    entity::EntityId,
+    equipment::EquipSlot,
     event::GameEvent,
     state::{GameState, MsgType},
 };
@@ -23,11 +26,15 @@ impl System for CombatSystem {
 impl CombatSystem {
     /// Process enemy death: effects, XP, loot, split behavior, quest tracking
     /// Returns the enemy name for logging purposes
-    fn process_enemy_death(
+    ///
+    /// `killer` is the creature that made the kill, if it was not the player;
+    /// the player gets no XP, loot or quest progress for it
+    pub(crate) fn process_enemy_death(
         state: &mut GameState,
         enemy_idx: usize,
         death_x: i32,
         death_y: i32,
+        killer: Option<EntityId>,
     ) -> String {
         let enemy_id = state.enemies[enemy_idx].id.clone();
         let enemy_name = state.enemies[enemy_idx].name().to_string();
@@ -46,7 +53,7 @@ impl CombatSystem {
             }
 
             // Award XP
-            if def.xp_value > 0 {
+            if def.xp_value > 0 && killer.is_none() {
                 state.gain_xp(def.xp_value);
             }
 
@@ -72,10 +79,7 @@ impl CombatSystem {
                                     && state.enemy_at(nx, ny).is_none()
                                     && !(nx == state.player_x && ny == state.player_y)
                                 {
-                                    state.enemies.push(Enemy::new(nx, ny, child_id));
-                                    state
-                                        .enemy_positions
-                                        .insert((nx, ny), state.enemies.len() - 1);
+                                    state.spawn_enemy(Enemy::new(nx, ny, child_id));
                                     spawned += 1;
                                 }
                             }
@@ -94,11 +98,15 @@ impl CombatSystem {
 
         // Emit event - LootSystem and QuestSystem handle loot drops and quest progress
         state.emit(GameEvent::EnemyKilled {
+            entity: state.enemies[enemy_idx].entity_id,
             enemy_id: enemy_id.clone(),
             x: death_x,
             y: death_y,
+            killer,
         });
-        state.meta.discover_enemy(&enemy_id);
+        if killer.is_none() {
+            state.meta.discover_enemy(&enemy_id);
+        }
 
         enemy_name
     }
@@ -143,7 +151,10 @@ impl CombatSystem {
     }
 
     pub fn attack_melee(state: &mut GameState, target_x: i32, target_y: i32) -> bool {
-        let ei = match state.enemy_at(target_x, target_y) {
+        let ei = match state
+            .enemy_at(target_x, target_y)
+            .and_then(|id| state.enemy_index(id))
+        {
             Some(i) => i,
             None => return false,
         };
@@ -155,6 +166,7 @@ impl CombatSystem {
         state.player_ap -= cost;
 
         state.enemies[ei].provoked = true;
+        state.make_noise(target_x, target_y, action_noise("attack_melee"));
 
         // Swarm behavior
         if state.enemies[ei].def().map(|d| d.swarm).unwrap_or(false) {
@@ -187,7 +199,12 @@ impl CombatSystem {
         // Apply adaptation damage bonus
         let adapt_mods = total_stat_modifiers(&state.adaptations);
         dmg += adapt_mods.damage_bonus;
-        state.enemies[ei].hp -= dmg;
+        dmg += state
+            .equipment
+            .weapon
+            .as_ref()
+            .map_or(0, |weapon| weapon.damage_bonus());
+        let dmg = state.damage_enemy(ei, dmg, weapon.damage_type);
         state.trigger_hit_flash(target_x, target_y);
         state.spawn_damage_number(target_x, target_y, dmg, false);
 
@@ -202,7 +219,7 @@ impl CombatSystem {
                     let percent = behavior.percent.unwrap_or(25);
                     let reflected = (dmg as u32 * percent / 100) as i32;
                     if reflected > 0 {
-                        state.player_hp -= reflected;
+                        let reflected = state.damage_player(reflected, weapon.damage_type);
                         state.log_typed(
                             format!("The enemy reflects {} damage back at you!", reflected),
                             MsgType::Combat,
@@ -215,7 +232,7 @@ impl CombatSystem {
         state.last_damage_dealt = dmg as u32;
 
         if state.enemies[ei].hp <= 0 {
-            let enemy_name = Self::process_enemy_death(state, ei, target_x, target_y);
+            let enemy_name = Self::process_enemy_death(state, ei, target_x, target_y, None);
             state.log_typed(
                 format!("You kill the {} {}!", enemy_name, dir),
                 MsgType::Combat,
@@ -230,6 +247,7 @@ impl CombatSystem {
                 MsgType::Combat,
             );
         }
+        state.wear_equipped(EquipSlot::Weapon, 1);
         true
     }
 
@@ -258,25 +276,33 @@ impl CombatSystem {
             return false;
         }
 
+        let from = (state.player_x, state.player_y);
+        if !state.has_line_of_fire(from, (target_x, target_y)) {
+            state.log_typed("No clear line of fire.", MsgType::Combat);
+            return false;
+        }
+
         let cost = weapon.ap_cost;
         if state.player_ap < cost {
             return false;
         }
 
-        if let Some(ammo_type) = &weapon.ammo_type {
-            if !state.inventory.iter().any(|id| id == ammo_type) {
-                state.log_typed(
-                    format!("Out of {}.", ammo_type.replace('_', " ")),
-                    MsgType::Combat,
-                );
-                return false;
-            }
-            if let Some(idx) = state.inventory.iter().position(|id| id == ammo_type) {
-                state.inventory.remove(idx);
-            }
+        if let Some(ammo_type) = &weapon.ammo_type
+            && !state.take_items(ammo_type, 1)
+        {
+            state.log_typed(
+                format!("Out of {}.", ammo_type.replace('_', " ")),
+                MsgType::Combat,
+            );
+            return false;
         }
 
         state.player_ap -= cost;
+        state.make_noise(
+            state.player_x,
+            state.player_y,
+            action_noise("attack_ranged"),
+        );
 
         // Spawn projectile trail
         let proj_char = if weapon.range > 3 { '*' } else { '-' };
@@ -286,7 +312,10 @@ impl CombatSystem {
             proj_char,
         );
 
-        let ei = match state.enemy_at(target_x, target_y) {
+        let ei = match state
+            .enemy_at(target_x, target_y)
+            .and_then(|id| state.enemy_index(id))
+        {
             Some(i) => i,
             None => {
                 state.log_typed("No target there.", MsgType::Combat);
@@ -307,23 +336,33 @@ impl CombatSystem {
 
         let enemy_reflex = state.enemies[ei].def().map(|d| d.reflex).unwrap_or(0);
         let enemy_armor = state.enemies[ei].def().map(|d| d.armor).unwrap_or(0);
-        let result = roll_attack(&mut state.rng, weapon, enemy_reflex, enemy_armor, 0);
+        let cover = state.cover_between(from, (target_x, target_y));
+        let result = roll_attack(
+            &mut state.rng,
+            weapon,
+            enemy_reflex,
+            enemy_armor,
+            cover.bonus(),
+        );
         let result = Self::apply_combat_mocks(state, result);
         let name = state.enemies[ei].name().to_string();
 
         if !result.hit {
-            state.log_typed(format!("Your shot misses the {}.", name), MsgType::Combat);
+            let msg = match cover {
+                Cover::None => format!("Your shot misses the {}.", name),
+                _ => format!("Your shot misses the {} in {}.", name, cover.label()),
+            };
+            state.log_typed(msg, MsgType::Combat);
             state.check_auto_end_turn();
             return true;
         }
 
-        let dmg = result.damage;
-        state.enemies[ei].hp -= dmg;
+        let dmg = state.damage_enemy(ei, result.damage, weapon.damage_type);
         state.trigger_hit_flash(target_x, target_y);
         state.spawn_damage_number(target_x, target_y, dmg, false);
 
         if state.enemies[ei].hp <= 0 {
-            let enemy_name = Self::process_enemy_death(state, ei, target_x, target_y);
+            let enemy_name = Self::process_enemy_death(state, ei, target_x, target_y, None);
             state.log_typed(
                 format!("You kill the {} with a ranged shot!", enemy_name),
                 MsgType::Combat,
@@ -335,6 +374,7 @@ impl CombatSystem {
                 MsgType::Combat,
             );
         }
+        state.wear_equipped(EquipSlot::Weapon, 1);
 
         state.check_auto_end_turn();
         true
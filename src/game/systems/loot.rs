@@ -14,7 +14,14 @@ impl System for LootSystem {
     }
 
     fn on_event(&self, state: &mut GameState, event: &GameEvent) {
-        if let GameEvent::EnemyKilled { enemy_id, x, y } = event {
+        if let GameEvent::EnemyKilled {
+            enemy_id,
+            x,
+            y,
+            killer: None,
+            ..
+        } = event
+        {
             Self::handle_enemy_death(state, enemy_id, *x, *y);
         }
     }
@@ -50,9 +57,7 @@ impl LootSystem {
         for entry in loot_table {
             cumulative += entry.weight;
             if roll < cumulative {
-                let item = Item::new(x, y, &entry.item);
-                state.items.push(item);
-                state.rebuild_spatial_index();
+                state.spawn_item(Item::new(x, y, &entry.item));
                 if let Some(def) = get_item_def(&entry.item) {
                     state.log_typed(format!("The enemy drops {}.", def.name), MsgType::Loot);
                 }
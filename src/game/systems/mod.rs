@@ -6,13 +6,17 @@ pub mod combat;
 pub mod loot;
 pub mod movement;
 pub mod quest;
+pub mod schedule;
 pub mod status;
 pub mod storm;
+pub mod timeline;
 
 pub use loot::LootSystem;
 pub use quest::QuestSystem;
+pub use schedule::{Phase, Schedule, TURN_SCHEDULE};
 pub use status::StatusEffectSystem;
 pub use storm::StormSystem;
+pub use timeline::TimelineSystem;
 
 /// Trait for game systems that operate on GameState
 pub trait System {
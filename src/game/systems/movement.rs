@@ -3,8 +3,10 @@
 use crate::game::{
     action::action_cost,
     adaptation::Adaptation,
+    damage::DamageType,
     event::GameEvent,
-    item::get_item_def,
+    faction::{Stance, get_faction_def},
+    item::{ItemInstance, get_item_def},
     map::Tile,
     npc::DialogueContext,
     state::{Decoy, GameState, MsgType},
@@ -37,7 +39,10 @@ impl MovementSystem {
 
     /// Handle NPC bump-to-talk interaction
     fn handle_npc_interaction(state: &mut GameState, new_x: i32, new_y: i32) -> bool {
-        let ni = match state.npc_at(new_x, new_y) {
+        let ni = match state
+            .npc_at(new_x, new_y)
+            .and_then(|id| state.npc_index(id))
+        {
             Some(i) => i,
             None => return false,
         };
@@ -48,6 +53,20 @@ impl MovementSystem {
         }
         state.player_ap -= cost;
 
+        // Factions that have turned on the player will not talk
+        let faction = state.npcs[ni].faction();
+        if state.faction_stance(faction) == Stance::Hostile {
+            let line = get_faction_def(faction)
+                .and_then(|d| d.greeting_hostile.as_deref())
+                .unwrap_or("...");
+            state.log_typed(
+                format!("{}: \"{}\"", state.npcs[ni].name(), line),
+                MsgType::Dialogue,
+            );
+            state.check_auto_end_turn();
+            return true;
+        }
+
         // Build dialogue context
         let visible_adaptations: Vec<Adaptation> = if state.adaptations_hidden_turns > 0 {
             Vec::new()
@@ -140,9 +159,8 @@ impl MovementSystem {
             if let (Some(gives), Some(consumes)) =
                 (&action.effect.gives_item, &action.effect.consumes)
             {
-                if let Some(idx) = state.inventory.iter().position(|id| id == consumes) {
-                    state.inventory.remove(idx);
-                    state.inventory.push(gives.clone());
+                if state.take_items(consumes, 1) {
+                    state.give_item(ItemInstance::new(gives));
                     let gives_name = get_item_def(gives)
                         .map(|d| d.name.as_str())
                         .unwrap_or("item");
@@ -198,7 +216,7 @@ impl MovementSystem {
             return false;
         }
 
-        let cost = action_cost("move");
+        let cost = action_cost(if state.sneaking { "sneak" } else { "move" });
         if state.player_ap < cost {
             return false;
         }
@@ -221,6 +239,7 @@ impl MovementSystem {
         state.quest_log.on_position_changed(new_x, new_y);
         state.update_fov();
         state.update_lighting();
+        state.make_noise(new_x, new_y, state.footstep_noise());
 
         // Pickup items at new position
         Self::pickup_items(state);
@@ -257,9 +276,12 @@ impl MovementSystem {
                 if state.adaptations.iter().any(|a| a.has_immunity("glass")) {
                     state.log("Your saltblood protects you from the glass.");
                 } else {
-                    state.player_hp -= 1;
+                    let dmg = state.damage_player(1, DamageType::Physical);
                     state.refraction += 1;
-                    state.log("Sharp glass cuts you! (-1 HP, +1 Refraction)");
+                    state.log(format!(
+                        "Sharp glass cuts you! (-{} HP, +1 Refraction)",
+                        dmg
+                    ));
                     state.check_adaptation_threshold();
                 }
             }
@@ -306,28 +328,29 @@ impl MovementSystem {
         let px = state.player_x;
         let py = state.player_y;
 
-        let indices = match state.item_positions.remove(&(px, py)) {
+        let item_ids = match state.item_positions.remove(&(px, py)) {
             Some(v) => v,
             None => return,
         };
 
         let mut picked_up = Vec::new();
+        let encumbrance = state.encumbrance();
 
-        // Process in reverse order to maintain valid indices
-        for &i in indices.iter().rev() {
-            if i >= state.items.len() {
-                continue;
-            }
-
-            let id = state.items[i].id.clone();
-            let def = get_item_def(&id);
+        // Most recently dropped first
+        for &item_id in item_ids.iter().rev() {
+            let item = match state.item(item_id) {
+                Some(item) => item.instance.clone(),
+                None => continue,
+            };
+            let id = item.id.clone();
+            let def = item.def();
 
             // Skip non-pickup items (e.g., light sources)
             if !def.map(|d| d.pickup).unwrap_or(true) {
                 continue;
             }
 
-            let name = def.map(|d| d.name.as_str()).unwrap_or("item");
+            let name = item.name();
 
             // Trigger on_pickup effects
             if let Some(d) = def {
@@ -338,22 +361,20 @@ impl MovementSystem {
                 }
             }
 
-            state.inventory.push(id.clone());
+            state.give_item(item);
             state.quest_log.on_item_collected(&id);
             state.emit(GameEvent::ItemPickedUp {
                 item_id: id.clone(),
             });
             state.meta.discover_item(&id);
             state.log_typed(format!("Picked up {}.", name), MsgType::Loot);
-            picked_up.push(i);
+            picked_up.push(item_id);
         }
 
-        // Remove picked up items (reverse order for valid indices)
-        for &i in picked_up.iter().rev() {
-            if i < state.items.len() {
-                state.items.remove(i);
-            }
+        for item_id in picked_up {
+            state.remove_item(item_id);
         }
+        state.note_encumbrance(encumbrance);
 
         // Rebuild spatial index
         state.rebuild_spatial_index();
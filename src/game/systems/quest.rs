@@ -6,13 +6,18 @@ use crate::game::state::GameState;
 pub struct QuestSystem;
 
 impl System for QuestSystem {
-    fn update(&self, _state: &mut GameState) {
-        // QuestSystem is reactive-only (event-driven)
+    fn update(&self, state: &mut GameState) {
+        // Notify quests of turn passing (for wait objectives)
+        state.quest_log.on_turn_passed();
     }
 
     fn on_event(&self, state: &mut GameState, event: &GameEvent) {
         match event {
-            GameEvent::EnemyKilled { enemy_id, .. } => {
+            GameEvent::EnemyKilled {
+                enemy_id,
+                killer: None,
+                ..
+            } => {
                 state.quest_log.on_enemy_killed(enemy_id);
             }
             GameEvent::ItemPickedUp { item_id } => {
@@ -0,0 +1,377 @@
+//! Ordered turn scheduler
+//!
+//! Systems are registered by name into phases that run in a fixed order:
+//! pre-turn, AI, environment, then post-turn. Within a phase a system runs
+//! after the systems it names in `after` and otherwise in registration order.
+//! Systems listed in `GameState::disabled_systems` are skipped, both for their
+//! turn update and for events.
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::timeline::scaled_ap;
+use super::{LootSystem, QuestSystem, StatusEffectSystem, StormSystem, System, TimelineSystem};
+use crate::game::event::GameEvent;
+use crate::game::state::GameState;
+
+/// When in a turn a system runs
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    /// Player resources and timers
+    PreTurn,
+    /// Enemy decisions and actions
+    Ai,
+    /// Storm, time of day, lighting and vision
+    Environment,
+    /// Bookkeeping once the world has moved
+    PostTurn,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 4] = [
+        Phase::PreTurn,
+        Phase::Ai,
+        Phase::Environment,
+        Phase::PostTurn,
+    ];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Phase::PreTurn => "pre_turn",
+            Phase::Ai => "ai",
+            Phase::Environment => "environment",
+            Phase::PostTurn => "post_turn",
+        }
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+/// A system with no event handling that runs a `GameState` function each turn
+pub struct TurnStep(pub fn(&mut GameState));
+
+impl System for TurnStep {
+    fn update(&self, state: &mut GameState) {
+        (self.0)(state)
+    }
+
+    fn on_event(&self, _state: &mut GameState, _event: &GameEvent) {}
+}
+
+struct Entry {
+    name: &'static str,
+    phase: Phase,
+    after: &'static [&'static str],
+    system: Box<dyn System + Send + Sync>,
+}
+
+/// Systems in the order they run
+#[derive(Default)]
+pub struct Schedule {
+    entries: Vec<Entry>,
+}
+
+impl Schedule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a system. `after` names systems that must run first; they
+    /// must be in the same phase or an earlier one.
+    pub fn with(
+        mut self,
+        name: &'static str,
+        phase: Phase,
+        after: &'static [&'static str],
+        system: impl System + Send + Sync + 'static,
+    ) -> Self {
+        self.entries.push(Entry {
+            name,
+            phase,
+            after,
+            system: Box::new(system),
+        });
+        self
+    }
+
+    /// Order the registered systems, checking names and ordering constraints
+    pub fn build(self) -> Result<Self, String> {
+        let mut pending = self.entries;
+        for (i, entry) in pending.iter().enumerate() {
+            if pending[..i].iter().any(|e| e.name == entry.name) {
+                return Err(format!("System '{}' is registered twice", entry.name));
+            }
+            for dep in entry.after {
+                match pending.iter().find(|e| e.name == *dep) {
+                    None => {
+                        return Err(format!(
+                            "System '{}' runs after unknown system '{}'",
+                            entry.name, dep
+                        ));
+                    }
+                    Some(d) if d.phase > entry.phase => {
+                        return Err(format!(
+                            "System '{}' ({}) cannot run after '{}' ({})",
+                            entry.name, entry.phase, dep, d.phase
+                        ));
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        // Phase by phase, repeatedly take the first system whose
+        // predecessors have all run
+        let mut entries: Vec<Entry> = Vec::with_capacity(pending.len());
+        for phase in Phase::ALL {
+            loop {
+                let ready = pending.iter().position(|e| {
+                    e.phase == phase
+                        && e.after
+                            .iter()
+                            .all(|dep| entries.iter().any(|r| r.name == *dep))
+                });
+                match ready {
+                    Some(index) => entries.push(pending.remove(index)),
+                    None => break,
+                }
+            }
+            if let Some(stuck) = pending.iter().find(|e| e.phase == phase) {
+                return Err(format!(
+                    "Systems in phase {} have cyclic ordering, starting at '{}'",
+                    phase, stuck.name
+                ));
+            }
+        }
+        Ok(Self { entries })
+    }
+
+    /// System names and phases in run order
+    pub fn systems(&self) -> impl Iterator<Item = (&'static str, Phase)> + '_ {
+        self.entries.iter().map(|e| (e.name, e.phase))
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.entries.iter().any(|e| e.name == name)
+    }
+
+    /// Run each enabled system's turn update in order
+    pub fn run(&self, state: &mut GameState) {
+        for entry in &self.entries {
+            if !state.disabled_systems.contains(entry.name) {
+                entry.system.update(state);
+            }
+        }
+    }
+
+    /// Pass an event to each enabled system in order
+    pub fn dispatch(&self, state: &mut GameState, event: &GameEvent) {
+        for entry in &self.entries {
+            if !state.disabled_systems.contains(entry.name) {
+                entry.system.on_event(state, event);
+            }
+        }
+    }
+}
+
+/// The systems `GameState::end_turn` runs
+pub static TURN_SCHEDULE: Lazy<Schedule> =
+    Lazy::new(|| default_schedule().expect("Invalid turn schedule"));
+
+fn default_schedule() -> Result<Schedule, String> {
+    Schedule::new()
+        .with(
+            "action_points",
+            Phase::PreTurn,
+            &[],
+            TurnStep(|s| {
+                let ap = scaled_ap(s.player_max_ap, s.player_speed());
+                s.player_ap = (ap - s.encumbrance().ap_penalty()).max(1);
+            }),
+        )
+        .with("status_effects", Phase::PreTurn, &[], StatusEffectSystem)
+        .with(
+            "psychic",
+            Phase::PreTurn,
+            &[],
+            TurnStep(|s| s.psychic.tick()),
+        )
+        .with("skills", Phase::PreTurn, &[], TurnStep(|s| s.skills.tick()))
+        .with(
+            "light_energy",
+            Phase::PreTurn,
+            &[],
+            TurnStep(|s| s.light_system.update(&mut s.rng)),
+        )
+        .with(
+            "void_energy",
+            Phase::PreTurn,
+            &[],
+            TurnStep(|s| s.void_system.update(&mut s.rng)),
+        )
+        .with(
+            "energy_hazards",
+            Phase::PreTurn,
+            &["light_energy", "void_energy"],
+            TurnStep(GameState::apply_energy_hazards),
+        )
+        .with(
+            "crystal_resonance",
+            Phase::PreTurn,
+            &[],
+            TurnStep(|s| s.crystal_system.update(&mut s.rng)),
+        )
+        .with(
+            "turn_counter",
+            Phase::PreTurn,
+            &["status_effects"],
+            TurnStep(GameState::tick_turn),
+        )
+        .with("enemies", Phase::Ai, &[], TimelineSystem)
+        .with("storm", Phase::Environment, &[], StormSystem)
+        .with(
+            "time_of_day",
+            Phase::Environment,
+            &["storm"],
+            TurnStep(GameState::tick_time),
+        )
+        .with(
+            "lighting",
+            Phase::Environment,
+            &["time_of_day"],
+            TurnStep(GameState::update_lighting),
+        )
+        .with(
+            "fov",
+            Phase::Environment,
+            &["lighting"],
+            TurnStep(GameState::update_fov),
+        )
+        .with(
+            "dynamic_events",
+            Phase::Environment,
+            &["fov"],
+            TurnStep(GameState::check_dynamic_events),
+        )
+        .with("loot", Phase::PostTurn, &[], LootSystem)
+        .with("quests", Phase::PostTurn, &["loot"], QuestSystem)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(schedule: &Schedule) -> Vec<&'static str> {
+        schedule.systems().map(|(name, _)| name).collect()
+    }
+
+    #[test]
+    fn systems_run_by_phase_then_declared_order() {
+        let schedule = Schedule::new()
+            .with("late", Phase::PostTurn, &[], TurnStep(|_| {}))
+            .with("second", Phase::PreTurn, &["first"], TurnStep(|_| {}))
+            .with("ai", Phase::Ai, &["second"], TurnStep(|_| {}))
+            .with("first", Phase::PreTurn, &[], TurnStep(|_| {}))
+            .with("other", Phase::PreTurn, &[], TurnStep(|_| {}))
+            .build()
+            .unwrap();
+        assert_eq!(
+            names(&schedule),
+            vec!["first", "second", "other", "ai", "late"]
+        );
+    }
+
+    #[test]
+    fn invalid_orderings_are_rejected() {
+        let unknown = Schedule::new()
+            .with("a", Phase::PreTurn, &["missing"], TurnStep(|_| {}))
+            .build();
+        assert!(unknown.is_err());
+
+        let later_phase = Schedule::new()
+            .with("a", Phase::PreTurn, &["b"], TurnStep(|_| {}))
+            .with("b", Phase::PostTurn, &[], TurnStep(|_| {}))
+            .build();
+        assert!(later_phase.is_err());
+
+        let cycle = Schedule::new()
+            .with("a", Phase::Ai, &["b"], TurnStep(|_| {}))
+            .with("b", Phase::Ai, &["a"], TurnStep(|_| {}))
+            .build();
+        assert!(matches!(cycle, Err(e) if e.contains("cyclic")));
+
+        let duplicate = Schedule::new()
+            .with("a", Phase::Ai, &[], TurnStep(|_| {}))
+            .with("a", Phase::Ai, &[], TurnStep(|_| {}))
+            .build();
+        assert!(duplicate.is_err());
+    }
+
+    #[test]
+    fn disabled_systems_are_skipped() {
+        let schedule = Schedule::new()
+            .with("heal", Phase::PreTurn, &[], TurnStep(|s| s.player_hp += 1))
+            .with(
+                "count",
+                Phase::PostTurn,
+                &[],
+                TurnStep(|s| s.salt_scrip += 1),
+            )
+            .build()
+            .unwrap();
+        let mut state = GameState::new(42);
+        let (hp, scrip) = (state.player_hp, state.salt_scrip);
+
+        state.disabled_systems.insert("heal".to_string());
+        schedule.run(&mut state);
+        assert_eq!(state.player_hp, hp);
+        assert_eq!(state.salt_scrip, scrip + 1);
+    }
+
+    #[test]
+    fn storm_arrival_is_logged_once() {
+        let mut state = GameState::new(42);
+        state.storm.turns_until = 1;
+        state.end_turn();
+        let arrivals: Vec<&str> = state
+            .messages
+            .iter()
+            .map(|m| m.text.as_str())
+            .filter(|text| text.contains("Intensity was") || text.contains("arrived with"))
+            .collect();
+        assert_eq!(arrivals, ["[Event] Storm arrived with intensity 3"]);
+    }
+
+    #[test]
+    fn default_schedule_keeps_turn_order() {
+        assert_eq!(
+            names(&TURN_SCHEDULE),
+            vec![
+                "action_points",
+                "status_effects",
+                "psychic",
+                "skills",
+                "light_energy",
+                "void_energy",
+                "energy_hazards",
+                "crystal_resonance",
+                "turn_counter",
+                "enemies",
+                "storm",
+                "time_of_day",
+                "lighting",
+                "fov",
+                "dynamic_events",
+                "loot",
+                "quests",
+            ]
+        );
+    }
+}
@@ -24,23 +24,21 @@ impl System for StatusEffectSystem {
 impl StatusEffectSystem {
     /// Tick all player status effects
     fn tick_player_effects(state: &mut GameState) {
-        let mut total_damage = 0;
-        let mut messages = Vec::new();
+        let mut ticks = Vec::new();
 
         for effect in &mut state.status_effects {
             let dmg = effect.tick();
             if dmg > 0 {
-                total_damage += dmg;
-                messages.push(format!("{} deals {} damage.", effect.name, dmg));
+                let kind = get_status_def(&effect.id)
+                    .map(|d| d.damage_type)
+                    .unwrap_or_default();
+                ticks.push((effect.name.clone(), dmg, kind));
             }
         }
 
-        for msg in messages {
-            state.log_typed(msg, MsgType::Combat);
-        }
-
-        if total_damage > 0 {
-            state.player_hp -= total_damage;
+        for (name, dmg, kind) in ticks {
+            let dmg = state.damage_player(dmg, kind);
+            state.log_typed(format!("{} deals {} damage.", name, dmg), MsgType::Combat);
         }
 
         state.status_effects.retain(|e| !e.is_expired());
@@ -50,34 +48,35 @@ impl StatusEffectSystem {
     fn tick_enemy_effects(state: &mut GameState) {
         let mut dead_enemies = Vec::new();
 
-        for (idx, enemy) in state.enemies.iter_mut().enumerate() {
-            if enemy.hp <= 0 {
+        for idx in 0..state.enemies.len() {
+            if state.enemies[idx].hp <= 0 {
                 continue;
             }
 
-            let mut enemy_damage = 0;
+            let mut ticks = Vec::new();
 
-            for effect in &mut enemy.status_effects {
+            for effect in &mut state.enemies[idx].status_effects {
                 if let Some(def) = get_status_def(&effect.id) {
                     if def.tick_damage > 0 {
-                        enemy_damage += def.tick_damage;
+                        ticks.push((def.tick_damage, def.damage_type));
                     }
                 }
                 effect.duration -= 1;
             }
 
-            enemy.status_effects.retain(|e| e.duration > 0);
+            state.enemies[idx].status_effects.retain(|e| e.duration > 0);
 
-            if enemy_damage > 0 {
-                enemy.hp -= enemy_damage;
-                if enemy.hp <= 0 {
-                    dead_enemies.push(idx);
-                }
+            for (dmg, kind) in ticks {
+                state.damage_enemy(idx, dmg, kind);
+            }
+            if state.enemies[idx].hp <= 0 {
+                dead_enemies.push(idx);
             }
         }
 
         // Handle enemies killed by status effects
         for idx in dead_enemies.into_iter().rev() {
+            let entity = state.enemies[idx].entity_id;
             let enemy_id = state.enemies[idx].id.clone();
             let x = state.enemies[idx].x;
             let y = state.enemies[idx].y;
@@ -91,7 +90,13 @@ impl StatusEffectSystem {
                 MsgType::Combat,
             );
 
-            state.emit(GameEvent::EnemyKilled { enemy_id, x, y });
+            state.emit(GameEvent::EnemyKilled {
+                entity,
+                enemy_id,
+                x,
+                y,
+                killer: None,
+            });
         }
     }
 
@@ -1,8 +1,9 @@
 use super::System;
+use crate::game::action::action_noise;
 use crate::game::enemy::Enemy;
 use crate::game::event::GameEvent;
 use crate::game::map::Tile;
-use crate::game::state::{GameState, MsgType};
+use crate::game::state::GameState;
 use crate::game::storm::{
     Storm, StormEditType, refraction_multiplier, storm_glass_drop_chance, wraith_spawn_max,
 };
@@ -12,17 +13,14 @@ use rand::Rng;
 pub struct StormSystem;
 
 impl System for StormSystem {
-    fn update(&self, _state: &mut GameState) {
-        // Storm ticking is handled in end_turn, this is for event reactions
+    fn update(&self, state: &mut GameState) {
+        if state.storm.tick() {
+            Self::apply_storm(state);
+        }
     }
 
-    fn on_event(&self, state: &mut GameState, event: &GameEvent) {
-        if let GameEvent::StormArrived { intensity } = event {
-            state.log_typed(
-                format!("The storm settles. Intensity was {}.", intensity),
-                MsgType::System,
-            );
-        }
+    fn on_event(&self, _state: &mut GameState, _event: &GameEvent) {
+        // Arrival is logged by GameState::handle_event
     }
 }
 
@@ -57,6 +55,7 @@ impl StormSystem {
 
         // Spawn storm enemies on glass tiles
         Self::spawn_storm_enemies(state);
+        Self::thunder(state);
 
         // Emit event and forecast next storm
         let intensity = state.storm.intensity;
@@ -66,6 +65,21 @@ impl StormSystem {
         state.update_lighting();
     }
 
+    /// Thunder from the changed tile nearest the player, which enemies in
+    /// earshot go to investigate
+    fn thunder(state: &mut GameState) {
+        let (px, py) = (state.player_x, state.player_y);
+        let width = state.map.width;
+        let nearest = state
+            .storm_changed_tiles
+            .iter()
+            .map(|&idx| ((idx % width) as i32, (idx / width) as i32))
+            .min_by_key(|&(x, y)| ((x - px).abs() + (y - py).abs(), y, x));
+        if let Some((x, y)) = nearest {
+            state.make_noise(x, y, action_noise("thunder"));
+        }
+    }
+
     /// Spawn wraiths on glass tiles after storm
     fn spawn_storm_enemies(state: &mut GameState) {
         let glass_tiles: Vec<(i32, i32)> = (0..state.map.tiles.len())
@@ -81,9 +95,7 @@ impl StormSystem {
             for _ in 0..spawn_count {
                 let idx = state.rng.gen_range(0..glass_tiles.len());
                 let (x, y) = glass_tiles[idx];
-                let enemy_idx = state.enemies.len();
-                state.enemies.push(Enemy::new(x, y, "refraction_wraith"));
-                state.enemy_positions.insert((x, y), enemy_idx);
+                state.spawn_enemy(Enemy::new(x, y, "refraction_wraith"));
                 state.log("A wraith coalesces from the storm's edge.");
             }
         }
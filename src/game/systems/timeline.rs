@@ -0,0 +1,166 @@
+//! Speed and energy
+//!
+//! Everyone gains energy each turn in proportion to their speed, where 100
+//! is normal. The player's share becomes the turn's action points, which
+//! actions spend at their `action_cost`. Enemies and NPCs bank theirs and act
+//! whenever they hold `ACTION_ENERGY`, the most energetic first, so a speed
+//! 150 wraith acts three times every two turns and a speed 50 golem every
+//! other turn. Every action taken is recorded in `GameState::action_log`.
+
+use serde::{Deserialize, Serialize};
+
+use super::System;
+use super::ai;
+use crate::game::{entity::EntityId, event::GameEvent, state::GameState};
+
+/// Speed of an ordinary actor
+pub const NORMAL_SPEED: i32 = 100;
+
+/// Slowest an actor can be made, so that everyone still acts eventually
+pub const MIN_SPEED: i32 = 10;
+
+/// Energy an enemy or NPC spends on one action
+pub const ACTION_ENERGY: i32 = 100;
+
+/// Records kept in `GameState::action_log`
+const ACTION_LOG_LIMIT: usize = 256;
+
+/// One action taken, for asserting on turn order
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActionRecord {
+    pub turn: u32,
+    /// `player`, or the definition id of the enemy or NPC
+    pub actor: String,
+    #[serde(default)]
+    pub entity: Option<EntityId>,
+}
+
+/// Action points the player gets for a turn at `speed`
+pub fn scaled_ap(max_ap: i32, speed: i32) -> i32 {
+    (max_ap * speed / NORMAL_SPEED).max(1)
+}
+
+#[derive(Clone, Copy)]
+enum Actor {
+    Enemy(EntityId),
+    Npc(EntityId),
+}
+
+impl Actor {
+    /// Energy banked by the actor, while it is still able to act
+    fn energy(self, state: &GameState) -> Option<i32> {
+        match self {
+            Actor::Enemy(id) => state
+                .enemy(id)
+                .filter(|e| e.hp > 0 && !e.ai_disabled)
+                .map(|e| e.energy),
+            Actor::Npc(id) => state.npc(id).map(|n| n.energy),
+        }
+    }
+
+    fn add_energy(self, state: &mut GameState, amount: i32) {
+        match self {
+            Actor::Enemy(id) => {
+                if let Some(enemy) = state.enemy_mut(id) {
+                    enemy.energy += amount;
+                }
+            }
+            Actor::Npc(id) => {
+                if let Some(i) = state.npc_index(id) {
+                    state.npcs[i].energy += amount;
+                }
+            }
+        }
+    }
+
+    fn speed(self, state: &GameState) -> i32 {
+        match self {
+            Actor::Enemy(id) => state.enemy(id).map_or(NORMAL_SPEED, |e| e.speed()),
+            Actor::Npc(id) => state.npc(id).map_or(NORMAL_SPEED, |n| n.speed()),
+        }
+    }
+}
+
+/// Gives enemies and NPCs energy and lets them act until it runs out
+pub struct TimelineSystem;
+
+impl System for TimelineSystem {
+    fn update(&self, state: &mut GameState) {
+        // The player has spent their action points by the time the turn ends
+        state.record_action("player", None);
+
+        // Enemies can be spawned or removed while others act, so track the
+        // actors present at the start of the turn by handle
+        let actors: Vec<Actor> = state
+            .enemies
+            .iter()
+            .filter(|e| e.hp > 0 && !e.ai_disabled)
+            .map(|e| Actor::Enemy(e.entity_id))
+            .chain(state.npcs.iter().map(|n| Actor::Npc(n.entity_id)))
+            .collect();
+        for &actor in &actors {
+            let speed = actor.speed(state);
+            actor.add_energy(state, speed);
+        }
+
+        // The first actor with the most energy goes next
+        loop {
+            let mut next: Option<(Actor, i32)> = None;
+            for &actor in &actors {
+                if let Some(energy) = actor.energy(state)
+                    && energy >= ACTION_ENERGY
+                    && next.is_none_or(|(_, most)| energy > most)
+                {
+                    next = Some((actor, energy));
+                }
+            }
+            let Some((actor, _)) = next else {
+                break;
+            };
+            actor.add_energy(state, -ACTION_ENERGY);
+            match actor {
+                Actor::Enemy(id) => {
+                    if let Some(i) = state.enemy_index(id) {
+                        let name = state.enemies[i].id.clone();
+                        state.record_action(&name, Some(id));
+                        ai::act(i, state);
+                    }
+                }
+                // NPCs have nothing to do on their turn yet
+                Actor::Npc(id) => {
+                    if let Some(npc) = state.npc(id) {
+                        let name = npc.id.clone();
+                        state.record_action(&name, Some(id));
+                    }
+                }
+            }
+        }
+    }
+
+    fn on_event(&self, _state: &mut GameState, _event: &GameEvent) {}
+}
+
+impl GameState {
+    /// Note that `actor` took an action this turn
+    pub fn record_action(&mut self, actor: &str, entity: Option<EntityId>) {
+        if self.action_log.len() >= ACTION_LOG_LIMIT {
+            self.action_log.remove(0);
+        }
+        self.action_log.push(ActionRecord {
+            turn: self.turn,
+            actor: actor.to_string(),
+            entity,
+        });
+    }
+
+    /// Player speed after status effects and adaptations
+    pub fn player_speed(&self) -> i32 {
+        let adaptations: i32 = self
+            .adaptations
+            .iter()
+            .filter_map(|a| a.effect_value("speed"))
+            .sum();
+        (NORMAL_SPEED + crate::game::status::speed_modifier(&self.status_effects) + adaptations)
+            .max(MIN_SPEED)
+    }
+}
@@ -0,0 +1,120 @@
+use super::{Clear, TestState};
+use crate::game::behavior_tree::BehaviorNode;
+use crate::game::enemy::{Enemy, get_enemy_def};
+use crate::game::systems::ai::run_tree;
+
+fn tree(json: &str) -> BehaviorNode {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn utility_tries_the_highest_scoring_option_first() {
+    let mut state = TestState::open(6).clearing(&[Clear::Decoys]).build();
+    let (px, py) = (state.player_x, state.player_y);
+    let id = state.spawn_enemy(Enemy::new(px + 2, py, "mirage_hound"));
+    let i = state.enemy_index(id).unwrap();
+    let ai = tree(
+        r#"{"type": "utility", "options": [
+            {"weight": 1.0, "node": {"type": "cast", "status": "slow", "duration": 3}},
+            {"weight": 5.0, "factor": "missing_hp", "node": {"type": "flee"}}]}"#,
+    );
+
+    // Unhurt, fleeing scores nothing and the cast wins
+    assert!(run_tree(i, &ai, &mut state));
+    assert!(state.has_status_effect("slow"));
+    assert_eq!(state.enemies[i].x, px + 2);
+
+    state.enemies[i].hp = 1;
+    assert!(run_tree(i, &ai, &mut state));
+    assert_eq!(state.enemies[i].x, px + 3);
+    assert_eq!(state.enemy_at(px + 3, py), Some(id));
+}
+
+#[test]
+fn heal_ally_picks_the_most_injured_ally_in_range() {
+    let mut state = TestState::open(6).clearing(&[Clear::Decoys]).build();
+    let (px, py) = (state.player_x, state.player_y);
+    let max_hp = get_enemy_def("mirage_hound").unwrap().max_hp;
+    let healer = state.spawn_enemy(Enemy::new(px + 3, py, "mirage_hound"));
+    let scratched = state.spawn_enemy(Enemy::new(px + 3, py + 1, "mirage_hound"));
+    let wounded = state.spawn_enemy(Enemy::new(px + 3, py - 2, "mirage_hound"));
+    let distant = state.spawn_enemy(Enemy::new(px - 5, py, "mirage_hound"));
+    state.enemy_mut(scratched).unwrap().hp = max_hp - 1;
+    state.enemy_mut(wounded).unwrap().hp = 2;
+    state.enemy_mut(distant).unwrap().hp = 1;
+    let ai = tree(
+        r#"{"type": "sequence", "children": [
+            {"type": "ally_nearby", "range": 4, "injured": true},
+            {"type": "heal_ally", "range": 4, "amount": 3}]}"#,
+    );
+    let i = state.enemy_index(healer).unwrap();
+
+    assert!(run_tree(i, &ai, &mut state));
+    assert_eq!(state.enemy(wounded).unwrap().hp, 5);
+    assert_eq!(state.enemy(scratched).unwrap().hp, max_hp - 1);
+    assert_eq!(state.enemy(distant).unwrap().hp, 1);
+
+    // Nobody in range needs healing once they are topped up
+    state.enemy_mut(scratched).unwrap().hp = max_hp;
+    state.enemy_mut(wounded).unwrap().hp = max_hp;
+    assert!(!run_tree(i, &ai, &mut state));
+}
+
+#[test]
+fn bomber_from_data_closes_in_and_explodes() {
+    let mut state = TestState::open(6).clearing(&[Clear::Decoys]).build();
+    let (px, py) = (state.player_x, state.player_y);
+    let id = state.spawn_enemy(Enemy::new(px + 3, py, "glass_bomber"));
+    let ai = get_enemy_def("glass_bomber").unwrap().ai.as_ref().unwrap();
+    let hp = state.player_hp;
+
+    let i = state.enemy_index(id).unwrap();
+    assert!(run_tree(i, ai, &mut state));
+    assert!(run_tree(i, ai, &mut state));
+    assert_eq!(state.enemies[i].x, px + 1);
+    assert_eq!(state.player_hp, hp);
+
+    assert!(run_tree(i, ai, &mut state));
+    assert!(state.enemies[i].hp <= 0);
+    assert!(state.player_hp < hp);
+    assert_eq!(state.enemy_at(px + 1, py), None);
+}
+
+#[test]
+fn approach_and_flee_follow_maps_shared_for_the_turn() {
+    let mut state = TestState::open(6).clearing(&[Clear::Decoys]).build();
+    state.update_fov();
+    let (px, py) = (state.player_x, state.player_y);
+    let chaser = state.spawn_enemy(Enemy::new(px + 4, py + 1, "mirage_hound"));
+    let runner = state.spawn_enemy(Enemy::new(px - 2, py, "mirage_hound"));
+
+    let i = state.enemy_index(chaser).unwrap();
+    assert!(run_tree(i, &tree(r#"{"type": "approach"}"#), &mut state));
+    assert_eq!(state.enemy_at(px + 3, py + 1), Some(chaser));
+    assert!(state.flow_fields.is_current(state.turn, (px, py)));
+
+    let i = state.enemy_index(runner).unwrap();
+    assert!(run_tree(i, &tree(r#"{"type": "flee"}"#), &mut state));
+    assert_eq!(state.enemy_at(px - 3, py), Some(runner));
+
+    // A new turn or a player move brings fresh maps
+    state.turn += 1;
+    assert!(!state.flow_fields.is_current(state.turn, (px, py)));
+    let i = state.enemy_index(chaser).unwrap();
+    assert!(run_tree(i, &tree(r#"{"type": "approach"}"#), &mut state));
+    assert!(state.flow_fields.is_current(state.turn, (px, py)));
+}
+
+#[test]
+fn approach_searches_its_own_path_with_the_maps_disabled() {
+    let mut state = TestState::open(6).clearing(&[Clear::Decoys]).build();
+    state.update_fov();
+    state.flow_fields.disabled = true;
+    let (px, py) = (state.player_x, state.player_y);
+    let chaser = state.spawn_enemy(Enemy::new(px + 4, py, "mirage_hound"));
+
+    let i = state.enemy_index(chaser).unwrap();
+    assert!(run_tree(i, &tree(r#"{"type": "approach"}"#), &mut state));
+    assert_eq!(state.enemy_at(px + 4, py), None);
+    assert!(!state.flow_fields.is_current(state.turn, (px, py)));
+}
@@ -0,0 +1,89 @@
+use super::{Clear, TestState};
+use crate::game::cover::Cover;
+use crate::game::enemy::{Enemy, get_enemy_def};
+use crate::game::interactable::Interactable;
+use crate::game::map::Tile;
+use crate::game::state::GameState;
+use crate::game::systems::ai::run_tree;
+
+fn set_tile(state: &mut GameState, x: i32, y: i32, tile: Tile) {
+    let idx = state.map.idx(x, y);
+    state.map.tiles[idx] = tile;
+}
+
+fn wall() -> Tile {
+    Tile::Wall {
+        id: "glass".to_string(),
+        hp: 5,
+    }
+}
+
+#[test]
+fn cover_comes_from_the_side_facing_the_attacker() {
+    let mut state = TestState::open(8)
+        .clearing(&[Clear::Decoys, Clear::Interactables])
+        .build();
+    set_tile(&mut state, 25, 20, wall());
+    set_tile(&mut state, 20, 25, Tile::Glass);
+    state
+        .interactables
+        .push(Interactable::new("salt_barricade".to_string(), 15, 21));
+    state.rebuild_spatial_index();
+
+    assert_eq!(state.cover_between((30, 20), (24, 20)), Cover::Full);
+    assert_eq!(state.cover_between((30, 22), (24, 20)), Cover::Full);
+    // Shot from behind, or from right next to the target
+    assert_eq!(state.cover_between((18, 20), (24, 20)), Cover::None);
+    assert_eq!(state.cover_between((25, 21), (24, 20)), Cover::None);
+
+    assert_eq!(state.cover_between((20, 29), (20, 24)), Cover::Half);
+    assert_eq!(state.cover_between((12, 21), (16, 21)), Cover::Half);
+}
+
+#[test]
+fn creatures_and_walls_block_the_line_of_fire() {
+    let mut state = TestState::open(8)
+        .clearing(&[Clear::Decoys, Clear::Interactables])
+        .build();
+    let (px, py) = (state.player_x, state.player_y);
+    assert!(state.has_line_of_fire((px, py), (px + 5, py + 2)));
+
+    state.spawn_enemy(Enemy::new(px + 2, py, "mirage_hound"));
+    assert!(!state.has_line_of_fire((px, py), (px + 4, py)));
+    // The creature being shot at does not block its own line
+    assert!(state.has_line_of_fire((px, py), (px + 2, py)));
+
+    set_tile(&mut state, px, py - 2, wall());
+    assert!(!state.has_line_of_fire((px, py), (px, py - 4)));
+}
+
+#[test]
+fn ranged_enemy_moves_into_cover_before_shooting() {
+    let mut state = TestState::open(8)
+        .clearing(&[Clear::Decoys, Clear::Interactables])
+        .build();
+    let (px, py) = (state.player_x, state.player_y);
+    set_tile(&mut state, px + 3, py + 1, wall());
+    let id = state.spawn_enemy(Enemy::new(px + 3, py + 3, "storm_archer"));
+    let ai = get_enemy_def("storm_archer").unwrap().ai.as_ref().unwrap();
+    let hp = state.player_hp;
+
+    for _ in 0..3 {
+        let i = state.enemy_index(id).unwrap();
+        run_tree(i, ai, &mut state);
+        let e = state.enemy(id).unwrap();
+        if (e.x, e.y) == (px + 3, py + 2) {
+            break;
+        }
+    }
+    let e = state.enemy(id).unwrap();
+    assert_eq!((e.x, e.y), (px + 3, py + 2));
+    assert_eq!(state.player_hp, hp);
+    assert_eq!(state.cover_between((px, py), (e.x, e.y)), Cover::Full);
+
+    // Settled in cover, it opens fire
+    let i = state.enemy_index(id).unwrap();
+    assert!(run_tree(i, ai, &mut state));
+    assert!(state.player_hp < hp);
+    assert_eq!(state.enemy(id).unwrap().x, px + 3);
+}
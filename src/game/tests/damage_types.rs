@@ -0,0 +1,100 @@
+use super::{Clear, TestState};
+use crate::game::adaptation::Adaptation;
+use crate::game::damage::DamageType;
+use crate::game::enemy::Enemy;
+use crate::game::equipment::EquipSlot;
+use crate::game::item::ItemInstance;
+use crate::game::map::Tile;
+use crate::game::status::StatusEffect;
+use crate::game::systems::combat::CombatSystem;
+use crate::game::void_energy::DistortionType;
+
+/// HP an `enemy` loses to one forced 10 damage melee hit with `weapon`
+fn melee_loss(weapon: &str, enemy: &str) -> i32 {
+    let mut state = TestState::open(8).clearing(&[Clear::StatusEffects]).build();
+    let (x, y) = (state.player_x + 1, state.player_y);
+    let id = state.spawn_enemy(Enemy::new(x, y, enemy));
+    state.enemy_mut(id).unwrap().hp = 100;
+    state.equipped_weapon = Some(weapon.to_string());
+    state.mock_combat_hit = Some(true);
+    state.mock_combat_damage = Some(10);
+    assert!(CombatSystem::attack_melee(&mut state, x, y));
+    100 - state.enemy(id).unwrap().hp
+}
+
+#[test]
+fn enemies_resist_and_are_weak_to_damage_types() {
+    // Dust wraiths shrug off a quarter of physical blows but light burns them
+    assert_eq!(melee_loss("salt_knife", "dust_wraith"), 7);
+    assert_eq!(melee_loss("saints_blade", "dust_wraith"), 15);
+    // Untyped enemies take the hit as it is
+    assert_eq!(melee_loss("saints_blade", "mirage_hound"), 10);
+}
+
+#[test]
+fn equipment_and_adaptations_protect_the_player() {
+    let mut state = TestState::open(8).clearing(&[Clear::StatusEffects]).build();
+    state.give_item(ItemInstance::new("saint_aegis"));
+    let idx = state.inventory.len() - 1;
+    assert!(state.equip_item(idx, EquipSlot::Jacket));
+    state
+        .adaptations
+        .push(Adaptation::from_id("prismhide").unwrap());
+    assert_eq!(state.player_resistance(DamageType::Light), 75);
+    assert_eq!(state.player_resistance(DamageType::Void), 25);
+
+    let hp = state.player_hp;
+    assert_eq!(state.damage_player(8, DamageType::Light), 2);
+    assert_eq!(state.damage_player(8, DamageType::Heat), 8);
+    assert_eq!(state.player_hp, hp - 10);
+
+    // Saltblood halves poison ticks
+    state
+        .adaptations
+        .push(Adaptation::from_id("saltblood").unwrap());
+    state.apply_status(StatusEffect::new("poison", 3));
+    let hp = state.player_hp;
+    state.end_turn();
+    assert_eq!(state.player_hp, hp - 1);
+}
+
+#[test]
+fn void_distortions_hurt_what_stands_in_them() {
+    let mut state = TestState::open(8).clearing(&[Clear::StatusEffects]).build();
+    let (px, py) = (state.player_x, state.player_y);
+    let stalker = state.spawn_enemy(Enemy::new(px + 6, py, "void_stalker"));
+    let hound = state.spawn_enemy(Enemy::new(px + 6, py + 1, "mirage_hound"));
+    for id in [stalker, hound] {
+        let enemy = state.enemy_mut(id).unwrap();
+        enemy.hp = 100;
+        enemy.ai_disabled = true;
+    }
+    state
+        .void_system
+        .create_distortion(px + 6, py, 4, 5, DistortionType::Material);
+
+    let hp = state.player_hp;
+    state.end_turn();
+    assert_eq!(state.player_hp, hp, "the player is out of reach");
+    // 4 void damage at the centre, resisted by the stalker; 3 one step out
+    assert_eq!(state.enemy(stalker).unwrap().hp, 99);
+    assert_eq!(state.enemy(hound).unwrap().hp, 97);
+}
+
+#[test]
+fn glass_cuts_the_player_as_physical_damage() {
+    let mut state = TestState::open(8).clearing(&[Clear::StatusEffects]).build();
+    let (px, py) = (state.player_x, state.player_y);
+    let idx = state.map.idx(px + 1, py);
+    state.map.tiles[idx] = Tile::Glass;
+    let (hp, refraction) = (state.player_hp, state.refraction);
+    assert!(state.try_move(1, 0));
+    assert_eq!(state.player_hp, hp - 1);
+    assert_eq!(state.refraction, refraction + 1);
+    assert!(
+        state
+            .messages
+            .iter()
+            .any(|m| m.text == "Sharp glass cuts you! (-1 HP, +1 Refraction)")
+    );
+}
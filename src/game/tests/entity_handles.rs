@@ -0,0 +1,107 @@
+use super::TestState;
+use crate::game::enemy::Enemy;
+use crate::game::event::GameEvent;
+use crate::game::item::Item;
+
+#[test]
+fn handles_follow_entities_when_earlier_ones_are_removed() {
+    let mut state = TestState::open(4).build();
+    let (px, py) = (state.player_x, state.player_y);
+    let first = state.spawn_item(Item::new(px + 2, py, "brine_vial"));
+    let second = state.spawn_item(Item::new(px + 3, py, "glass_pick"));
+    let hound = state.spawn_enemy(Enemy::new(px - 2, py, "mirage_hound"));
+    let slime = state.spawn_enemy(Enemy::new(px - 3, py, "splitter_slime"));
+
+    assert!(state.remove_item(first).is_some());
+    assert!(state.despawn_enemy(hound).is_some());
+
+    assert!(state.item(first).is_none());
+    assert_eq!(state.item(second).unwrap().id(), "glass_pick");
+    assert!(state.enemy(hound).is_none());
+    assert_eq!(state.enemy(slime).unwrap().id, "splitter_slime");
+    assert_eq!(state.enemy_at(px - 3, py), Some(slime));
+    assert_eq!(state.enemy_at(px - 2, py), None);
+}
+
+#[test]
+fn removed_handle_does_not_resolve_to_respawned_entity() {
+    let mut state = TestState::open(4).build();
+    let (px, py) = (state.player_x, state.player_y);
+    let hound = state.spawn_enemy(Enemy::new(px + 2, py, "mirage_hound"));
+    state.end_turn();
+
+    // Removed and another enemy spawned in the same turn, reusing its slot
+    state.despawn_enemy(hound);
+    let slime = state.spawn_enemy(Enemy::new(px + 2, py, "splitter_slime"));
+
+    assert_ne!(hound, slime);
+    assert!(state.enemy(hound).is_none());
+    assert_eq!(state.enemy(slime).unwrap().id, "splitter_slime");
+    assert_eq!(state.enemy_at(px + 2, py), Some(slime));
+
+    state.end_turn();
+    assert!(state.enemy(hound).is_none());
+    assert!(state.enemy(slime).is_some());
+}
+
+#[test]
+fn split_on_death_keeps_other_handles_valid() {
+    let mut state = TestState::open(4).build();
+    let (px, py) = (state.player_x, state.player_y);
+    let hound = state.spawn_enemy(Enemy::new(px - 3, py, "mirage_hound"));
+    let slime = state.spawn_enemy(Enemy::new(px + 1, py, "splitter_slime"));
+    state.enemy_mut(hound).unwrap().ai_disabled = true;
+    state.mock_combat_hit = Some(true);
+    state.mock_combat_damage = Some(100);
+
+    assert!(state.attack_melee(px + 1, py));
+
+    let killed = state
+        .drain_events()
+        .into_iter()
+        .find_map(|event| match event {
+            GameEvent::EnemyKilled { entity, .. } => Some(entity),
+            _ => None,
+        });
+    assert_eq!(killed, Some(slime));
+    assert!(state.enemies.iter().any(|e| e.id == "glass_beetle"));
+    assert_eq!(state.enemy(hound).unwrap().x, px - 3);
+    assert_eq!(state.enemy_at(px - 3, py), Some(hound));
+    assert_eq!(state.enemy_at(px + 1, py), None);
+}
+
+#[test]
+fn entities_added_without_handles_get_unique_ones() {
+    let mut state = TestState::open(4).build();
+    let (px, py) = (state.player_x, state.player_y);
+    state.enemies.push(Enemy::new(px + 2, py, "mirage_hound"));
+    let vial = Item::new(px + 3, py, "brine_vial");
+    state.items.push(vial.clone());
+    state.items.push(vial);
+    state.end_turn();
+
+    let hound = state.enemies[0].entity_id;
+    assert!(!hound.is_none());
+    assert_eq!(
+        state.enemy_at(state.enemies[0].x, state.enemies[0].y),
+        Some(hound)
+    );
+    let (first, second) = (state.items[0].entity_id, state.items[1].entity_id);
+    assert!(!first.is_none());
+    assert_ne!(first, second);
+    assert_eq!(state.item_index(second), Some(1));
+}
+
+#[test]
+fn picking_up_items_leaves_other_item_handles_valid() {
+    let mut state = TestState::open(4).build();
+    let (px, py) = (state.player_x, state.player_y);
+    let underfoot = state.spawn_item(Item::new(px, py, "brine_vial"));
+    let elsewhere = state.spawn_item(Item::new(px + 1, py, "glass_pick"));
+
+    state.pickup_items();
+
+    assert!(state.item(underfoot).is_none());
+    assert!(state.has_item("brine_vial"));
+    assert_eq!(state.item(elsewhere).unwrap().x, px + 1);
+}
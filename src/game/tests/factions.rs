@@ -0,0 +1,194 @@
+use super::{Clear, TestState};
+use crate::game::enemy::{Enemy, get_enemy_def};
+use crate::game::entity::EntityId;
+use crate::game::event::GameEvent;
+use crate::game::map::Tile;
+use crate::game::npc::Npc;
+use crate::game::state::GameState;
+use crate::game::systems::TURN_SCHEDULE;
+use crate::game::systems::ai::act;
+
+fn run(state: &mut GameState, id: EntityId) {
+    let i = state.enemy_index(id).unwrap();
+    act(i, state);
+}
+
+#[test]
+fn guards_fight_wild_creatures_instead_of_the_player() {
+    let mut state = TestState::open(8)
+        .clearing(&[Clear::Decoys, Clear::Reputation])
+        .build();
+    let (px, py) = (state.player_x, state.player_y);
+    let guard = state.spawn_enemy(Enemy::new(px + 4, py, "sand_engineer_friendly"));
+    let hound = state.spawn_enemy(Enemy::new(px + 5, py, "mirage_hound"));
+    let hound_hp = state.enemy(hound).unwrap().hp;
+    let hp = state.player_hp;
+
+    run(&mut state, guard);
+    assert!(state.enemy(hound).unwrap().hp < hound_hp);
+
+    // The hound answers the guard next to it rather than the player
+    let guard_hp = state.enemy(guard).unwrap().hp;
+    run(&mut state, hound);
+    assert!(state.enemy(guard).unwrap().hp < guard_hp);
+    assert_eq!(state.player_hp, hp);
+    assert_eq!(state.enemy(hound).unwrap().x, px + 5);
+}
+
+#[test]
+fn guards_do_not_see_creatures_through_walls() {
+    let mut state = TestState::open(8)
+        .clearing(&[Clear::Decoys, Clear::Reputation])
+        .build();
+    let (px, py) = (state.player_x, state.player_y);
+    let guard = state.spawn_enemy(Enemy::new(px + 2, py, "sand_engineer_friendly"));
+    let hound = state.spawn_enemy(Enemy::new(px + 4, py, "mirage_hound"));
+    state.enemy_mut(hound).unwrap().ai_disabled = true;
+    let idx = state.map.idx(px + 3, py);
+    state.map.tiles[idx] = Tile::Wall {
+        id: "sandstone".into(),
+        hp: 10,
+    };
+    let hound_hp = state.enemy(hound).unwrap().hp;
+
+    run(&mut state, guard);
+    assert_eq!(state.enemy(hound).unwrap().hp, hound_hp);
+    assert_eq!(state.enemy_at(px + 2, py), Some(guard));
+}
+
+#[test]
+fn creatures_kill_their_foes_without_rewarding_the_player() {
+    let mut state = TestState::open(8)
+        .clearing(&[Clear::Decoys, Clear::Reputation])
+        .build();
+    let (px, py) = (state.player_x, state.player_y);
+    let guard = state.spawn_enemy(Enemy::new(px + 4, py, "mirror_monk_friendly"));
+    let hound = state.spawn_enemy(Enemy::new(px + 5, py, "mirage_hound"));
+    state.enemy_mut(hound).unwrap().hp = 1;
+    let xp = state.player_xp;
+
+    run(&mut state, guard);
+    assert!(state.enemy(hound).unwrap().hp <= 0);
+    assert_eq!(state.enemy_at(px + 5, py), None);
+    assert_eq!(state.player_xp, xp);
+    assert!(
+        state
+            .messages
+            .iter()
+            .any(|m| m.text == "Mirror Monk kills the Mirage Hound!")
+    );
+}
+
+#[test]
+fn foes_killed_by_creatures_still_split() {
+    let mut state = TestState::open(8)
+        .clearing(&[Clear::Decoys, Clear::Reputation])
+        .build();
+    let (px, py) = (state.player_x, state.player_y);
+    let monk = state.spawn_enemy(Enemy::new(px + 4, py, "mirror_monk_friendly"));
+    let slime = state.spawn_enemy(Enemy::new(px + 5, py, "splitter_slime"));
+    state.enemy_mut(slime).unwrap().hp = 1;
+    let xp = state.player_xp;
+
+    run(&mut state, monk);
+    assert!(state.enemy(slime).unwrap().hp <= 0);
+    assert!(state.enemies.iter().any(|e| e.id == "glass_beetle"));
+    assert_eq!(state.player_xp, xp);
+}
+
+#[test]
+fn creature_kills_do_not_count_toward_kill_quests() {
+    let mut state = TestState::open(8)
+        .clearing(&[Clear::Decoys, Clear::Reputation])
+        .build();
+    let (px, py) = (state.player_x, state.player_y);
+    assert!(state.accept_quest("pest_control"));
+    let progress = |state: &GameState| {
+        state
+            .quest_log
+            .get_active("pest_control")
+            .unwrap()
+            .objectives[0]
+            .current
+    };
+    let dispatch = |state: &mut GameState| {
+        let events = state.drain_events();
+        for event in &events {
+            TURN_SCHEDULE.dispatch(state, event);
+        }
+        events
+    };
+
+    let monk = state.spawn_enemy(Enemy::new(px + 4, py, "mirror_monk_friendly"));
+    let beetle = state.spawn_enemy(Enemy::new(px + 5, py, "salt_beetle"));
+    state.enemy_mut(beetle).unwrap().hp = 1;
+    run(&mut state, monk);
+    assert!(state.enemy(beetle).unwrap().hp <= 0);
+    let killer = dispatch(&mut state)
+        .into_iter()
+        .find_map(|event| match event {
+            GameEvent::EnemyKilled { killer, .. } => Some(killer),
+            _ => None,
+        });
+    assert_eq!(killer, Some(Some(monk)));
+    assert_eq!(progress(&state), 0);
+
+    // The player's own kill still counts
+    let beetle = state.spawn_enemy(Enemy::new(px - 1, py, "salt_beetle"));
+    state.enemy_mut(beetle).unwrap().hp = 1;
+    state.mock_combat_hit = Some(true);
+    assert!(state.attack_melee(px - 1, py));
+    dispatch(&mut state);
+    assert_eq!(progress(&state), 1);
+}
+
+#[test]
+fn reputation_turns_factions_against_the_player_and_back() {
+    let mut state = TestState::open(8)
+        .clearing(&[Clear::Decoys, Clear::Reputation])
+        .build();
+    let (px, py) = (state.player_x, state.player_y);
+    let monk = state.spawn_enemy(Enemy::new(px + 1, py, "mirror_monk_friendly"));
+    let hp = state.player_hp;
+
+    run(&mut state, monk);
+    assert_eq!(state.player_hp, hp);
+
+    state.modify_reputation("MirrorMonks", -60);
+    assert_eq!(state.demeanor_label(state.enemy(monk).unwrap()), "hostile");
+    run(&mut state, monk);
+    assert!(state.player_hp < hp);
+
+    // Drones attack on sight until the archive is won over
+    let mut state = TestState::open(8)
+        .clearing(&[Clear::Decoys, Clear::Reputation])
+        .build();
+    let drone = state.spawn_enemy(Enemy::new(px + 1, py, "archive_drone"));
+    let max_hp = get_enemy_def("archive_drone").unwrap().max_hp;
+    state.modify_reputation("archive", 20);
+    run(&mut state, drone);
+    assert_eq!(state.player_hp, hp);
+    state.modify_reputation("archive", -20);
+    run(&mut state, drone);
+    assert!(state.player_hp < hp);
+    assert_eq!(state.enemy(drone).unwrap().hp, max_hp);
+}
+
+#[test]
+fn hostile_factions_refuse_to_talk() {
+    let mut state = TestState::open(8)
+        .clearing(&[Clear::Decoys, Clear::Reputation])
+        .build();
+    let (px, py) = (state.player_x, state.player_y);
+    state.npcs.push(Npc::new(px + 1, py, "mirror_monk"));
+    state.rebuild_spatial_index();
+    state.modify_reputation("MirrorMonks", -60);
+
+    assert!(state.try_move(1, 0));
+    assert!(state.pending_dialogue.is_none());
+    assert!(state.messages.iter().any(|m| {
+        m.text
+            .ends_with("\"The unmarked have no place in our choir.\"")
+    }));
+    assert_eq!(state.player_x, px);
+}
@@ -0,0 +1,84 @@
+use super::{Clear, TestState};
+use crate::game::equipment::EquipSlot;
+use crate::game::inventory::Encumbrance;
+use crate::game::item::{Item, ItemInstance};
+
+#[test]
+fn capacity_grows_with_max_hp_and_the_backpack() {
+    let mut state = TestState::open(4)
+        .clearing(&[Clear::Chests, Clear::StatusEffects, Clear::Gear])
+        .max_hp(20)
+        .build();
+    assert_eq!(state.carry_capacity(), 30);
+    state.player_max_hp = 30;
+    assert_eq!(state.carry_capacity(), 35);
+
+    state.give_item(ItemInstance::new("glass_frame_pack"));
+    assert!(state.equip_item(0, EquipSlot::Backpack));
+    assert_eq!(state.carry_capacity(), 60);
+    assert_eq!(state.carried_weight(), 3, "worn items still weigh");
+
+    // A broken pack carries nothing extra
+    state.wear_equipped(EquipSlot::Backpack, 1000);
+    assert_eq!(state.carry_capacity(), 35);
+}
+
+#[test]
+fn heavy_loads_cost_ap_and_reflex() {
+    let mut state = TestState::open(4)
+        .clearing(&[Clear::Chests, Clear::StatusEffects, Clear::Gear])
+        .max_hp(20)
+        .build();
+    state.end_turn();
+    let (ap, reflex) = (state.player_ap, state.effective_reflex());
+
+    // Four aegises weigh 40 against a capacity of 30
+    for _ in 0..4 {
+        state.give_item(ItemInstance::new("saint_aegis"));
+    }
+    assert_eq!(state.encumbrance(), Encumbrance::Burdened);
+    state.end_turn();
+    assert_eq!(state.player_ap, ap - 1);
+    assert_eq!(state.effective_reflex(), reflex - 2);
+
+    state.give_item(ItemInstance::new("saint_aegis"));
+    assert_eq!(state.encumbrance(), Encumbrance::Overloaded);
+    state.end_turn();
+    assert_eq!(state.player_ap, ap - 2);
+    assert_eq!(state.effective_reflex(), reflex - 5);
+}
+
+#[test]
+fn picking_up_warns_when_the_load_gets_heavier() {
+    let mut state = TestState::open(4)
+        .clearing(&[Clear::Chests, Clear::StatusEffects, Clear::Gear])
+        .max_hp(20)
+        .build();
+    for _ in 0..3 {
+        state.give_item(ItemInstance::new("saint_aegis"));
+    }
+    let (px, py) = (state.player_x, state.player_y);
+    state.spawn_item(Item::new(px + 1, py, "saint_aegis"));
+    assert!(state.try_move(1, 0));
+    assert_eq!(state.item_count("saint_aegis"), 4);
+    assert!(state.messages.iter().any(|m| m.text == "You are burdened."));
+}
+
+#[test]
+fn auto_explore_leaves_behind_what_it_cannot_carry() {
+    let mut state = TestState::open(4)
+        .clearing(&[Clear::Chests, Clear::StatusEffects, Clear::Gear])
+        .max_hp(20)
+        .build();
+    for _ in 0..2 {
+        state.give_item(ItemInstance::new("saint_aegis"));
+    }
+    let (px, py) = (state.player_x, state.player_y);
+    state.spawn_item(Item::new(px, py, "saint_aegis"));
+    state.spawn_item(Item::new(px, py, "brine_vial"));
+
+    state.auto_explore();
+    assert_eq!(state.item_count("brine_vial"), 1);
+    assert_eq!(state.item_count("saint_aegis"), 2);
+    assert!(state.items.iter().any(|i| i.id() == "saint_aegis"));
+}
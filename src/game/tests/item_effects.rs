@@ -0,0 +1,97 @@
+use super::TestState;
+use crate::game::enemy::Enemy;
+use crate::game::event::GameEvent;
+use crate::game::item::{ItemDef, ItemInstance};
+use crate::game::map::Tile;
+
+fn item_with(effects: &str) -> ItemDef {
+    serde_json::from_str(&format!(
+        r#"{{"id": "test_item", "name": "Test Item", "glyph": "!", "description": "",
+            "usable": true, "use_effects": {}}}"#,
+        effects
+    ))
+    .unwrap()
+}
+
+#[test]
+fn area_damage_hits_enemies_within_radius() {
+    let mut state = TestState::open(4).build();
+    let (px, py) = (state.player_x, state.player_y);
+    let near = state.spawn_enemy(Enemy::new(px + 2, py + 1, "mirage_hound"));
+    let far = state.spawn_enemy(Enemy::new(px - 3, py, "mirage_hound"));
+    let hp = state.enemy(far).unwrap().hp;
+    let bomb =
+        item_with(r#"[{"type": "area_damage", "damage": 100, "target": "area", "radius": 1}]"#);
+
+    assert_eq!(state.apply_item_effects(&bomb, px + 2, py), 1);
+
+    assert!(state.enemy(near).unwrap().hp <= 0);
+    assert_eq!(state.enemy(far).unwrap().hp, hp);
+    assert!(
+        state
+            .drain_events()
+            .iter()
+            .any(|e| matches!(e, GameEvent::EnemyKilled { entity, .. } if *entity == near))
+    );
+}
+
+#[test]
+fn effects_with_unmet_conditions_do_not_apply() {
+    let mut state = TestState::open(4).build();
+    let (px, py) = (state.player_x, state.player_y);
+    state.player_hp = state.player_max_hp;
+    let tonic = item_with(
+        r#"[{"type": "heal", "amount": 5, "conditions": [{"type": "hp_below", "percent": 50}]},
+            {"type": "modify_stat", "stat": "salt_scrip", "amount": 3,
+             "conditions": [{"type": "lacks_status", "status": "burn"}]}]"#,
+    );
+    let scrip = state.salt_scrip;
+
+    assert_eq!(state.apply_item_effects(&tonic, px, py), 1);
+    assert_eq!(state.salt_scrip, scrip + 3);
+
+    state.player_hp = 1;
+    assert_eq!(state.apply_item_effects(&tonic, px, py), 2);
+    assert_eq!(state.player_hp, 6);
+}
+
+#[test]
+fn teleport_and_spawn_use_the_target_tile() {
+    let mut state = TestState::open(4).build();
+    let (px, py) = (state.player_x, state.player_y);
+    let blink = item_with(r#"[{"type": "teleport", "target": "tile"}]"#);
+    let lure = item_with(
+        r#"[{"type": "spawn_entity", "entity": "enemy", "id": "mirage_hound", "count": 2, "target": "tile"}]"#,
+    );
+
+    assert_eq!(state.apply_item_effects(&lure, px + 3, py), 1);
+    assert!(state.enemy_at(px + 3, py).is_some());
+    assert_eq!(state.enemies.len(), 2);
+
+    // Occupied tiles cannot be teleported onto
+    assert_eq!(state.apply_item_effects(&blink, px + 3, py), 0);
+    assert_eq!((state.player_x, state.player_y), (px, py));
+    assert_eq!(state.apply_item_effects(&blink, px - 2, py - 2), 1);
+    assert_eq!((state.player_x, state.player_y), (px - 2, py - 2));
+}
+
+#[test]
+fn tile_use_without_effect_keeps_item_and_ap() {
+    let mut state = TestState::open(4).build();
+    let (px, py) = (state.player_x, state.player_y);
+    state.inventory = vec![ItemInstance::new("glass_pick")];
+    let ap = state.player_ap;
+
+    assert!(!state.use_item_on_tile(0, px + 1, py));
+    assert_eq!(state.player_ap, ap);
+    assert_eq!(state.inventory.len(), 1);
+
+    let idx = state.map.idx(px + 1, py);
+    state.map.tiles[idx] = Tile::Wall {
+        id: "glass".to_string(),
+        hp: 5,
+    };
+    assert!(state.use_item_on_tile(0, px + 1, py));
+    assert!(state.map.tiles[idx].walkable());
+    assert!(state.player_ap < ap);
+}
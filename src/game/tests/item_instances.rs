@@ -0,0 +1,161 @@
+use super::{Clear, TestState};
+use crate::game::chest::Chest;
+use crate::game::damage::DamageType;
+use crate::game::enemy::Enemy;
+use crate::game::equipment::EquipSlot;
+use crate::game::item::{Condition, Item, ItemInstance};
+use crate::game::save::{SaveFormat, decode_bytes, encode_as};
+use crate::game::state::GameState;
+use crate::game::systems::combat::CombatSystem;
+
+fn knife(affixes: &[&str]) -> ItemInstance {
+    let mut knife = ItemInstance::new("salt_knife");
+    knife.affixes = affixes.iter().map(|a| a.to_string()).collect();
+    knife.repair();
+    knife
+}
+
+fn wear(state: &mut GameState, item: ItemInstance, slot: EquipSlot) {
+    state.give_item(item);
+    assert!(state.equip_item(state.inventory.len() - 1, slot));
+}
+
+#[test]
+fn affixes_name_and_price_an_item() {
+    let plain = knife(&[]);
+    assert_eq!(plain.name(), "Salt Knife");
+    assert_eq!(plain.durability, Some(40));
+    assert_eq!(plain.value(), 15);
+
+    let fancy = knife(&["keen", "of_the_dunes"]);
+    assert_eq!(fancy.name(), "Keen Salt Knife of the Dunes");
+    assert_eq!(fancy.damage_bonus(), 1);
+    assert_eq!(fancy.resistances().get(&DamageType::Heat), Some(&15));
+
+    let mut brittle = knife(&["brittle"]);
+    assert_eq!(brittle.durability, Some(20));
+    assert_eq!(brittle.value(), 10);
+    brittle.wear(12);
+    assert_eq!(brittle.condition(), Condition::Damaged);
+    assert_eq!(brittle.display_name(), "Brittle Salt Knife (damaged)");
+    assert_eq!(brittle.value(), 5);
+
+    brittle.custom_name = Some("Old Faithful".into());
+    assert_eq!(brittle.name(), "Old Faithful");
+    // Consumables never wear
+    assert_eq!(ItemInstance::new("brine_vial").durability, None);
+}
+
+#[test]
+fn weapons_wear_with_each_hit_until_they_break() {
+    let mut state = TestState::open(4)
+        .clearing(&[Clear::Chests, Clear::StatusEffects, Clear::Gear])
+        .build();
+    let (x, y) = (state.player_x + 1, state.player_y);
+    let id = state.spawn_enemy(Enemy::new(x, y, "mirage_hound"));
+    let enemy = state.enemy_mut(id).unwrap();
+    enemy.hp = 100;
+    enemy.ai_disabled = true;
+
+    let mut blade = knife(&["keen"]);
+    blade.durability = Some(2);
+    wear(&mut state, blade, EquipSlot::Weapon);
+    state.mock_combat_hit = Some(true);
+    state.mock_combat_damage = Some(5);
+
+    assert!(CombatSystem::attack_melee(&mut state, x, y));
+    assert_eq!(state.enemy(id).unwrap().hp, 94, "keen adds a point");
+    assert_eq!(state.equipment.weapon.as_ref().unwrap().durability, Some(1));
+
+    state.player_ap = state.player_max_ap;
+    assert!(CombatSystem::attack_melee(&mut state, x, y));
+    assert!(state.equipment.weapon.as_ref().unwrap().is_broken());
+    assert!(state.equipped_weapon.is_none());
+    assert!(
+        state
+            .messages
+            .iter()
+            .any(|m| m.text == "Your Keen Salt Knife breaks!")
+    );
+
+    // Broken, it adds nothing
+    let hp = state.enemy(id).unwrap().hp;
+    state.player_ap = state.player_max_ap;
+    assert!(CombatSystem::attack_melee(&mut state, x, y));
+    assert_eq!(state.enemy(id).unwrap().hp, hp - 5);
+}
+
+#[test]
+fn armor_adds_up_over_slots_and_broken_pieces_give_none() {
+    let mut state = TestState::open(4)
+        .clearing(&[Clear::Chests, Clear::StatusEffects, Clear::Gear])
+        .build();
+    let mut vest = ItemInstance::new("salt_vest");
+    vest.affixes = vec!["reinforced".into()];
+    wear(&mut state, vest, EquipSlot::Jacket);
+    wear(
+        &mut state,
+        ItemInstance::new("salt_miner_helmet"),
+        EquipSlot::Head,
+    );
+    assert_eq!(state.player_armor, 4);
+
+    state.wear_armor(1);
+    assert_eq!(
+        state.equipment.jacket.as_ref().unwrap().durability,
+        Some(39)
+    );
+    assert_eq!(state.equipment.head.as_ref().unwrap().durability, Some(29));
+
+    state.wear_equipped(EquipSlot::Jacket, 100);
+    assert_eq!(state.player_armor, 1);
+    // Taking it off and on again does not mend it
+    assert!(state.unequip_slot(EquipSlot::Jacket));
+    let idx = state.inventory.len() - 1;
+    assert!(state.equip_item(idx, EquipSlot::Jacket));
+    assert_eq!(state.player_armor, 1);
+}
+
+#[test]
+fn instances_survive_chests_the_ground_and_saves() {
+    let mut state = TestState::open(4)
+        .clearing(&[Clear::Chests, Clear::StatusEffects, Clear::Gear])
+        .build();
+    let mut blade = knife(&["storm_forged", "of_mirrors"]);
+    blade.wear(7);
+    let (px, py) = (state.player_x, state.player_y);
+
+    state.chests.push(Chest::new(px, py, "wooden_chest"));
+    state.give_item(blade.clone());
+    assert!(state.transfer_to_chest(0, 0));
+    assert_eq!(state.chests[0].inventory, [blade.clone()]);
+    assert!(state.transfer_from_chest(0, 0));
+    assert_eq!(state.inventory, [blade.clone()]);
+
+    state.inventory.clear();
+    state.spawn_item(Item::place(px + 1, py, blade.clone()));
+    assert!(state.try_move(1, 0));
+    assert_eq!(state.inventory, [blade.clone()]);
+
+    for format in SaveFormat::ALL {
+        let bytes = encode_as(&state, format).unwrap();
+        let (_, loaded) = decode_bytes(&bytes).unwrap();
+        assert_eq!(loaded.inventory, [blade.clone()], "{:?}", format);
+    }
+}
+
+#[test]
+fn bare_item_ids_load_as_fresh_instances() {
+    let items: Vec<ItemInstance> =
+        ron::from_str(r#"["brine_vial", (id: "salt_knife", affixes: ["keen"])]"#).unwrap();
+    assert_eq!(items[0], ItemInstance::new("brine_vial"));
+    assert_eq!(items[1].durability, Some(40));
+    assert_eq!(items[1].name(), "Keen Salt Knife");
+
+    let packed = rmp_serde::to_vec_named(&["glass_pick"]).unwrap();
+    let items: Vec<ItemInstance> = rmp_serde::from_slice(&packed).unwrap();
+    assert_eq!(items, [ItemInstance::new("glass_pick")]);
+
+    let ground: Item = ron::from_str(r#"(x: 3, y: 4, id: "hand_torch")"#).unwrap();
+    assert_eq!((ground.x, ground.y, ground.id()), (3, 4, "hand_torch"));
+}
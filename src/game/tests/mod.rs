@@ -1 +1,123 @@
+pub mod ai_trees;
+pub mod cover;
+pub mod damage_types;
+pub mod entity_handles;
+pub mod factions;
+pub mod inventory_weight;
+pub mod item_effects;
+pub mod item_instances;
 pub mod quest_enforcement;
+pub mod stealth;
+pub mod throwing;
+pub mod timeline;
+
+use crate::game::equipment::EquipSlot;
+use crate::game::item::ItemInstance;
+use crate::game::map::Tile;
+use crate::game::state::GameState;
+
+/// Parts of a new game a `TestState` can clear besides creatures and items
+/// on the ground
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Clear {
+    Decoys,
+    Interactables,
+    Chests,
+    StatusEffects,
+    /// Standing with every faction
+    Reputation,
+    /// Everything carried and worn
+    Gear,
+    /// Light sources placed on the map
+    Lights,
+}
+
+/// Builds games for tests: seed 42 with no enemies, NPCs or items, the
+/// player at (20, 20) and open floor around them
+pub struct TestState {
+    radius: i32,
+    clear: Vec<Clear>,
+    layer: i32,
+    carrying: Vec<String>,
+    max_hp: Option<i32>,
+}
+
+impl TestState {
+    /// Open floor `radius` tiles around the player
+    pub fn open(radius: i32) -> Self {
+        Self {
+            radius,
+            clear: Vec::new(),
+            layer: 0,
+            carrying: Vec::new(),
+            max_hp: None,
+        }
+    }
+
+    pub fn clearing(mut self, parts: &[Clear]) -> Self {
+        self.clear.extend_from_slice(parts);
+        self
+    }
+
+    /// Count the game as being underground, where only the player's lantern
+    /// and placed lights give light
+    pub fn underground(mut self) -> Self {
+        self.layer = -1;
+        self
+    }
+
+    /// Put `items` in the player's inventory, after anything cleared
+    pub fn carrying(mut self, items: &[&str]) -> Self {
+        self.carrying.extend(items.iter().map(|id| id.to_string()));
+        self
+    }
+
+    /// Set the player's max HP
+    pub fn max_hp(mut self, hp: i32) -> Self {
+        self.max_hp = Some(hp);
+        self
+    }
+
+    pub fn build(self) -> GameState {
+        let mut state = GameState::new(42);
+        state.enemies.clear();
+        state.npcs.clear();
+        state.items.clear();
+        for part in &self.clear {
+            match part {
+                Clear::Decoys => state.decoys.clear(),
+                Clear::Interactables => state.interactables.clear(),
+                Clear::Chests => state.chests.clear(),
+                Clear::StatusEffects => state.status_effects.clear(),
+                Clear::Reputation => state.faction_reputation.clear(),
+                Clear::Gear => {
+                    state.inventory.clear();
+                    for &slot in EquipSlot::all() {
+                        state.equipment.set(slot, None);
+                    }
+                    state.recalc_equipment_stats();
+                }
+                Clear::Lights => state.map.lights.clear(),
+            }
+        }
+        for id in &self.carrying {
+            state.inventory.push(ItemInstance::new(id));
+        }
+        if let Some(hp) = self.max_hp {
+            state.player_max_hp = hp;
+        }
+        state.layer = self.layer;
+        state.player_x = 20;
+        state.player_y = 20;
+        for dy in -self.radius..=self.radius {
+            for dx in -self.radius..=self.radius {
+                let idx = state.map.idx(state.player_x + dx, state.player_y + dy);
+                state.map.tiles[idx] = Tile::default_floor();
+            }
+        }
+        state.rebuild_spatial_index();
+        state.update_fov();
+        state.update_lighting();
+        state
+    }
+}
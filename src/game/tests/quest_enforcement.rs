@@ -1,3 +1,4 @@
+use crate::game::item::ItemInstance;
 use crate::game::state::GameState;
 
 #[test]
@@ -68,7 +69,7 @@ fn test_quest_objective_progression() {
     assert!(!quest.objectives[2].completed); // return_to_pilgrim still pending
 
     // Simulate collecting scripture shard
-    state.inventory.push("scripture_shard".to_string());
+    state.give_item(ItemInstance::new("scripture_shard"));
     state.quest_log.on_item_collected("scripture_shard");
     let quest = &state.quest_log.active[0];
     assert!(quest.objectives[0].completed); // find_dying_pilgrim completed
@@ -0,0 +1,166 @@
+use super::{Clear, TestState};
+use crate::game::enemy::Enemy;
+use crate::game::entity::EntityId;
+use crate::game::map::Tile;
+use crate::game::state::GameState;
+use crate::game::stealth::Visibility;
+use crate::game::systems::ai::act;
+
+/// Steps from the enemy `id` to (x, y)
+fn distance(state: &GameState, id: EntityId, (x, y): (i32, i32)) -> i32 {
+    let e = state.enemy(id).unwrap();
+    (e.x - x).abs() + (e.y - y).abs()
+}
+
+fn run(state: &mut GameState, id: EntityId) {
+    let i = state.enemy_index(id).unwrap();
+    act(i, state);
+}
+
+#[test]
+fn sneaking_in_the_dark_slips_past_enemies() {
+    let mut state = TestState::open(12)
+        .clearing(&[Clear::Lights, Clear::Gear])
+        .underground()
+        .build();
+    let (px, py) = (state.player_x, state.player_y);
+    let hound = state.spawn_enemy(Enemy::new(px + 4, py, "mirage_hound"));
+
+    state.toggle_sneak();
+    assert_eq!(state.player_visibility(), Visibility::Hidden);
+    run(&mut state, hound);
+    assert!(!state.enemy(hound).unwrap().noticed_player);
+    assert_eq!(distance(&state, hound, (px + 4, py)), 0);
+
+    state.toggle_sneak();
+    assert_eq!(state.player_visibility(), Visibility::Exposed);
+    run(&mut state, hound);
+    assert!(state.enemy(hound).unwrap().noticed_player);
+    assert_ne!(distance(&state, hound, (px + 4, py)), 0);
+    assert!(
+        state
+            .messages
+            .iter()
+            .any(|m| m.text == "Mirage Hound notices you!")
+    );
+    assert_eq!(state.player_visibility(), Visibility::Seen);
+
+    // Sneaking does not shake off an enemy that has seen the player
+    state.toggle_sneak();
+    run(&mut state, hound);
+    assert!(state.enemy(hound).unwrap().noticed_player);
+    assert_eq!(state.player_visibility(), Visibility::Seen);
+}
+
+#[test]
+fn glare_hides_the_player_a_little() {
+    let mut state = TestState::open(12)
+        .clearing(&[Clear::Lights, Clear::Gear])
+        .underground()
+        .build();
+    assert_eq!(state.detection_range(6), 6);
+    let idx = state.map.idx(state.player_x, state.player_y);
+    state.map.tiles[idx] = Tile::Glare;
+    assert_eq!(state.detection_range(6), 5);
+    assert_eq!(state.player_visibility(), Visibility::Obscured);
+}
+
+#[test]
+fn enemies_investigate_noises_that_reach_them() {
+    let mut state = TestState::open(12)
+        .clearing(&[Clear::Lights, Clear::Gear])
+        .underground()
+        .build();
+    let (px, py) = (state.player_x, state.player_y);
+    let hound = state.spawn_enemy(Enemy::new(px + 10, py, "mirage_hound"));
+    let beetle = state.spawn_enemy(Enemy::new(px - 10, py, "glass_beetle"));
+    // Wall the beetle in so the noise cannot reach it
+    for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+        let idx = state.map.idx(px - 10 + dx, py + dy);
+        state.map.tiles[idx] = Tile::Wall {
+            id: "sandstone".into(),
+            hp: 10,
+        };
+    }
+
+    state.make_noise(px, py, 10);
+    assert_eq!(state.enemy(hound).unwrap().heard, Some((px, py)));
+    assert_eq!(state.enemy(beetle).unwrap().heard, None);
+
+    run(&mut state, hound);
+    assert_eq!(distance(&state, hound, (px, py)), 9);
+    run(&mut state, beetle);
+    assert_eq!(state.enemy(beetle).unwrap().x, px - 10);
+}
+
+#[test]
+fn enemies_search_where_they_lost_sight_of_the_player() {
+    let mut state = TestState::open(12)
+        .clearing(&[Clear::Lights, Clear::Gear])
+        .underground()
+        .build();
+    let (px, py) = (state.player_x, state.player_y);
+    let hound = state.spawn_enemy(Enemy::new(px + 5, py, "mirage_hound"));
+    run(&mut state, hound);
+    assert!(state.enemy(hound).unwrap().noticed_player);
+
+    let last_seen = (px - 6, py);
+    state.player_x = last_seen.0;
+    state.update_lighting();
+    let before = distance(&state, hound, last_seen);
+    run(&mut state, hound);
+    assert!(!state.enemy(hound).unwrap().noticed_player);
+    assert_eq!(state.enemy(hound).unwrap().heard, Some(last_seen));
+    assert!(distance(&state, hound, last_seen) < before);
+}
+
+#[test]
+fn sneaking_steps_are_silent_but_slow() {
+    let mut state = TestState::open(12)
+        .clearing(&[Clear::Lights, Clear::Gear])
+        .underground()
+        .build();
+    let (px, py) = (state.player_x, state.player_y);
+    let hound = state.spawn_enemy(Enemy::new(px - 2, py + 2, "mirage_hound"));
+    state.toggle_sneak();
+    let ap = state.player_ap;
+    assert!(state.try_move(-1, 0));
+    assert_eq!(state.player_ap, ap - 2);
+    assert_eq!(state.enemy(hound).unwrap().heard, None);
+
+    state.toggle_sneak();
+    assert!(state.try_move(-1, 0));
+    assert_eq!(state.enemy(hound).unwrap().heard, Some((px - 2, py)));
+}
+
+#[test]
+fn walls_hide_the_player_from_enemies() {
+    let mut state = TestState::open(12)
+        .clearing(&[Clear::Lights, Clear::Gear])
+        .underground()
+        .build();
+    let (px, py) = (state.player_x, state.player_y);
+    let hound = state.spawn_enemy(Enemy::new(px + 4, py, "mirage_hound"));
+    let idx = state.map.idx(px + 2, py);
+    state.map.tiles[idx] = Tile::Wall {
+        id: "sandstone".into(),
+        hp: 10,
+    };
+    let i = state.enemy_index(hound).unwrap();
+    state.perceive(i);
+    assert!(!state.enemy(hound).unwrap().noticed_player);
+
+    state.map.tiles[idx] = Tile::default_floor();
+    state.perceive(i);
+    assert!(state.enemy(hound).unwrap().noticed_player);
+
+    // Losing sight behind the wall, it goes around to where the player stands
+    state.map.tiles[idx] = Tile::Wall {
+        id: "sandstone".into(),
+        hp: 10,
+    };
+    run(&mut state, hound);
+    assert!(!state.enemy(hound).unwrap().noticed_player);
+    assert_eq!(state.enemy(hound).unwrap().heard, Some((px, py)));
+    assert!(distance(&state, hound, (px, py)) < 4);
+}
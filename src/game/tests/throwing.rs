@@ -0,0 +1,109 @@
+use super::{Clear, TestState};
+use crate::game::action::action_cost;
+use crate::game::enemy::Enemy;
+use crate::game::entity::EntityId;
+use crate::game::item::ItemInstance;
+use crate::game::map::Tile;
+use crate::game::state::GameState;
+
+/// Spawn a sturdy enemy that stays put at `(x, y)`
+fn dummy(state: &mut GameState, x: i32, y: i32) -> EntityId {
+    let id = state.spawn_enemy(Enemy::new(x, y, "mirage_hound"));
+    let enemy = state.enemy_mut(id).unwrap();
+    enemy.hp = 100;
+    enemy.ai_disabled = true;
+    id
+}
+
+#[test]
+fn straight_throws_stop_at_the_first_creature_and_arcs_fly_over() {
+    let mut state = TestState::open(8)
+        .clearing(&[Clear::StatusEffects, Clear::Gear])
+        .carrying(&["glass_shard"])
+        .build();
+    let (px, py) = (state.player_x, state.player_y);
+    dummy(&mut state, px + 2, py);
+
+    let shard = state.throw_target(0, px + 4, py).unwrap();
+    assert_eq!(shard.landing, (px + 2, py));
+    assert_eq!(shard.tiles, [(px + 2, py)]);
+
+    state.inventory = vec![ItemInstance::new("brine_flask")];
+    let flask = state.throw_target(0, px + 4, py).unwrap();
+    assert_eq!(flask.landing, (px + 4, py));
+    assert_eq!(flask.tiles.len(), 9);
+
+    // Walls stop both, and out of range is refused
+    let idx = state.map.idx(px + 3, py);
+    state.map.tiles[idx] = Tile::Wall {
+        id: "sandstone".to_string(),
+        hp: 10,
+    };
+    assert_eq!(
+        state.throw_target(0, px + 4, py).unwrap().landing,
+        (px + 2, py)
+    );
+    assert!(state.throw_target(0, px + 7, py).is_err());
+}
+
+#[test]
+fn thrown_items_are_used_up_and_hurt_what_they_hit() {
+    let mut state = TestState::open(8)
+        .clearing(&[Clear::StatusEffects, Clear::Gear])
+        .carrying(&["glass_shard"])
+        .build();
+    let (px, py) = (state.player_x, state.player_y);
+    let id = dummy(&mut state, px + 3, py);
+    let ap = state.player_ap;
+
+    assert!(state.throw_item(0, px + 3, py));
+    assert!(state.inventory.is_empty());
+    assert_eq!(state.player_ap, ap - action_cost("throw_item"));
+    let enemy = state.enemy(id).unwrap();
+    assert_eq!(enemy.hp, 97);
+    assert!(enemy.status_effects.iter().any(|s| s.id == "bleed"));
+
+    // Nothing left to throw
+    assert!(!state.throw_item(0, px + 3, py));
+}
+
+#[test]
+fn blasts_reshape_the_ground_and_only_reach_the_player_with_friendly_fire() {
+    let mut state = TestState::open(8)
+        .clearing(&[Clear::StatusEffects, Clear::Gear])
+        .carrying(&["brine_flask"])
+        .build();
+    let (px, py) = (state.player_x, state.player_y);
+    let hp = state.player_hp;
+    assert!(state.throw_item(0, px + 1, py));
+    assert_eq!(state.player_hp, hp, "flasks spare the thrower");
+    assert_eq!(state.map.get(px + 2, py), Some(&Tile::floor("brine_mud")));
+    assert_eq!(state.map.get(px, py), Some(&Tile::default_floor()));
+
+    let mut state = TestState::open(8)
+        .clearing(&[Clear::StatusEffects, Clear::Gear])
+        .carrying(&["glass_bomb"])
+        .build();
+    let preview = state.throw_target(0, px + 1, py).unwrap();
+    assert!(preview.tiles.contains(&(px, py)));
+    assert!(state.throw_item(0, px + 1, py));
+    assert!(state.player_hp < hp);
+    assert_eq!(state.map.get(px, py), Some(&Tile::Glass));
+}
+
+#[test]
+fn flares_land_where_they_are_thrown() {
+    let mut state = TestState::open(8)
+        .clearing(&[Clear::StatusEffects, Clear::Gear])
+        .carrying(&["flare"])
+        .build();
+    let (px, py) = (state.player_x, state.player_y);
+    assert!(state.throw_item(0, px + 5, py + 2));
+    assert!(state.inventory.is_empty());
+    assert!(
+        state
+            .items
+            .iter()
+            .any(|i| i.id() == "flare" && (i.x, i.y) == (px + 5, py + 2))
+    );
+}
@@ -0,0 +1,138 @@
+use super::{Clear, TestState};
+use crate::game::adaptation::Adaptation;
+use crate::game::enemy::Enemy;
+use crate::game::map::Tile;
+use crate::game::state::GameState;
+use crate::game::status::StatusEffect;
+
+/// Actors that acted in `turn`
+fn actors(state: &GameState, turn: u32) -> Vec<&str> {
+    state
+        .action_log
+        .iter()
+        .filter(|a| a.turn == turn)
+        .map(|a| a.actor.as_str())
+        .collect()
+}
+
+#[test]
+fn speed_decides_how_often_enemies_act() {
+    let mut state = TestState::open(10).build();
+    let (px, py) = (state.player_x, state.player_y);
+    let hound = state.spawn_enemy(Enemy::new(px + 9, py + 9, "mirage_hound"));
+    state.spawn_enemy(Enemy::new(px - 9, py + 9, "refraction_wraith"));
+
+    state.end_turn();
+    let turn = state.turn;
+    assert_eq!(
+        actors(&state, turn),
+        ["player", "refraction_wraith", "mirage_hound"]
+    );
+    // The wraith banks enough for two actions and spends the first before
+    // the hound, which wins the tie for the second by being spawned first
+    state.end_turn();
+    assert_eq!(
+        actors(&state, turn + 1),
+        [
+            "player",
+            "refraction_wraith",
+            "mirage_hound",
+            "refraction_wraith"
+        ]
+    );
+
+    // Slowed to half speed, the hound sits out every other turn
+    state.enemy_mut(hound).unwrap().apply_status("slow", 10);
+    state.end_turn();
+    state.end_turn();
+    let hound_turns = state
+        .action_log
+        .iter()
+        .filter(|a| a.entity == Some(hound) && a.turn > turn + 1)
+        .count();
+    assert_eq!(hound_turns, 1);
+}
+
+#[test]
+fn player_speed_scales_action_points() {
+    let mut state = TestState::open(10).build();
+    state.player_max_ap = 4;
+    state.end_turn();
+    assert_eq!(state.player_ap, 4);
+
+    state.apply_status(StatusEffect::new("slow", 5));
+    state.end_turn();
+    assert_eq!(state.player_ap, 2);
+
+    // Sunveins quickens the player even while slowed
+    state
+        .adaptations
+        .push(Adaptation::from_id("sunveins").unwrap());
+    assert_eq!(state.player_speed(), 75);
+    state.end_turn();
+    assert_eq!(state.player_ap, 3);
+}
+
+#[test]
+fn resting_runs_each_turn_on_the_timeline() {
+    let mut state = TestState::open(10).build();
+    let (px, py) = (state.player_x, state.player_y);
+    let wraith = state.spawn_enemy(Enemy::new(px + 45, py, "refraction_wraith"));
+    let hound = state.spawn_enemy(Enemy::new(px + 45, py + 2, "mirage_hound"));
+    let start = state.turn;
+    state.player_hp = 1;
+
+    state.rest().unwrap();
+    assert_eq!(state.turn, start + 10);
+    assert_eq!(state.player_hp, 1 + state.player_max_hp / 2);
+    let turns_of = |id| {
+        state
+            .action_log
+            .iter()
+            .filter(|a| a.entity == Some(id))
+            .count()
+    };
+    assert_eq!(turns_of(wraith), 15);
+    assert_eq!(turns_of(hound), 10);
+    assert_eq!(actors(&state, start + 1)[0], "player");
+}
+
+#[test]
+fn an_enemy_closing_in_interrupts_rest() {
+    let mut state = TestState::open(10).build();
+    let (px, py) = (state.player_x, state.player_y);
+    for x in px..=px + 34 {
+        let idx = state.map.idx(x, py);
+        state.map.tiles[idx] = Tile::default_floor();
+    }
+    let hound = state.spawn_enemy(Enemy::new(px + 33, py, "mirage_hound"));
+    state.enemy_mut(hound).unwrap().heard = Some((px, py));
+    let start = state.turn;
+    state.player_hp = 1;
+
+    state.rest().unwrap();
+    assert_eq!(state.turn, start + 1);
+    // Only the one turn that passed heals
+    assert_eq!(state.player_hp, 1 + state.player_max_hp / 2 / 10);
+    assert!(
+        state
+            .messages
+            .iter()
+            .any(|m| m.text == "Your rest is interrupted!")
+    );
+}
+
+#[test]
+fn corpses_and_friendly_creatures_do_not_stop_rest() {
+    let mut state = TestState::open(10)
+        .clearing(&[Clear::Decoys, Clear::Reputation])
+        .build();
+    let (px, py) = (state.player_x, state.player_y);
+    let corpse = state.spawn_enemy(Enemy::new(px + 3, py, "mirage_hound"));
+    state.enemy_mut(corpse).unwrap().hp = 0;
+    state.spawn_enemy(Enemy::new(px - 3, py, "mirror_monk_friendly"));
+    let start = state.turn;
+
+    state.rest().unwrap();
+    assert_eq!(state.turn, start + 10);
+}
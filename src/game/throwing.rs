@@ -0,0 +1,135 @@
+//! Thrown items
+//!
+//! Items with a `throw` entry in items.json can be thrown at a visible tile
+//! within `range`. A straight throw stops at the first creature in its path
+//! and an `arc` flies over creatures; both stop short of walls. The item's
+//! throw `effects`, in the `use_effects` syntax, then land on every tile
+//! within `radius` of where it came down, leaving out the player's tile
+//! unless `friendly_fire` is set. Items that `land` are left on the landing
+//! tile instead of being used up. The clatter draws enemies that have not
+//! noticed the player to the landing tile.
+
+use serde::Deserialize;
+
+use super::{
+    action::{action_cost, action_noise},
+    item::{Item, ItemDef, get_item_def},
+    item_effect::ItemEffect,
+    state::{GameState, MsgType, line_path},
+};
+
+/// How an item behaves when thrown
+#[derive(Clone, Debug, Deserialize)]
+pub struct ThrowDef {
+    /// Furthest tile it can be thrown at
+    pub range: i32,
+    /// Reach of its effects around the landing tile
+    #[serde(default)]
+    pub radius: i32,
+    /// Lobbed over creatures rather than stopping at the first one
+    #[serde(default)]
+    pub arc: bool,
+    /// Whether its effects reach the player
+    #[serde(default)]
+    pub friendly_fire: bool,
+    /// Left on the landing tile instead of being used up
+    #[serde(default)]
+    pub lands: bool,
+    #[serde(default)]
+    pub effects: Vec<ItemEffect>,
+}
+
+/// Where a throw comes down and the tiles its effects reach
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThrowTarget {
+    pub landing: (i32, i32),
+    pub tiles: Vec<(i32, i32)>,
+}
+
+impl GameState {
+    /// Where throwing the inventory item at `idx` at `(x, y)` would land and
+    /// what it would hit, or why it cannot be thrown there
+    pub fn throw_target(&self, idx: usize, x: i32, y: i32) -> Result<ThrowTarget, String> {
+        let (def, throw) = throwable(self.inventory.get(idx).map(|item| &item.id))?;
+        let from = (self.player_x, self.player_y);
+        if (x - from.0).abs().max((y - from.1).abs()) > throw.range {
+            return Err("That is too far to throw.".into());
+        }
+        if self.map.get(x, y).is_none() || !self.visible.contains(&self.map.idx(x, y)) {
+            return Err(format!("You can't see where to throw the {}.", def.name));
+        }
+
+        let mut landing = from;
+        for &(tx, ty) in line_path(from, (x, y)).iter().skip(1) {
+            if !self.map.get(tx, ty).is_some_and(|t| t.walkable()) {
+                break;
+            }
+            landing = (tx, ty);
+            let occupied = self.enemy_at(tx, ty).is_some() || self.npc_at(tx, ty).is_some();
+            if occupied && !throw.arc {
+                break;
+            }
+        }
+        if landing == from {
+            return Err("There is no room to throw.".into());
+        }
+
+        let r = throw.radius.max(0);
+        let tiles = (-r..=r)
+            .flat_map(|dy| (-r..=r).map(move |dx| (landing.0 + dx, landing.1 + dy)))
+            .filter(|&(tx, ty)| self.map.get(tx, ty).is_some())
+            .filter(|&tile| throw.friendly_fire || tile != from)
+            .collect();
+        Ok(ThrowTarget { landing, tiles })
+    }
+
+    /// Throw the inventory item at `idx` at `(x, y)`
+    pub fn throw_item(&mut self, idx: usize, x: i32, y: i32) -> bool {
+        let (def, throw) = match throwable(self.inventory.get(idx).map(|item| &item.id)) {
+            Ok(found) => found,
+            Err(e) => {
+                self.log(e);
+                return false;
+            }
+        };
+        let target = match self.throw_target(idx, x, y) {
+            Ok(target) => target,
+            Err(e) => {
+                self.log(e);
+                return false;
+            }
+        };
+        let cost = action_cost("throw_item");
+        if self.player_ap < cost {
+            return false;
+        }
+        self.player_ap -= cost;
+        let Some(thrown) = self.take_item(idx) else {
+            return false;
+        };
+
+        let (lx, ly) = target.landing;
+        let glyph = def.glyph.chars().next().unwrap_or('*');
+        self.spawn_projectile((self.player_x, self.player_y), (lx, ly), glyph);
+        self.log_typed(format!("You throw the {}.", thrown.name()), MsgType::Combat);
+        self.apply_effects(def, &throw.effects, lx, ly, Some(&target.tiles));
+        self.make_noise(lx, ly, action_noise("throw_item"));
+        if throw.lands {
+            self.spawn_item(Item::place(lx, ly, thrown));
+            self.update_lighting();
+        }
+
+        self.check_auto_end_turn();
+        true
+    }
+}
+
+fn throwable(id: Option<&String>) -> Result<(&'static ItemDef, &'static ThrowDef), String> {
+    let def = id
+        .and_then(|id| get_item_def(id))
+        .ok_or("Nothing to throw.")?;
+    match &def.throw {
+        Some(throw) => Ok((def, throw)),
+        None => Err(format!("The {} is not made for throwing.", def.name)),
+    }
+}
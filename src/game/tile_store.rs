@@ -0,0 +1,211 @@
+//! Persistent per-tile world state
+//!
+//! World tiles are generated lazily from their seed the first time they are
+//! entered. When the player leaves a tile, its live state (map edits, entities,
+//! loot, exploration) is stashed here and restored on re-entry instead of being
+//! regenerated, so broken walls, storm glass and looted chests stay that way.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+use super::{
+    chest::Chest, enemy::Enemy, generation::PlacedMicroStructure, interactable::Interactable,
+    item::Item, map::Map, npc::Npc,
+};
+
+/// Identifies a single map in the world: a world-map cell plus a depth layer
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct TileKey {
+    pub world_x: usize,
+    pub world_y: usize,
+    /// 0 = surface, negative = underground
+    pub layer: i32,
+}
+
+impl TileKey {
+    pub fn new(world_x: usize, world_y: usize, layer: i32) -> Self {
+        Self {
+            world_x,
+            world_y,
+            layer,
+        }
+    }
+}
+
+/// Full snapshot of a tile as the player left it
+#[derive(Serialize, Deserialize)]
+pub struct TileSnapshot {
+    pub map: Map,
+    pub enemies: Vec<Enemy>,
+    pub npcs: Vec<Npc>,
+    pub items: Vec<Item>,
+    pub chests: Vec<Chest>,
+    pub interactables: Vec<Interactable>,
+    #[serde(default)]
+    pub microstructures: Vec<PlacedMicroStructure>,
+    #[serde(default, serialize_with = "super::state_hash::sorted_set")]
+    pub revealed: HashSet<usize>,
+}
+
+/// Visited tiles keyed by world position and layer
+#[derive(Default, Serialize, Deserialize)]
+pub struct TileStore {
+    tiles: BTreeMap<TileKey, TileSnapshot>,
+}
+
+impl TileStore {
+    /// Store a snapshot, replacing any previous one for the same key
+    pub fn insert(&mut self, key: TileKey, snapshot: TileSnapshot) {
+        self.tiles.insert(key, snapshot);
+    }
+
+    /// Remove and return the snapshot for a tile, if it has been visited
+    pub fn take(&mut self, key: TileKey) -> Option<TileSnapshot> {
+        self.tiles.remove(&key)
+    }
+
+    pub fn get(&self, key: TileKey) -> Option<&TileSnapshot> {
+        self.tiles.get(&key)
+    }
+
+    pub fn contains(&self, key: TileKey) -> bool {
+        self.tiles.contains_key(&key)
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &TileKey> {
+        self.tiles.keys()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&TileKey, &TileSnapshot)> {
+        self.tiles.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&TileKey, &mut TileSnapshot)> {
+        self.tiles.iter_mut()
+    }
+
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::map::Tile;
+    use crate::game::state::GameState;
+    use crate::game::systems::StormSystem;
+
+    #[test]
+    fn storm_edited_tile_survives_round_trip() {
+        let mut state = GameState::new(42);
+        let (wx, wy) = (state.world_x, state.world_y);
+
+        state.storm.intensity = 3;
+        StormSystem::apply_storm(&mut state);
+        let marker = state.map.idx(state.player_x + 2, state.player_y);
+        state.map.tiles[marker] = Tile::Glass;
+        let edited_tiles = state.map.tiles.clone();
+        let enemy_count = state.enemies.len();
+
+        state.travel_to_tile(wx + 1, wy);
+        assert_eq!(state.world_x, wx + 1);
+        assert!(state.tile_store.contains(TileKey::new(wx, wy, 0)));
+
+        state.travel_to_tile(wx, wy);
+        assert_eq!(
+            state.messages.last().unwrap().text,
+            "You return to a familiar area."
+        );
+        assert_eq!(state.map.tiles, edited_tiles);
+        assert_eq!(state.enemies.len(), enemy_count);
+        assert!(!state.tile_store.contains(TileKey::new(wx, wy, 0)));
+        assert!(state.tile_store.contains(TileKey::new(wx + 1, wy, 0)));
+    }
+
+    #[test]
+    fn looted_items_and_killed_enemies_stay_gone() {
+        let mut state = GameState::new(42);
+        let (wx, wy) = (state.world_x, state.world_y);
+
+        state.items.clear();
+        for enemy in &mut state.enemies {
+            enemy.hp = 0;
+        }
+        state.rebuild_spatial_index();
+
+        state.travel_to_tile(wx, wy + 1);
+        state.travel_to_tile(wx, wy);
+
+        assert!(state.items.is_empty());
+        assert!(state.enemies.iter().all(|e| e.hp <= 0));
+    }
+
+    #[test]
+    fn visited_tiles_persist_through_save() {
+        let mut state = GameState::new(42);
+        let (wx, wy) = (state.world_x, state.world_y);
+        let marker = state.map.idx(state.player_x + 2, state.player_y);
+        state.map.tiles[marker] = Tile::Glass;
+        state.travel_to_tile(wx - 1, wy);
+
+        let path = "/tmp/test_tile_store_save.ron";
+        state.save(path).unwrap();
+        let mut loaded = GameState::load(path).unwrap();
+        std::fs::remove_file(path).ok();
+
+        assert_eq!(loaded.tile_store.len(), state.tile_store.len());
+        loaded.travel_to_tile(wx, wy);
+        assert_eq!(loaded.map.tiles[marker], Tile::Glass);
+    }
+
+    #[test]
+    fn subterranean_levels_are_populated_and_persist() {
+        let mut state = GameState::new(42);
+        let (wx, wy) = (state.world_x, state.world_y);
+        let stairs = state.map.idx(state.player_x, state.player_y);
+        state.map.tiles[stairs] = Tile::StairsDown;
+        let surface_pos = (state.player_x, state.player_y);
+
+        assert!(state.enter_subterranean());
+        assert_eq!(state.layer, -1);
+        assert!(!state.enemies.is_empty());
+        assert!(!state.chests.is_empty());
+        assert_eq!(
+            state.map.get(state.player_x, state.player_y),
+            Some(&Tile::StairsUp)
+        );
+
+        // Chain down another layer and back up
+        let (down_x, down_y) = state.map.find_tile(&Tile::StairsDown).unwrap();
+        state.enemies.truncate(1);
+        let layer1_tiles = state.map.tiles.clone();
+        state.player_x = down_x;
+        state.player_y = down_y;
+        assert!(state.enter_subterranean());
+        assert_eq!(state.layer, -2);
+        assert!(state.tile_store.contains(TileKey::new(wx, wy, -1)));
+
+        assert!(state.exit_subterranean());
+        assert_eq!(state.layer, -1);
+        assert_eq!(state.map.tiles, layer1_tiles);
+        assert_eq!(state.enemies.len(), 1);
+        assert_eq!((state.player_x, state.player_y), (down_x, down_y));
+
+        let (up_x, up_y) = state.map.find_tile(&Tile::StairsUp).unwrap();
+        state.player_x = up_x;
+        state.player_y = up_y;
+        state.messages.clear();
+        assert!(state.exit_subterranean());
+        assert_eq!(state.layer, 0);
+        let texts: Vec<&str> = state.messages.iter().map(|m| m.text.as_str()).collect();
+        assert_eq!(texts, ["You return to the surface."]);
+        assert_eq!((state.player_x, state.player_y), surface_pos);
+        assert!(state.tile_store.contains(TileKey::new(wx, wy, -1)));
+        assert!(state.tile_store.contains(TileKey::new(wx, wy, -2)));
+    }
+}
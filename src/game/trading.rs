@@ -1,6 +1,9 @@
+use crate::game::content;
+use crate::game::inventory::{add_to_stacks, count_items, take_units};
+use crate::game::item::ItemInstance;
 use once_cell::sync::Lazy;
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Trading system with faction, tier, and relationship-based availability
 #[derive(Clone, Debug, Deserialize)]
@@ -42,8 +45,8 @@ struct TradersFile {
     traders: Vec<TraderTable>,
 }
 
-static TRADERS: Lazy<HashMap<String, TraderTable>> = Lazy::new(|| {
-    let data = include_str!("../../data/traders.json");
+static TRADERS: Lazy<BTreeMap<String, TraderTable>> = Lazy::new(|| {
+    let data = &content::load("traders.json");
     let file: TradersFile = serde_json::from_str(data).expect("Failed to parse traders.json");
     file.traders
         .into_iter()
@@ -203,7 +206,7 @@ pub fn execute_trade(
     item_id: &str,
     quantity: u32,
     player_currency: &mut u32,
-    player_inventory: &mut Vec<String>,
+    player_inventory: &mut Vec<ItemInstance>,
 ) -> Result<String, String> {
     let item = trade_interface
         .available_items
@@ -223,9 +226,13 @@ pub fn execute_trade(
 
     // Execute transaction
     *player_currency -= total_cost;
-    for _ in 0..quantity {
-        player_inventory.push(item_id.to_string());
-    }
+    add_to_stacks(
+        player_inventory,
+        ItemInstance {
+            quantity,
+            ..ItemInstance::new(item_id)
+        },
+    );
 
     if item.stock != -1 {
         item.stock -= quantity as i32;
@@ -237,45 +244,28 @@ pub fn execute_trade(
     ))
 }
 
-/// Sell item to trader
+/// Sell item to trader. Each unit fetches its own value, so worn copies and
+/// copies with affixes sell for less or more.
 pub fn execute_sell(
     trade_interface: &TradeInterface,
     item_id: &str,
     quantity: u32,
     player_currency: &mut u32,
-    player_inventory: &mut Vec<String>,
+    player_inventory: &mut Vec<ItemInstance>,
 ) -> Result<String, String> {
     if !trade_interface.can_sell_to {
         return Err("Trader refuses to buy from you".to_string());
     }
 
-    let item_count = player_inventory.iter().filter(|id| *id == item_id).count() as u32;
-    if item_count < quantity {
+    if count_items(player_inventory, item_id) < quantity {
         return Err("You don't have enough of that item".to_string());
     }
 
-    // Get base item value (simplified - could be from item definitions)
-    let base_value = match item_id {
-        "glass_shard" => 5,
-        "salt_crystal" => 10,
-        "cloth_scrap" => 3,
-        "metal_wire" => 15,
-        _ => 8, // Default value
-    };
-
-    let sell_price = (base_value as f32 * trade_interface.sell_price_multiplier) as u32;
-    let total_value = sell_price * quantity;
-
-    // Remove items from inventory
-    let mut removed = 0;
-    player_inventory.retain(|id| {
-        if id == item_id && removed < quantity {
-            removed += 1;
-            false
-        } else {
-            true
-        }
-    });
+    let sold = take_units(player_inventory, item_id, quantity).unwrap_or_default();
+    let total_value: u32 = sold
+        .iter()
+        .map(|item| (item.value() as f32 * trade_interface.sell_price_multiplier) as u32)
+        .sum();
 
     *player_currency += total_value;
 
@@ -1,3 +1,4 @@
+use crate::game::content;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
@@ -18,7 +19,7 @@ pub struct TutorialData {
 }
 
 static TUTORIAL_DATA: Lazy<TutorialData> = Lazy::new(|| {
-    let data_str = include_str!("../../data/tutorial.json");
+    let data_str = &content::load("tutorial.json");
     serde_json::from_str(data_str).expect("Failed to parse tutorial.json")
 });
 
@@ -29,6 +30,7 @@ pub fn get_tutorial_data() -> &'static TutorialData {
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct TutorialProgress {
     /// Set of tutorial message IDs that have been shown
+    #[serde(serialize_with = "super::state_hash::sorted_set")]
     shown_messages: HashSet<String>,
     /// Whether tutorial is completely disabled
     tutorial_disabled: bool,
@@ -3,6 +3,7 @@ pub mod des;
 pub mod game;
 pub mod ipc;
 pub mod renderer;
+pub mod replay;
 pub mod satellite;
 pub mod terminal_spawn;
 pub mod tilegen_tools;
@@ -147,7 +148,9 @@ mod tests {
     #[test]
     fn saltblood_prevents_glass_damage() {
         let mut state = GameState::new(42);
-        state.adaptations.push(Adaptation::Saltblood);
+        state
+            .adaptations
+            .push(Adaptation::from_id("saltblood").unwrap());
         let idx = state.map.idx(state.player_x + 1, state.player_y);
         state.map.tiles[idx] = Tile::Glass;
         let initial_hp = state.player_hp;
@@ -159,7 +162,7 @@ mod tests {
     fn items_spawn_in_map() {
         let state = GameState::new(42);
         assert!(!state.items.is_empty());
-        assert!(state.items.iter().any(|i| i.id == "storm_glass"));
+        assert!(state.items.iter().any(|i| i.id() == "storm_glass"));
     }
 
     #[test]
@@ -203,14 +206,14 @@ mod tests {
         let items_before = state.items.len();
         state.pickup_items();
         assert_eq!(state.items.len(), items_before - 1);
-        assert!(state.inventory.contains(&"brine_vial".to_string()));
+        assert!(state.has_item("brine_vial"));
     }
 
     #[test]
     fn brine_vial_heals() {
         let mut state = GameState::new(42);
         state.player_hp = 10;
-        state.inventory.push("brine_vial".to_string());
+        state.give_item(ItemInstance::new("brine_vial"));
         state.use_item(0);
         assert_eq!(state.player_hp, 15);
     }
@@ -236,8 +239,9 @@ mod tests {
         assert!(dialogue.contains("unmarked"));
 
         // With Prismhide
+        let prismhide = [Adaptation::from_id("prismhide").unwrap()];
         let ctx = DialogueContext {
-            adaptations: &[Adaptation::Prismhide],
+            adaptations: &prismhide,
             inventory: &[],
             salt_scrip: 0,
             faction_reputation: &empty_rep,
@@ -258,12 +262,12 @@ mod tests {
         state.npcs.push(Npc::new(npc_x, npc_y, "mirror_monk"));
         state.rebuild_spatial_index();
 
-        // Get the index of our NPC
-        let npc_idx = state
+        // Get the handle of our NPC
+        let npc = state
             .npc_at(npc_x, npc_y)
             .expect("NPC should be in spatial index");
         assert!(
-            !state.npcs[npc_idx].talked,
+            !state.npc(npc).unwrap().talked,
             "NPC should not be talked to initially"
         );
 
@@ -271,7 +275,7 @@ mod tests {
         state.try_move(1, 0);
 
         assert!(
-            state.npcs[npc_idx].talked,
+            state.npc(npc).unwrap().talked,
             "NPC should be talked to after bump"
         );
     }
@@ -1,6 +1,6 @@
 use crossterm::{
     ExecutableCommand,
-    event::{self, Event, KeyEventKind},
+    event::{self, Event, KeyCode, KeyEventKind},
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use ratatui::{
@@ -8,268 +8,22 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph},
 };
 use saltglass_steppe::cli::{LaunchMode, parse_args};
+use saltglass_steppe::content::{self, ContentConfig};
+use saltglass_steppe::replay::{Recorder, Replay, ReplayOutcome};
 use saltglass_steppe::satellite::SatelliteApp;
+use saltglass_steppe::save_slots::slot_name_for;
 use saltglass_steppe::ui::{
-    Action, MainMenuState, MenuAction, UiState, handle_input, handle_menu_input,
-    render_book_reader, render_bottom_panel, render_chest_ui, render_controls,
-    render_crafting_menu, render_damage_numbers, render_death_screen, render_debug_console,
-    render_debug_menu, render_dialog_box, render_inventory_menu, render_issue_reporter,
-    render_menu, render_pause_menu, render_psychic_menu, render_quest_log, render_side_panel,
-    render_skills_menu, render_target_hud, render_wiki,
+    Action, MainMenuState, MenuAction, SaveSession, UiState, apply_action, apply_pending,
+    handle_input, handle_menu_input, render_book_reader, render_bottom_panel, render_chest_ui,
+    render_controls, render_crafting_menu, render_damage_numbers, render_death_screen,
+    render_debug_console, render_debug_menu, render_dialog_box, render_inventory_menu,
+    render_issue_reporter, render_menu, render_pause_menu, render_psychic_menu, render_quest_log,
+    render_side_panel, render_skills_menu, render_target_hud, render_wiki,
 };
-use saltglass_steppe::{GameState, Renderer, get_item_def};
+use saltglass_steppe::{GameState, Renderer, SaveFormat, SaveSlots};
 use std::io::{Result, stdout};
-
-const SAVE_FILE: &str = "savegame.ron";
-
-fn update(state: &mut GameState, action: Action, ui: &mut UiState) -> Option<bool> {
-    match action {
-        Action::Quit => return Some(false),
-        Action::ReturnToMainMenu => return None, // Signal to return to main menu
-        Action::OpenPauseMenu => ui.pause_menu.open(),
-        Action::OpenControls => ui.show_controls = true,
-        Action::EnterLook => {
-            ui.look_mode.active = true;
-            ui.look_mode.x = state.player_x;
-            ui.look_mode.y = state.player_y;
-        }
-        Action::BreakWall(x, y) => {
-            if state.player_hp > 0 {
-                state.try_break_wall(x, y);
-            }
-        }
-        Action::Save => match state.save(SAVE_FILE) {
-            Ok(_) => state.log("Game saved."),
-            Err(e) => state.log(format!("Save failed: {}", e)),
-        },
-        Action::Load => match GameState::load(SAVE_FILE) {
-            Ok(loaded) => {
-                *state = loaded;
-                state.log("Game loaded.");
-            }
-            Err(e) => state.log(format!("Load failed: {}", e)),
-        },
-        Action::UseItem(idx) => {
-            if state.player_hp > 0 {
-                state.use_item(idx);
-                ui.inventory_menu.close();
-            }
-        }
-        Action::Move(dx, dy) => {
-            if state.player_hp > 0 {
-                let new_x = state.player_x + dx;
-                let new_y = state.player_y + dy;
-                if let Some(ei) = state.enemy_at(new_x, new_y) {
-                    ui.target_enemy = Some(ei);
-                }
-                state.try_move(dx, dy);
-            }
-        }
-        Action::EndTurn => {
-            if state.player_hp > 0 {
-                state.end_turn();
-            }
-        }
-        Action::Wait => {
-            if state.player_hp > 0 {
-                state.wait_turn();
-            }
-        }
-        Action::AutoExplore => {
-            if state.player_hp > 0 {
-                state.auto_explore();
-            }
-        }
-        Action::RangedAttack(x, y) => {
-            if state.player_hp > 0 {
-                // Auto-target enemy when attacking
-                if let Some(ei) = state.enemy_at(x, y) {
-                    ui.target_enemy = Some(ei);
-                }
-                state.try_ranged_attack(x, y);
-            }
-        }
-        Action::SetTarget(x, y) => {
-            ui.target_enemy = state.enemy_at(x, y);
-        }
-        Action::UseStairs => {
-            if state.player_hp > 0 {
-                // Check what tile we're standing on
-                if let Some(tile) = state.map.get(state.player_x, state.player_y) {
-                    match tile {
-                        saltglass_steppe::Tile::StairsDown => {
-                            state.enter_subterranean();
-                        }
-                        saltglass_steppe::Tile::StairsUp => {
-                            state.exit_subterranean();
-                        }
-                        saltglass_steppe::Tile::WorldExit => {
-                            // Simple world map travel - for now just show a message
-                            // TODO: Add proper world map UI
-                            state.log("Use arrow keys to choose direction, then press > again.");
-                        }
-                        _ => {
-                            state.log("No stairs here.");
-                        }
-                    }
-                }
-            }
-        }
-        Action::TradeBuy(idx) => {
-            if let Some(interface) = &mut ui.trade_menu.interface {
-                if let Some(item) = interface.available_items.get(idx) {
-                    use saltglass_steppe::trading::execute_trade;
-                    match execute_trade(
-                        interface,
-                        &item.item_id.clone(),
-                        1,
-                        &mut state.salt_scrip,
-                        &mut state.inventory,
-                    ) {
-                        Ok(msg) => state.log_typed(msg, saltglass_steppe::MsgType::Social),
-                        Err(e) => state.log(e),
-                    }
-                }
-            }
-        }
-        Action::TradeSell(idx) => {
-            if let Some(interface) = &ui.trade_menu.interface {
-                if let Some(item_id) = state.inventory.get(idx) {
-                    use saltglass_steppe::trading::execute_sell;
-                    match execute_sell(
-                        interface,
-                        &item_id.clone(),
-                        1,
-                        &mut state.salt_scrip,
-                        &mut state.inventory,
-                    ) {
-                        Ok(msg) => state.log_typed(msg, saltglass_steppe::MsgType::Social),
-                        Err(e) => state.log(e),
-                    }
-                }
-            }
-        }
-        Action::DebugCommand(cmd) => {
-            state.debug_command(&cmd);
-        }
-        Action::SubmitIssueReport => {
-            let report = state.create_issue_report(
-                ui.issue_reporter.description.clone(),
-                ui.issue_reporter.steps.clone(),
-                ui.issue_reporter.expected.clone(),
-                ui.issue_reporter.actual.clone(),
-                ui.issue_reporter.severity.clone(),
-                ui.issue_reporter.category.clone(),
-            );
-            match state.save_issue_report(&report) {
-                Ok(_) => {
-                    state.log(format!("Issue report saved: {}", report.id));
-                    ui.issue_reporter.close();
-                }
-                Err(e) => state.log(format!("Failed to save issue report: {}", e)),
-            }
-        }
-        Action::OpenDebugMenu => {
-            ui.debug_menu.toggle();
-        }
-        Action::OpenIssueReporter => {
-            ui.issue_reporter.open();
-        }
-        Action::OpenInventory => {
-            ui.inventory_menu.open();
-        }
-        Action::EquipSelected => {
-            if let Some(idx) = ui.inventory_menu.selected_inv_index() {
-                if idx < state.inventory.len() {
-                    if let Some(def) = get_item_def(&state.inventory[idx]) {
-                        if let Some(slot_str) = &def.equip_slot {
-                            if let Ok(slot) = slot_str.parse::<saltglass_steppe::EquipSlot>() {
-                                state.equip_item(idx, slot);
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        Action::UnequipSelected => {
-            if let Some(slot) = ui.inventory_menu.selected_equip_slot() {
-                state.unequip_slot(slot);
-            }
-        }
-        Action::OpenQuestLog => {
-            ui.quest_log.open();
-        }
-        Action::OpenCrafting => {
-            ui.crafting_menu.open();
-        }
-        Action::OpenChest(_) => {
-            // Check if player is standing on a chest
-            if let Some(&chest_idx) = state.chest_positions.get(&(state.player_x, state.player_y)) {
-                if state.open_chest(chest_idx) {
-                    ui.chest_ui = Some(saltglass_steppe::ui::ChestUI::new(chest_idx));
-                }
-            } else {
-                state.log("No chest here.");
-            }
-        }
-        Action::ChestTransfer => {
-            if let Some(ref chest_ui) = ui.chest_ui {
-                let chest_index = chest_ui.chest_index;
-                if let Some(chest_item_idx) = chest_ui.get_selected_chest_item() {
-                    state.transfer_from_chest(chest_index, chest_item_idx);
-                } else if let Some(inv_item_idx) = chest_ui.get_selected_inventory_item() {
-                    state.transfer_to_chest(chest_index, inv_item_idx);
-                }
-            }
-        }
-        Action::CloseChest => {
-            ui.chest_ui = None;
-        }
-        Action::Interact(_, _) => {
-            if state.player_hp > 0 {
-                state.interact_at(state.player_x, state.player_y);
-            }
-        }
-        Action::Examine(_, _) => {
-            if state.player_hp > 0 {
-                state.examine_at(state.player_x, state.player_y);
-            }
-        }
-        Action::OpenWiki => {
-            ui.wiki_menu.open();
-        }
-        Action::OpenPsychicMenu => {
-            ui.psychic_menu.toggle();
-        }
-        Action::OpenSkillsMenu => {
-            ui.skills_menu.open();
-        }
-        Action::UsePsychicAbility(ability_id) => {
-            state.use_psychic_ability(&ability_id);
-        }
-        Action::RangedAttackMode => {
-            // TODO: Implement ranged attack mode
-        }
-        Action::TargetMode => {
-            // TODO: Implement targeting mode
-        }
-        Action::OpenWorldMap => {
-            ui.world_map_view.toggle(state.world_x, state.world_y);
-        }
-        Action::WorldMapTravel(wx, wy) => {
-            if state.player_hp > 0 && state.layer == 0 {
-                state.travel_to_tile_safe(wx, wy);
-            }
-        }
-        Action::Craft => {
-            if let Some(recipe_id) = ui.crafting_menu.selected_recipe_id() {
-                state.craft(recipe_id);
-            }
-        }
-        Action::None => {}
-    }
-    Some(true)
-}
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 fn render(frame: &mut Frame, state: &GameState, ui: &mut UiState, renderer: &mut Renderer) {
     // Fullscreen menus
@@ -284,6 +38,7 @@ fn render(frame: &mut Frame, state: &GameState, ui: &mut UiState, renderer: &mut
             &ui.inventory_menu,
             &state.inventory,
             &state.equipment,
+            (state.carried_weight(), state.carry_capacity()),
         );
         return;
     }
@@ -350,7 +105,11 @@ fn render(frame: &mut Frame, state: &GameState, ui: &mut UiState, renderer: &mut
         .split(frame.area());
 
     // Left side: game area with look mode and map
-    let desc_height = if ui.look_mode.active { 3u16 } else { 0 };
+    let desc_height = if ui.look_mode.active || ui.throw_mode.active {
+        3u16
+    } else {
+        0
+    };
     let game_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -374,20 +133,40 @@ fn render(frame: &mut Frame, state: &GameState, ui: &mut UiState, renderer: &mut
         );
     }
 
+    // Throw preview: where the item lands and the tiles it reaches
+    ui.throw_mode.blast.clear();
+    if ui.throw_mode.active {
+        let (idx, x, y) = (ui.throw_mode.item, ui.throw_mode.x, ui.throw_mode.y);
+        let desc = match state.throw_target(idx, x, y) {
+            Ok(target) => {
+                let hit = target
+                    .tiles
+                    .iter()
+                    .filter(|&&(tx, ty)| state.enemy_at(tx, ty).is_some())
+                    .count();
+                let you = if target.tiles.contains(&(state.player_x, state.player_y)) {
+                    " - you are in the blast!"
+                } else {
+                    ""
+                };
+                ui.throw_mode.blast = target.tiles;
+                format!(
+                    "{} tiles, {} enemies{}",
+                    ui.throw_mode.blast.len(),
+                    hit,
+                    you
+                )
+            }
+            Err(e) => e,
+        };
+        let block = Block::default()
+            .title(" Throw (Enter to throw, Esc to cancel) ")
+            .borders(Borders::ALL);
+        frame.render_widget(Paragraph::new(desc).block(block), game_chunks[0]);
+    }
+
     // Render game map using new modular renderer
-    let look_cursor = if ui.look_mode.active {
-        Some((ui.look_mode.x, ui.look_mode.y))
-    } else {
-        None
-    };
-    renderer.render_game(
-        frame,
-        game_chunks[1],
-        state,
-        ui.frame_count,
-        look_cursor,
-        ui.debug_console.active,
-    );
+    renderer.render_game(frame, game_chunks[1], state, ui);
     render_damage_numbers(frame, game_chunks[1], state);
 
     // Bottom panel with log
@@ -397,8 +176,8 @@ fn render(frame: &mut Frame, state: &GameState, ui: &mut UiState, renderer: &mut
     render_side_panel(frame, main_chunks[1], state);
 
     // Target HUD (bottom left)
-    if let Some(target_idx) = ui.target_enemy {
-        render_target_hud(frame, state, target_idx);
+    if let Some(target) = ui.target_enemy {
+        render_target_hud(frame, state, target);
     }
 
     // Pause menu overlay (rendered last)
@@ -432,7 +211,22 @@ fn main() -> Result<()> {
     let launch_mode = parse_args();
 
     match launch_mode {
-        LaunchMode::MainGame => run_main_game(),
+        LaunchMode::MainGame {
+            save_format,
+            record,
+            content,
+        } => {
+            configure_content(&content);
+            run_main_game(save_format, record)
+        }
+        LaunchMode::Replay {
+            path,
+            headless,
+            content,
+        } => {
+            configure_content(&content);
+            run_replay(&path, headless)
+        }
         LaunchMode::LogUi => run_satellite_ui("log-ui"),
         LaunchMode::GameLogUi => run_satellite_ui("game-log-ui"),
         LaunchMode::StatusUi => run_satellite_ui("status-ui"),
@@ -441,6 +235,19 @@ fn main() -> Result<()> {
     }
 }
 
+/// Load content from the data directory and mods, printing what was
+/// overridden and any conflicts or errors
+fn configure_content(config: &ContentConfig) {
+    match content::configure(config) {
+        Ok(report) => {
+            if !report.files.is_empty() || !report.is_clean() {
+                eprint!("{}", report);
+            }
+        }
+        Err(e) => eprintln!("{}", e),
+    }
+}
+
 fn run_satellite_ui(ui_type: &str) -> Result<()> {
     let socket_path = "/tmp/saltglass-steppe.sock";
     let mut app = match SatelliteApp::new(socket_path) {
@@ -465,7 +272,43 @@ fn run_satellite_ui(ui_type: &str) -> Result<()> {
     }
 }
 
-fn run_main_game() -> Result<()> {
+/// Start recording a session, logging to the game if the file cannot be written
+fn start_recording(path: &Path, state: &mut GameState, new_game: bool) -> Option<Recorder> {
+    let recorder = if new_game {
+        Recorder::new_game(path, state)
+    } else {
+        Recorder::from_state(path, state)
+    };
+    match recorder {
+        Ok(recorder) => Some(recorder),
+        Err(e) => {
+            state.log(format!("Recording failed: {}", e));
+            None
+        }
+    }
+}
+
+/// Record an applied action. Loading a save starts a new recording from the
+/// loaded state, since the replay cannot follow the game into the slot.
+fn record_action(
+    recorder: &mut Option<Recorder>,
+    turn: u32,
+    action: &Action,
+    state: &mut GameState,
+) {
+    let Some(active) = recorder else {
+        return;
+    };
+    if *action == Action::Load {
+        let path = active.path().to_path_buf();
+        *recorder = start_recording(&path, state, false);
+    } else if let Err(e) = active.record(turn, action, state) {
+        state.log(format!("Recording failed: {}", e));
+        *recorder = None;
+    }
+}
+
+fn run_main_game(save_format: SaveFormat, record: Option<PathBuf>) -> Result<()> {
     stdout().execute(EnterAlternateScreen)?;
     enable_raw_mode()?;
     let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
@@ -495,7 +338,7 @@ fn run_main_game() -> Result<()> {
         // Main menu loop
         let mut menu_state = MainMenuState::new();
         let mut menu_tick: u64 = 0;
-        let (class_id, seed) = loop {
+        let (mut state, slot, new_game) = loop {
             terminal.draw(|f| render_menu(f, menu_tick, &menu_state))?;
             menu_tick = menu_tick.wrapping_add(1);
             match handle_menu_input(&mut menu_state)? {
@@ -504,7 +347,8 @@ fn run_main_game() -> Result<()> {
                         .duration_since(std::time::UNIX_EPOCH)
                         .unwrap()
                         .as_secs();
-                    break (class, seed);
+                    let slot = slot_name_for(&class, seed);
+                    break (GameState::new_with_class(seed, &class), slot, true);
                 }
                 MenuAction::NewGameWithSeed(seed) => {
                     // Get the selected class
@@ -513,8 +357,13 @@ fn run_main_game() -> Result<()> {
                         .filter(|c| menu_state.meta.is_class_unlocked(&c.id))
                         .collect();
                     let class = classes.get(menu_state.class_index).unwrap().id.clone();
-                    break (class, seed);
+                    let slot = slot_name_for(&class, seed);
+                    break (GameState::new_with_class(seed, &class), slot, true);
                 }
+                MenuAction::LoadGame(slot) => match SaveSlots::open_default().load(&slot) {
+                    Ok(state) => break (state, slot, false),
+                    Err(e) => menu_state.message = Some(format!("Load failed: {}", e)),
+                },
                 MenuAction::Controls => {
                     // Show controls screen
                     loop {
@@ -533,12 +382,16 @@ fn run_main_game() -> Result<()> {
                     stdout().execute(LeaveAlternateScreen)?;
                     return Ok(());
                 }
-                MenuAction::LoadGame(_) | MenuAction::None => {}
+                MenuAction::None => {}
             }
         };
 
-        // Create game with selected class and seed
-        let mut state = GameState::new_with_class(seed, &class_id);
+        let slots = SaveSlots::open_default().with_format(save_format);
+        let mut session = SaveSession::new(slots, &state, &slot);
+        let mut current_tile = (state.world_x, state.world_y, state.layer);
+        let mut recorder = record
+            .as_deref()
+            .and_then(|path| start_recording(path, &mut state, new_game));
         let mut ui = UiState::new();
         // Initialize camera to player position
         ui.camera_x = state.player_x as f32;
@@ -557,48 +410,9 @@ fn run_main_game() -> Result<()> {
                 ui.dialog_box.tick(16); // ~60fps
             }
 
-            // Check for pending dialogue from NPC interaction
-            if let Some((speaker, text)) = state.pending_dialogue.take() {
-                ui.dialog_box.show(&speaker, &text);
-            }
-
-            // Check for pending book open
-            if let Some(book_id) = state.pending_book_open.take() {
-                ui.book_reader.open(&book_id);
-            }
-
-            // Check for pending trade (only if no dialog is active)
-            if let Some(trader_id) = state.pending_trade.take() {
-                if ui.dialog_box.active {
-                    // Put the trade back if dialog is still active
-                    state.pending_trade = Some(trader_id);
-                } else {
-                    use saltglass_steppe::trading::{calculate_area_tier, get_trade_interface};
-                    let area_tier = calculate_area_tier(&state.enemies);
-                    if let Some(interface) = get_trade_interface(
-                        &trader_id,
-                        area_tier,
-                        &state.faction_reputation,
-                        None, // Player faction not yet implemented
-                    ) {
-                        // Close other menus to ensure trade menu has focus
-                        ui.inventory_menu.close();
-                        ui.quest_log.close();
-                        ui.crafting_menu.close();
-                        ui.wiki_menu.close();
-                        ui.pause_menu.close();
-                        ui.trade_menu.open(trader_id, interface);
-                    } else {
-                        state.log("This merchant has nothing to trade.");
-                    }
-                }
-            }
-
-            // Clear target if enemy is dead
-            if let Some(ei) = ui.target_enemy {
-                if ei >= state.enemies.len() || state.enemies[ei].hp <= 0 {
-                    ui.target_enemy = None;
-                }
+            apply_pending(&mut state, &mut ui);
+            if content::watched_files_changed() {
+                state.reload_content();
             }
 
             if ui.show_controls {
@@ -613,8 +427,24 @@ fn run_main_game() -> Result<()> {
             } else {
                 terminal.draw(|frame| render(frame, &state, &mut ui, &mut renderer))?;
                 let action = handle_input(&mut ui, &mut state)?;
-                match update(&mut state, action, &mut ui) {
+                let turn = state.turn;
+                let applied = action.clone();
+                session.history.record(&applied, &state);
+                match apply_action(&mut state, action, &mut ui, Some(&mut session)) {
                     Some(true) => {
+                        record_action(&mut recorder, turn, &applied, &mut state);
+
+                        // Autosave whenever the player reaches a different map
+                        let tile = (state.world_x, state.world_y, state.layer);
+                        if tile != current_tile {
+                            current_tile = tile;
+                            if state.player_hp > 0 {
+                                if let Err(e) = session.autosave(&mut state) {
+                                    state.log(format!("Autosave failed: {}", e));
+                                }
+                            }
+                        }
+
                         // Send game state update to satellite terminals
                         let adaptations: Vec<String> = state
                             .adaptations
@@ -650,12 +480,12 @@ fn run_main_game() -> Result<()> {
                         ]
                         .iter()
                         .filter_map(|(slot, item)| {
-                            item.as_ref().map(|i| format!("{}: {}", slot, i))
+                            item.as_ref().map(|i| format!("{}: {}", slot, i.name()))
                         })
                         .collect();
 
                         let _ = ipc_server.send_message(IpcMessage::InventoryUpdate {
-                            items: state.inventory.clone(),
+                            items: state.inventory.iter().map(|i| i.display_name()).collect(),
                             equipped: equipped_items,
                         });
 
@@ -692,12 +522,23 @@ fn run_main_game() -> Result<()> {
                         // Handle incoming commands from debug terminal
                         while let Some(message) = ipc_server.try_recv_message() {
                             if let IpcMessage::Command { action } = message {
+                                let turn = state.turn;
+                                let applied = Action::DebugCommand(action.clone());
+                                session.history.record(&applied, &state);
                                 state.debug_command(&action);
+                                record_action(&mut recorder, turn, &applied, &mut state);
                             }
                         }
                     }
-                    Some(false) => break 'main, // Quit
-                    None => break,              // Return to main menu
+                    outcome => {
+                        if let Some(recorder) = &mut recorder {
+                            let _ = recorder.finish(&state);
+                        }
+                        match outcome {
+                            Some(false) => break 'main, // Quit
+                            _ => break,                 // Return to main menu
+                        }
+                    }
                 }
             }
         }
@@ -707,3 +548,77 @@ fn run_main_game() -> Result<()> {
     stdout().execute(LeaveAlternateScreen)?;
     Ok(())
 }
+
+fn run_replay(path: &Path, headless: bool) -> Result<()> {
+    let to_io = |e: String| std::io::Error::new(std::io::ErrorKind::InvalidData, e);
+    let replay = Replay::load(path).map_err(to_io)?;
+    let mut state = replay.initial_state(path).map_err(to_io)?;
+    let mut ui = UiState::new();
+
+    let outcome = if headless {
+        replay.play(&mut state, &mut ui, |_, _| Result::Ok(true))?
+    } else {
+        stdout().execute(EnterAlternateScreen)?;
+        enable_raw_mode()?;
+        let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
+        let mut renderer = Renderer::new().map_err(|e| to_io(e.to_string()))?;
+        ui.camera_x = state.player_x as f32;
+        ui.camera_y = state.player_y as f32;
+
+        let outcome = replay.play(&mut state, &mut ui, |state, ui| -> Result<bool> {
+            ui.tick_frame();
+            state.tick_animation();
+            ui.update_camera(state.player_x, state.player_y);
+            terminal.draw(|frame| render(frame, state, ui, &mut renderer))?;
+            // Esc stops the replay early
+            if event::poll(Duration::from_millis(50))?
+                && let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+                && key.code == KeyCode::Esc
+            {
+                return Ok(false);
+            }
+            Ok(true)
+        })?;
+
+        // Hold the final frame until a key is pressed
+        state.log("Replay finished. Press any key to exit.");
+        loop {
+            terminal.draw(|frame| render(frame, &state, &mut ui, &mut renderer))?;
+            if event::poll(Duration::from_millis(16))?
+                && let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+            {
+                break;
+            }
+        }
+        disable_raw_mode()?;
+        stdout().execute(LeaveAlternateScreen)?;
+        outcome
+    };
+
+    match outcome {
+        ReplayOutcome::Completed => {
+            println!(
+                "Replayed {} steps to turn {}; {} checkpoints matched",
+                replay.steps.len(),
+                state.turn,
+                replay.checkpoints.len()
+            );
+            Ok(())
+        }
+        ReplayOutcome::Stopped(steps) => {
+            println!(
+                "Replay stopped after {} of {} steps (turn {})",
+                steps,
+                replay.steps.len(),
+                state.turn
+            );
+            Ok(())
+        }
+        ReplayOutcome::Diverged(divergence) => {
+            eprintln!("{}", divergence);
+            std::process::exit(1);
+        }
+    }
+}
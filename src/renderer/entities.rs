@@ -2,6 +2,7 @@
 
 use super::config::{RenderConfig, parse_color};
 use crate::GameState;
+use crate::game::EntityId;
 use ratatui::prelude::*;
 
 /// Handles rendering of all entities (player, enemies, NPCs, items)
@@ -49,12 +50,12 @@ impl EntityRenderer {
         }
 
         // Render enemies
-        for (pos, &enemy_idx) in &state.enemy_positions {
+        for (pos, &enemy_id) in &state.enemy_positions {
             if let Some((screen_x, screen_y)) =
                 self.world_to_screen(pos.0, pos.1, cam_x, cam_y, view_width, view_height)
             {
                 if let Some(span) =
-                    self.render_enemy(state, enemy_idx, pos.0, pos.1, light_map, frame_count)
+                    self.render_enemy(state, enemy_id, pos.0, pos.1, light_map, frame_count)
                 {
                     entity_spans[screen_y as usize][screen_x as usize] = Some(span);
                 }
@@ -62,12 +63,12 @@ impl EntityRenderer {
         }
 
         // Render NPCs
-        for (pos, &npc_idx) in &state.npc_positions {
+        for (pos, &npc_id) in &state.npc_positions {
             if let Some((screen_x, screen_y)) =
                 self.world_to_screen(pos.0, pos.1, cam_x, cam_y, view_width, view_height)
             {
                 if let Some(span) =
-                    self.render_npc(state, npc_idx, pos.0, pos.1, light_map, frame_count)
+                    self.render_npc(state, npc_id, pos.0, pos.1, light_map, frame_count)
                 {
                     entity_spans[screen_y as usize][screen_x as usize] = Some(span);
                 }
@@ -75,12 +76,12 @@ impl EntityRenderer {
         }
 
         // Render items
-        for (pos, item_indices) in &state.item_positions {
+        for (pos, item_ids) in &state.item_positions {
             if let Some((screen_x, screen_y)) =
                 self.world_to_screen(pos.0, pos.1, cam_x, cam_y, view_width, view_height)
             {
                 if let Some(span) =
-                    self.render_item(state, item_indices[0], pos.0, pos.1, light_map, frame_count)
+                    self.render_item(state, item_ids[0], pos.0, pos.1, light_map, frame_count)
                 {
                     entity_spans[screen_y as usize][screen_x as usize] = Some(span);
                 }
@@ -161,82 +162,9 @@ impl EntityRenderer {
         }
 
         // Apply adaptation visual effects
-        for adaptation in &state.adaptations {
-            match adaptation.name() {
-                "Prismhide" => {
-                    // Crystalline shimmer effect
-                    let shimmer_phase =
-                        ((frame_count / 6) + (state.player_x as u64 ^ state.player_y as u64)) % 3;
-                    let color = match shimmer_phase {
-                        0 => Color::Cyan,
-                        1 => Color::LightCyan,
-                        _ => Color::White,
-                    };
-                    style = style.fg(color);
-                }
-                "Sunveins" => {
-                    // Pulsing inner light
-                    let pulse_phase = (frame_count / 4) % 4;
-                    if pulse_phase < 2 {
-                        style = style.fg(Color::Yellow);
-                    }
-                }
-                "Mirage Step" => {
-                    // Flickering/fading effect
-                    let fade_phase = (frame_count / 8) % 8;
-                    if fade_phase < 4 {
-                        style = style.fg(Color::LightBlue);
-                    }
-                }
-                "Saltblood" => {
-                    // Subtle white glow
-                    style = style.fg(Color::White);
-                }
-                "Quantum Entanglement" => {
-                    // Rainbow psychic aura
-                    let rainbow_phase = (frame_count / 5) % 4;
-                    let color = match rainbow_phase {
-                        0 => Color::Magenta,
-                        1 => Color::Cyan,
-                        2 => Color::Yellow,
-                        _ => Color::Green,
-                    };
-                    style = style.fg(color);
-                }
-                "Phase Walking" => {
-                    // Drifting translucent effect
-                    let drift_phase = ((frame_count / 7)
-                        + (state.player_x as u64 * 3 + state.player_y as u64 * 7))
-                        % 10;
-                    if drift_phase < 3 {
-                        style = style.fg(Color::LightMagenta);
-                    }
-                }
-                "Storm Affinity" => {
-                    // Storm-like wave effect
-                    let wave_phase =
-                        ((frame_count / 3) + (state.player_x as u64 + state.player_y as u64)) % 6;
-                    if wave_phase < 3 {
-                        style = style.fg(Color::LightCyan);
-                    }
-                }
-                "Crystalline Consciousness" => {
-                    // Complex multi-effect for transcendent adaptation
-                    let rainbow_phase = (frame_count / 2) % 4;
-                    let pulse_phase = (frame_count / 3) % 4;
-                    let color = match rainbow_phase {
-                        0 => Color::White,
-                        1 => Color::LightCyan,
-                        2 => Color::LightMagenta,
-                        _ => Color::LightYellow,
-                    };
-                    if pulse_phase < 2 {
-                        style = style.fg(color);
-                    } else {
-                        style = style.fg(Color::White);
-                    }
-                }
-                _ => {} // No visual effect for other adaptations
+        for effect in state.get_adaptation_visual_effects() {
+            if let Some(color) = effect.color_at(frame_count, state.player_x, state.player_y) {
+                style = style.fg(color);
             }
         }
 
@@ -271,7 +199,7 @@ impl EntityRenderer {
     fn render_enemy(
         &self,
         state: &GameState,
-        enemy_idx: usize,
+        enemy_id: EntityId,
         x: i32,
         y: i32,
         light_map: &[u8],
@@ -282,7 +210,7 @@ impl EntityRenderer {
             return None;
         }
 
-        let enemy = &state.enemies[enemy_idx];
+        let enemy = state.enemy(enemy_id)?;
         let light_level = self.get_light_level(x, y, light_map, state.map.width, state.map.height);
 
         // Apply hit flash
@@ -324,7 +252,7 @@ impl EntityRenderer {
     fn render_npc(
         &self,
         state: &GameState,
-        npc_idx: usize,
+        npc_id: EntityId,
         x: i32,
         y: i32,
         light_map: &[u8],
@@ -335,7 +263,7 @@ impl EntityRenderer {
             return None;
         }
 
-        let npc = &state.npcs[npc_idx];
+        let npc = state.npc(npc_id)?;
         let light_level = self.get_light_level(x, y, light_map, state.map.width, state.map.height);
         let base_color = parse_color(&self.config.colors.entities.npcs.base);
         let style = Style::default()
@@ -349,7 +277,7 @@ impl EntityRenderer {
     fn render_item(
         &self,
         state: &GameState,
-        item_idx: usize,
+        item_id: EntityId,
         x: i32,
         y: i32,
         light_map: &[u8],
@@ -360,7 +288,7 @@ impl EntityRenderer {
             return None;
         }
 
-        let item = &state.items[item_idx];
+        let item = state.item(item_id)?;
         let light_level = self.get_light_level(x, y, light_map, state.map.width, state.map.height);
         let base_color = parse_color(&self.config.colors.entities.items.base);
         let style = Style::default().fg(self.dim_color(base_color, light_level));
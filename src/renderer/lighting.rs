@@ -55,7 +55,7 @@ impl LightingRenderer {
         if self.config.lighting.equipment_light_detection {
             // Equipment is a struct, not a collection, so we need to check individual fields
             if let Some(ref weapon) = state.equipment.weapon {
-                if let Some(light_radius) = self.get_item_light_radius(weapon) {
+                if let Some(light_radius) = self.get_item_light_radius(&weapon.id) {
                     light_sources.push(LightSource {
                         x: state.player_x,
                         y: state.player_y,
@@ -65,7 +65,7 @@ impl LightingRenderer {
                 }
             }
             if let Some(ref ranged_weapon) = state.equipment.ranged_weapon {
-                if let Some(light_radius) = self.get_item_light_radius(ranged_weapon) {
+                if let Some(light_radius) = self.get_item_light_radius(&ranged_weapon.id) {
                     light_sources.push(LightSource {
                         x: state.player_x,
                         y: state.player_y,
@@ -31,6 +31,7 @@ use self::{
     tiles::TileRenderer,
 };
 use crate::GameState;
+use crate::ui::UiState;
 use ratatui::{
     prelude::*,
     widgets::{Block, Borders, Paragraph},
@@ -85,16 +86,19 @@ impl Renderer {
         })
     }
 
-    /// Render the main game view
-    pub fn render_game(
-        &mut self,
-        frame: &mut Frame,
-        area: Rect,
-        state: &GameState,
-        frame_count: u64,
-        look_cursor: Option<(i32, i32)>,
-        pause_particles: bool,
-    ) {
+    /// Render the main game view, with the look or throw cursor and the
+    /// tiles a throw would reach
+    pub fn render_game(&mut self, frame: &mut Frame, area: Rect, state: &GameState, ui: &UiState) {
+        let frame_count = ui.frame_count;
+        let look_cursor = if ui.look_mode.active {
+            Some((ui.look_mode.x, ui.look_mode.y))
+        } else if ui.throw_mode.active {
+            Some((ui.throw_mode.x, ui.throw_mode.y))
+        } else {
+            None
+        };
+        // Particles stand still while the debug console is open
+        let pause_particles = ui.debug_console.active;
         let title = Line::from(format!(" Turn {} ", state.turn));
         let block = Block::default().title(title).borders(Borders::ALL);
         let inner = block.inner(area);
@@ -206,6 +210,19 @@ impl Renderer {
             }
         }
 
+        // Tint the tiles a throw would reach
+        for &(hx, hy) in &ui.throw_mode.blast {
+            let (Ok(sx), Ok(sy)) = (
+                usize::try_from(hx - adjusted_cam_x),
+                usize::try_from(hy - adjusted_cam_y),
+            ) else {
+                continue;
+            };
+            if let Some(span) = final_spans.get_mut(sy).and_then(|row| row.get_mut(sx)) {
+                span.style = span.style.bg(Color::Red);
+            }
+        }
+
         // Apply look cursor highlighting
         let final_spans = if let Some((lx, ly)) = look_cursor {
             let screen_x = lx - adjusted_cam_x;
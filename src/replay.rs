@@ -0,0 +1,792 @@
+//! Input recording and deterministic replay
+//!
+//! `GameState` draws all randomness from its seeded RNG, so a session is
+//! reproducible from its starting state plus the stream of `ui::Action`s
+//! applied to it. A `Recorder` writes that stream to a RON replay file along
+//! with `GameState::state_hash` checkpoints; replaying the file re-applies the
+//! actions and compares hashes at each checkpoint to catch divergence.
+//!
+//! A recording covers play since the game was started or last loaded. New
+//! games are replayed from their seed and class; loaded games store the
+//! starting state in a binary save next to the replay file.
+//!
+//! Independently of recording, an `ActionHistory` keeps the last few actions
+//! of every session so an issue report can include them as a DES scenario.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::des::{self, EntitySpawn, EntityType, LogQuery, PlayerSetup, ScheduledAction};
+use crate::game::save::{decode_bytes, encode_binary};
+use crate::game::state::GameState;
+use crate::ui::{Action, UiState, apply_action, apply_pending};
+
+/// Current replay file version
+pub const REPLAY_FORMAT_VERSION: u32 = 1;
+
+/// Steps between state hash checkpoints. The file is also rewritten at each
+/// checkpoint, so a crash loses at most this many actions.
+pub const CHECKPOINT_INTERVAL: usize = 25;
+
+/// Actions kept by `ActionHistory` for issue reports
+pub const HISTORY_LENGTH: usize = 50;
+
+/// How the state a replay starts from is rebuilt
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ReplayStart {
+    /// `GameState::new_with_class(seed, class_id)`
+    NewGame { seed: u64, class_id: String },
+    /// A save file, relative to the replay file's directory
+    Save(PathBuf),
+}
+
+/// One applied action and the turn it was issued on
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReplayStep {
+    pub turn: u32,
+    pub action: Action,
+}
+
+/// State hash after `step` actions had been applied
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub step: usize,
+    pub turn: u32,
+    pub hash: u64,
+}
+
+/// A recorded session
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub format_version: u32,
+    /// Crate version of the build that recorded the session
+    pub game_version: String,
+    pub start: ReplayStart,
+    /// Hash of the starting state
+    pub start_hash: u64,
+    pub steps: Vec<ReplayStep>,
+    pub checkpoints: Vec<Checkpoint>,
+}
+
+/// First checkpoint where a replayed state did not match the recording
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    /// Number of steps applied when the hashes were compared
+    pub step: usize,
+    pub turn: u32,
+    pub expected: u64,
+    pub actual: u64,
+}
+
+impl std::fmt::Display for Divergence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "replay diverged after step {} (turn {}): expected state hash {:016x}, got {:016x}",
+            self.step, self.turn, self.expected, self.actual
+        )
+    }
+}
+
+/// How a replay run ended
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplayOutcome {
+    /// Every step was applied and every checkpoint matched
+    Completed,
+    /// Stopped by the viewer after this many steps
+    Stopped(usize),
+    Diverged(Divergence),
+}
+
+impl Replay {
+    fn new(start: ReplayStart, state: &GameState) -> Self {
+        Self {
+            format_version: REPLAY_FORMAT_VERSION,
+            game_version: env!("CARGO_PKG_VERSION").to_string(),
+            start,
+            start_hash: state.state_hash(),
+            steps: Vec::new(),
+            checkpoints: Vec::new(),
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let data = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let replay: Replay = ron::from_str(&data).map_err(|e| e.to_string())?;
+        if replay.format_version > REPLAY_FORMAT_VERSION {
+            return Err(format!(
+                "Replay format v{} was written by a newer game ({}); this build reads up to v{}",
+                replay.format_version, replay.game_version, REPLAY_FORMAT_VERSION
+            ));
+        }
+        Ok(replay)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let data = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| e.to_string())?;
+        fs::write(path, data).map_err(|e| e.to_string())
+    }
+
+    /// Rebuild the state the recording started from. `replay_path` locates
+    /// start saves, which are stored next to the replay file.
+    pub fn initial_state(&self, replay_path: &Path) -> Result<GameState, String> {
+        let state = match &self.start {
+            ReplayStart::NewGame { seed, class_id } => GameState::new_with_class(*seed, class_id),
+            ReplayStart::Save(file) => {
+                let path = replay_path
+                    .parent()
+                    .unwrap_or_else(|| Path::new(""))
+                    .join(file);
+                let data = fs::read(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+                let (_, mut state) = decode_bytes(&data)?;
+                state.rebuild_spatial_index();
+                state.update_lighting();
+                state
+            }
+        };
+        self.verify(0, &state).map_err(|d| d.to_string())?;
+        Ok(state)
+    }
+
+    /// Compare a state against the checkpoint recorded after `step` actions,
+    /// if there is one. Step 0 is checked against the starting state.
+    pub fn verify(&self, step: usize, state: &GameState) -> Result<(), Divergence> {
+        let expected = if step == 0 {
+            Some(self.start_hash)
+        } else {
+            self.checkpoints
+                .iter()
+                .find(|c| c.step == step)
+                .map(|c| c.hash)
+        };
+        match expected {
+            Some(expected) => {
+                let actual = state.state_hash();
+                if actual == expected {
+                    Ok(())
+                } else {
+                    Err(Divergence {
+                        step,
+                        turn: state.turn,
+                        expected,
+                        actual,
+                    })
+                }
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Apply the recorded steps to `state`, which starts as
+    /// `initial_state`, through the same dispatch as the live game, checking
+    /// the state hash at every checkpoint. `after_step` runs after each step
+    /// and returns false to stop early.
+    pub fn play<E>(
+        &self,
+        state: &mut GameState,
+        ui: &mut UiState,
+        mut after_step: impl FnMut(&mut GameState, &mut UiState) -> Result<bool, E>,
+    ) -> Result<ReplayOutcome, E> {
+        for (i, step) in self.steps.iter().enumerate() {
+            apply_pending(state, ui);
+            // Dialogue is dismissed by keys that are not recorded; close it so a
+            // trade it was holding back opens as it did in the recorded session
+            if ui.dialog_box.active {
+                ui.dialog_box.close();
+                apply_pending(state, ui);
+            }
+            // Saving and loading touch the disk, not the game, so only their
+            // checkpoints are checked
+            if !matches!(step.action, Action::Save | Action::Load) {
+                apply_action(state, step.action.clone(), ui, None);
+            }
+            if let Err(divergence) = self.verify(i + 1, state) {
+                return Ok(ReplayOutcome::Diverged(divergence));
+            }
+            if !after_step(state, ui)? {
+                return Ok(ReplayOutcome::Stopped(i + 1));
+            }
+        }
+        Ok(ReplayOutcome::Completed)
+    }
+}
+
+/// Writes a replay file as actions are applied
+pub struct Recorder {
+    path: PathBuf,
+    replay: Replay,
+}
+
+impl Recorder {
+    /// Record a game just created with `GameState::new_with_class`
+    pub fn new_game(path: impl Into<PathBuf>, state: &GameState) -> Result<Self, String> {
+        let start = ReplayStart::NewGame {
+            seed: state.seed,
+            class_id: state.class_id.clone(),
+        };
+        Self::start(path.into(), start, state)
+    }
+
+    /// Record from an arbitrary state, such as one just loaded from a save.
+    /// The state is written next to the replay file as `<name>.start.sgsb`.
+    pub fn from_state(path: impl Into<PathBuf>, state: &GameState) -> Result<Self, String> {
+        let path = path.into();
+        let stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "replay".to_string());
+        let start_file = PathBuf::from(format!("{}.start.sgsb", stem));
+        let start_path = path
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .join(&start_file);
+        fs::write(&start_path, encode_binary(state)?)
+            .map_err(|e| format!("{}: {}", start_path.display(), e))?;
+        // Hash the state as it will be loaded back, with the RNG reset to its seed
+        let (_, reloaded) = decode_bytes(&fs::read(&start_path).map_err(|e| e.to_string())?)?;
+        Self::start(path, ReplayStart::Save(start_file), &reloaded)
+    }
+
+    fn start(path: PathBuf, start: ReplayStart, state: &GameState) -> Result<Self, String> {
+        let recorder = Self {
+            path,
+            replay: Replay::new(start, state),
+        };
+        recorder.flush()?;
+        Ok(recorder)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// Record an action issued on `turn`, after it has been applied to `state`.
+    /// Actions that never reach the game (`None`, quitting) are ignored.
+    pub fn record(&mut self, turn: u32, action: &Action, state: &GameState) -> Result<(), String> {
+        if matches!(
+            action,
+            Action::None | Action::Quit | Action::ReturnToMainMenu
+        ) {
+            return Ok(());
+        }
+        self.replay.steps.push(ReplayStep {
+            turn,
+            action: action.clone(),
+        });
+        if self.replay.steps.len().is_multiple_of(CHECKPOINT_INTERVAL) {
+            self.checkpoint(state);
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Write a final checkpoint and the complete recording
+    pub fn finish(&mut self, state: &GameState) -> Result<(), String> {
+        self.checkpoint(state);
+        self.flush()
+    }
+
+    fn checkpoint(&mut self, state: &GameState) {
+        let step = self.replay.steps.len();
+        if self
+            .replay
+            .checkpoints
+            .last()
+            .is_some_and(|c| c.step == step)
+        {
+            return;
+        }
+        self.replay.checkpoints.push(Checkpoint {
+            step,
+            turn: state.turn,
+            hash: state.state_hash(),
+        });
+    }
+
+    fn flush(&self) -> Result<(), String> {
+        self.replay.save(&self.path)
+    }
+}
+
+/// Player and enemy setup captured just before an action
+struct HistoryEntry {
+    action: des::Action,
+    player: PlayerSetup,
+    enemies: Vec<EntitySpawn>,
+}
+
+/// Rolling window of the most recent gameplay actions, each with a snapshot
+/// of the state it was issued from, exportable as a DES scenario.
+///
+/// DES scenarios start from a fresh game of the same seed, so the export
+/// travels to the world tile and depth the player was on and reproduces the
+/// player and enemies there, but not map changes made before the window
+/// began.
+pub struct ActionHistory {
+    capacity: usize,
+    entries: VecDeque<HistoryEntry>,
+}
+
+impl Default for ActionHistory {
+    fn default() -> Self {
+        Self::new(HISTORY_LENGTH)
+    }
+}
+
+impl ActionHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Note `action` before it is applied to `state`. Menu navigation and
+    /// other actions that leave the game untouched are ignored; loading a
+    /// save starts a new history.
+    pub fn record(&mut self, action: &Action, state: &GameState) {
+        if *action == Action::Load {
+            self.clear();
+            return;
+        }
+        let Some(action) = des_action(action, state) else {
+            return;
+        };
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(HistoryEntry {
+            action,
+            player: player_setup(state),
+            enemies: enemy_spawns(state),
+        });
+    }
+
+    /// Build a scenario that sets up the player as they were before the
+    /// oldest kept action and then replays each action on its own turn.
+    pub fn to_scenario(&self, name: &str, seed: u64) -> Option<des::Scenario> {
+        let first = self.entries.front()?;
+        Some(des::Scenario {
+            name: name.to_string(),
+            seed: Some(seed),
+            mocks: des::MockSettings::default(),
+            entities: first.enemies.clone(),
+            player: first.player.clone(),
+            actions: self
+                .entries
+                .iter()
+                .enumerate()
+                .map(|(turn, entry)| ScheduledAction {
+                    turn: turn as u32,
+                    action: entry.action.clone(),
+                    actor: des::Actor::Player,
+                })
+                .collect(),
+            assertions: Vec::new(),
+            base: None,
+            variables: HashMap::new(),
+            map_setup: des::MapSetup::default(),
+            disabled_systems: Vec::new(),
+        })
+    }
+}
+
+fn player_setup(state: &GameState) -> PlayerSetup {
+    PlayerSetup {
+        world_x: Some(state.world_x),
+        world_y: Some(state.world_y),
+        layer: Some(state.layer),
+        x: Some(state.player_x),
+        y: Some(state.player_y),
+        hp: Some(state.player_hp),
+        max_hp: Some(state.player_max_hp),
+        ap: Some(state.player_ap),
+        max_ap: Some(state.player_max_ap),
+        xp: Some(state.player_xp),
+        inventory: state.inventory.clone(),
+        adaptations: state
+            .adaptations
+            .iter()
+            .map(|a| a.id().to_string())
+            .collect(),
+        equipped_weapon: state.equipment.weapon.as_ref().map(|w| w.id.clone()),
+    }
+}
+
+fn enemy_spawns(state: &GameState) -> Vec<EntitySpawn> {
+    state
+        .enemies
+        .iter()
+        .filter(|e| e.hp > 0)
+        .map(|e| EntitySpawn {
+            entity_type: EntityType::Enemy,
+            id: e.id().to_string(),
+            name: None,
+            x: e.x,
+            y: e.y,
+            hp: Some(e.hp),
+            ai_disabled: e.ai_disabled,
+            inventory: e.inventory.clone(),
+            properties: HashMap::new(),
+        })
+        .collect()
+}
+
+/// The DES equivalent of a UI action, or `None` if it never touches the game.
+/// Actions DES has no counterpart for become log entries so the gap shows up
+/// in the scenario.
+fn des_action(action: &Action, state: &GameState) -> Option<des::Action> {
+    let action = match action {
+        Action::Move(dx, dy) => des::Action::Move { dx: *dx, dy: *dy },
+        Action::Wait => des::Action::Wait { turns: 1 },
+        Action::EndTurn => des::Action::EndTurn,
+        Action::AutoExplore => des::Action::AutoExplore,
+        Action::ToggleSneak => des::Action::ToggleSneak,
+        Action::UseItem(item_index) => des::Action::UseItem {
+            item_index: *item_index,
+        },
+        Action::ThrowItem(item_index, x, y) => des::Action::ThrowItem {
+            item_index: *item_index,
+            x: *x,
+            y: *y,
+        },
+        Action::RangedAttack(x, y) => des::Action::RangedAttack {
+            target_x: *x,
+            target_y: *y,
+        },
+        Action::Equip(item_index) => {
+            let slot = state
+                .inventory
+                .get(*item_index)
+                .and_then(|item| item.def())
+                .and_then(|def| def.equip_slot.clone())?;
+            des::Action::Equip {
+                item_index: *item_index,
+                slot,
+            }
+        }
+        Action::Unequip(slot) => des::Action::Unequip {
+            slot: slot.id().to_string(),
+        },
+        Action::Craft(recipe_id) => des::Action::Craft {
+            recipe_id: recipe_id.clone(),
+        },
+        Action::UsePsychicAbility(ability_id) => des::Action::UseAbility {
+            ability_id: ability_id.clone(),
+        },
+        // The game applies these where the player stands
+        Action::Interact(_, _) => des::Action::Interact {
+            target_x: state.player_x,
+            target_y: state.player_y,
+        },
+        Action::Examine(_, _) => des::Action::Examine {
+            target_x: state.player_x,
+            target_y: state.player_y,
+        },
+        Action::BreakWall(..)
+        | Action::UseStairs
+        | Action::WorldMapTravel(..)
+        | Action::UpgradeSkill(_)
+        | Action::UseAbility(_)
+        | Action::TradeBuy(_)
+        | Action::TradeSell(_)
+        | Action::OpenChest(_)
+        | Action::TakeFromChest(..)
+        | Action::StoreInChest(..)
+        | Action::DebugCommand(_) => des::Action::Log {
+            query: LogQuery::Custom {
+                message: format!("Not reproducible in DES: {:?}", action),
+            },
+        },
+        _ => return None,
+    };
+    Some(action)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Apply an action as the live game does, without a save session
+    fn apply(state: &mut GameState, action: &Action) {
+        apply_action(state, action.clone(), &mut UiState::new(), None);
+    }
+
+    fn play(recorder: &mut Recorder, state: &mut GameState, actions: &[Action]) {
+        let mut ui = UiState::new();
+        for action in actions {
+            let turn = state.turn;
+            apply_pending(state, &mut ui);
+            apply_action(state, action.clone(), &mut ui, None);
+            recorder.record(turn, action, state).unwrap();
+        }
+        recorder.finish(state).unwrap();
+    }
+
+    /// Replay to the end, returning how it went
+    fn replay_all(replay: &Replay, state: &mut GameState) -> ReplayOutcome {
+        let mut ui = UiState::new();
+        replay
+            .play(state, &mut ui, |_, _| Ok::<_, ()>(true))
+            .unwrap()
+    }
+
+    fn session() -> Vec<Action> {
+        let moves = [
+            Action::Move(1, 0),
+            Action::Move(0, 1),
+            Action::Wait,
+            Action::Move(-1, 0),
+            Action::None,
+        ];
+        moves.iter().cycle().take(60).cloned().collect()
+    }
+
+    #[test]
+    fn recorded_new_game_replays_identically() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.replay.ron");
+        let mut state = GameState::new_with_class(42, "scavenger");
+        let mut recorder = Recorder::new_game(&path, &state).unwrap();
+        play(&mut recorder, &mut state, &session());
+
+        let replay = Replay::load(&path).unwrap();
+        assert_eq!(replay.steps.len(), 48);
+        assert_eq!(replay.checkpoints.len(), 2);
+        assert_eq!(replay.checkpoints[1].hash, state.state_hash());
+
+        let mut replayed = replay.initial_state(&path).unwrap();
+        let mut ui = UiState::new();
+        let mut next = 1;
+        let outcome = replay
+            .play(&mut replayed, &mut ui, |state, _| {
+                // Each step is applied on the turn it was recorded on
+                if let Some(step) = replay.steps.get(next) {
+                    assert_eq!(step.turn, state.turn);
+                }
+                next += 1;
+                Ok::<_, ()>(true)
+            })
+            .unwrap();
+        assert_eq!(outcome, ReplayOutcome::Completed);
+        assert_eq!(replayed.state_hash(), state.state_hash());
+    }
+
+    #[test]
+    fn loaded_game_replays_from_start_save() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("loaded.replay.ron");
+        let mut state = GameState::new(7);
+        state.wait_turn();
+        let mut recorder = Recorder::from_state(&path, &state).unwrap();
+        assert!(dir.path().join("loaded.replay.start.sgsb").exists());
+
+        // The live game plays on from the state as loaded
+        let mut live = recorder.replay().initial_state(&path).unwrap();
+        play(&mut recorder, &mut live, &session());
+
+        let replay = Replay::load(&path).unwrap();
+        let mut replayed = replay.initial_state(&path).unwrap();
+        assert_eq!(replay_all(&replay, &mut replayed), ReplayOutcome::Completed);
+    }
+
+    #[test]
+    fn replay_applies_every_action_like_the_live_game() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("items.replay.ron");
+        let mut state = GameState::new(42);
+        state.player_hp = 10;
+        state.give_item(crate::game::ItemInstance::new("brine_vial"));
+        let mut recorder = Recorder::from_state(&path, &state).unwrap();
+        let mut live = recorder.replay().initial_state(&path).unwrap();
+        let actions = [
+            Action::OpenInventory,
+            Action::UseItem(0),
+            Action::ToggleSneak,
+            Action::EndTurn,
+        ];
+        play(&mut recorder, &mut live, &actions);
+        assert!(live.sneaking);
+        assert!(!live.has_item("brine_vial"));
+
+        let replay = Replay::load(&path).unwrap();
+        let mut replayed = replay.initial_state(&path).unwrap();
+        assert_eq!(replay_all(&replay, &mut replayed), ReplayOutcome::Completed);
+        assert_eq!(replayed.state_hash(), live.state_hash());
+    }
+
+    #[test]
+    fn divergence_is_reported_at_checkpoint() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.replay.ron");
+        let mut state = GameState::new(42);
+        let mut recorder = Recorder::new_game(&path, &state).unwrap();
+        play(&mut recorder, &mut state, &session());
+
+        let replay = Replay::load(&path).unwrap();
+        let mut replayed = replay.initial_state(&path).unwrap();
+        replayed.salt_scrip += 1;
+        match replay_all(&replay, &mut replayed) {
+            ReplayOutcome::Diverged(divergence) => {
+                assert_eq!(divergence.step, CHECKPOINT_INTERVAL)
+            }
+            outcome => panic!("tampered state should diverge, got {:?}", outcome),
+        }
+    }
+
+    #[test]
+    fn checkpoints_after_saving_are_checked() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("saved.replay.ron");
+        let mut state = GameState::new(42);
+        let mut recorder = Recorder::new_game(&path, &state).unwrap();
+        play(&mut recorder, &mut state, &[Action::Wait, Action::Save]);
+
+        let replay = Replay::load(&path).unwrap();
+        assert_eq!(replay.checkpoints.last().unwrap().step, 2);
+        let mut replayed = replay.initial_state(&path).unwrap();
+        replayed.salt_scrip += 1;
+        match replay_all(&replay, &mut replayed) {
+            ReplayOutcome::Diverged(divergence) => assert_eq!(divergence.step, 2),
+            outcome => panic!("tampered state should diverge, got {:?}", outcome),
+        }
+    }
+
+    #[test]
+    fn history_keeps_recent_actions_and_setup_before_them() {
+        let mut state = GameState::new(42);
+        let mut history = ActionHistory::new(3);
+        let mut before = Vec::new();
+        for action in session().iter().take(10) {
+            if *action != Action::None {
+                before.push((state.player_x, state.player_y));
+            }
+            history.record(action, &state);
+            apply(&mut state, action);
+        }
+        assert_eq!(history.len(), 3);
+
+        let scenario = history.to_scenario("history", state.seed).unwrap();
+        let (x, y) = before[before.len() - 3];
+        assert_eq!((scenario.player.x, scenario.player.y), (Some(x), Some(y)));
+        assert!(matches!(
+            scenario.actions[0].action,
+            des::Action::Move { dx: 0, dy: 1 }
+        ));
+        assert!(matches!(
+            scenario.actions[1].action,
+            des::Action::Wait { turns: 1 }
+        ));
+        assert_eq!(scenario.actions[2].turn, 2);
+
+        history.record(&Action::Load, &state);
+        assert!(history.to_scenario("empty", state.seed).is_none());
+    }
+
+    #[test]
+    fn exported_history_starts_where_the_player_was() {
+        let mut state = GameState::new(42);
+        let (wx, wy) = (state.world_x, state.world_y);
+        state.travel_to_tile(wx + 1, wy);
+        let stairs = state.map.idx(state.player_x, state.player_y);
+        state.map.tiles[stairs] = crate::game::map::Tile::StairsDown;
+        assert!(state.enter_subterranean());
+
+        let mut history = ActionHistory::default();
+        for _ in 0..3 {
+            history.record(&Action::Wait, &state);
+            apply(&mut state, &Action::Wait);
+        }
+        let scenario = history.to_scenario("underground", state.seed).unwrap();
+        assert_eq!(
+            (
+                scenario.player.world_x,
+                scenario.player.world_y,
+                scenario.player.layer
+            ),
+            (Some(wx + 1), Some(wy), Some(-1))
+        );
+
+        let result = des::DesExecutor::new(&scenario).run(&scenario);
+        let replayed = result.final_state.unwrap();
+        assert_eq!(
+            (replayed.world_x, replayed.world_y, replayed.layer),
+            (wx + 1, wy, -1)
+        );
+        assert_eq!(
+            (replayed.player_x, replayed.player_y),
+            (state.player_x, state.player_y)
+        );
+        let idx = state.map.idx(state.player_x + 10, state.player_y + 10);
+        assert_eq!(replayed.map.tiles[idx], state.map.tiles[idx]);
+    }
+
+    #[test]
+    fn exported_history_runs_as_des_scenario() {
+        let mut state = GameState::new(42);
+        let mut history = ActionHistory::default();
+        for action in session().iter().take(12) {
+            history.record(action, &state);
+            apply(&mut state, action);
+        }
+        history.record(&Action::OpenInventory, &state);
+
+        let json = history
+            .to_scenario("exported", state.seed)
+            .unwrap()
+            .to_json()
+            .unwrap();
+        let scenario = des::Scenario::from_json(&json).unwrap();
+        assert_eq!(scenario.actions.len(), 10);
+        let result = des::DesExecutor::new(&scenario).run(&scenario);
+        let replayed = result.final_state.unwrap();
+        assert_eq!(
+            (replayed.player_x, replayed.player_y),
+            (state.player_x, state.player_y)
+        );
+    }
+
+    #[test]
+    fn sneaking_exports_to_des() {
+        let state = GameState::new(42);
+        let action = des_action(&Action::ToggleSneak, &state);
+        assert!(matches!(action, Some(des::Action::ToggleSneak)));
+    }
+
+    #[test]
+    fn thrown_items_export_to_des() {
+        let state = GameState::new(42);
+        let action = des_action(&Action::ThrowItem(1, 3, 4), &state);
+        assert!(matches!(
+            action,
+            Some(des::Action::ThrowItem {
+                item_index: 1,
+                x: 3,
+                y: 4
+            })
+        ));
+    }
+}
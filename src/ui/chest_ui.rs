@@ -6,7 +6,7 @@ use ratatui::{
     widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
 };
 
-use crate::game::{Chest, get_chest_def};
+use crate::game::{Chest, ItemInstance, get_chest_def};
 
 pub struct ChestUI {
     pub chest_index: usize,
@@ -80,7 +80,7 @@ pub fn render_chest_ui(
     frame: &mut Frame,
     area: Rect,
     chest: &Chest,
-    player_inventory: &[String],
+    player_inventory: &[ItemInstance],
     ui_state: &mut ChestUI,
 ) {
     let chunks = Layout::default()
@@ -116,11 +116,7 @@ pub fn render_chest_ui(
         .inventory
         .iter()
         .enumerate()
-        .map(|(i, item)| {
-            let item_def = crate::game::get_item_def(&item.id);
-            let name = item_def.map(|def| def.name.as_str()).unwrap_or(&item.id);
-            ListItem::new(format!("{}. {}", i + 1, name))
-        })
+        .map(|(i, item)| ListItem::new(format!("{}. {}", i + 1, item.display_name())))
         .collect();
 
     let chest_title = format!(
@@ -146,11 +142,7 @@ pub fn render_chest_ui(
     let inventory_items: Vec<ListItem> = player_inventory
         .iter()
         .enumerate()
-        .map(|(i, item_id)| {
-            let item_def = crate::game::get_item_def(item_id);
-            let name = item_def.map(|def| def.name.as_str()).unwrap_or(item_id);
-            ListItem::new(format!("{}. {}", i + 1, name))
-        })
+        .map(|(i, item)| ListItem::new(format!("{}. {}", i + 1, item.display_name())))
         .collect();
 
     let inventory_style = if ui_state.selected_panel == ChestPanel::PlayerInventory {
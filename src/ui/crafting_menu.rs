@@ -1,6 +1,6 @@
 //! Crafting menu UI
 
-use crate::game::{GameState, all_recipe_ids, can_craft, get_item_def, get_recipe};
+use crate::game::{GameState, all_recipe_ids, can_craft, count_items, get_item_def, get_recipe};
 use ratatui::{
     prelude::*,
     widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
@@ -96,7 +96,7 @@ pub fn render_crafting_menu(frame: &mut Frame, menu: &CraftingMenu, state: &Game
             ];
 
             for (item_id, &required) in &recipe.materials {
-                let have = state.inventory.iter().filter(|id| *id == item_id).count() as u32;
+                let have = count_items(&state.inventory, item_id);
                 let item_name = get_item_def(item_id)
                     .map(|d| d.name.as_str())
                     .unwrap_or(item_id);
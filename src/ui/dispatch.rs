@@ -0,0 +1,386 @@
+//! Applying player actions to the game
+//!
+//! The interactive game loop and replays both go through `apply_action`, so a
+//! replayed action has exactly the effect it had when it was recorded.
+
+use std::time::{Duration, Instant};
+
+use super::skills_menu::use_ability;
+use super::{Action, ChestUI, UiState};
+use crate::game::save_slots::slot_name_for;
+use crate::game::{AUTOSAVE_SLOT, EquipSlot, GameState, MsgType, SaveSlots, Tile};
+use crate::replay::ActionHistory;
+
+/// Save slot the running game writes to, playtime not yet added to the state,
+/// and the recent actions attached to issue reports
+pub struct SaveSession {
+    pub slots: SaveSlots,
+    pub slot: String,
+    pub played_since: Instant,
+    pub history: ActionHistory,
+}
+
+impl SaveSession {
+    pub fn new(slots: SaveSlots, state: &GameState, slot: &str) -> Self {
+        // Autosaves belong to a game; manual saves go back to that game's own slot
+        let slot = if slot.starts_with(AUTOSAVE_SLOT) {
+            slot_name_for(&state.class_id, state.seed)
+        } else {
+            slot.to_string()
+        };
+        Self {
+            slots,
+            slot,
+            played_since: Instant::now(),
+            history: ActionHistory::default(),
+        }
+    }
+
+    /// Move whole elapsed seconds into the state's playtime counter
+    pub fn flush_playtime(&mut self, state: &mut GameState) {
+        let secs = self.played_since.elapsed().as_secs();
+        state.playtime_secs += secs;
+        self.played_since += Duration::from_secs(secs);
+    }
+
+    pub fn save(&mut self, state: &mut GameState) -> Result<(), String> {
+        self.flush_playtime(state);
+        self.slots.save(&self.slot, state).map(|_| ())
+    }
+
+    pub fn autosave(&mut self, state: &mut GameState) -> Result<(), String> {
+        self.flush_playtime(state);
+        self.slots.autosave(state).map(|_| ())
+    }
+}
+
+/// Apply an action to the game. Returns `Some(false)` to quit and `None` to
+/// return to the main menu. Replays pass no session, so saving and loading
+/// are skipped.
+pub fn apply_action(
+    state: &mut GameState,
+    action: Action,
+    ui: &mut UiState,
+    session: Option<&mut SaveSession>,
+) -> Option<bool> {
+    match action {
+        Action::Quit => return Some(false),
+        Action::ReturnToMainMenu => return None, // Signal to return to main menu
+        Action::OpenPauseMenu => ui.pause_menu.open(),
+        Action::OpenControls => ui.show_controls = true,
+        Action::EnterLook => {
+            ui.look_mode.active = true;
+            ui.look_mode.x = state.player_x;
+            ui.look_mode.y = state.player_y;
+        }
+        Action::BreakWall(x, y) => {
+            if state.player_hp > 0 {
+                state.try_break_wall(x, y);
+            }
+        }
+        Action::Save => {
+            if let Some(session) = session {
+                match session.save(state) {
+                    Ok(_) => state.log(format!("Game saved to slot '{}'.", session.slot)),
+                    Err(e) => state.log(format!("Save failed: {}", e)),
+                }
+            }
+        }
+        Action::Load => {
+            if let Some(session) = session {
+                match session.slots.load(&session.slot) {
+                    Ok(loaded) => {
+                        *state = loaded;
+                        session.played_since = Instant::now();
+                        state.log("Game loaded.");
+                    }
+                    Err(e) => state.log(format!("Load failed: {}", e)),
+                }
+            }
+        }
+        Action::UseItem(idx) => {
+            if state.player_hp > 0 {
+                state.use_item(idx);
+                ui.inventory_menu.close();
+            }
+        }
+        Action::ThrowItem(idx, x, y) => {
+            if state.player_hp > 0 {
+                state.throw_item(idx, x, y);
+            }
+        }
+        Action::Move(dx, dy) => {
+            if state.player_hp > 0 {
+                let new_x = state.player_x + dx;
+                let new_y = state.player_y + dy;
+                if let Some(ei) = state.enemy_at(new_x, new_y) {
+                    ui.target_enemy = Some(ei);
+                }
+                state.try_move(dx, dy);
+            }
+        }
+        Action::EndTurn => {
+            if state.player_hp > 0 {
+                state.end_turn();
+            }
+        }
+        Action::Wait => {
+            if state.player_hp > 0 {
+                state.wait_turn();
+            }
+        }
+        Action::AutoExplore => {
+            if state.player_hp > 0 {
+                state.auto_explore();
+            }
+        }
+        Action::ToggleSneak => state.toggle_sneak(),
+        Action::RangedAttack(x, y) => {
+            if state.player_hp > 0 {
+                // Auto-target enemy when attacking
+                if let Some(ei) = state.enemy_at(x, y) {
+                    ui.target_enemy = Some(ei);
+                }
+                state.try_ranged_attack(x, y);
+            }
+        }
+        Action::SetTarget(x, y) => {
+            ui.target_enemy = state.enemy_at(x, y);
+        }
+        Action::UseStairs => {
+            if state.player_hp > 0 {
+                // Check what tile we're standing on
+                if let Some(tile) = state.map.get(state.player_x, state.player_y) {
+                    match tile {
+                        Tile::StairsDown => {
+                            state.enter_subterranean();
+                        }
+                        Tile::StairsUp => {
+                            state.exit_subterranean();
+                        }
+                        Tile::WorldExit => {
+                            // Simple world map travel - for now just show a message
+                            // TODO: Add proper world map UI
+                            state.log("Use arrow keys to choose direction, then press > again.");
+                        }
+                        _ => {
+                            state.log("No stairs here.");
+                        }
+                    }
+                }
+            }
+        }
+        Action::TradeBuy(idx) => {
+            if let Some(interface) = &mut ui.trade_menu.interface
+                && let Some(item) = interface.available_items.get(idx)
+            {
+                use crate::game::trading::execute_trade;
+                match execute_trade(
+                    interface,
+                    &item.item_id.clone(),
+                    1,
+                    &mut state.salt_scrip,
+                    &mut state.inventory,
+                ) {
+                    Ok(msg) => state.log_typed(msg, MsgType::Social),
+                    Err(e) => state.log(e),
+                }
+            }
+        }
+        Action::TradeSell(idx) => {
+            if let Some(interface) = &ui.trade_menu.interface
+                && let Some(item) = state.inventory.get(idx)
+            {
+                use crate::game::trading::execute_sell;
+                match execute_sell(
+                    interface,
+                    &item.id.clone(),
+                    1,
+                    &mut state.salt_scrip,
+                    &mut state.inventory,
+                ) {
+                    Ok(msg) => state.log_typed(msg, MsgType::Social),
+                    Err(e) => state.log(e),
+                }
+            }
+        }
+        Action::DebugCommand(cmd) => {
+            state.debug_command(&cmd);
+        }
+        Action::SubmitIssueReport => {
+            let mut report = state.create_issue_report(
+                ui.issue_reporter.description.clone(),
+                ui.issue_reporter.steps.clone(),
+                ui.issue_reporter.expected.clone(),
+                ui.issue_reporter.actual.clone(),
+                ui.issue_reporter.severity.clone(),
+                ui.issue_reporter.category.clone(),
+            );
+            // Attach the last few actions as a runnable DES scenario
+            let scenario = session.and_then(|s| s.history.to_scenario(&report.id, state.seed));
+            if scenario.is_some() {
+                report.scenario_file = Some(format!("{}.scenario.json", report.id));
+            }
+            match state.save_issue_report(&report) {
+                Ok(_) => {
+                    if let (Some(scenario), Some(file)) = (&scenario, &report.scenario_file)
+                        && let Err(e) = scenario.save(format!("issue_reports/{}", file))
+                    {
+                        state.log(format!("Failed to save issue scenario: {}", e));
+                    }
+                    state.log(format!("Issue report saved: {}", report.id));
+                    ui.issue_reporter.close();
+                }
+                Err(e) => state.log(format!("Failed to save issue report: {}", e)),
+            }
+        }
+        Action::OpenDebugMenu => {
+            ui.debug_menu.toggle();
+        }
+        Action::OpenIssueReporter => {
+            ui.issue_reporter.open();
+        }
+        Action::OpenInventory => {
+            ui.inventory_menu.open();
+        }
+        Action::Equip(idx) => {
+            if idx < state.inventory.len()
+                && let Some(def) = state.inventory[idx].def()
+                && let Some(slot_str) = &def.equip_slot
+                && let Ok(slot) = slot_str.parse::<EquipSlot>()
+            {
+                state.equip_item(idx, slot);
+            }
+        }
+        Action::Unequip(slot) => {
+            state.unequip_slot(slot);
+        }
+        Action::OpenQuestLog => {
+            ui.quest_log.open();
+        }
+        Action::OpenCrafting => {
+            ui.crafting_menu.open();
+        }
+        Action::OpenChest(_) => {
+            // Check if player is standing on a chest
+            if let Some(chest_idx) = state
+                .chest_positions
+                .get(&(state.player_x, state.player_y))
+                .and_then(|&id| state.chest_index(id))
+            {
+                if state.open_chest(chest_idx) {
+                    ui.chest_ui = Some(ChestUI::new(chest_idx));
+                }
+            } else {
+                state.log("No chest here.");
+            }
+        }
+        Action::TakeFromChest(chest_index, item_idx) => {
+            state.transfer_from_chest(chest_index, item_idx);
+        }
+        Action::StoreInChest(chest_index, item_idx) => {
+            state.transfer_to_chest(chest_index, item_idx);
+        }
+        Action::CloseChest => {
+            ui.chest_ui = None;
+        }
+        Action::Interact(_, _) => {
+            if state.player_hp > 0 {
+                state.interact_at(state.player_x, state.player_y);
+            }
+        }
+        Action::Examine(_, _) => {
+            if state.player_hp > 0 {
+                state.examine_at(state.player_x, state.player_y);
+            }
+        }
+        Action::OpenWiki => {
+            ui.wiki_menu.open();
+        }
+        Action::OpenPsychicMenu => {
+            ui.psychic_menu.toggle();
+        }
+        Action::OpenSkillsMenu => {
+            ui.skills_menu.open();
+        }
+        Action::UsePsychicAbility(ability_id) => {
+            state.use_psychic_ability(&ability_id);
+        }
+        Action::UpgradeSkill(skill_id) => {
+            if let Err(e) = state.skills.upgrade_skill(&skill_id) {
+                state.log(e);
+            }
+        }
+        Action::UseAbility(ability_id) => match use_ability(state, &ability_id) {
+            Ok(()) => ui.skills_menu.active = false,
+            Err(e) => state.log(e),
+        },
+        Action::RangedAttackMode => {
+            // TODO: Implement ranged attack mode
+        }
+        Action::TargetMode => {
+            // TODO: Implement targeting mode
+        }
+        Action::OpenWorldMap => {
+            ui.world_map_view.toggle(state.world_x, state.world_y);
+        }
+        Action::WorldMapTravel(wx, wy) => {
+            if state.player_hp > 0 && state.layer == 0 {
+                state.travel_to_tile_safe(wx, wy);
+            }
+        }
+        Action::Craft(recipe_id) => {
+            state.craft(&recipe_id);
+        }
+        Action::None => {}
+    }
+    Some(true)
+}
+
+/// Open dialogue, books and trades requested by the last action, and drop a
+/// dead target
+pub fn apply_pending(state: &mut GameState, ui: &mut UiState) {
+    // Check for pending dialogue from NPC interaction
+    if let Some((speaker, text)) = state.pending_dialogue.take() {
+        ui.dialog_box.show(&speaker, &text);
+    }
+
+    // Check for pending book open
+    if let Some(book_id) = state.pending_book_open.take() {
+        ui.book_reader.open(&book_id);
+    }
+
+    // Check for pending trade (only if no dialog is active)
+    if let Some(trader_id) = state.pending_trade.take() {
+        if ui.dialog_box.active {
+            // Put the trade back if dialog is still active
+            state.pending_trade = Some(trader_id);
+        } else {
+            use crate::game::trading::{calculate_area_tier, get_trade_interface};
+            let area_tier = calculate_area_tier(&state.enemies);
+            if let Some(interface) = get_trade_interface(
+                &trader_id,
+                area_tier,
+                &state.faction_reputation,
+                None, // Player faction not yet implemented
+            ) {
+                // Close other menus to ensure trade menu has focus
+                ui.inventory_menu.close();
+                ui.quest_log.close();
+                ui.crafting_menu.close();
+                ui.wiki_menu.close();
+                ui.pause_menu.close();
+                ui.trade_menu.open(trader_id, interface);
+            } else {
+                state.log("This merchant has nothing to trade.");
+            }
+        }
+    }
+
+    // Clear target if enemy is dead
+    if let Some(target) = ui.target_enemy
+        && state.enemy(target).is_none_or(|e| e.hp <= 0)
+    {
+        ui.target_enemy = None;
+    }
+}
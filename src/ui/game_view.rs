@@ -280,8 +280,10 @@ fn render_tile(
     }
 
     // Enemy
-    if let Some(&ei) = state.enemy_positions.get(&(x as i32, y as i32)) {
-        let e = &state.enemies[ei];
+    if let Some(e) = state
+        .enemy_at(x as i32, y as i32)
+        .and_then(|id| state.enemy(id))
+    {
         if visible {
             // Hit flash takes priority
             if has_flash && (frame_count % 2 == 0) {
@@ -352,8 +354,10 @@ fn render_tile(
     }
 
     // NPC
-    if let Some(&ni) = state.npc_positions.get(&(x as i32, y as i32)) {
-        let npc = &state.npcs[ni];
+    if let Some(npc) = state
+        .npc_at(x as i32, y as i32)
+        .and_then(|id| state.npc(id))
+    {
         if visible {
             let light = state.get_light_level(x as i32, y as i32);
             return (
@@ -370,8 +374,11 @@ fn render_tile(
     }
 
     // Item
-    if let Some(items) = state.item_positions.get(&(x as i32, y as i32)) {
-        let item = &state.items[items[0]];
+    if let Some(item) = state
+        .item_positions
+        .get(&(x as i32, y as i32))
+        .and_then(|ids| state.item(ids[0]))
+    {
         if visible {
             let light = state.get_light_level(x as i32, y as i32);
             return (
@@ -2,7 +2,7 @@
 
 use super::theme::theme;
 use crate::game::equipment::EquipSlot;
-use crate::game::{GameState, MsgType, get_item_def, get_quest_def};
+use crate::game::{Cover, Encumbrance, EntityId, GameState, MsgType, Visibility, get_quest_def};
 use ratatui::{
     prelude::*,
     widgets::{Block, Borders, List, ListItem, Paragraph},
@@ -57,7 +57,7 @@ pub fn render_side_panel(frame: &mut Frame, area: Rect, state: &GameState) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(10), // Stats (expanded for sanity)
+            Constraint::Length(11), // Stats (expanded for sanity)
             Constraint::Length(5),  // Status Effects
             Constraint::Length(7),  // Storm Forecast
             Constraint::Length(9),  // Equipment
@@ -152,6 +152,34 @@ pub fn render_side_panel(frame: &mut Frame, area: Rect, state: &GameState) {
                 Style::default().fg(Color::Blue),
             ),
         ]),
+        Line::from(vec![
+            Span::raw("Load: "),
+            Span::styled(
+                format!("{}/{}", state.carried_weight(), state.carry_capacity()),
+                Style::default().fg(match state.encumbrance() {
+                    Encumbrance::Unburdened => Color::White,
+                    Encumbrance::Burdened => Color::Yellow,
+                    Encumbrance::Overloaded => Color::Red,
+                }),
+            ),
+            Span::raw(match state.encumbrance() {
+                Encumbrance::Unburdened => String::new(),
+                encumbrance => format!(" {}", encumbrance.label()),
+            }),
+        ]),
+        Line::from(vec![
+            Span::raw("Vis: "),
+            Span::styled(
+                state.player_visibility().label(),
+                Style::default().fg(match state.player_visibility() {
+                    Visibility::Hidden => Color::DarkGray,
+                    Visibility::Obscured => Color::Green,
+                    Visibility::Exposed => Color::Yellow,
+                    Visibility::Seen => Color::Red,
+                }),
+            ),
+            Span::raw(if state.sneaking { " Sneaking" } else { "" }),
+        ]),
         Line::from(vec![
             Span::raw("Scrip: "),
             Span::styled(
@@ -205,9 +233,8 @@ pub fn render_side_panel(frame: &mut Frame, area: Rect, state: &GameState) {
         let item_name = state
             .equipment
             .get(*slot)
-            .and_then(|id| get_item_def(id))
-            .map(|d| d.name.as_str())
-            .unwrap_or("-");
+            .map(|item| item.name())
+            .unwrap_or_else(|| "-".into());
         equip_lines.push(Line::from(format!(
             "{}: {}",
             slot.display_name(),
@@ -298,6 +325,7 @@ pub fn render_bottom_panel(frame: &mut Frame, area: Rect, state: &GameState) {
         "x     Look mode",
         "e     End turn",
         "o     Auto-explore",
+        "z     Sneak",
         "Esc   Menu",
     ];
     let hotkey_lines: Vec<Line> = hotkeys.iter().map(|s| Line::from(*s)).collect();
@@ -311,8 +339,8 @@ pub fn render_inventory_bar(frame: &mut Frame, area: Rect, state: &GameState) {
         .iter()
         .enumerate()
         .take(9)
-        .map(|(i, id)| {
-            let name = get_item_def(id).map(|d| d.name.as_str()).unwrap_or("?");
+        .map(|(i, item)| {
+            let name = item.display_name();
             Span::raw(format!("[{}]{} ", i + 1, name))
         })
         .collect();
@@ -329,11 +357,10 @@ pub fn render_inventory_bar(frame: &mut Frame, area: Rect, state: &GameState) {
 }
 
 /// Render target enemy HUD (bottom left overlay)
-pub fn render_target_hud(frame: &mut Frame, state: &GameState, target_idx: usize) {
-    if target_idx >= state.enemies.len() {
+pub fn render_target_hud(frame: &mut Frame, state: &GameState, target: EntityId) {
+    let Some(enemy) = state.enemy(target) else {
         return;
-    }
-    let enemy = &state.enemies[target_idx];
+    };
     if enemy.hp <= 0 {
         return;
     }
@@ -341,12 +368,12 @@ pub fn render_target_hud(frame: &mut Frame, state: &GameState, target_idx: usize
     let def = enemy.def();
     let name = enemy.name();
     let max_hp = def.map(|d| d.max_hp).unwrap_or(10);
-    let demeanor = format!("{:?}", enemy.demeanor()).to_lowercase();
+    let demeanor = state.demeanor_label(enemy);
 
     let bar_width = 12;
     let (hp_bar, hp_color) = render_bar(enemy.hp, max_hp, bar_width);
 
-    let lines = vec![
+    let mut lines = vec![
         Line::from(Span::styled(name, Style::default().fg(Color::Red).bold())),
         Line::from(vec![
             Span::raw("HP "),
@@ -362,9 +389,34 @@ pub fn render_target_hud(frame: &mut Frame, state: &GameState, target_idx: usize
         )),
     ];
 
+    // Cover against the player's shots
+    let cover = state.cover_between((state.player_x, state.player_y), (enemy.x, enemy.y));
+    let indicator = match cover {
+        Cover::None => None,
+        Cover::Half => Some(('◐', Color::Yellow)),
+        Cover::Full => Some(('●', Color::LightRed)),
+    };
+    if let Some((glyph, color)) = indicator {
+        lines.push(Line::from(Span::styled(
+            format!("{} {} (-{}%)", glyph, cover.label(), cover.bonus()),
+            Style::default().fg(color),
+        )));
+    }
+
+    // Resistances and vulnerabilities to the player's damage types
+    for (kind, &percent) in def.iter().flat_map(|d| &d.resistances) {
+        let (text, color) = match percent {
+            100.. => (format!("immune {}", kind.name()), Color::DarkGray),
+            1.. => (format!("resist {} {}%", kind.name(), percent), Color::Cyan),
+            ..0 => (format!("weak {} {}%", kind.name(), -percent), Color::Green),
+            0 => continue,
+        };
+        lines.push(Line::from(Span::styled(text, Style::default().fg(color))));
+    }
+
     let area = frame.area();
     let width = 22u16;
-    let height = 5u16;
+    let height = lines.len() as u16 + 2;
     let x = area.width.saturating_sub(width + 1);
     let y = area.height.saturating_sub(height + 1);
     let hud_area = Rect::new(x, y, width, height);
@@ -5,9 +5,11 @@ use super::{
     CraftingMenu, DebugMenu, InventoryMenu, IssueReporter, MenuPanel, PsychicMenu, QuestLogMenu,
     SkillsMenu, TradeMenu, WikiMenu, WorldMapView,
 };
-use crate::GameState;
 use crate::all_recipe_ids;
+use crate::game::EntityId;
+use crate::{EquipSlot, GameState};
 use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use serde::{Deserialize, Serialize};
 use std::io::Result;
 
 /// Look mode cursor state
@@ -27,6 +29,42 @@ impl Default for LookMode {
     }
 }
 
+/// Throw targeting state: the inventory item being thrown, the cursor over
+/// the tile it is aimed at and the tiles it would reach from there
+#[derive(Default)]
+pub struct ThrowMode {
+    pub active: bool,
+    pub item: usize,
+    pub x: i32,
+    pub y: i32,
+    pub blast: Vec<(i32, i32)>,
+}
+
+impl ThrowMode {
+    /// Aim the item at `idx`, starting at the nearest visible enemy within
+    /// its range or at the player
+    pub fn open(&mut self, state: &GameState, idx: usize) {
+        let (px, py) = (state.player_x, state.player_y);
+        let range = state
+            .inventory
+            .get(idx)
+            .and_then(|item| item.def())
+            .and_then(|d| d.throw.as_ref())
+            .map_or(0, |t| t.range);
+        let target = state
+            .enemies
+            .iter()
+            .filter(|e| e.hp > 0 && state.visible.contains(&state.map.idx(e.x, e.y)))
+            .map(|e| ((e.x - px).abs().max((e.y - py).abs()), (e.x, e.y)))
+            .filter(|&(dist, _)| dist <= range)
+            .min()
+            .map_or((px, py), |(_, pos)| pos);
+        self.active = true;
+        self.item = idx;
+        (self.x, self.y) = target;
+    }
+}
+
 /// Debug console state
 #[derive(Default)]
 pub struct DebugConsole {
@@ -162,6 +200,8 @@ impl DebugConsole {
             "interact",
             "examine",
             "collect_data",
+            "reload_content",
+            "watch_content",
         ];
 
         for cmd in &commands {
@@ -285,6 +325,7 @@ impl DialogBox {
 /// UI-specific state, separate from game logic
 pub struct UiState {
     pub look_mode: LookMode,
+    pub throw_mode: ThrowMode,
     pub frame_count: u64,
     pub show_controls: bool,
     pub inventory_menu: InventoryMenu,
@@ -294,7 +335,7 @@ pub struct UiState {
     pub wiki_menu: WikiMenu,
     pub trade_menu: TradeMenu,
     pub world_map_view: WorldMapView,
-    pub target_enemy: Option<usize>,
+    pub target_enemy: Option<EntityId>,
     pub debug_console: DebugConsole,
     pub debug_menu: DebugMenu,
     pub issue_reporter: IssueReporter,
@@ -364,6 +405,7 @@ impl UiState {
     pub fn new() -> Self {
         Self {
             look_mode: LookMode::default(),
+            throw_mode: ThrowMode::default(),
             frame_count: 0,
             show_controls: false,
             inventory_menu: InventoryMenu::default(),
@@ -399,23 +441,30 @@ impl UiState {
     }
 }
 
-/// Game actions that can be triggered by input
+/// Game actions that can be triggered by input.
+///
+/// Actions carry everything needed to apply them (item indices, recipe and
+/// skill ids) rather than reading menu selections, so a recorded stream of
+/// actions replays without the UI that produced it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Action {
     Quit,
     Move(i32, i32),
     Save,
     Load,
     UseItem(usize),
+    ThrowItem(usize, i32, i32),
     OpenControls,
     EnterLook,
     BreakWall(i32, i32),
     EndTurn,
     Wait,
     AutoExplore,
+    ToggleSneak,
     RangedAttack(i32, i32),
     OpenInventory,
-    EquipSelected,
-    UnequipSelected,
+    Equip(usize),
+    Unequip(EquipSlot),
     OpenQuestLog,
     OpenCrafting,
     OpenWiki,
@@ -426,7 +475,9 @@ pub enum Action {
     TargetMode,
     OpenWorldMap,
     WorldMapTravel(usize, usize),
-    Craft,
+    Craft(String),
+    UpgradeSkill(String),
+    UseAbility(String),
     TradeBuy(usize),
     TradeSell(usize),
     OpenPauseMenu,
@@ -438,7 +489,8 @@ pub enum Action {
     OpenIssueReporter,
     SubmitIssueReport,
     OpenChest(usize),
-    ChestTransfer,
+    TakeFromChest(usize, usize),
+    StoreInChest(usize, usize),
     CloseChest,
     Interact(i32, i32),
     Examine(i32, i32),
@@ -492,7 +544,7 @@ pub fn handle_input(ui: &mut UiState, state: &mut GameState) -> Result<Action> {
         }
         // Skills menu input
         if ui.skills_menu.active {
-            return Ok(handle_skills_menu_input(ui, state, key.code));
+            return Ok(handle_skills_menu_input(ui, key.code));
         }
         // Debug menu input
         if ui.debug_menu.active {
@@ -539,6 +591,10 @@ pub fn handle_input(ui: &mut UiState, state: &mut GameState) -> Result<Action> {
         if ui.look_mode.active {
             return Ok(handle_look_input(ui, key.code));
         }
+        // Throw targeting input
+        if ui.throw_mode.active {
+            return Ok(handle_throw_input(ui, key.code));
+        }
         // Normal game input
         return Ok(handle_game_input(ui, key.code));
     }
@@ -601,7 +657,11 @@ fn handle_crafting_input(ui: &mut UiState, code: KeyCode) -> Action {
         KeyCode::Esc | KeyCode::Char('c') => ui.crafting_menu.close(),
         KeyCode::Char('j') | KeyCode::Down => ui.crafting_menu.navigate(1, all_recipe_ids().len()),
         KeyCode::Char('k') | KeyCode::Up => ui.crafting_menu.navigate(-1, all_recipe_ids().len()),
-        KeyCode::Enter => return Action::Craft,
+        KeyCode::Enter => {
+            if let Some(recipe_id) = ui.crafting_menu.selected_recipe_id() {
+                return Action::Craft(recipe_id.to_string());
+            }
+        }
         _ => {}
     }
     Action::None
@@ -630,14 +690,36 @@ fn handle_inventory_input(ui: &mut UiState, state: &mut GameState, code: KeyCode
                 }
             }
         }
+        KeyCode::Char('t') => {
+            // Aim the selected item if it can be thrown
+            if let Some(idx) = ui.inventory_menu.selected_inv_index() {
+                let throwable = state
+                    .inventory
+                    .get(idx)
+                    .and_then(|item| item.def())
+                    .is_some_and(|d| d.throw.is_some());
+                if throwable && ui.inventory_menu.inspect_item.is_none() {
+                    ui.inventory_menu.close();
+                    ui.throw_mode.open(state, idx);
+                }
+            }
+        }
         KeyCode::Enter => {
             if ui.inventory_menu.inspect_item.is_some() {
                 ui.inventory_menu.inspect_item = None;
             } else {
-                return match ui.inventory_menu.panel {
-                    MenuPanel::Inventory => Action::EquipSelected,
-                    MenuPanel::Equipment => Action::UnequipSelected,
-                };
+                match ui.inventory_menu.panel {
+                    MenuPanel::Inventory => {
+                        if let Some(idx) = ui.inventory_menu.selected_inv_index() {
+                            return Action::Equip(idx);
+                        }
+                    }
+                    MenuPanel::Equipment => {
+                        if let Some(slot) = ui.inventory_menu.selected_equip_slot() {
+                            return Action::Unequip(slot);
+                        }
+                    }
+                }
             }
         }
         _ => {}
@@ -672,6 +754,23 @@ fn handle_look_input(ui: &mut UiState, code: KeyCode) -> Action {
     Action::None
 }
 
+fn handle_throw_input(ui: &mut UiState, code: KeyCode) -> Action {
+    let mode = &mut ui.throw_mode;
+    match code {
+        KeyCode::Esc => mode.active = false,
+        KeyCode::Up | KeyCode::Char('k') => mode.y -= 1,
+        KeyCode::Down | KeyCode::Char('j') => mode.y += 1,
+        KeyCode::Left | KeyCode::Char('h') => mode.x -= 1,
+        KeyCode::Right | KeyCode::Char('l') => mode.x += 1,
+        KeyCode::Enter | KeyCode::Char('t') => {
+            mode.active = false;
+            return Action::ThrowItem(mode.item, mode.x, mode.y);
+        }
+        _ => {}
+    }
+    Action::None
+}
+
 /// Pause menu options
 pub const PAUSE_OPTIONS: &[&str] = &["Resume", "Save", "Controls", "Main Menu", "Quit"];
 
@@ -786,6 +885,7 @@ fn handle_game_input(ui: &mut UiState, code: KeyCode) -> Action {
         KeyCode::Char('e') => Action::Wait,
         KeyCode::Char('E') => Action::Interact(0, 0), // Will be set to player position in main loop
         KeyCode::Char('o') => Action::AutoExplore,
+        KeyCode::Char('z') => Action::ToggleSneak,
         KeyCode::Char('i') => Action::OpenInventory,
         KeyCode::Char('q') => Action::OpenQuestLog,
         KeyCode::Char('c') => Action::OpenCrafting,
@@ -829,7 +929,19 @@ fn handle_chest_input(ui: &mut UiState, key: KeyCode) -> Action {
             }
             Action::None
         }
-        KeyCode::Enter => Action::ChestTransfer,
+        KeyCode::Enter => match &ui.chest_ui {
+            Some(chest_ui) => {
+                let chest = chest_ui.chest_index;
+                if let Some(item) = chest_ui.get_selected_chest_item() {
+                    Action::TakeFromChest(chest, item)
+                } else if let Some(item) = chest_ui.get_selected_inventory_item() {
+                    Action::StoreInChest(chest, item)
+                } else {
+                    Action::None
+                }
+            }
+            None => Action::None,
+        },
         KeyCode::Esc => Action::CloseChest,
         _ => Action::None,
     }
@@ -998,7 +1110,7 @@ fn handle_psychic_menu_input(ui: &mut UiState, state: &mut GameState, code: KeyC
     }
 }
 
-fn handle_skills_menu_input(ui: &mut UiState, state: &mut GameState, code: KeyCode) -> Action {
+fn handle_skills_menu_input(ui: &mut UiState, code: KeyCode) -> Action {
     use super::skills_menu::SkillsMenuMode;
     use crate::game::skills::{get_abilities_by_category, get_skills_by_category};
 
@@ -1035,29 +1147,16 @@ fn handle_skills_menu_input(ui: &mut UiState, state: &mut GameState, code: KeyCo
             ui.skills_menu.navigate_down(max_items);
             Action::None
         }
-        KeyCode::Enter => {
-            match ui.skills_menu.mode {
-                SkillsMenuMode::Skills => {
-                    match ui.skills_menu.upgrade_skill(state) {
-                        Ok(()) => {
-                            // Success message will be logged by the upgrade function
-                        }
-                        Err(e) => {
-                            state.log(e);
-                        }
-                    }
-                }
-                SkillsMenuMode::Abilities => match ui.skills_menu.use_ability(state) {
-                    Ok(()) => {
-                        ui.skills_menu.active = false;
-                    }
-                    Err(e) => {
-                        state.log(e);
-                    }
-                },
-            }
-            Action::None
-        }
+        KeyCode::Enter => match ui.skills_menu.mode {
+            SkillsMenuMode::Skills => ui
+                .skills_menu
+                .get_selected_skill()
+                .map_or(Action::None, Action::UpgradeSkill),
+            SkillsMenuMode::Abilities => ui
+                .skills_menu
+                .get_selected_ability()
+                .map_or(Action::None, Action::UseAbility),
+        },
         _ => Action::None,
     }
 }
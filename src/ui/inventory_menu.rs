@@ -6,7 +6,7 @@ use ratatui::{
     widgets::{Block, Borders, List, ListItem, Paragraph},
 };
 
-use crate::game::{Equipment, equipment::EquipSlot, get_item_def, inspect::inspect_item};
+use crate::game::{Equipment, ItemInstance, equipment::EquipSlot, inspect::inspect_instance};
 
 /// Menu focus panel
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -22,7 +22,7 @@ pub struct InventoryMenu {
     pub panel: MenuPanel,
     pub inv_index: usize,
     pub equip_index: usize,
-    pub inspect_item: Option<String>,
+    pub inspect_item: Option<ItemInstance>,
 }
 
 impl Default for InventoryMenu {
@@ -79,19 +79,19 @@ impl InventoryMenu {
         };
     }
 
-    pub fn inspect(&mut self, inventory: &[String], equipment: &Equipment) {
+    pub fn inspect(&mut self, inventory: &[ItemInstance], equipment: &Equipment) {
         if self.inspect_item.is_some() {
             self.inspect_item = None;
             return;
         }
-        let item_id = match self.panel {
+        let item = match self.panel {
             MenuPanel::Inventory => inventory.get(self.inv_index).cloned(),
             MenuPanel::Equipment => {
                 let slot = EquipSlot::all()[self.equip_index];
                 equipment.get(slot).cloned()
             }
         };
-        self.inspect_item = item_id;
+        self.inspect_item = item;
     }
 
     /// Get selected inventory index (for equip action)
@@ -113,12 +113,13 @@ impl InventoryMenu {
     }
 }
 
-/// Render the inventory menu
+/// Render the inventory menu; `load` is the weight carried and the capacity
 pub fn render_inventory_menu(
     frame: &mut Frame,
     menu: &InventoryMenu,
-    inventory: &[String],
+    inventory: &[ItemInstance],
     equipment: &Equipment,
+    load: (u32, u32),
 ) {
     let area = frame.area();
 
@@ -129,12 +130,13 @@ pub fn render_inventory_menu(
         .split(area);
 
     // Inventory panel
+    let marker = if menu.panel == MenuPanel::Inventory {
+        " [*]"
+    } else {
+        ""
+    };
     let inv_block = Block::default()
-        .title(if menu.panel == MenuPanel::Inventory {
-            " Inventory [*] "
-        } else {
-            " Inventory "
-        })
+        .title(format!(" Inventory{} - {}/{} wt ", marker, load.0, load.1))
         .borders(Borders::ALL)
         .border_style(if menu.panel == MenuPanel::Inventory {
             Style::default().fg(Color::Yellow)
@@ -145,8 +147,8 @@ pub fn render_inventory_menu(
     let inv_items: Vec<ListItem> = inventory
         .iter()
         .enumerate()
-        .map(|(i, id)| {
-            let name = get_item_def(id).map(|d| d.name.as_str()).unwrap_or(id);
+        .map(|(i, item)| {
+            let name = item.display_name();
             let style = if menu.panel == MenuPanel::Inventory && i == menu.inv_index {
                 Style::default().fg(Color::Black).bg(Color::Yellow)
             } else {
@@ -187,9 +189,8 @@ pub fn render_inventory_menu(
         .map(|(i, &slot)| {
             let item_name = equipment
                 .get(slot)
-                .and_then(|id| get_item_def(id))
-                .map(|d| d.name.as_str())
-                .unwrap_or("---");
+                .map(|item| item.display_name())
+                .unwrap_or_else(|| "---".into());
             let style = if menu.panel == MenuPanel::Equipment && i == menu.equip_index {
                 Style::default().fg(Color::Black).bg(Color::Cyan)
             } else {
@@ -203,12 +204,12 @@ pub fn render_inventory_menu(
     frame.render_widget(equip_list, chunks[1]);
 
     // Inspect overlay
-    if let Some(ref item_id) = menu.inspect_item {
-        render_inspect_overlay(frame, item_id, area);
+    if let Some(ref item) = menu.inspect_item {
+        render_inspect_overlay(frame, item, area);
     }
 
     // Help bar at bottom
-    let help = " [h/l] Switch panel | [j/k] Navigate | [x] Inspect | [u] Use | [Enter] Equip/Unequip | [Esc/i] Close ";
+    let help = " [h/l] Switch panel | [j/k] Navigate | [x] Inspect | [u] Use | [t] Throw | [Enter] Equip/Unequip | [Esc/i] Close ";
     let help_area = Rect::new(
         area.x,
         area.y + area.height.saturating_sub(1),
@@ -221,10 +222,10 @@ pub fn render_inventory_menu(
     );
 }
 
-fn render_inspect_overlay(frame: &mut Frame, item_id: &str, area: Rect) {
+fn render_inspect_overlay(frame: &mut Frame, item: &ItemInstance, area: Rect) {
     use ratatui::widgets::Wrap;
 
-    let info = inspect_item(item_id);
+    let info = inspect_instance(item);
     let width = 50u16.min(area.width.saturating_sub(4));
 
     let mut lines: Vec<Line> = Vec::new();
@@ -9,13 +9,13 @@ use ratatui::{
 use std::io::Result;
 
 use super::input::PAUSE_OPTIONS;
-use crate::game::{MetaProgress, all_classes};
+use crate::game::{MetaProgress, SaveSlots, SlotMeta, all_classes};
 
 /// Main menu action result
 pub enum MenuAction {
     NewGame(String),      // class_id
     NewGameWithSeed(u64), // seed for new game
-    LoadGame(String),     // save file path
+    LoadGame(String),     // save slot name
     Controls,
     Quit,
     None,
@@ -31,6 +31,13 @@ pub struct MainMenuState {
     pub seed_text: String,
     pub pending_seed: Option<u64>,
     pub meta: MetaProgress,
+    pub slot_select: bool,
+    pub slot_index: usize,
+    pub slots: Vec<SlotMeta>,
+    /// Waiting for y/n before deleting the selected slot
+    pub confirm_delete: bool,
+    /// Error or status line shown under the menu options
+    pub message: Option<String>,
 }
 
 impl MainMenuState {
@@ -40,9 +47,22 @@ impl MainMenuState {
             ..Default::default()
         }
     }
+
+    fn open_slot_select(&mut self) {
+        self.slots = SaveSlots::open_default().list();
+        self.slot_index = 0;
+        self.slot_select = true;
+        self.confirm_delete = false;
+    }
 }
 
-const MAIN_OPTIONS: &[&str] = &["New Game", "Generate World", "Controls", "Quit"];
+const MAIN_OPTIONS: &[&str] = &[
+    "New Game",
+    "Load Game",
+    "Generate World",
+    "Controls",
+    "Quit",
+];
 
 /// Handle main menu input
 pub fn handle_menu_input(state: &mut MainMenuState) -> Result<MenuAction> {
@@ -90,6 +110,54 @@ pub fn handle_menu_input(state: &mut MainMenuState) -> Result<MenuAction> {
             });
         }
 
+        if state.slot_select && state.confirm_delete {
+            // Delete confirmation: only 'y' deletes, anything else cancels
+            state.confirm_delete = false;
+            if let KeyCode::Char('y' | 'Y') = key.code
+                && let Some(slot) = state.slots.get(state.slot_index)
+            {
+                let _ = SaveSlots::open_default().delete(&slot.name);
+                state.slots.remove(state.slot_index);
+                state.slot_index = state.slot_index.min(state.slots.len().saturating_sub(1));
+            }
+            return Ok(MenuAction::None);
+        }
+
+        if state.slot_select {
+            // Save slot selection mode
+            let count = state.slots.len();
+            return Ok(match key.code {
+                KeyCode::Esc => {
+                    state.slot_select = false;
+                    MenuAction::None
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    if count > 0 {
+                        state.slot_index = (state.slot_index + count - 1) % count;
+                    }
+                    MenuAction::None
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    if count > 0 {
+                        state.slot_index = (state.slot_index + 1) % count;
+                    }
+                    MenuAction::None
+                }
+                KeyCode::Char('d') | KeyCode::Delete => {
+                    state.confirm_delete = count > 0;
+                    MenuAction::None
+                }
+                KeyCode::Enter => match state.slots.get(state.slot_index) {
+                    Some(slot) => {
+                        state.slot_select = false;
+                        MenuAction::LoadGame(slot.name.clone())
+                    }
+                    None => MenuAction::None,
+                },
+                _ => MenuAction::None,
+            });
+        }
+
         if state.class_select {
             // Class selection mode
             let classes: Vec<_> = all_classes()
@@ -145,12 +213,16 @@ pub fn handle_menu_input(state: &mut MainMenuState) -> Result<MenuAction> {
                     MenuAction::None
                 }
                 1 => {
+                    state.open_slot_select();
+                    MenuAction::None
+                }
+                2 => {
                     state.seed_input = true;
                     state.seed_text.clear();
                     MenuAction::None
                 }
-                2 => MenuAction::Controls,
-                3 => MenuAction::Quit,
+                3 => MenuAction::Controls,
+                4 => MenuAction::Quit,
                 _ => MenuAction::None,
             },
             KeyCode::Char('q') | KeyCode::Esc => MenuAction::Quit,
@@ -213,12 +285,13 @@ pub fn render_menu(frame: &mut Frame, tick: u64, state: &MainMenuState) {
 
     // Menu options
     for (i, opt) in MAIN_OPTIONS.iter().enumerate() {
-        let style = if i == state.selected && !state.class_select {
+        let overlay = state.class_select || state.slot_select;
+        let style = if i == state.selected && !overlay {
             Style::default().fg(Color::Yellow).bold()
         } else {
             Style::default().fg(Color::White)
         };
-        let prefix = if i == state.selected && !state.class_select {
+        let prefix = if i == state.selected && !overlay {
             "► "
         } else {
             "  "
@@ -230,6 +303,13 @@ pub fn render_menu(frame: &mut Frame, tick: u64, state: &MainMenuState) {
     }
 
     lines.push(Line::from(""));
+    if let Some(message) = &state.message {
+        lines.push(Line::from(Span::styled(
+            message.clone(),
+            Style::default().fg(Color::Red),
+        )));
+        lines.push(Line::from(""));
+    }
     lines.push(Line::from(Span::styled(
         "─────────────────────────────────────────────────────────────────────────",
         Style::default().fg(Color::DarkGray),
@@ -256,6 +336,91 @@ pub fn render_menu(frame: &mut Frame, tick: u64, state: &MainMenuState) {
     if state.seed_input {
         render_seed_input(frame, state);
     }
+
+    // Save slot overlay
+    if state.slot_select {
+        render_slot_select(frame, state);
+    }
+}
+
+fn render_slot_select(frame: &mut Frame, state: &MainMenuState) {
+    let area = frame.area();
+
+    let width = 72u16.min(area.width.saturating_sub(4));
+    let height = (state.slots.len() as u16 * 2 + 5)
+        .max(7)
+        .min(area.height.saturating_sub(4));
+    let x = (area.width.saturating_sub(width)) / 2;
+    let y = (area.height.saturating_sub(height)) / 2;
+    let popup = Rect::new(x, y, width, height);
+
+    frame.render_widget(Clear, popup);
+
+    let mut lines: Vec<Line> = vec![Line::from("")];
+    if state.slots.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  No saved games.",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    for (i, slot) in state.slots.iter().enumerate() {
+        let selected = i == state.slot_index;
+        let style = if selected {
+            Style::default().fg(Color::Yellow).bold()
+        } else if slot.is_autosave() {
+            Style::default().fg(Color::Gray)
+        } else {
+            Style::default()
+        };
+        let prefix = if selected { "► " } else { "  " };
+        let saved_at = slot
+            .saved_at
+            .with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M");
+        lines.push(Line::from(Span::styled(
+            format!("{}{:<28}{:>20}", prefix, slot.name, saved_at.to_string()),
+            style,
+        )));
+        let depth = if slot.layer < 0 {
+            format!("  depth {}", -slot.layer)
+        } else {
+            String::new()
+        };
+        lines.push(Line::from(Span::styled(
+            format!(
+                "    {} Lv{}  turn {}  ({}, {}){}  played {}",
+                if slot.class_id.is_empty() {
+                    "-"
+                } else {
+                    &slot.class_id
+                },
+                slot.level,
+                slot.turn,
+                slot.world_x,
+                slot.world_y,
+                depth,
+                slot.playtime_display()
+            ),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    lines.push(Line::from(""));
+    match state.slots.get(state.slot_index) {
+        Some(slot) if state.confirm_delete => lines.push(Line::from(Span::styled(
+            format!("Delete '{}'? [y] Yes  [n] No", slot.name),
+            Style::default().fg(Color::Red).bold(),
+        ))),
+        _ => lines.push(Line::from(Span::styled(
+            "[Enter] Load  [d] Delete  [Esc] Back",
+            Style::default().fg(Color::DarkGray),
+        ))),
+    }
+
+    let block = Block::default()
+        .title(" Load Game ")
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::Black));
+    frame.render_widget(Paragraph::new(lines).block(block), popup);
 }
 
 fn render_seed_input(frame: &mut Frame, state: &MainMenuState) {
@@ -375,6 +540,7 @@ pub fn render_controls(frame: &mut Frame) {
         Line::from("  Actions:"),
         Line::from("    x    Look at (examine tile)"),
         Line::from("    o    Auto-explore"),
+        Line::from("    z    Toggle sneaking"),
         Line::from("    e    End turn (wait)"),
         Line::from("    1-3  Use inventory item"),
         Line::from("    S    Save game"),
@@ -4,6 +4,7 @@ pub mod book_reader;
 pub mod chest_ui;
 pub mod crafting_menu;
 pub mod debug_menu;
+pub mod dispatch;
 pub mod game_view;
 pub mod hud;
 pub mod input;
@@ -23,6 +24,7 @@ pub use book_reader::render_book_reader;
 pub use chest_ui::{ChestPanel, ChestUI, render_chest_ui};
 pub use crafting_menu::{CraftingMenu, render_crafting_menu};
 pub use debug_menu::{DebugMenu, render_debug_menu};
+pub use dispatch::{SaveSession, apply_action, apply_pending};
 pub use game_view::{
     dim_color, render_damage_numbers, render_death_screen, render_debug_console, render_dialog_box,
     render_map,
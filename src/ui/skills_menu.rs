@@ -143,18 +143,17 @@ impl SkillsMenu {
     /// Use selected ability
     pub fn use_ability(&self, game_state: &mut GameState) -> Result<(), String> {
         let ability_id = self.get_selected_ability().ok_or("No ability selected")?;
-
-        match game_state.skills.use_ability(&ability_id) {
-            Ok(effect_id) => {
-                // Apply the effect
-                apply_ability_effect(game_state, &effect_id);
-                Ok(())
-            }
-            Err(e) => Err(e),
-        }
+        use_ability(game_state, &ability_id)
     }
 }
 
+/// Use an ability by id and apply its effect
+pub fn use_ability(game_state: &mut GameState, ability_id: &str) -> Result<(), String> {
+    let effect_id = game_state.skills.use_ability(ability_id)?;
+    apply_ability_effect(game_state, &effect_id);
+    Ok(())
+}
+
 /// Apply ability effect to game state
 fn apply_ability_effect(game_state: &mut GameState, effect_id: &str) {
     match effect_id {
@@ -185,15 +185,15 @@ pub fn render_trade_menu(f: &mut Frame, menu: &TradeMenu, state: &GameState) {
                 .inventory
                 .iter()
                 .enumerate()
-                .map(|(i, item_id)| {
-                    let def = match get_item_def(item_id) {
+                .map(|(i, item)| {
+                    let def = match item.def() {
                         Some(d) => d,
                         None => {
                             // Fallback for missing item definitions
                             return ListItem::new(Line::from(vec![
                                 Span::styled("? ", Style::default().fg(Color::Red)),
                                 Span::styled(
-                                    format!("MISSING: {}", item_id),
+                                    format!("MISSING: {}", item.id),
                                     if i == menu.selected_index {
                                         Style::default().fg(Color::Black).bg(Color::White)
                                     } else {
@@ -210,8 +210,8 @@ pub fn render_trade_menu(f: &mut Frame, menu: &TradeMenu, state: &GameState) {
                     };
 
                     let sell_price =
-                        (def.value as f32 * trade_interface.sell_price_multiplier) as u32;
-                    let name = format!("{: <20}", def.name);
+                        (item.value() as f32 * trade_interface.sell_price_multiplier) as u32;
+                    let name = format!("{: <20}", item.display_name());
                     let price = format!("{: >4} scrip", sell_price);
 
                     ListItem::new(Line::from(vec![
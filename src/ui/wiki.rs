@@ -158,8 +158,13 @@ fn get_entries(tab: WikiTab, meta: &MetaProgress) -> Vec<(String, String, bool)>
                 let def = get_item_def(id)?;
                 let discovered = meta.discovered_items.contains(id);
                 let mut desc = format!("{}\n\n{}", def.name, def.description);
-                if def.heal > 0 {
-                    desc.push_str(&format!("\n\nHeals: {} HP", def.heal));
+                let effects: Vec<String> = def
+                    .use_effects
+                    .iter()
+                    .filter_map(|e| e.describe().map(|(_, text)| text))
+                    .collect();
+                if !effects.is_empty() {
+                    desc.push_str(&format!("\n\nOn use: {}", effects.join(", ")));
                 }
                 if def.armor_value > 0 {
                     desc.push_str(&format!("\nArmor: +{}", def.armor_value));
@@ -320,3 +320,210 @@ fn interaction_system_test() {
             .collect::<Vec<_>>()
     );
 }
+
+#[test]
+fn scenarios_reproduce_across_runs_and_threads() {
+    use saltglass_steppe::des::determinism::{check_repeat, check_thread_counts};
+
+    let scenarios: Vec<Scenario> = [
+        "tests/scenarios/basic_movement.json",
+        "tests/scenarios/combat_behaviors_test.json",
+        "tests/scenarios/ranged_attack.json",
+        "tests/scenarios/interaction_system_test.json",
+    ]
+    .iter()
+    .map(|path| Scenario::from_file(path).expect("Failed to parse scenario"))
+    .collect();
+
+    for scenario in &scenarios {
+        assert_eq!(check_repeat(scenario, 2), None);
+    }
+    assert_eq!(check_thread_counts(&scenarios, &[1, 4]), Ok(Vec::new()));
+}
+
+#[test]
+fn action_order_follows_speed() {
+    let result = run_scenario("tests/scenarios/action_order.json").expect("Failed to run scenario");
+    assert!(
+        result.success,
+        "Action order test failed: {:?}",
+        result
+            .assertion_results
+            .iter()
+            .filter(|r| !r.passed)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn disabled_systems_are_skipped() {
+    let result =
+        run_scenario("tests/scenarios/disabled_systems.json").expect("Failed to run scenario");
+    assert!(
+        result.success,
+        "Disabled systems test failed: {:?}",
+        result
+            .assertion_results
+            .iter()
+            .filter(|r| !r.passed)
+            .collect::<Vec<_>>()
+    );
+
+    let mut scenario =
+        Scenario::from_file("tests/scenarios/disabled_systems.json").expect("Failed to parse");
+    scenario.disabled_systems.push("enemeis".to_string());
+    let result = saltglass_steppe::des::DesExecutor::new(&scenario).run(&scenario);
+    assert!(
+        !result.success,
+        "Unknown system names should fail the scenario"
+    );
+}
+
+#[test]
+fn entity_actor_test() {
+    let result = run_scenario("tests/scenarios/entity_actor.json").expect("Failed to run scenario");
+    assert!(
+        result.success,
+        "Entity actor test failed: {:?}\n{:?}",
+        result
+            .assertion_results
+            .iter()
+            .filter(|r| !r.passed)
+            .collect::<Vec<_>>(),
+        result.logs.iter().map(|l| &l.message).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn item_effects_test() {
+    let result = run_scenario("tests/scenarios/item_effects.json").expect("Failed to run scenario");
+    assert!(
+        result.success,
+        "Item effects test failed: {:?}\n{:?}",
+        result
+            .assertion_results
+            .iter()
+            .filter(|r| !r.passed)
+            .collect::<Vec<_>>(),
+        result.logs.iter().map(|l| &l.message).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn damage_types_meet_resistances() {
+    let result = run_scenario("tests/scenarios/damage_types.json").expect("Failed to run scenario");
+    assert!(
+        result.success,
+        "Damage types test failed: {:?}",
+        result
+            .assertion_results
+            .iter()
+            .filter(|r| !r.passed)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn thrown_items_hit_and_blast() {
+    let result = run_scenario("tests/scenarios/throwing.json").expect("Failed to run scenario");
+    assert!(
+        result.success,
+        "Throwing test failed: {:?}",
+        result
+            .assertion_results
+            .iter()
+            .filter(|r| !r.passed)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn line_of_fire_blocks_shots() {
+    let result = run_scenario("tests/scenarios/line_of_fire.json").expect("Failed to run scenario");
+    assert!(
+        result.success,
+        "Line of fire test failed: {:?}",
+        result
+            .assertion_results
+            .iter()
+            .filter(|r| !r.passed)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn player_adaptations_test() {
+    let result =
+        run_scenario("tests/scenarios/player_adaptations.json").expect("Failed to run scenario");
+    assert!(
+        result.success,
+        "Player adaptations test failed: {:?}",
+        result
+            .assertion_results
+            .iter()
+            .filter(|r| !r.passed)
+            .collect::<Vec<_>>()
+    );
+
+    // Ids match in any case, and the old `miragestep` spelling still works
+    let mut scenario =
+        Scenario::from_file("tests/scenarios/player_adaptations.json").expect("Failed to parse");
+    scenario.player.adaptations = vec!["Prismhide".to_string(), "MirageStep".to_string()];
+    scenario.assertions.clear();
+    let result = saltglass_steppe::des::DesExecutor::new(&scenario).run(&scenario);
+    assert!(result.success);
+    let state = result.final_state.unwrap();
+    assert!(state.has_adaptation("prismhide") && state.has_adaptation("mirage_step"));
+
+    scenario.player.adaptations.push("prizmhide".to_string());
+    let result = saltglass_steppe::des::DesExecutor::new(&scenario).run(&scenario);
+    assert!(
+        !result.success,
+        "Unknown adaptation ids should fail the scenario"
+    );
+}
+
+#[test]
+fn item_instances_wear_and_carry_affixes() {
+    let result =
+        run_scenario("tests/scenarios/item_instances.json").expect("Failed to run scenario");
+    assert!(
+        result.success,
+        "Item instances test failed: {:?}",
+        result
+            .assertion_results
+            .iter()
+            .filter(|r| !r.passed)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn factions_fight_each_other_and_follow_reputation() {
+    let result =
+        run_scenario("tests/scenarios/faction_hostility.json").expect("Failed to run scenario");
+    assert!(
+        result.success,
+        "Faction hostility test failed: {:?}",
+        result
+            .assertion_results
+            .iter()
+            .filter(|r| !r.passed)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn sneaking_players_go_unnoticed() {
+    let result =
+        run_scenario("tests/scenarios/stealth_sneaking.json").expect("Failed to run scenario");
+    assert!(
+        result.success,
+        "Stealth sneaking test failed: {:?}",
+        result
+            .assertion_results
+            .iter()
+            .filter(|r| !r.passed)
+            .collect::<Vec<_>>()
+    );
+}